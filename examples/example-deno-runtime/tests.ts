@@ -7,6 +7,7 @@ import type {
   Exports,
   Imports,
 } from "../example-protocol/bindings/ts-runtime/index.ts";
+import { implementedExports } from "../example-protocol/bindings/ts-runtime/index.ts";
 import type {
   ExplicitBoundPoint,
   FpAdjacentlyTagged,
@@ -33,6 +34,13 @@ let voidFunctionCalled = false;
 let globalState = 0;
 
 const imports: Imports = {
+  importBorrowedString: (arg: string): string => {
+    assertEquals(arg, "Hello");
+    return "Hello, world!";
+  },
+
+  importUppercaseString: (arg: string): string => arg.toUpperCase(),
+
   importExplicitBoundPoint: (arg: ExplicitBoundPoint<number>) => {
     assertEquals(arg.value, 123);
   },
@@ -389,6 +397,16 @@ Deno.test("primitives", async () => {
   assertEquals(plugin.exportPrimitiveI32AddThree?.(-32), -32 + 3);
   assertEquals(plugin.exportPrimitiveI64AddThree?.(-64n), -64n + 3n);
 
+  assertEquals(
+    plugin.exportPrimitiveI128AddThree?.(-(2n ** 100n)),
+    -(2n ** 100n) + 3n,
+  );
+  assertEquals(
+    plugin.exportPrimitiveU128AddThree?.(2n ** 128n - 4n),
+    2n ** 128n - 1n,
+  );
+  assertEquals(plugin.exportPrimitiveCharToUppercase?.("a"), "A");
+
   assertEquals(plugin.exportMultiplePrimitives?.(-8, "Hello, 🇳🇱!"), -64n);
 
   // Precise float comparison is fine as long as the denominator is a power of two
@@ -400,6 +418,14 @@ Deno.test("primitives", async () => {
   assertEquals(plugin.exportPrimitiveF64AddThreeWasmer2?.(12.5), 12.5 + 3.0);
 });
 
+Deno.test("implemented exports", async () => {
+  const plugin = await loadExamplePlugin();
+
+  const exports = implementedExports(plugin);
+  assert(exports.includes("exportString"));
+  assert(!exports.includes("exportVoidFunction"));
+});
+
 Deno.test("arrays", async () => {
   const plugin = await loadExamplePlugin();
 
@@ -419,6 +445,19 @@ Deno.test("string", async () => {
   assertEquals(plugin.exportString?.("Hello, plugin!"), "Hello, world!");
 });
 
+Deno.test("borrowed arguments", async () => {
+  const plugin = await loadExamplePlugin();
+
+  assertEquals(plugin.exportBorrowedString?.("Hello, plugin!"), "Hello, world!");
+  assertEquals(plugin.exportBorrowedBytes?.([1, 2, 3]), [3, 2, 1]);
+});
+
+Deno.test("named import module", async () => {
+  const plugin = await loadExamplePlugin();
+
+  assertEquals(plugin.exportUppercaseString?.("Hello, plugin!"), "HELLO, PLUGIN!");
+});
+
 Deno.test("timestamp", async () => {
   const plugin = await loadExamplePlugin();
 
@@ -647,6 +686,17 @@ Deno.test("options", async () => {
   });
 });
 
+Deno.test("compact structs", async () => {
+  const { exportCompactPoint } = await loadExamplePlugin();
+  assert(exportCompactPoint);
+
+  assertEquals(exportCompactPoint({ x: 1, y: 2, label: null }), {
+    x: 2,
+    y: 1,
+    label: "swapped",
+  });
+});
+
 function isOk<T, E>(result: Result<T, E>): result is { Ok: T } {
   return "Ok" in result;
 }
@@ -4,6 +4,7 @@ use example_bindings::*;
 use serde_bytes::ByteBuf;
 use std::collections::{BTreeMap};
 use std::panic;
+use std::io::Write;
 use time::{macros::datetime, OffsetDateTime};
 
 // This plugin contains implementations for all the functions it may export
@@ -93,6 +94,21 @@ fn export_primitive_u64_add_three(arg: u64) -> u64 {
     import_primitive_u64_add_one(arg + 1) + 1
 }
 
+#[fp_export_impl(example_bindings)]
+fn export_primitive_i128_add_three(arg: i128) -> i128 {
+    arg + 3
+}
+
+#[fp_export_impl(example_bindings)]
+fn export_primitive_u128_add_three(arg: u128) -> u128 {
+    arg + 3
+}
+
+#[fp_export_impl(example_bindings)]
+fn export_primitive_char_to_uppercase(arg: char) -> char {
+    arg.to_ascii_uppercase()
+}
+
 #[fp_export_impl(example_bindings)]
 fn export_array_u8(arg: [u8; 3]) -> [u8; 3] {
     assert_eq!(arg, [1u8, 2u8, 3u8]);
@@ -147,6 +163,24 @@ fn export_string(arg: String) -> String {
     "Hello, world!".to_owned()
 }
 
+#[fp_export_impl(example_bindings)]
+fn export_borrowed_string(arg: String) -> String {
+    assert_eq!(arg, "Hello, plugin!");
+    import_borrowed_string(&arg[..5])
+}
+
+#[fp_export_impl(example_bindings)]
+fn export_borrowed_bytes(arg: Vec<u8>) -> Vec<u8> {
+    assert_eq!(arg, [1, 2, 3]);
+    arg.into_iter().rev().collect()
+}
+
+#[fp_export_impl(example_bindings)]
+fn export_uppercase_string(arg: String) -> String {
+    assert_eq!(arg, "Hello, plugin!");
+    text::import_uppercase_string(arg)
+}
+
 #[fp_export_impl(example_bindings)]
 fn export_multiple_primitives(arg1: i8, arg2: String) -> i64 {
     assert_eq!(arg1, -8);
@@ -460,6 +494,40 @@ fn export_struct_with_options(arg: StructWithOptions) -> StructWithOptions {
     value
 }
 
+#[fp_export_impl(example_bindings)]
+fn export_compact_point(arg: CompactPoint) -> CompactPoint {
+    assert_eq!(
+        arg,
+        CompactPoint {
+            x: 1,
+            y: 2,
+            label: None
+        }
+    );
+    CompactPoint {
+        x: arg.y,
+        y: arg.x,
+        label: Some("swapped".to_owned()),
+    }
+}
+
+#[fp_export_impl(example_bindings)]
+fn export_print(stdout: String, stderr: String) {
+    print!("{stdout}");
+    eprint!("{stderr}");
+    std::io::stdout().flush().unwrap();
+}
+
+#[fp_export_impl(example_bindings)]
+fn export_read_file(path: String) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|error| error.to_string())
+}
+
+#[fp_export_impl(example_bindings)]
+fn export_write_file(path: String, contents: String) -> Result<(), String> {
+    std::fs::write(path, contents).map_err(|error| error.to_string())
+}
+
 #[fp_export_impl(example_bindings)]
 fn init() {
     init_panic_hook();
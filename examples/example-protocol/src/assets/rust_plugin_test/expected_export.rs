@@ -27,6 +27,15 @@ pub fn export_array_u8(arg: [u8; 3]) -> [u8; 3];
 #[fp_bindgen_support::fp_export_signature]
 pub async fn export_async_struct(arg1: FpPropertyRenaming, arg2: u64) -> FpPropertyRenaming;
 
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_borrowed_bytes(arg: Vec<u8>) -> Vec<u8>;
+
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_borrowed_string(arg: String) -> String;
+
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_compact_point(arg: CompactPoint) -> CompactPoint;
+
 #[fp_bindgen_support::fp_export_signature]
 pub fn export_fp_adjacently_tagged(arg: FpAdjacentlyTagged) -> FpAdjacentlyTagged;
 
@@ -66,6 +75,9 @@ pub fn export_primitive_bool_negate(arg: bool) -> bool;
 #[fp_bindgen_support::fp_export_signature]
 pub async fn export_primitive_bool_negate_async(arg: bool) -> bool;
 
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_primitive_char_to_uppercase(arg: char) -> char;
+
 #[fp_bindgen_support::fp_export_signature]
 pub fn export_primitive_f32_add_three(arg: f32) -> f32;
 
@@ -84,6 +96,9 @@ pub async fn export_primitive_f64_add_three_async(arg: f64) -> f64;
 #[fp_bindgen_support::fp_export_signature]
 pub fn export_primitive_f64_add_three_wasmer2(arg: f64) -> f64;
 
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_primitive_i128_add_three(arg: i128) -> i128;
+
 #[fp_bindgen_support::fp_export_signature]
 pub fn export_primitive_i16_add_three(arg: i16) -> i16;
 
@@ -108,6 +123,9 @@ pub fn export_primitive_i8_add_three(arg: i8) -> i8;
 #[fp_bindgen_support::fp_export_signature]
 pub async fn export_primitive_i8_add_three_async(arg: i8) -> i8;
 
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_primitive_u128_add_three(arg: u128) -> u128;
+
 #[fp_bindgen_support::fp_export_signature]
 pub fn export_primitive_u16_add_three(arg: u16) -> u16;
 
@@ -132,6 +150,12 @@ pub fn export_primitive_u8_add_three(arg: u8) -> u8;
 #[fp_bindgen_support::fp_export_signature]
 pub async fn export_primitive_u8_add_three_async(arg: u8) -> u8;
 
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_print(stdout: String, stderr: String);
+
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_read_file(path: String) -> Result<String, String>;
+
 #[fp_bindgen_support::fp_export_signature]
 pub async fn export_reset_global_state();
 
@@ -162,9 +186,15 @@ pub fn export_struct_with_options(arg: StructWithOptions) -> StructWithOptions;
 #[fp_bindgen_support::fp_export_signature]
 pub fn export_timestamp(arg: MyDateTime) -> MyDateTime;
 
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_uppercase_string(arg: String) -> String;
+
 #[fp_bindgen_support::fp_export_signature]
 pub fn export_void_function();
 
+#[fp_bindgen_support::fp_export_signature]
+pub fn export_write_file(path: String, contents: String) -> Result<(), String>;
+
 /// Example how plugin could expose async data-fetching capabilities.
 #[fp_bindgen_support::fp_export_signature]
 pub async fn fetch_data(r#type: String) -> Result<String, String>;
@@ -24,6 +24,9 @@ pub fn import_array_u32(arg: [u32; 3]) -> [u32; 3];
 #[fp_bindgen_support::fp_import_signature]
 pub fn import_array_u8(arg: [u8; 3]) -> [u8; 3];
 
+#[fp_bindgen_support::fp_import_signature]
+pub fn import_borrowed_string(arg: &str) -> String;
+
 #[fp_bindgen_support::fp_import_signature]
 pub fn import_explicit_bound_point(arg: ExplicitBoundPoint<u64>);
 
@@ -180,3 +183,10 @@ pub fn log(message: String);
 /// See `types/http.rs` for more info.
 #[fp_bindgen_support::fp_import_signature]
 pub async fn make_http_request(request: Request) -> HttpResult;
+
+pub mod text {
+    use crate::types::*;
+
+    #[fp_bindgen_support::fp_import_signature(module = "text")]
+    pub fn import_uppercase_string(arg: String) -> String;
+}
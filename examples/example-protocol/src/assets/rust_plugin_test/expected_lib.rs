@@ -11,3 +11,17 @@ pub use import::*;
 pub use types::*;
 
 pub use fp_bindgen_support::*;
+
+/// Returns the version of the protocol the plugin was built against, so that
+/// runtimes can verify their compatibility when loading the plugin.
+#[doc(hidden)]
+#[no_mangle]
+pub fn __fp_protocol_version() -> fp_bindgen_support::common::mem::FatPtr {
+    fp_bindgen_support::guest::io::export_value_to_host(
+        &fp_bindgen_support::common::protocol::ProtocolVersion {
+            name: "example-protocol".to_owned(),
+            version: "0.1.0".to_owned(),
+            hash: "5c1bf67516549a9d".to_owned(),
+        },
+    )
+}
@@ -7,6 +7,27 @@ pub use redux_example::StateUpdate;
 
 pub type Body = serde_bytes::ByteBuf;
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CompactPoint {
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl Serialize for CompactPoint {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Compact<'a>(
+            &'a i32,
+            &'a i32,
+            &'a Option<String>,
+        );
+
+        Compact(&self.x, &self.y, &self.label).serialize(serializer)
+    }
+}
+
 /// # This is an enum with doc comments.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum DocExampleEnum {
@@ -1,18 +1,24 @@
 #![allow(clippy::let_and_return, unused)]
 use super::types::*;
 use fp_bindgen_support::{
-    common::{abi::WasmAbi, mem::FatPtr},
+    common::{abi::WasmAbi, format::WireFormat, mem::FatPtr, protocol::ProtocolVersion},
     wasmer2_host::{
+        callback::invoke_callback,
         errors::{InvocationError, RuntimeError},
+        events::{subscribe, unsubscribe},
+        handle::{release_handle, retain_handle},
+        link::{link_plugin, unlink_plugins, LinkError},
         mem::{
-            deserialize_from_slice, export_to_guest, export_to_guest_raw, import_from_guest,
-            import_from_guest_raw, serialize_to_vec,
+            deserialize_from_guest, export_to_guest, export_to_guest_raw, import_from_guest,
+            import_from_guest_raw, serialize_for_guest,
         },
+        protocol::{check_protocol_version, negotiate_wire_format},
         r#async::{create_future_value, future::ModuleRawFuture, resolve_async_value},
         runtime::RuntimeInstanceData,
     },
 };
 use std::cell::RefCell;
+use std::sync::Arc;
 use wasmer::{imports, Function, ImportObject, Instance, Module, Store, WasmerEnv};
 
 #[derive(Clone)]
@@ -22,19 +28,50 @@ pub struct Runtime {
 }
 
 impl Runtime {
+    /// Wire format the runtime asks the plugin to use.
+    const PREFERRED_WIRE_FORMAT: WireFormat = WireFormat::MessagePack;
+
     pub fn new(wasm_module: impl AsRef<[u8]>) -> Result<Self, RuntimeError> {
+        Self::with_wasi_config(wasm_module, WasiConfig::default())
+    }
+
+    /// Creates a runtime in which the plugin runs in the given WASI environment.
+    pub fn with_wasi_config(
+        wasm_module: impl AsRef<[u8]>,
+        wasi_config: WasiConfig,
+    ) -> Result<Self, RuntimeError> {
         let store = Self::default_store();
         let module = Module::new(&store, wasm_module)?;
         let mut env = RuntimeInstanceData::default();
-        let mut wasi_env = wasmer_wasi::WasiState::new("fp").finalize().unwrap();
+        let mut wasi_env = wasi_config
+            .finalize()
+            .map_err(|error| RuntimeError::WasiSetup(error.to_string()))?;
         let mut import_object = wasi_env.import_object(&module).unwrap();
-        let namespace = create_import_object(module.store(), &env);
-        import_object.register("fp", namespace);
+        for (name, namespace) in create_import_namespaces(module.store(), &env) {
+            import_object.register(name, namespace);
+        }
         let instance = Instance::new(&module, &import_object).unwrap();
         env.init_with_instance(&instance).unwrap();
+        check_protocol_version(&instance, &env, &Self::protocol_version())?;
+        negotiate_wire_format(&instance, &env, Self::PREFERRED_WIRE_FORMAT)?;
         Ok(Self { instance, env })
     }
 
+    /// Returns the version of the protocol this runtime was generated for.
+    pub fn protocol_version() -> ProtocolVersion {
+        ProtocolVersion {
+            name: "example-protocol".to_owned(),
+            version: "0.1.0".to_owned(),
+            hash: "5c1bf67516549a9d".to_owned(),
+        }
+    }
+
+    /// Returns the format in which values are passed to and from the plugin,
+    /// as negotiated when the plugin was loaded.
+    pub fn wire_format(&self) -> WireFormat {
+        self.env.wire_format()
+    }
+
     #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
     fn default_store() -> wasmer::Store {
         let compiler = wasmer::Cranelift::default();
@@ -49,10 +86,435 @@ impl Runtime {
         Store::new(&engine)
     }
 
+    /// Returns the names of all the exports implemented by the plugin.
+    pub fn implemented_exports(&self) -> Vec<&'static str> {
+        [
+            "export_array_f32",
+            "export_array_f64",
+            "export_array_i16",
+            "export_array_i32",
+            "export_array_i8",
+            "export_array_u16",
+            "export_array_u32",
+            "export_array_u8",
+            "export_async_struct",
+            "export_borrowed_bytes",
+            "export_borrowed_string",
+            "export_compact_point",
+            "export_fp_adjacently_tagged",
+            "export_fp_enum",
+            "export_fp_flatten",
+            "export_fp_internally_tagged",
+            "export_fp_struct",
+            "export_fp_untagged",
+            "export_generics",
+            "export_get_bytes",
+            "export_get_serde_bytes",
+            "export_increment_global_state",
+            "export_multiple_primitives",
+            "export_primitive_bool_negate",
+            "export_primitive_bool_negate_async",
+            "export_primitive_char_to_uppercase",
+            "export_primitive_f32_add_three",
+            "export_primitive_f32_add_three_async",
+            "export_primitive_f32_add_three_wasmer2",
+            "export_primitive_f64_add_three",
+            "export_primitive_f64_add_three_async",
+            "export_primitive_f64_add_three_wasmer2",
+            "export_primitive_i128_add_three",
+            "export_primitive_i16_add_three",
+            "export_primitive_i16_add_three_async",
+            "export_primitive_i32_add_three",
+            "export_primitive_i32_add_three_async",
+            "export_primitive_i64_add_three",
+            "export_primitive_i64_add_three_async",
+            "export_primitive_i8_add_three",
+            "export_primitive_i8_add_three_async",
+            "export_primitive_u128_add_three",
+            "export_primitive_u16_add_three",
+            "export_primitive_u16_add_three_async",
+            "export_primitive_u32_add_three",
+            "export_primitive_u32_add_three_async",
+            "export_primitive_u64_add_three",
+            "export_primitive_u64_add_three_async",
+            "export_primitive_u8_add_three",
+            "export_primitive_u8_add_three_async",
+            "export_print",
+            "export_read_file",
+            "export_reset_global_state",
+            "export_serde_adjacently_tagged",
+            "export_serde_enum",
+            "export_serde_flatten",
+            "export_serde_internally_tagged",
+            "export_serde_struct",
+            "export_serde_untagged",
+            "export_string",
+            "export_struct_with_options",
+            "export_timestamp",
+            "export_uppercase_string",
+            "export_void_function",
+            "export_write_file",
+            "fetch_data",
+            "init",
+            "reducer_bridge",
+        ]
+        .iter()
+        .copied()
+        .filter(|name| self.has_export(name))
+        .collect()
+    }
+
+    /// Returns whether the plugin implements the `export_array_f32` export.
+    pub fn has_export_array_f32(&self) -> bool {
+        self.has_export("export_array_f32")
+    }
+
+    /// Returns whether the plugin implements the `export_array_f64` export.
+    pub fn has_export_array_f64(&self) -> bool {
+        self.has_export("export_array_f64")
+    }
+
+    /// Returns whether the plugin implements the `export_array_i16` export.
+    pub fn has_export_array_i16(&self) -> bool {
+        self.has_export("export_array_i16")
+    }
+
+    /// Returns whether the plugin implements the `export_array_i32` export.
+    pub fn has_export_array_i32(&self) -> bool {
+        self.has_export("export_array_i32")
+    }
+
+    /// Returns whether the plugin implements the `export_array_i8` export.
+    pub fn has_export_array_i8(&self) -> bool {
+        self.has_export("export_array_i8")
+    }
+
+    /// Returns whether the plugin implements the `export_array_u16` export.
+    pub fn has_export_array_u16(&self) -> bool {
+        self.has_export("export_array_u16")
+    }
+
+    /// Returns whether the plugin implements the `export_array_u32` export.
+    pub fn has_export_array_u32(&self) -> bool {
+        self.has_export("export_array_u32")
+    }
+
+    /// Returns whether the plugin implements the `export_array_u8` export.
+    pub fn has_export_array_u8(&self) -> bool {
+        self.has_export("export_array_u8")
+    }
+
+    /// Returns whether the plugin implements the `export_async_struct` export.
+    pub fn has_export_async_struct(&self) -> bool {
+        self.has_export("export_async_struct")
+    }
+
+    /// Returns whether the plugin implements the `export_borrowed_bytes` export.
+    pub fn has_export_borrowed_bytes(&self) -> bool {
+        self.has_export("export_borrowed_bytes")
+    }
+
+    /// Returns whether the plugin implements the `export_borrowed_string` export.
+    pub fn has_export_borrowed_string(&self) -> bool {
+        self.has_export("export_borrowed_string")
+    }
+
+    /// Returns whether the plugin implements the `export_compact_point` export.
+    pub fn has_export_compact_point(&self) -> bool {
+        self.has_export("export_compact_point")
+    }
+
+    /// Returns whether the plugin implements the `export_fp_adjacently_tagged` export.
+    pub fn has_export_fp_adjacently_tagged(&self) -> bool {
+        self.has_export("export_fp_adjacently_tagged")
+    }
+
+    /// Returns whether the plugin implements the `export_fp_enum` export.
+    pub fn has_export_fp_enum(&self) -> bool {
+        self.has_export("export_fp_enum")
+    }
+
+    /// Returns whether the plugin implements the `export_fp_flatten` export.
+    pub fn has_export_fp_flatten(&self) -> bool {
+        self.has_export("export_fp_flatten")
+    }
+
+    /// Returns whether the plugin implements the `export_fp_internally_tagged` export.
+    pub fn has_export_fp_internally_tagged(&self) -> bool {
+        self.has_export("export_fp_internally_tagged")
+    }
+
+    /// Returns whether the plugin implements the `export_fp_struct` export.
+    pub fn has_export_fp_struct(&self) -> bool {
+        self.has_export("export_fp_struct")
+    }
+
+    /// Returns whether the plugin implements the `export_fp_untagged` export.
+    pub fn has_export_fp_untagged(&self) -> bool {
+        self.has_export("export_fp_untagged")
+    }
+
+    /// Returns whether the plugin implements the `export_generics` export.
+    pub fn has_export_generics(&self) -> bool {
+        self.has_export("export_generics")
+    }
+
+    /// Returns whether the plugin implements the `export_get_bytes` export.
+    pub fn has_export_get_bytes(&self) -> bool {
+        self.has_export("export_get_bytes")
+    }
+
+    /// Returns whether the plugin implements the `export_get_serde_bytes` export.
+    pub fn has_export_get_serde_bytes(&self) -> bool {
+        self.has_export("export_get_serde_bytes")
+    }
+
+    /// Returns whether the plugin implements the `export_increment_global_state` export.
+    pub fn has_export_increment_global_state(&self) -> bool {
+        self.has_export("export_increment_global_state")
+    }
+
+    /// Returns whether the plugin implements the `export_multiple_primitives` export.
+    pub fn has_export_multiple_primitives(&self) -> bool {
+        self.has_export("export_multiple_primitives")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_bool_negate` export.
+    pub fn has_export_primitive_bool_negate(&self) -> bool {
+        self.has_export("export_primitive_bool_negate")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_bool_negate_async` export.
+    pub fn has_export_primitive_bool_negate_async(&self) -> bool {
+        self.has_export("export_primitive_bool_negate_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_char_to_uppercase` export.
+    pub fn has_export_primitive_char_to_uppercase(&self) -> bool {
+        self.has_export("export_primitive_char_to_uppercase")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_f32_add_three` export.
+    pub fn has_export_primitive_f32_add_three(&self) -> bool {
+        self.has_export("export_primitive_f32_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_f32_add_three_async` export.
+    pub fn has_export_primitive_f32_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_f32_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_f32_add_three_wasmer2` export.
+    pub fn has_export_primitive_f32_add_three_wasmer2(&self) -> bool {
+        self.has_export("export_primitive_f32_add_three_wasmer2")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_f64_add_three` export.
+    pub fn has_export_primitive_f64_add_three(&self) -> bool {
+        self.has_export("export_primitive_f64_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_f64_add_three_async` export.
+    pub fn has_export_primitive_f64_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_f64_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_f64_add_three_wasmer2` export.
+    pub fn has_export_primitive_f64_add_three_wasmer2(&self) -> bool {
+        self.has_export("export_primitive_f64_add_three_wasmer2")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i128_add_three` export.
+    pub fn has_export_primitive_i128_add_three(&self) -> bool {
+        self.has_export("export_primitive_i128_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i16_add_three` export.
+    pub fn has_export_primitive_i16_add_three(&self) -> bool {
+        self.has_export("export_primitive_i16_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i16_add_three_async` export.
+    pub fn has_export_primitive_i16_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_i16_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i32_add_three` export.
+    pub fn has_export_primitive_i32_add_three(&self) -> bool {
+        self.has_export("export_primitive_i32_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i32_add_three_async` export.
+    pub fn has_export_primitive_i32_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_i32_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i64_add_three` export.
+    pub fn has_export_primitive_i64_add_three(&self) -> bool {
+        self.has_export("export_primitive_i64_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i64_add_three_async` export.
+    pub fn has_export_primitive_i64_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_i64_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i8_add_three` export.
+    pub fn has_export_primitive_i8_add_three(&self) -> bool {
+        self.has_export("export_primitive_i8_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_i8_add_three_async` export.
+    pub fn has_export_primitive_i8_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_i8_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u128_add_three` export.
+    pub fn has_export_primitive_u128_add_three(&self) -> bool {
+        self.has_export("export_primitive_u128_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u16_add_three` export.
+    pub fn has_export_primitive_u16_add_three(&self) -> bool {
+        self.has_export("export_primitive_u16_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u16_add_three_async` export.
+    pub fn has_export_primitive_u16_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_u16_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u32_add_three` export.
+    pub fn has_export_primitive_u32_add_three(&self) -> bool {
+        self.has_export("export_primitive_u32_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u32_add_three_async` export.
+    pub fn has_export_primitive_u32_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_u32_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u64_add_three` export.
+    pub fn has_export_primitive_u64_add_three(&self) -> bool {
+        self.has_export("export_primitive_u64_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u64_add_three_async` export.
+    pub fn has_export_primitive_u64_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_u64_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u8_add_three` export.
+    pub fn has_export_primitive_u8_add_three(&self) -> bool {
+        self.has_export("export_primitive_u8_add_three")
+    }
+
+    /// Returns whether the plugin implements the `export_primitive_u8_add_three_async` export.
+    pub fn has_export_primitive_u8_add_three_async(&self) -> bool {
+        self.has_export("export_primitive_u8_add_three_async")
+    }
+
+    /// Returns whether the plugin implements the `export_print` export.
+    pub fn has_export_print(&self) -> bool {
+        self.has_export("export_print")
+    }
+
+    /// Returns whether the plugin implements the `export_read_file` export.
+    pub fn has_export_read_file(&self) -> bool {
+        self.has_export("export_read_file")
+    }
+
+    /// Returns whether the plugin implements the `export_reset_global_state` export.
+    pub fn has_export_reset_global_state(&self) -> bool {
+        self.has_export("export_reset_global_state")
+    }
+
+    /// Returns whether the plugin implements the `export_serde_adjacently_tagged` export.
+    pub fn has_export_serde_adjacently_tagged(&self) -> bool {
+        self.has_export("export_serde_adjacently_tagged")
+    }
+
+    /// Returns whether the plugin implements the `export_serde_enum` export.
+    pub fn has_export_serde_enum(&self) -> bool {
+        self.has_export("export_serde_enum")
+    }
+
+    /// Returns whether the plugin implements the `export_serde_flatten` export.
+    pub fn has_export_serde_flatten(&self) -> bool {
+        self.has_export("export_serde_flatten")
+    }
+
+    /// Returns whether the plugin implements the `export_serde_internally_tagged` export.
+    pub fn has_export_serde_internally_tagged(&self) -> bool {
+        self.has_export("export_serde_internally_tagged")
+    }
+
+    /// Returns whether the plugin implements the `export_serde_struct` export.
+    pub fn has_export_serde_struct(&self) -> bool {
+        self.has_export("export_serde_struct")
+    }
+
+    /// Returns whether the plugin implements the `export_serde_untagged` export.
+    pub fn has_export_serde_untagged(&self) -> bool {
+        self.has_export("export_serde_untagged")
+    }
+
+    /// Returns whether the plugin implements the `export_string` export.
+    pub fn has_export_string(&self) -> bool {
+        self.has_export("export_string")
+    }
+
+    /// Returns whether the plugin implements the `export_struct_with_options` export.
+    pub fn has_export_struct_with_options(&self) -> bool {
+        self.has_export("export_struct_with_options")
+    }
+
+    /// Returns whether the plugin implements the `export_timestamp` export.
+    pub fn has_export_timestamp(&self) -> bool {
+        self.has_export("export_timestamp")
+    }
+
+    /// Returns whether the plugin implements the `export_uppercase_string` export.
+    pub fn has_export_uppercase_string(&self) -> bool {
+        self.has_export("export_uppercase_string")
+    }
+
+    /// Returns whether the plugin implements the `export_void_function` export.
+    pub fn has_export_void_function(&self) -> bool {
+        self.has_export("export_void_function")
+    }
+
+    /// Returns whether the plugin implements the `export_write_file` export.
+    pub fn has_export_write_file(&self) -> bool {
+        self.has_export("export_write_file")
+    }
+
+    /// Returns whether the plugin implements the `fetch_data` export.
+    pub fn has_fetch_data(&self) -> bool {
+        self.has_export("fetch_data")
+    }
+
+    /// Returns whether the plugin implements the `init` export.
+    pub fn has_init(&self) -> bool {
+        self.has_export("init")
+    }
+
+    /// Returns whether the plugin implements the `reducer_bridge` export.
+    pub fn has_reducer_bridge(&self) -> bool {
+        self.has_export("reducer_bridge")
+    }
+
+    fn has_export(&self, name: &str) -> bool {
+        self.instance
+            .exports
+            .get_function(&format!("__fp_gen_{name}"))
+            .is_ok()
+    }
+
     pub fn export_array_f32(&self, arg: [f32; 3]) -> Result<[f32; 3], InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_array_f32_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_array_f32_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -65,14 +527,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_array_f32".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_array_f64(&self, arg: [f64; 3]) -> Result<[f64; 3], InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_array_f64_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_array_f64_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -85,14 +547,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_array_f64".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_array_i16(&self, arg: [i16; 3]) -> Result<[i16; 3], InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_array_i16_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_array_i16_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -105,14 +567,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_array_i16".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_array_i32(&self, arg: [i32; 3]) -> Result<[i32; 3], InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_array_i32_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_array_i32_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -125,14 +587,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_array_i32".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_array_i8(&self, arg: [i8; 3]) -> Result<[i8; 3], InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_array_i8_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_array_i8_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -145,14 +607,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_array_i8".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_array_u16(&self, arg: [u16; 3]) -> Result<[u16; 3], InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_array_u16_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_array_u16_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -165,14 +627,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_array_u16".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_array_u32(&self, arg: [u32; 3]) -> Result<[u32; 3], InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_array_u32_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_array_u32_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -185,14 +647,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_array_u32".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_array_u8(&self, arg: [u8; 3]) -> Result<[u8; 3], InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_array_u8_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_array_u8_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -205,7 +667,7 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_array_u8".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -214,10 +676,10 @@ impl Runtime {
         arg1: FpPropertyRenaming,
         arg2: u64,
     ) -> Result<FpPropertyRenaming, InvocationError> {
-        let arg1 = serialize_to_vec(&arg1);
+        let arg1 = serialize_for_guest(&self.env, &arg1)?;
         let result = self.export_async_struct_raw(arg1, arg2);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_async_struct_raw(
@@ -236,7 +698,67 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_async_struct".to_owned())
             })?;
         let result = function.call(arg1.to_abi(), arg2.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
+        Ok(result)
+    }
+
+    pub fn export_borrowed_bytes(&self, arg: &[u8]) -> Result<Vec<u8>, InvocationError> {
+        let arg = serialize_for_guest(&self.env, &arg)?;
+        let result = self.export_borrowed_bytes_raw(arg);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_borrowed_bytes_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
+        let arg = export_to_guest_raw(&self.env, arg);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<FatPtr, FatPtr>("__fp_gen_export_borrowed_bytes")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported("__fp_gen_export_borrowed_bytes".to_owned())
+            })?;
+        let result = function.call(arg.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
+        Ok(result)
+    }
+
+    pub fn export_borrowed_string(&self, arg: &str) -> Result<String, InvocationError> {
+        let arg = serialize_for_guest(&self.env, &arg)?;
+        let result = self.export_borrowed_string_raw(arg);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_borrowed_string_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
+        let arg = export_to_guest_raw(&self.env, arg);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<FatPtr, FatPtr>("__fp_gen_export_borrowed_string")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported("__fp_gen_export_borrowed_string".to_owned())
+            })?;
+        let result = function.call(arg.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
+        Ok(result)
+    }
+
+    pub fn export_compact_point(&self, arg: CompactPoint) -> Result<CompactPoint, InvocationError> {
+        let arg = serialize_for_guest(&self.env, &arg)?;
+        let result = self.export_compact_point_raw(arg);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_compact_point_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
+        let arg = export_to_guest_raw(&self.env, arg);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<FatPtr, FatPtr>("__fp_gen_export_compact_point")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported("__fp_gen_export_compact_point".to_owned())
+            })?;
+        let result = function.call(arg.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -244,9 +766,9 @@ impl Runtime {
         &self,
         arg: FpAdjacentlyTagged,
     ) -> Result<FpAdjacentlyTagged, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_fp_adjacently_tagged_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_fp_adjacently_tagged_raw(
@@ -264,7 +786,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -272,9 +794,9 @@ impl Runtime {
         &self,
         arg: FpVariantRenaming,
     ) -> Result<FpVariantRenaming, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_fp_enum_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_fp_enum_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -287,14 +809,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_fp_enum".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_fp_flatten(&self, arg: FpFlatten) -> Result<FpFlatten, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_fp_flatten_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_fp_flatten_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -307,7 +829,7 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_fp_flatten".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -315,9 +837,9 @@ impl Runtime {
         &self,
         arg: FpInternallyTagged,
     ) -> Result<FpInternallyTagged, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_fp_internally_tagged_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_fp_internally_tagged_raw(
@@ -335,7 +857,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -343,9 +865,9 @@ impl Runtime {
         &self,
         arg: FpPropertyRenaming,
     ) -> Result<FpPropertyRenaming, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_fp_struct_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_fp_struct_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -358,14 +880,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_fp_struct".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_fp_untagged(&self, arg: FpUntagged) -> Result<FpUntagged, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_fp_untagged_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_fp_untagged_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -378,7 +900,7 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_fp_untagged".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -386,9 +908,9 @@ impl Runtime {
         &self,
         arg: StructWithGenerics<u64>,
     ) -> Result<StructWithGenerics<u64>, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_generics_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_generics_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -401,13 +923,13 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_generics".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_get_bytes(&self) -> Result<Result<bytes::Bytes, String>, InvocationError> {
         let result = self.export_get_bytes_raw();
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_get_bytes_raw(&self) -> Result<Vec<u8>, InvocationError> {
@@ -419,7 +941,7 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_get_bytes".to_owned())
             })?;
         let result = function.call()?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -427,7 +949,7 @@ impl Runtime {
         &self,
     ) -> Result<Result<serde_bytes::ByteBuf, String>, InvocationError> {
         let result = self.export_get_serde_bytes_raw();
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_get_serde_bytes_raw(&self) -> Result<Vec<u8>, InvocationError> {
@@ -439,14 +961,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_get_serde_bytes".to_owned())
             })?;
         let result = function.call()?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub async fn export_increment_global_state(&self) -> Result<(), InvocationError> {
         let result = self.export_increment_global_state_raw();
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_increment_global_state_raw(&self) -> Result<Vec<u8>, InvocationError> {
@@ -460,7 +982,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call()?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -469,7 +991,7 @@ impl Runtime {
         arg1: i8,
         arg2: String,
     ) -> Result<i64, InvocationError> {
-        let arg2 = serialize_to_vec(&arg2);
+        let arg2 = serialize_for_guest(&self.env, &arg2)?;
         let result = self.export_multiple_primitives_raw(arg1, arg2);
         result
     }
@@ -522,7 +1044,7 @@ impl Runtime {
     ) -> Result<bool, InvocationError> {
         let result = self.export_primitive_bool_negate_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_bool_negate_async_raw(
@@ -541,7 +1063,32 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
+        Ok(result)
+    }
+
+    pub fn export_primitive_char_to_uppercase(&self, arg: char) -> Result<char, InvocationError> {
+        let arg = serialize_for_guest(&self.env, &arg)?;
+        let result = self.export_primitive_char_to_uppercase_raw(arg);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_primitive_char_to_uppercase_raw(
+        &self,
+        arg: Vec<u8>,
+    ) -> Result<Vec<u8>, InvocationError> {
+        let arg = export_to_guest_raw(&self.env, arg);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<FatPtr, FatPtr>("__fp_gen_export_primitive_char_to_uppercase")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported(
+                    "__fp_gen_export_primitive_char_to_uppercase".to_owned(),
+                )
+            })?;
+        let result = function.call(arg.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -572,7 +1119,7 @@ impl Runtime {
     ) -> Result<f32, InvocationError> {
         let result = self.export_primitive_f32_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_f32_add_three_async_raw(
@@ -591,7 +1138,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -646,7 +1193,7 @@ impl Runtime {
     ) -> Result<f64, InvocationError> {
         let result = self.export_primitive_f64_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_f64_add_three_async_raw(
@@ -665,7 +1212,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -693,6 +1240,31 @@ impl Runtime {
         Ok(result)
     }
 
+    pub fn export_primitive_i128_add_three(&self, arg: i128) -> Result<i128, InvocationError> {
+        let arg = serialize_for_guest(&self.env, &arg)?;
+        let result = self.export_primitive_i128_add_three_raw(arg);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_primitive_i128_add_three_raw(
+        &self,
+        arg: Vec<u8>,
+    ) -> Result<Vec<u8>, InvocationError> {
+        let arg = export_to_guest_raw(&self.env, arg);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<FatPtr, FatPtr>("__fp_gen_export_primitive_i128_add_three")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported(
+                    "__fp_gen_export_primitive_i128_add_three".to_owned(),
+                )
+            })?;
+        let result = function.call(arg.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
+        Ok(result)
+    }
+
     pub fn export_primitive_i16_add_three(&self, arg: i16) -> Result<i16, InvocationError> {
         let result = self.export_primitive_i16_add_three_raw(arg);
         result
@@ -720,7 +1292,7 @@ impl Runtime {
     ) -> Result<i16, InvocationError> {
         let result = self.export_primitive_i16_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_i16_add_three_async_raw(
@@ -739,7 +1311,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -770,7 +1342,7 @@ impl Runtime {
     ) -> Result<i32, InvocationError> {
         let result = self.export_primitive_i32_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_i32_add_three_async_raw(
@@ -789,7 +1361,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -820,7 +1392,7 @@ impl Runtime {
     ) -> Result<i64, InvocationError> {
         let result = self.export_primitive_i64_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_i64_add_three_async_raw(
@@ -839,7 +1411,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -870,7 +1442,7 @@ impl Runtime {
     ) -> Result<i8, InvocationError> {
         let result = self.export_primitive_i8_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_i8_add_three_async_raw(
@@ -889,7 +1461,32 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
+        Ok(result)
+    }
+
+    pub fn export_primitive_u128_add_three(&self, arg: u128) -> Result<u128, InvocationError> {
+        let arg = serialize_for_guest(&self.env, &arg)?;
+        let result = self.export_primitive_u128_add_three_raw(arg);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_primitive_u128_add_three_raw(
+        &self,
+        arg: Vec<u8>,
+    ) -> Result<Vec<u8>, InvocationError> {
+        let arg = export_to_guest_raw(&self.env, arg);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<FatPtr, FatPtr>("__fp_gen_export_primitive_u128_add_three")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported(
+                    "__fp_gen_export_primitive_u128_add_three".to_owned(),
+                )
+            })?;
+        let result = function.call(arg.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -920,7 +1517,7 @@ impl Runtime {
     ) -> Result<u16, InvocationError> {
         let result = self.export_primitive_u16_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_u16_add_three_async_raw(
@@ -939,7 +1536,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -970,7 +1567,7 @@ impl Runtime {
     ) -> Result<u32, InvocationError> {
         let result = self.export_primitive_u32_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_u32_add_three_async_raw(
@@ -989,7 +1586,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -1020,7 +1617,7 @@ impl Runtime {
     ) -> Result<u64, InvocationError> {
         let result = self.export_primitive_u64_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_u64_add_three_async_raw(
@@ -1039,7 +1636,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -1070,7 +1667,7 @@ impl Runtime {
     ) -> Result<u8, InvocationError> {
         let result = self.export_primitive_u8_add_three_async_raw(arg);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_primitive_u8_add_three_async_raw(
@@ -1089,14 +1686,62 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
+        Ok(result)
+    }
+
+    pub fn export_print(&self, stdout: String, stderr: String) -> Result<(), InvocationError> {
+        let stdout = serialize_for_guest(&self.env, &stdout)?;
+        let stderr = serialize_for_guest(&self.env, &stderr)?;
+        let result = self.export_print_raw(stdout, stderr);
+        result
+    }
+    pub fn export_print_raw(
+        &self,
+        stdout: Vec<u8>,
+        stderr: Vec<u8>,
+    ) -> Result<(), InvocationError> {
+        let stdout = export_to_guest_raw(&self.env, stdout);
+        let stderr = export_to_guest_raw(&self.env, stderr);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<(FatPtr, FatPtr), ()>("__fp_gen_export_print")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported("__fp_gen_export_print".to_owned())
+            })?;
+        let result = function.call(stdout.to_abi(), stderr.to_abi())?;
+        let result = WasmAbi::from_abi(result);
+        Ok(result)
+    }
+
+    pub fn export_read_file(
+        &self,
+        path: String,
+    ) -> Result<Result<String, String>, InvocationError> {
+        let path = serialize_for_guest(&self.env, &path)?;
+        let result = self.export_read_file_raw(path);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_read_file_raw(&self, path: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
+        let path = export_to_guest_raw(&self.env, path);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<FatPtr, FatPtr>("__fp_gen_export_read_file")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported("__fp_gen_export_read_file".to_owned())
+            })?;
+        let result = function.call(path.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub async fn export_reset_global_state(&self) -> Result<(), InvocationError> {
         let result = self.export_reset_global_state_raw();
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn export_reset_global_state_raw(&self) -> Result<Vec<u8>, InvocationError> {
@@ -1110,7 +1755,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call()?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -1118,9 +1763,9 @@ impl Runtime {
         &self,
         arg: SerdeAdjacentlyTagged,
     ) -> Result<SerdeAdjacentlyTagged, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_serde_adjacently_tagged_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_serde_adjacently_tagged_raw(
@@ -1138,7 +1783,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -1146,9 +1791,9 @@ impl Runtime {
         &self,
         arg: SerdeVariantRenaming,
     ) -> Result<SerdeVariantRenaming, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_serde_enum_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_serde_enum_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1161,14 +1806,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_serde_enum".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_serde_flatten(&self, arg: SerdeFlatten) -> Result<SerdeFlatten, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_serde_flatten_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_serde_flatten_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1181,7 +1826,7 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_serde_flatten".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -1189,9 +1834,9 @@ impl Runtime {
         &self,
         arg: SerdeInternallyTagged,
     ) -> Result<SerdeInternallyTagged, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_serde_internally_tagged_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_serde_internally_tagged_raw(
@@ -1209,7 +1854,7 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -1217,9 +1862,9 @@ impl Runtime {
         &self,
         arg: SerdePropertyRenaming,
     ) -> Result<SerdePropertyRenaming, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_serde_struct_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_serde_struct_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1232,7 +1877,7 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_serde_struct".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -1240,9 +1885,9 @@ impl Runtime {
         &self,
         arg: SerdeUntagged,
     ) -> Result<SerdeUntagged, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_serde_untagged_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_serde_untagged_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1255,14 +1900,14 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_serde_untagged".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_string(&self, arg: String) -> Result<String, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_string_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_string_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1275,7 +1920,7 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_string".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -1283,9 +1928,9 @@ impl Runtime {
         &self,
         arg: StructWithOptions,
     ) -> Result<StructWithOptions, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_struct_with_options_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_struct_with_options_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1300,14 +1945,14 @@ impl Runtime {
                 )
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
     pub fn export_timestamp(&self, arg: MyDateTime) -> Result<MyDateTime, InvocationError> {
-        let arg = serialize_to_vec(&arg);
+        let arg = serialize_for_guest(&self.env, &arg)?;
         let result = self.export_timestamp_raw(arg);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn export_timestamp_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1320,7 +1965,27 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_export_timestamp".to_owned())
             })?;
         let result = function.call(arg.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
+        Ok(result)
+    }
+
+    pub fn export_uppercase_string(&self, arg: String) -> Result<String, InvocationError> {
+        let arg = serialize_for_guest(&self.env, &arg)?;
+        let result = self.export_uppercase_string_raw(arg);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_uppercase_string_raw(&self, arg: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
+        let arg = export_to_guest_raw(&self.env, arg);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<FatPtr, FatPtr>("__fp_gen_export_uppercase_string")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported("__fp_gen_export_uppercase_string".to_owned())
+            })?;
+        let result = function.call(arg.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 
@@ -1341,15 +2006,45 @@ impl Runtime {
         Ok(result)
     }
 
+    pub fn export_write_file(
+        &self,
+        path: String,
+        contents: String,
+    ) -> Result<Result<(), String>, InvocationError> {
+        let path = serialize_for_guest(&self.env, &path)?;
+        let contents = serialize_for_guest(&self.env, &contents)?;
+        let result = self.export_write_file_raw(path, contents);
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
+        result
+    }
+    pub fn export_write_file_raw(
+        &self,
+        path: Vec<u8>,
+        contents: Vec<u8>,
+    ) -> Result<Vec<u8>, InvocationError> {
+        let path = export_to_guest_raw(&self.env, path);
+        let contents = export_to_guest_raw(&self.env, contents);
+        let function = self
+            .instance
+            .exports
+            .get_native_function::<(FatPtr, FatPtr), FatPtr>("__fp_gen_export_write_file")
+            .map_err(|_| {
+                InvocationError::FunctionNotExported("__fp_gen_export_write_file".to_owned())
+            })?;
+        let result = function.call(path.to_abi(), contents.to_abi())?;
+        let result = import_from_guest_raw(&self.env, result)?;
+        Ok(result)
+    }
+
     /// Example how plugin could expose async data-fetching capabilities.
     pub async fn fetch_data(
         &self,
         r#type: String,
     ) -> Result<Result<String, String>, InvocationError> {
-        let r#type = serialize_to_vec(&r#type);
+        let r#type = serialize_for_guest(&self.env, &r#type)?;
         let result = self.fetch_data_raw(r#type);
         let result = result.await;
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub async fn fetch_data_raw(&self, r#type: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1360,7 +2055,7 @@ impl Runtime {
             .get_native_function::<FatPtr, FatPtr>("__fp_gen_fetch_data")
             .map_err(|_| InvocationError::FunctionNotExported("__fp_gen_fetch_data".to_owned()))?;
         let result = function.call(r#type.to_abi())?;
-        let result = ModuleRawFuture::new(self.env.clone(), result).await;
+        let result = ModuleRawFuture::new(self.env.clone(), result).await?;
         Ok(result)
     }
 
@@ -1382,9 +2077,9 @@ impl Runtime {
 
     /// Example how plugin could expose a reducer.
     pub fn reducer_bridge(&self, action: ReduxAction) -> Result<StateUpdate, InvocationError> {
-        let action = serialize_to_vec(&action);
+        let action = serialize_for_guest(&self.env, &action)?;
         let result = self.reducer_bridge_raw(action);
-        let result = result.map(|ref data| deserialize_from_slice(data));
+        let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));
         result
     }
     pub fn reducer_bridge_raw(&self, action: Vec<u8>) -> Result<Vec<u8>, InvocationError> {
@@ -1397,17 +2092,42 @@ impl Runtime {
                 InvocationError::FunctionNotExported("__fp_gen_reducer_bridge".to_owned())
             })?;
         let result = function.call(action.to_abi())?;
-        let result = import_from_guest_raw(&self.env, result);
+        let result = import_from_guest_raw(&self.env, result)?;
         Ok(result)
     }
 }
 
-fn create_import_object(store: &Store, env: &RuntimeInstanceData) -> wasmer::Exports {
+fn create_import_namespaces(
+    store: &Store,
+    env: &RuntimeInstanceData,
+) -> Vec<(&'static str, wasmer::Exports)> {
+    let mut namespaces = Vec::new();
+
     let mut namespace = wasmer::Exports::new();
+    namespace.insert(
+        "__fp_host_invoke_callback",
+        Function::new_native_with_env(store, env.clone(), invoke_callback),
+    );
+    namespace.insert(
+        "__fp_host_release_handle",
+        Function::new_native_with_env(store, env.clone(), release_handle),
+    );
     namespace.insert(
         "__fp_host_resolve_async_value",
         Function::new_native_with_env(store, env.clone(), resolve_async_value),
     );
+    namespace.insert(
+        "__fp_host_retain_handle",
+        Function::new_native_with_env(store, env.clone(), retain_handle),
+    );
+    namespace.insert(
+        "__fp_host_subscribe",
+        Function::new_native_with_env(store, env.clone(), subscribe),
+    );
+    namespace.insert(
+        "__fp_host_unsubscribe",
+        Function::new_native_with_env(store, env.clone(), unsubscribe),
+    );
     namespace.insert(
         "__fp_gen_import_array_f32",
         Function::new_native_with_env(store, env.clone(), _import_array_f32),
@@ -1440,6 +2160,10 @@ fn create_import_object(store: &Store, env: &RuntimeInstanceData) -> wasmer::Exp
         "__fp_gen_import_array_u8",
         Function::new_native_with_env(store, env.clone(), _import_array_u8),
     );
+    namespace.insert(
+        "__fp_gen_import_borrowed_string",
+        Function::new_native_with_env(store, env.clone(), _import_borrowed_string),
+    );
     namespace.insert(
         "__fp_gen_import_explicit_bound_point",
         Function::new_native_with_env(store, env.clone(), _import_explicit_bound_point),
@@ -1644,482 +2368,916 @@ fn create_import_object(store: &Store, env: &RuntimeInstanceData) -> wasmer::Exp
         "__fp_gen_make_http_request",
         Function::new_native_with_env(store, env.clone(), _make_http_request),
     );
-    namespace
+    namespaces.push(("fp", namespace));
+
+    let mut namespace = wasmer::Exports::new();
+    namespace.insert(
+        "__fp_gen_import_uppercase_string",
+        Function::new_native_with_env(store, env.clone(), _import_uppercase_string),
+    );
+    namespaces.push(("text", namespace));
+
+    namespaces
+}
+
+/// Configures the WASI environment in which a plugin runs.
+///
+/// By default, plugins run without any arguments, environment variables or
+/// access to the file system, while their output to stdout and stderr is
+/// forwarded to that of the host process.
+#[derive(Debug, Default)]
+pub struct WasiConfig {
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    mapped_dirs: Vec<MappedDir>,
+    stdout: Option<OutputCallback>,
+    stderr: Option<OutputCallback>,
+}
+
+impl WasiConfig {
+    /// Returns a new config for an empty WASI environment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a command-line argument for the plugin.
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Adds an environment variable for the plugin.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Makes the directory at `host_path` available to the plugin as `alias`,
+    /// with read and write access.
+    pub fn map_dir(
+        mut self,
+        alias: impl Into<String>,
+        host_path: impl Into<std::path::PathBuf>,
+    ) -> Self {
+        self.mapped_dirs.push(MappedDir {
+            alias: alias.into(),
+            host_path: host_path.into(),
+            read_only: false,
+        });
+        self
+    }
+
+    /// Makes the directory at `host_path` available to the plugin as `alias`,
+    /// with read access only.
+    pub fn map_dir_read_only(
+        mut self,
+        alias: impl Into<String>,
+        host_path: impl Into<std::path::PathBuf>,
+    ) -> Self {
+        self.mapped_dirs.push(MappedDir {
+            alias: alias.into(),
+            host_path: host_path.into(),
+            read_only: true,
+        });
+        self
+    }
+
+    /// Passes everything the plugin writes to stdout on to the given callback.
+    pub fn stdout(mut self, callback: impl FnMut(&[u8]) + Send + Sync + 'static) -> Self {
+        self.stdout = Some(OutputCallback(Box::new(callback)));
+        self
+    }
+
+    /// Passes everything the plugin writes to stderr on to the given callback.
+    pub fn stderr(mut self, callback: impl FnMut(&[u8]) + Send + Sync + 'static) -> Self {
+        self.stderr = Some(OutputCallback(Box::new(callback)));
+        self
+    }
+
+    fn finalize(self) -> Result<wasmer_wasi::WasiEnv, wasmer_wasi::WasiStateCreationError> {
+        let mut state = wasmer_wasi::WasiState::new("fp");
+        state.args(&self.args).envs(self.envs);
+        let read_only_dirs = self
+            .mapped_dirs
+            .iter()
+            .filter(|dir| dir.read_only)
+            .map(|dir| dir.host_path.clone())
+            .collect::<Vec<_>>();
+        state.set_fs(Box::new(HostFileSystem {
+            read_only_dirs: std::sync::Arc::new(read_only_dirs),
+        }));
+        for dir in self.mapped_dirs {
+            state.preopen(|preopen| {
+                preopen
+                    .directory(&dir.host_path)
+                    .alias(&dir.alias)
+                    .read(true)
+                    .write(!dir.read_only)
+                    .create(!dir.read_only)
+            })?;
+        }
+        if let Some(stdout) = self.stdout {
+            state.stdout(Box::new(stdout));
+        }
+        if let Some(stderr) = self.stderr {
+            state.stderr(Box::new(stderr));
+        }
+        state.finalize()
+    }
+}
+
+/// Collects the output of a plugin in memory.
+///
+/// Use [OutputBuffer::writer()] to create a callback for
+/// [WasiConfig::stdout()] or [WasiConfig::stderr()].
+#[derive(Clone, Debug, Default)]
+pub struct OutputBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl OutputBuffer {
+    /// Returns a new, empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a callback that appends everything passed to it to the buffer.
+    pub fn writer(&self) -> impl FnMut(&[u8]) + Send + Sync + 'static {
+        let buffer = self.0.clone();
+        move |bytes| buffer.lock().unwrap().extend_from_slice(bytes)
+    }
+
+    /// Returns the output collected so far, and clears the buffer.
+    pub fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+#[derive(Debug)]
+struct MappedDir {
+    alias: String,
+    host_path: std::path::PathBuf,
+    read_only: bool,
+}
+
+/// Gives plugins access to the file system of the host, except that nothing
+/// can be created, changed or removed inside read-only directories.
+///
+/// The rights of the pre-opened directories are not enough to guarantee this,
+/// because `wasmer-wasi` doesn't check them when a plugin creates a file.
+#[derive(Debug)]
+struct HostFileSystem {
+    read_only_dirs: std::sync::Arc<Vec<std::path::PathBuf>>,
 }
 
-pub fn _import_array_f32(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<[f32; 3]>(env, arg);
+impl wasmer_vfs::FileSystem for HostFileSystem {
+    fn read_dir(&self, path: &std::path::Path) -> wasmer_vfs::Result<wasmer_vfs::ReadDir> {
+        wasmer_vfs::FileSystem::read_dir(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn create_dir(&self, path: &std::path::Path) -> wasmer_vfs::Result<()> {
+        check_writable(&self.read_only_dirs, path)?;
+        wasmer_vfs::FileSystem::create_dir(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn remove_dir(&self, path: &std::path::Path) -> wasmer_vfs::Result<()> {
+        check_writable(&self.read_only_dirs, path)?;
+        wasmer_vfs::FileSystem::remove_dir(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn rename(&self, from: &std::path::Path, to: &std::path::Path) -> wasmer_vfs::Result<()> {
+        check_writable(&self.read_only_dirs, from)?;
+        check_writable(&self.read_only_dirs, to)?;
+        wasmer_vfs::FileSystem::rename(&wasmer_vfs::host_fs::FileSystem, from, to)
+    }
+
+    fn metadata(&self, path: &std::path::Path) -> wasmer_vfs::Result<wasmer_vfs::Metadata> {
+        wasmer_vfs::FileSystem::metadata(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn remove_file(&self, path: &std::path::Path) -> wasmer_vfs::Result<()> {
+        check_writable(&self.read_only_dirs, path)?;
+        wasmer_vfs::FileSystem::remove_file(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn new_open_options(&self) -> wasmer_vfs::OpenOptions {
+        wasmer_vfs::OpenOptions::new(Box::new(HostFileOpener {
+            read_only_dirs: self.read_only_dirs.clone(),
+        }))
+    }
+}
+
+struct HostFileOpener {
+    read_only_dirs: std::sync::Arc<Vec<std::path::PathBuf>>,
+}
+
+impl wasmer_vfs::FileOpener for HostFileOpener {
+    fn open(
+        &mut self,
+        path: &std::path::Path,
+        conf: &wasmer_vfs::OpenOptionsConfig,
+    ) -> wasmer_vfs::Result<Box<dyn wasmer_vfs::VirtualFile>> {
+        if conf.write() || conf.append() || conf.truncate() || conf.create() || conf.create_new() {
+            check_writable(&self.read_only_dirs, path)?;
+        }
+        wasmer_vfs::FileOpener::open(&mut wasmer_vfs::host_fs::FileOpener, path, conf)
+    }
+}
+
+fn check_writable(
+    read_only_dirs: &[std::path::PathBuf],
+    path: &std::path::Path,
+) -> wasmer_vfs::Result<()> {
+    if read_only_dirs.iter().any(|dir| path.starts_with(dir)) {
+        Err(wasmer_vfs::FsError::PermissionDenied)
+    } else {
+        Ok(())
+    }
+}
+
+/// Output stream that passes everything written to it on to a callback.
+struct OutputCallback(Box<dyn FnMut(&[u8]) + Send + Sync>);
+
+impl std::fmt::Debug for OutputCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OutputCallback")
+    }
+}
+
+impl std::io::Read for OutputCallback {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(0)
+    }
+}
+
+impl std::io::Write for OutputCallback {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        (self.0)(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::io::Seek for OutputCallback {
+    fn seek(&mut self, _pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "cannot seek in an output stream",
+        ))
+    }
+}
+
+impl wasmer_wasi::VirtualFile for OutputCallback {
+    fn last_accessed(&self) -> u64 {
+        0
+    }
+
+    fn last_modified(&self) -> u64 {
+        0
+    }
+
+    fn created_time(&self) -> u64 {
+        0
+    }
+
+    fn size(&self) -> u64 {
+        0
+    }
+
+    fn set_len(&mut self, _new_size: u64) -> Result<(), wasmer_wasi::FsError> {
+        Err(wasmer_wasi::FsError::PermissionDenied)
+    }
+
+    fn unlink(&mut self) -> Result<(), wasmer_wasi::FsError> {
+        Ok(())
+    }
+
+    fn bytes_available(&self) -> Result<usize, wasmer_wasi::FsError> {
+        Ok(0)
+    }
+}
+
+pub fn _import_array_f32(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<[f32; 3]>(env, arg)?;
     export_to_guest(env, &super::import_array_f32(arg))
 }
 
-pub fn _import_array_f64(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<[f64; 3]>(env, arg);
+pub fn _import_array_f64(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<[f64; 3]>(env, arg)?;
     export_to_guest(env, &super::import_array_f64(arg))
 }
 
-pub fn _import_array_i16(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<[i16; 3]>(env, arg);
+pub fn _import_array_i16(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<[i16; 3]>(env, arg)?;
     export_to_guest(env, &super::import_array_i16(arg))
 }
 
-pub fn _import_array_i32(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<[i32; 3]>(env, arg);
+pub fn _import_array_i32(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<[i32; 3]>(env, arg)?;
     export_to_guest(env, &super::import_array_i32(arg))
 }
 
-pub fn _import_array_i8(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<[i8; 3]>(env, arg);
+pub fn _import_array_i8(env: &RuntimeInstanceData, arg: FatPtr) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<[i8; 3]>(env, arg)?;
     export_to_guest(env, &super::import_array_i8(arg))
 }
 
-pub fn _import_array_u16(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<[u16; 3]>(env, arg);
+pub fn _import_array_u16(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<[u16; 3]>(env, arg)?;
     export_to_guest(env, &super::import_array_u16(arg))
 }
 
-pub fn _import_array_u32(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<[u32; 3]>(env, arg);
+pub fn _import_array_u32(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<[u32; 3]>(env, arg)?;
     export_to_guest(env, &super::import_array_u32(arg))
 }
 
-pub fn _import_array_u8(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<[u8; 3]>(env, arg);
+pub fn _import_array_u8(env: &RuntimeInstanceData, arg: FatPtr) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<[u8; 3]>(env, arg)?;
     export_to_guest(env, &super::import_array_u8(arg))
 }
 
-pub fn _import_explicit_bound_point(env: &RuntimeInstanceData, arg: FatPtr) {
-    let arg = import_from_guest::<ExplicitBoundPoint<u64>>(env, arg);
-    super::import_explicit_bound_point(arg)
+pub fn _import_borrowed_string(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<String>(env, arg)?;
+    export_to_guest(env, &super::import_borrowed_string(arg))
 }
 
-pub fn _import_fp_adjacently_tagged(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<FpAdjacentlyTagged>(env, arg);
+pub fn _import_explicit_bound_point(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<(), InvocationError> {
+    let arg = import_from_guest::<ExplicitBoundPoint<u64>>(env, arg)?;
+    super::import_explicit_bound_point(arg);
+    Ok(())
+}
+
+pub fn _import_fp_adjacently_tagged(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<FpAdjacentlyTagged>(env, arg)?;
     export_to_guest(env, &super::import_fp_adjacently_tagged(arg))
 }
 
-pub fn _import_fp_enum(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<FpVariantRenaming>(env, arg);
+pub fn _import_fp_enum(env: &RuntimeInstanceData, arg: FatPtr) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<FpVariantRenaming>(env, arg)?;
     export_to_guest(env, &super::import_fp_enum(arg))
 }
 
-pub fn _import_fp_flatten(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<FpFlatten>(env, arg);
+pub fn _import_fp_flatten(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<FpFlatten>(env, arg)?;
     export_to_guest(env, &super::import_fp_flatten(arg))
 }
 
-pub fn _import_fp_internally_tagged(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<FpInternallyTagged>(env, arg);
+pub fn _import_fp_internally_tagged(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<FpInternallyTagged>(env, arg)?;
     export_to_guest(env, &super::import_fp_internally_tagged(arg))
 }
 
-pub fn _import_fp_struct(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<FpPropertyRenaming>(env, arg);
+pub fn _import_fp_struct(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<FpPropertyRenaming>(env, arg)?;
     export_to_guest(env, &super::import_fp_struct(arg))
 }
 
-pub fn _import_fp_untagged(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<FpUntagged>(env, arg);
+pub fn _import_fp_untagged(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<FpUntagged>(env, arg)?;
     export_to_guest(env, &super::import_fp_untagged(arg))
 }
 
-pub fn _import_generics(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<StructWithGenerics<u64>>(env, arg);
+pub fn _import_generics(env: &RuntimeInstanceData, arg: FatPtr) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<StructWithGenerics<u64>>(env, arg)?;
     export_to_guest(env, &super::import_generics(arg))
 }
 
-pub fn _import_get_bytes(env: &RuntimeInstanceData) -> FatPtr {
+pub fn _import_get_bytes(env: &RuntimeInstanceData) -> Result<FatPtr, InvocationError> {
     export_to_guest(env, &super::import_get_bytes())
 }
 
-pub fn _import_get_serde_bytes(env: &RuntimeInstanceData) -> FatPtr {
+pub fn _import_get_serde_bytes(env: &RuntimeInstanceData) -> Result<FatPtr, InvocationError> {
     export_to_guest(env, &super::import_get_serde_bytes())
 }
 
-pub fn _import_increment_global_state(env: &RuntimeInstanceData) -> FatPtr {
+pub fn _import_increment_global_state(
+    env: &RuntimeInstanceData,
+) -> Result<FatPtr, InvocationError> {
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_increment_global_state().await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_increment_global_state` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_multiple_primitives(
     env: &RuntimeInstanceData,
     arg1: <i8 as WasmAbi>::AbiType,
     arg2: FatPtr,
-) -> <i64 as WasmAbi>::AbiType {
+) -> Result<<i64 as WasmAbi>::AbiType, InvocationError> {
     let arg1 = WasmAbi::from_abi(arg1);
-    let arg2 = import_from_guest::<String>(env, arg2);
-    super::import_multiple_primitives(arg1, arg2).to_abi()
+    let arg2 = import_from_guest::<String>(env, arg2)?;
+    Ok(super::import_multiple_primitives(arg1, arg2).to_abi())
 }
 
 pub fn _import_primitive_bool_negate(
     env: &RuntimeInstanceData,
     arg: <bool as WasmAbi>::AbiType,
-) -> <bool as WasmAbi>::AbiType {
+) -> Result<<bool as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_bool_negate(arg).to_abi()
+    Ok(super::import_primitive_bool_negate(arg).to_abi())
 }
 
 pub fn _import_primitive_bool_negate_async(
     env: &RuntimeInstanceData,
     arg: <bool as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_bool_negate_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_bool_negate_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_f32_add_one(
     env: &RuntimeInstanceData,
     arg: <f32 as WasmAbi>::AbiType,
-) -> <f32 as WasmAbi>::AbiType {
+) -> Result<<f32 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_f32_add_one(arg).to_abi()
+    Ok(super::import_primitive_f32_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_f32_add_one_async(
     env: &RuntimeInstanceData,
     arg: <f32 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_f32_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_f32_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_f32_add_one_wasmer2(
     env: &RuntimeInstanceData,
     arg: FatPtr,
-) -> <f32 as WasmAbi>::AbiType {
-    let arg = import_from_guest::<[f32; 1]>(env, arg);
-    super::import_primitive_f32_add_one_wasmer2(arg).to_abi()
+) -> Result<<f32 as WasmAbi>::AbiType, InvocationError> {
+    let arg = import_from_guest::<[f32; 1]>(env, arg)?;
+    Ok(super::import_primitive_f32_add_one_wasmer2(arg).to_abi())
 }
 
 pub fn _import_primitive_f64_add_one(
     env: &RuntimeInstanceData,
     arg: <f64 as WasmAbi>::AbiType,
-) -> <f64 as WasmAbi>::AbiType {
+) -> Result<<f64 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_f64_add_one(arg).to_abi()
+    Ok(super::import_primitive_f64_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_f64_add_one_async(
     env: &RuntimeInstanceData,
     arg: <f64 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_f64_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_f64_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_f64_add_one_wasmer2(
     env: &RuntimeInstanceData,
     arg: FatPtr,
-) -> <f64 as WasmAbi>::AbiType {
-    let arg = import_from_guest::<[f64; 1]>(env, arg);
-    super::import_primitive_f64_add_one_wasmer2(arg).to_abi()
+) -> Result<<f64 as WasmAbi>::AbiType, InvocationError> {
+    let arg = import_from_guest::<[f64; 1]>(env, arg)?;
+    Ok(super::import_primitive_f64_add_one_wasmer2(arg).to_abi())
 }
 
 pub fn _import_primitive_i16_add_one(
     env: &RuntimeInstanceData,
     arg: <i16 as WasmAbi>::AbiType,
-) -> <i16 as WasmAbi>::AbiType {
+) -> Result<<i16 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_i16_add_one(arg).to_abi()
+    Ok(super::import_primitive_i16_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_i16_add_one_async(
     env: &RuntimeInstanceData,
     arg: <i16 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_i16_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_i16_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_i32_add_one(
     env: &RuntimeInstanceData,
     arg: <i32 as WasmAbi>::AbiType,
-) -> <i32 as WasmAbi>::AbiType {
+) -> Result<<i32 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_i32_add_one(arg).to_abi()
+    Ok(super::import_primitive_i32_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_i32_add_one_async(
     env: &RuntimeInstanceData,
     arg: <i32 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_i32_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_i32_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_i64_add_one(
     env: &RuntimeInstanceData,
     arg: <i64 as WasmAbi>::AbiType,
-) -> <i64 as WasmAbi>::AbiType {
+) -> Result<<i64 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_i64_add_one(arg).to_abi()
+    Ok(super::import_primitive_i64_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_i64_add_one_async(
     env: &RuntimeInstanceData,
     arg: <i64 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_i64_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_i64_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_i8_add_one(
     env: &RuntimeInstanceData,
     arg: <i8 as WasmAbi>::AbiType,
-) -> <i8 as WasmAbi>::AbiType {
+) -> Result<<i8 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_i8_add_one(arg).to_abi()
+    Ok(super::import_primitive_i8_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_i8_add_one_async(
     env: &RuntimeInstanceData,
     arg: <i8 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_i8_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_i8_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_u16_add_one(
     env: &RuntimeInstanceData,
     arg: <u16 as WasmAbi>::AbiType,
-) -> <u16 as WasmAbi>::AbiType {
+) -> Result<<u16 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_u16_add_one(arg).to_abi()
+    Ok(super::import_primitive_u16_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_u16_add_one_async(
     env: &RuntimeInstanceData,
     arg: <u16 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_u16_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_u16_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_u32_add_one(
     env: &RuntimeInstanceData,
     arg: <u32 as WasmAbi>::AbiType,
-) -> <u32 as WasmAbi>::AbiType {
+) -> Result<<u32 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_u32_add_one(arg).to_abi()
+    Ok(super::import_primitive_u32_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_u32_add_one_async(
     env: &RuntimeInstanceData,
     arg: <u32 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_u32_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_u32_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_u64_add_one(
     env: &RuntimeInstanceData,
     arg: <u64 as WasmAbi>::AbiType,
-) -> <u64 as WasmAbi>::AbiType {
+) -> Result<<u64 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_u64_add_one(arg).to_abi()
+    Ok(super::import_primitive_u64_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_u64_add_one_async(
     env: &RuntimeInstanceData,
     arg: <u64 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_u64_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_u64_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
 pub fn _import_primitive_u8_add_one(
     env: &RuntimeInstanceData,
     arg: <u8 as WasmAbi>::AbiType,
-) -> <u8 as WasmAbi>::AbiType {
+) -> Result<<u8 as WasmAbi>::AbiType, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
-    super::import_primitive_u8_add_one(arg).to_abi()
+    Ok(super::import_primitive_u8_add_one(arg).to_abi())
 }
 
 pub fn _import_primitive_u8_add_one_async(
     env: &RuntimeInstanceData,
     arg: <u8 as WasmAbi>::AbiType,
-) -> FatPtr {
+) -> Result<FatPtr, InvocationError> {
     let arg = WasmAbi::from_abi(arg);
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_primitive_u8_add_one_async(arg).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `import_primitive_u8_add_one_async` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
-pub fn _import_reset_global_state(env: &RuntimeInstanceData) -> FatPtr {
+pub fn _import_reset_global_state(env: &RuntimeInstanceData) -> Result<FatPtr, InvocationError> {
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::import_reset_global_state().await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!(
+                "Could not pass the result of `import_reset_global_state` to the plugin: {error}"
+            )
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
 
-pub fn _import_serde_adjacently_tagged(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<SerdeAdjacentlyTagged>(env, arg);
+pub fn _import_serde_adjacently_tagged(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<SerdeAdjacentlyTagged>(env, arg)?;
     export_to_guest(env, &super::import_serde_adjacently_tagged(arg))
 }
 
-pub fn _import_serde_enum(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<SerdeVariantRenaming>(env, arg);
+pub fn _import_serde_enum(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<SerdeVariantRenaming>(env, arg)?;
     export_to_guest(env, &super::import_serde_enum(arg))
 }
 
-pub fn _import_serde_flatten(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<SerdeFlatten>(env, arg);
+pub fn _import_serde_flatten(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<SerdeFlatten>(env, arg)?;
     export_to_guest(env, &super::import_serde_flatten(arg))
 }
 
-pub fn _import_serde_internally_tagged(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<SerdeInternallyTagged>(env, arg);
+pub fn _import_serde_internally_tagged(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<SerdeInternallyTagged>(env, arg)?;
     export_to_guest(env, &super::import_serde_internally_tagged(arg))
 }
 
-pub fn _import_serde_struct(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<SerdePropertyRenaming>(env, arg);
+pub fn _import_serde_struct(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<SerdePropertyRenaming>(env, arg)?;
     export_to_guest(env, &super::import_serde_struct(arg))
 }
 
-pub fn _import_serde_untagged(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<SerdeUntagged>(env, arg);
+pub fn _import_serde_untagged(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<SerdeUntagged>(env, arg)?;
     export_to_guest(env, &super::import_serde_untagged(arg))
 }
 
-pub fn _import_string(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<String>(env, arg);
+pub fn _import_string(env: &RuntimeInstanceData, arg: FatPtr) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<String>(env, arg)?;
     export_to_guest(env, &super::import_string(arg))
 }
 
-pub fn _import_struct_with_options(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<StructWithOptions>(env, arg);
+pub fn _import_struct_with_options(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<StructWithOptions>(env, arg)?;
     export_to_guest(env, &super::import_struct_with_options(arg))
 }
 
-pub fn _import_timestamp(env: &RuntimeInstanceData, arg: FatPtr) -> FatPtr {
-    let arg = import_from_guest::<MyDateTime>(env, arg);
+pub fn _import_timestamp(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<MyDateTime>(env, arg)?;
     export_to_guest(env, &super::import_timestamp(arg))
 }
 
-pub fn _import_void_function(env: &RuntimeInstanceData) {
-    super::import_void_function()
+pub fn _import_uppercase_string(
+    env: &RuntimeInstanceData,
+    arg: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let arg = import_from_guest::<String>(env, arg)?;
+    export_to_guest(env, &super::text::import_uppercase_string(arg))
+}
+
+pub fn _import_void_function(env: &RuntimeInstanceData) -> Result<(), InvocationError> {
+    super::import_void_function();
+    Ok(())
 }
 
-pub fn _import_void_function_empty_result(env: &RuntimeInstanceData) -> FatPtr {
+pub fn _import_void_function_empty_result(
+    env: &RuntimeInstanceData,
+) -> Result<FatPtr, InvocationError> {
     export_to_guest(env, &super::import_void_function_empty_result())
 }
 
-pub fn _import_void_function_empty_return(env: &RuntimeInstanceData) {
-    super::import_void_function_empty_return()
+pub fn _import_void_function_empty_return(
+    env: &RuntimeInstanceData,
+) -> Result<(), InvocationError> {
+    super::import_void_function_empty_return();
+    Ok(())
 }
 
-pub fn _log(env: &RuntimeInstanceData, message: FatPtr) {
-    let message = import_from_guest::<String>(env, message);
-    super::log(message)
+pub fn _log(env: &RuntimeInstanceData, message: FatPtr) -> Result<(), InvocationError> {
+    let message = import_from_guest::<String>(env, message)?;
+    super::log(message);
+    Ok(())
 }
 
-pub fn _make_http_request(env: &RuntimeInstanceData, request: FatPtr) -> FatPtr {
-    let request = import_from_guest::<Request>(env, request);
+pub fn _make_http_request(
+    env: &RuntimeInstanceData,
+    request: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let request = import_from_guest::<Request>(env, request)?;
     let env = env.clone();
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {
         let result = super::make_http_request(request).await;
-        let result_ptr = export_to_guest(&env, &result);
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {
+            panic!("Could not pass the result of `make_http_request` to the plugin: {error}")
+        });
         env.guest_resolve_async_value(async_ptr, result_ptr);
     });
-    async_ptr
+    Ok(async_ptr)
 }
@@ -0,0 +1,1451 @@
+// ============================================= //
+// WebAssembly runtime for TypeScript            //
+//                                               //
+// This file is generated. PLEASE DO NOT MODIFY. //
+// ============================================= //
+// deno-lint-ignore-file no-explicit-any no-unused-vars
+
+import { encode, decode } from "@msgpack/msgpack";
+
+import type * as types from "./types";
+
+type FatPtr = bigint;
+
+export type Imports = {
+    importArrayF32: (arg: Float32Array) => Float32Array;
+    importArrayF64: (arg: Float64Array) => Float64Array;
+    importArrayI16: (arg: Int16Array) => Int16Array;
+    importArrayI32: (arg: Int32Array) => Int32Array;
+    importArrayI8: (arg: Int8Array) => Int8Array;
+    importArrayU16: (arg: Uint16Array) => Uint16Array;
+    importArrayU32: (arg: Uint32Array) => Uint32Array;
+    importArrayU8: (arg: Uint8Array) => Uint8Array;
+    importBorrowedString: (arg: string) => string;
+    importExplicitBoundPoint: (arg: types.ExplicitBoundPoint<number>) => void;
+    importFpAdjacentlyTagged: (arg: types.FpAdjacentlyTagged) => types.FpAdjacentlyTagged;
+    importFpEnum: (arg: types.FpVariantRenaming) => types.FpVariantRenaming;
+    importFpFlatten: (arg: types.FpFlatten) => types.FpFlatten;
+    importFpInternallyTagged: (arg: types.FpInternallyTagged) => types.FpInternallyTagged;
+    importFpStruct: (arg: types.FpPropertyRenaming) => types.FpPropertyRenaming;
+    importFpUntagged: (arg: types.FpUntagged) => types.FpUntagged;
+    importGenerics: (arg: types.StructWithGenerics<number>) => types.StructWithGenerics<number>;
+    importGetBytes: () => types.Result<Uint8Array, string>;
+    importGetSerdeBytes: () => types.Result<Uint8Array, string>;
+    importIncrementGlobalState: () => Promise<void>;
+    importMultiplePrimitives: (arg1: number, arg2: string) => bigint;
+    importPrimitiveBoolNegate: (arg: boolean) => boolean;
+    importPrimitiveBoolNegateAsync: (arg: boolean) => Promise<boolean>;
+    importPrimitiveF32AddOne: (arg: number) => number;
+    importPrimitiveF32AddOneAsync: (arg: number) => Promise<number>;
+    importPrimitiveF32AddOneWasmer2: (arg: Float32Array) => number;
+    importPrimitiveF64AddOne: (arg: number) => number;
+    importPrimitiveF64AddOneAsync: (arg: number) => Promise<number>;
+    importPrimitiveF64AddOneWasmer2: (arg: Float64Array) => number;
+    importPrimitiveI16AddOne: (arg: number) => number;
+    importPrimitiveI16AddOneAsync: (arg: number) => Promise<number>;
+    importPrimitiveI32AddOne: (arg: number) => number;
+    importPrimitiveI32AddOneAsync: (arg: number) => Promise<number>;
+    importPrimitiveI64AddOne: (arg: bigint) => bigint;
+    importPrimitiveI64AddOneAsync: (arg: bigint) => Promise<number>;
+    importPrimitiveI8AddOne: (arg: number) => number;
+    importPrimitiveI8AddOneAsync: (arg: number) => Promise<number>;
+    importPrimitiveU16AddOne: (arg: number) => number;
+    importPrimitiveU16AddOneAsync: (arg: number) => Promise<number>;
+    importPrimitiveU32AddOne: (arg: number) => number;
+    importPrimitiveU32AddOneAsync: (arg: number) => Promise<number>;
+    importPrimitiveU64AddOne: (arg: bigint) => bigint;
+    importPrimitiveU64AddOneAsync: (arg: bigint) => Promise<number>;
+    importPrimitiveU8AddOne: (arg: number) => number;
+    importPrimitiveU8AddOneAsync: (arg: number) => Promise<number>;
+    importResetGlobalState: () => Promise<void>;
+    importSerdeAdjacentlyTagged: (arg: types.SerdeAdjacentlyTagged) => types.SerdeAdjacentlyTagged;
+    importSerdeEnum: (arg: types.SerdeVariantRenaming) => types.SerdeVariantRenaming;
+    importSerdeFlatten: (arg: types.SerdeFlatten) => types.SerdeFlatten;
+    importSerdeInternallyTagged: (arg: types.SerdeInternallyTagged) => types.SerdeInternallyTagged;
+    importSerdeStruct: (arg: types.SerdePropertyRenaming) => types.SerdePropertyRenaming;
+    importSerdeUntagged: (arg: types.SerdeUntagged) => types.SerdeUntagged;
+    importString: (arg: string) => string;
+    importStructWithOptions: (arg: types.StructWithOptions) => types.StructWithOptions;
+    importTimestamp: (arg: types.MyDateTime) => types.MyDateTime;
+    importVoidFunction: () => void;
+    importVoidFunctionEmptyResult: () => types.Result<void, number>;
+    importVoidFunctionEmptyReturn: () => void;
+    log: (message: string) => void;
+    makeHttpRequest: (request: types.Request) => Promise<types.HttpResult>;
+} & TextImports;
+
+export type TextImports = {
+    importUppercaseString: (arg: string) => string;
+};
+
+export type Exports = {
+    exportArrayF32?: (arg: Float32Array) => Float32Array;
+    exportArrayF64?: (arg: Float64Array) => Float64Array;
+    exportArrayI16?: (arg: Int16Array) => Int16Array;
+    exportArrayI32?: (arg: Int32Array) => Int32Array;
+    exportArrayI8?: (arg: Int8Array) => Int8Array;
+    exportArrayU16?: (arg: Uint16Array) => Uint16Array;
+    exportArrayU32?: (arg: Uint32Array) => Uint32Array;
+    exportArrayU8?: (arg: Uint8Array) => Uint8Array;
+    exportAsyncStruct?: (arg1: types.FpPropertyRenaming, arg2: bigint) => Promise<types.FpPropertyRenaming>;
+    exportBorrowedBytes?: (arg: Array<number>) => Array<number>;
+    exportBorrowedString?: (arg: string) => string;
+    exportCompactPoint?: (arg: types.CompactPoint) => types.CompactPoint;
+    exportFpAdjacentlyTagged?: (arg: types.FpAdjacentlyTagged) => types.FpAdjacentlyTagged;
+    exportFpEnum?: (arg: types.FpVariantRenaming) => types.FpVariantRenaming;
+    exportFpFlatten?: (arg: types.FpFlatten) => types.FpFlatten;
+    exportFpInternallyTagged?: (arg: types.FpInternallyTagged) => types.FpInternallyTagged;
+    exportFpStruct?: (arg: types.FpPropertyRenaming) => types.FpPropertyRenaming;
+    exportFpUntagged?: (arg: types.FpUntagged) => types.FpUntagged;
+    exportGenerics?: (arg: types.StructWithGenerics<number>) => types.StructWithGenerics<number>;
+    exportGetBytes?: () => types.Result<Uint8Array, string>;
+    exportGetSerdeBytes?: () => types.Result<Uint8Array, string>;
+    exportIncrementGlobalState?: () => Promise<void>;
+    exportMultiplePrimitives?: (arg1: number, arg2: string) => bigint;
+    exportPrimitiveBoolNegate?: (arg: boolean) => boolean;
+    exportPrimitiveBoolNegateAsync?: (arg: boolean) => Promise<boolean>;
+    exportPrimitiveCharToUppercase?: (arg: string) => string;
+    exportPrimitiveF32AddThree?: (arg: number) => number;
+    exportPrimitiveF32AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveF32AddThreeWasmer2?: (arg: number) => number;
+    exportPrimitiveF64AddThree?: (arg: number) => number;
+    exportPrimitiveF64AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveF64AddThreeWasmer2?: (arg: number) => number;
+    exportPrimitiveI128AddThree?: (arg: bigint) => bigint;
+    exportPrimitiveI16AddThree?: (arg: number) => number;
+    exportPrimitiveI16AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveI32AddThree?: (arg: number) => number;
+    exportPrimitiveI32AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveI64AddThree?: (arg: bigint) => bigint;
+    exportPrimitiveI64AddThreeAsync?: (arg: bigint) => Promise<number>;
+    exportPrimitiveI8AddThree?: (arg: number) => number;
+    exportPrimitiveI8AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveU128AddThree?: (arg: bigint) => bigint;
+    exportPrimitiveU16AddThree?: (arg: number) => number;
+    exportPrimitiveU16AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveU32AddThree?: (arg: number) => number;
+    exportPrimitiveU32AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveU64AddThree?: (arg: bigint) => bigint;
+    exportPrimitiveU64AddThreeAsync?: (arg: bigint) => Promise<number>;
+    exportPrimitiveU8AddThree?: (arg: number) => number;
+    exportPrimitiveU8AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrint?: (stdout: string, stderr: string) => void;
+    exportReadFile?: (path: string) => types.Result<string, string>;
+    exportResetGlobalState?: () => Promise<void>;
+    exportSerdeAdjacentlyTagged?: (arg: types.SerdeAdjacentlyTagged) => types.SerdeAdjacentlyTagged;
+    exportSerdeEnum?: (arg: types.SerdeVariantRenaming) => types.SerdeVariantRenaming;
+    exportSerdeFlatten?: (arg: types.SerdeFlatten) => types.SerdeFlatten;
+    exportSerdeInternallyTagged?: (arg: types.SerdeInternallyTagged) => types.SerdeInternallyTagged;
+    exportSerdeStruct?: (arg: types.SerdePropertyRenaming) => types.SerdePropertyRenaming;
+    exportSerdeUntagged?: (arg: types.SerdeUntagged) => types.SerdeUntagged;
+    exportString?: (arg: string) => string;
+    exportStructWithOptions?: (arg: types.StructWithOptions) => types.StructWithOptions;
+    exportTimestamp?: (arg: types.MyDateTime) => types.MyDateTime;
+    exportUppercaseString?: (arg: string) => string;
+    exportVoidFunction?: () => void;
+    exportWriteFile?: (path: string, contents: string) => types.Result<void, string>;
+    fetchData?: (rType: string) => Promise<types.Result<string, string>>;
+    init?: () => void;
+    reducerBridge?: (action: types.ReduxAction) => types.StateUpdate;
+};
+
+/**
+ * Represents an unrecoverable error in the FP runtime.
+ *
+ * After this, your only recourse is to create a new runtime, probably with a different WASM plugin.
+ */
+export class FPRuntimeError extends Error {
+    constructor(message: string) {
+        super(message);
+    }
+}
+
+/**
+ * Identifies a specific revision of a protocol.
+ */
+export type ProtocolVersion = {
+    name: string;
+    version: string;
+    hash: string;
+};
+
+/**
+ * The version of the protocol this runtime was generated for.
+ */
+export const PROTOCOL_VERSION: ProtocolVersion = {
+    name: "example-protocol",
+    version: "0.1.0",
+    hash: "5c1bf67516549a9d",
+};
+
+/**
+ * Creates a runtime for executing the given plugin.
+ *
+ * @param source The response for fetching the WASM plugin.
+ * @param importFunctions The host functions that may be imported by the plugin.
+ * @returns The functions that may be exported by the plugin.
+ */
+export async function createRuntime(
+    source: Response | Promise<Response>,
+    importFunctions: Imports
+): Promise<Exports> {
+    const promises = new Map<FatPtr, ((result: FatPtr) => void) | FatPtr>();
+
+    function createAsyncValue(): FatPtr {
+        const len = 12; // std::mem::size_of::<AsyncValue>()
+        const fatPtr = malloc(len);
+        const [ptr] = fromFatPtr(fatPtr);
+        const buffer = new Uint8Array(memory.buffer, ptr, len);
+        buffer.fill(0);
+        return fatPtr;
+    }
+
+    function interpretSign(num: number, cap: number) {
+        if (num < cap) {
+            return num;
+        } else {
+            return num - (cap << 1);
+        }
+    }
+
+    function interpretBigSign(num: bigint, cap: bigint) {
+        if (num < cap) {
+            return num;
+        } else {
+            return num - (cap << 1n);
+        }
+    }
+
+    function parseObject<T>(fatPtr: FatPtr, normalize: Normalizer = identity): T {
+        checkWireFormat(fatPtr);
+        const [ptr, len] = fromFatPtr(fatPtr);
+        const buffer = new Uint8Array(memory.buffer, ptr, len);
+        // Without creating a copy of the memory, we risk corruption of any
+        // embedded `Uint8Array` objects returned from `decode()` after `free()`
+        // has been called :(
+        const copy = new Uint8Array(len);
+        copy.set(buffer);
+        free(fatPtr);
+        const object = normalize(decode(copy)) as T;
+        return object;
+    }
+
+    function promiseFromPtr(ptr: FatPtr): Promise<FatPtr> {
+        const resultPtr = promises.get(ptr);
+        if (resultPtr) {
+            if (typeof resultPtr === "function") {
+                throw new FPRuntimeError("Already created promise for this value");
+            }
+
+            promises.delete(ptr);
+            return Promise.resolve(resultPtr);
+        } else {
+            return new Promise((resolve) => {
+                promises.set(ptr, resolve as (result: FatPtr) => void);
+            });
+        }
+    }
+
+    function resolvePromise(asyncValuePtr: FatPtr, resultPtr: FatPtr) {
+        const resolve = promises.get(asyncValuePtr);
+        if (resolve) {
+            if (typeof resolve !== "function") {
+                throw new FPRuntimeError("Tried to resolve invalid promise");
+            }
+
+            promises.delete(asyncValuePtr);
+            resolve(resultPtr);
+        } else {
+            promises.set(asyncValuePtr, resultPtr);
+        }
+    }
+
+    function serializeObject<T>(object: T, denormalize: Normalizer = identity): FatPtr {
+        return exportToMemory(encode(denormalize(object)));
+    }
+
+    function exportToMemory(serialized: Uint8Array): FatPtr {
+        const fatPtr = malloc(serialized.length);
+        const [ptr, len] = fromFatPtr(fatPtr);
+        const buffer = new Uint8Array(memory.buffer, ptr, len);
+        buffer.set(serialized);
+        return fatPtr;
+    }
+
+    function importFromMemory(fatPtr: FatPtr): Uint8Array {
+        checkWireFormat(fatPtr);
+        const [ptr, len] = fromFatPtr(fatPtr);
+        const buffer = new Uint8Array(memory.buffer, ptr, len);
+        const copy = new Uint8Array(len);
+        copy.set(buffer);
+        free(fatPtr);
+        return copy;
+    }
+
+    const { instance } = await WebAssembly.instantiateStreaming(source, {
+        fp: {
+            __fp_gen_import_array_f32: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<Float32Array>(arg_ptr);
+                return serializeObject(importFunctions.importArrayF32(arg));
+            },
+            __fp_gen_import_array_f64: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<Float64Array>(arg_ptr);
+                return serializeObject(importFunctions.importArrayF64(arg));
+            },
+            __fp_gen_import_array_i16: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<Int16Array>(arg_ptr);
+                return serializeObject(importFunctions.importArrayI16(arg));
+            },
+            __fp_gen_import_array_i32: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<Int32Array>(arg_ptr);
+                return serializeObject(importFunctions.importArrayI32(arg));
+            },
+            __fp_gen_import_array_i8: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<Int8Array>(arg_ptr);
+                return serializeObject(importFunctions.importArrayI8(arg));
+            },
+            __fp_gen_import_array_u16: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<Uint16Array>(arg_ptr);
+                return serializeObject(importFunctions.importArrayU16(arg));
+            },
+            __fp_gen_import_array_u32: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<Uint32Array>(arg_ptr);
+                return serializeObject(importFunctions.importArrayU32(arg));
+            },
+            __fp_gen_import_array_u8: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<Uint8Array>(arg_ptr);
+                return serializeObject(importFunctions.importArrayU8(arg));
+            },
+            __fp_gen_import_borrowed_string: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<string>(arg_ptr);
+                return serializeObject(importFunctions.importBorrowedString(arg));
+            },
+            __fp_gen_import_explicit_bound_point: (arg_ptr: FatPtr) => {
+                const arg = parseObject<types.ExplicitBoundPoint<number>>(arg_ptr);
+                importFunctions.importExplicitBoundPoint(arg);
+            },
+            __fp_gen_import_fp_adjacently_tagged: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.FpAdjacentlyTagged>(arg_ptr);
+                return serializeObject(importFunctions.importFpAdjacentlyTagged(arg));
+            },
+            __fp_gen_import_fp_enum: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.FpVariantRenaming>(arg_ptr);
+                return serializeObject(importFunctions.importFpEnum(arg));
+            },
+            __fp_gen_import_fp_flatten: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.FpFlatten>(arg_ptr);
+                return serializeObject(importFunctions.importFpFlatten(arg));
+            },
+            __fp_gen_import_fp_internally_tagged: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.FpInternallyTagged>(arg_ptr);
+                return serializeObject(importFunctions.importFpInternallyTagged(arg));
+            },
+            __fp_gen_import_fp_struct: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.FpPropertyRenaming>(arg_ptr);
+                return serializeObject(importFunctions.importFpStruct(arg));
+            },
+            __fp_gen_import_fp_untagged: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.FpUntagged>(arg_ptr);
+                return serializeObject(importFunctions.importFpUntagged(arg));
+            },
+            __fp_gen_import_generics: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.StructWithGenerics<number>>(arg_ptr, normalizeStructWithGenerics(identity));
+                return serializeObject(importFunctions.importGenerics(arg), denormalizeStructWithGenerics(identity));
+            },
+            __fp_gen_import_get_bytes: (): FatPtr => {
+                return serializeObject(importFunctions.importGetBytes());
+            },
+            __fp_gen_import_get_serde_bytes: (): FatPtr => {
+                return serializeObject(importFunctions.importGetSerdeBytes());
+            },
+            __fp_gen_import_increment_global_state: (): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importIncrementGlobalState()
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_increment_global_state"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_multiple_primitives: (arg1: number, arg2_ptr: FatPtr): bigint => {
+                const arg2 = parseObject<string>(arg2_ptr);
+                return interpretBigSign(importFunctions.importMultiplePrimitives(arg1, arg2), 9223372036854775808n);
+            },
+            __fp_gen_import_primitive_bool_negate: (arg: boolean): boolean => {
+                return !!importFunctions.importPrimitiveBoolNegate(arg);
+            },
+            __fp_gen_import_primitive_bool_negate_async: (arg: boolean): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveBoolNegateAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_bool_negate_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_f32_add_one: (arg: number): number => {
+                return importFunctions.importPrimitiveF32AddOne(arg);
+            },
+            __fp_gen_import_primitive_f32_add_one_async: (arg: number): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveF32AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_f32_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_f32_add_one_wasmer2: (arg_ptr: FatPtr): number => {
+                const arg = parseObject<Float32Array>(arg_ptr);
+                return importFunctions.importPrimitiveF32AddOneWasmer2(arg);
+            },
+            __fp_gen_import_primitive_f64_add_one: (arg: number): number => {
+                return importFunctions.importPrimitiveF64AddOne(arg);
+            },
+            __fp_gen_import_primitive_f64_add_one_async: (arg: number): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveF64AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_f64_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_f64_add_one_wasmer2: (arg_ptr: FatPtr): number => {
+                const arg = parseObject<Float64Array>(arg_ptr);
+                return importFunctions.importPrimitiveF64AddOneWasmer2(arg);
+            },
+            __fp_gen_import_primitive_i16_add_one: (arg: number): number => {
+                return interpretSign(importFunctions.importPrimitiveI16AddOne(arg), 32768);
+            },
+            __fp_gen_import_primitive_i16_add_one_async: (arg: number): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveI16AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_i16_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_i32_add_one: (arg: number): number => {
+                return interpretSign(importFunctions.importPrimitiveI32AddOne(arg), 2147483648);
+            },
+            __fp_gen_import_primitive_i32_add_one_async: (arg: number): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveI32AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_i32_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_i64_add_one: (arg: bigint): bigint => {
+                return interpretBigSign(importFunctions.importPrimitiveI64AddOne(arg), 9223372036854775808n);
+            },
+            __fp_gen_import_primitive_i64_add_one_async: (arg: bigint): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveI64AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_i64_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_i8_add_one: (arg: number): number => {
+                return interpretSign(importFunctions.importPrimitiveI8AddOne(arg), 128);
+            },
+            __fp_gen_import_primitive_i8_add_one_async: (arg: number): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveI8AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_i8_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_u16_add_one: (arg: number): number => {
+                return importFunctions.importPrimitiveU16AddOne(arg);
+            },
+            __fp_gen_import_primitive_u16_add_one_async: (arg: number): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveU16AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_u16_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_u32_add_one: (arg: number): number => {
+                return importFunctions.importPrimitiveU32AddOne(arg);
+            },
+            __fp_gen_import_primitive_u32_add_one_async: (arg: number): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveU32AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_u32_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_u64_add_one: (arg: bigint): bigint => {
+                return importFunctions.importPrimitiveU64AddOne(arg);
+            },
+            __fp_gen_import_primitive_u64_add_one_async: (arg: bigint): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveU64AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_u64_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_primitive_u8_add_one: (arg: number): number => {
+                return importFunctions.importPrimitiveU8AddOne(arg);
+            },
+            __fp_gen_import_primitive_u8_add_one_async: (arg: number): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importPrimitiveU8AddOneAsync(arg)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_primitive_u8_add_one_async"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_reset_global_state: (): FatPtr => {
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.importResetGlobalState()
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "import_reset_global_state"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_gen_import_serde_adjacently_tagged: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.SerdeAdjacentlyTagged>(arg_ptr);
+                return serializeObject(importFunctions.importSerdeAdjacentlyTagged(arg));
+            },
+            __fp_gen_import_serde_enum: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.SerdeVariantRenaming>(arg_ptr);
+                return serializeObject(importFunctions.importSerdeEnum(arg));
+            },
+            __fp_gen_import_serde_flatten: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.SerdeFlatten>(arg_ptr);
+                return serializeObject(importFunctions.importSerdeFlatten(arg));
+            },
+            __fp_gen_import_serde_internally_tagged: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.SerdeInternallyTagged>(arg_ptr);
+                return serializeObject(importFunctions.importSerdeInternallyTagged(arg));
+            },
+            __fp_gen_import_serde_struct: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.SerdePropertyRenaming>(arg_ptr);
+                return serializeObject(importFunctions.importSerdeStruct(arg));
+            },
+            __fp_gen_import_serde_untagged: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.SerdeUntagged>(arg_ptr);
+                return serializeObject(importFunctions.importSerdeUntagged(arg));
+            },
+            __fp_gen_import_string: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<string>(arg_ptr);
+                return serializeObject(importFunctions.importString(arg));
+            },
+            __fp_gen_import_struct_with_options: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.StructWithOptions>(arg_ptr);
+                return serializeObject(importFunctions.importStructWithOptions(arg));
+            },
+            __fp_gen_import_timestamp: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<types.MyDateTime>(arg_ptr, normalizeMyDateTime);
+                return serializeObject(importFunctions.importTimestamp(arg), denormalizeMyDateTime);
+            },
+            __fp_gen_import_void_function: () => {
+                importFunctions.importVoidFunction();
+            },
+            __fp_gen_import_void_function_empty_result: (): FatPtr => {
+                return serializeObject(importFunctions.importVoidFunctionEmptyResult());
+            },
+            __fp_gen_import_void_function_empty_return: () => {
+                importFunctions.importVoidFunctionEmptyReturn();
+            },
+            __fp_gen_log: (message_ptr: FatPtr) => {
+                const message = parseObject<string>(message_ptr);
+                importFunctions.log(message);
+            },
+            __fp_gen_make_http_request: (request_ptr: FatPtr): FatPtr => {
+                const request = parseObject<types.Request>(request_ptr, normalizeRequest);
+                const _async_result_ptr = createAsyncValue();
+                importFunctions.makeHttpRequest(request)
+                    .then((result) => {
+                        resolveFuture(_async_result_ptr, serializeObject(result));
+                    })
+                    .catch((error) => {
+                        console.error(
+                            'Unrecoverable exception trying to call async host function "make_http_request"',
+                            error
+                        );
+                    });
+                return _async_result_ptr;
+            },
+            __fp_host_resolve_async_value: resolvePromise,
+        },
+        text: {
+            __fp_gen_import_uppercase_string: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<string>(arg_ptr);
+                return serializeObject(importFunctions.importUppercaseString(arg));
+            },
+        },
+    });
+
+    const getExport = <T>(name: string): T => {
+        const exp = instance.exports[name];
+        if (!exp) {
+            throw new FPRuntimeError(`Plugin did not export expected symbol: "${name}"`);
+        }
+        return exp as unknown as T;
+    };
+
+    const memory = getExport<WebAssembly.Memory>("memory");
+    const malloc = getExport<(len: number) => FatPtr>("__fp_malloc");
+    const free = getExport<(ptr: FatPtr) => void>("__fp_free");
+
+    const getProtocolVersion = instance.exports.__fp_protocol_version as unknown as
+        | (() => FatPtr)
+        | undefined;
+    if (getProtocolVersion) {
+        checkProtocolVersion(parseObject<ProtocolVersion>(getProtocolVersion()));
+    } else {
+        console.warn(
+            "Plugin does not declare its protocol version, so it cannot be verified to be " +
+                `compatible with ${formatProtocolVersion(PROTOCOL_VERSION)}`
+        );
+    }
+    const resolveFuture = getExport<(asyncValuePtr: FatPtr, resultPtr: FatPtr) => void>("__fp_guest_resolve_async_value");
+
+    return {
+        exportArrayF32: (() => {
+            const export_fn = instance.exports.__fp_gen_export_array_f32 as any;
+            if (!export_fn) return;
+
+            return (arg: Float32Array) => {
+                const arg_ptr = serializeObject(Array.from(arg));
+                return parseObject<Float32Array>(export_fn(arg_ptr));
+            };
+        })(),
+        exportArrayF64: (() => {
+            const export_fn = instance.exports.__fp_gen_export_array_f64 as any;
+            if (!export_fn) return;
+
+            return (arg: Float64Array) => {
+                const arg_ptr = serializeObject(Array.from(arg));
+                return parseObject<Float64Array>(export_fn(arg_ptr));
+            };
+        })(),
+        exportArrayI16: (() => {
+            const export_fn = instance.exports.__fp_gen_export_array_i16 as any;
+            if (!export_fn) return;
+
+            return (arg: Int16Array) => {
+                const arg_ptr = serializeObject(Array.from(arg));
+                return parseObject<Int16Array>(export_fn(arg_ptr));
+            };
+        })(),
+        exportArrayI32: (() => {
+            const export_fn = instance.exports.__fp_gen_export_array_i32 as any;
+            if (!export_fn) return;
+
+            return (arg: Int32Array) => {
+                const arg_ptr = serializeObject(Array.from(arg));
+                return parseObject<Int32Array>(export_fn(arg_ptr));
+            };
+        })(),
+        exportArrayI8: (() => {
+            const export_fn = instance.exports.__fp_gen_export_array_i8 as any;
+            if (!export_fn) return;
+
+            return (arg: Int8Array) => {
+                const arg_ptr = serializeObject(Array.from(arg));
+                return parseObject<Int8Array>(export_fn(arg_ptr));
+            };
+        })(),
+        exportArrayU16: (() => {
+            const export_fn = instance.exports.__fp_gen_export_array_u16 as any;
+            if (!export_fn) return;
+
+            return (arg: Uint16Array) => {
+                const arg_ptr = serializeObject(Array.from(arg));
+                return parseObject<Uint16Array>(export_fn(arg_ptr));
+            };
+        })(),
+        exportArrayU32: (() => {
+            const export_fn = instance.exports.__fp_gen_export_array_u32 as any;
+            if (!export_fn) return;
+
+            return (arg: Uint32Array) => {
+                const arg_ptr = serializeObject(Array.from(arg));
+                return parseObject<Uint32Array>(export_fn(arg_ptr));
+            };
+        })(),
+        exportArrayU8: (() => {
+            const export_fn = instance.exports.__fp_gen_export_array_u8 as any;
+            if (!export_fn) return;
+
+            return (arg: Uint8Array) => {
+                const arg_ptr = serializeObject(Array.from(arg));
+                return parseObject<Uint8Array>(export_fn(arg_ptr));
+            };
+        })(),
+        exportAsyncStruct: (() => {
+            const export_fn = instance.exports.__fp_gen_export_async_struct as any;
+            if (!export_fn) return;
+
+            return (arg1: types.FpPropertyRenaming, arg2: bigint) => {
+                const arg1_ptr = serializeObject(arg1);
+                return promiseFromPtr(export_fn(arg1_ptr, arg2)).then((ptr) => parseObject<types.FpPropertyRenaming>(ptr));
+            };
+        })(),
+        exportBorrowedBytes: (() => {
+            const export_fn = instance.exports.__fp_gen_export_borrowed_bytes as any;
+            if (!export_fn) return;
+
+            return (arg: Array<number>) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<Array<number>>(export_fn(arg_ptr));
+            };
+        })(),
+        exportBorrowedString: (() => {
+            const export_fn = instance.exports.__fp_gen_export_borrowed_string as any;
+            if (!export_fn) return;
+
+            return (arg: string) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<string>(export_fn(arg_ptr));
+            };
+        })(),
+        exportCompactPoint: (() => {
+            const export_fn = instance.exports.__fp_gen_export_compact_point as any;
+            if (!export_fn) return;
+
+            return (arg: types.CompactPoint) => {
+                const arg_ptr = serializeObject(arg, denormalizeCompactPoint);
+                return parseObject<types.CompactPoint>(export_fn(arg_ptr), normalizeCompactPoint);
+            };
+        })(),
+        exportFpAdjacentlyTagged: (() => {
+            const export_fn = instance.exports.__fp_gen_export_fp_adjacently_tagged as any;
+            if (!export_fn) return;
+
+            return (arg: types.FpAdjacentlyTagged) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.FpAdjacentlyTagged>(export_fn(arg_ptr));
+            };
+        })(),
+        exportFpEnum: (() => {
+            const export_fn = instance.exports.__fp_gen_export_fp_enum as any;
+            if (!export_fn) return;
+
+            return (arg: types.FpVariantRenaming) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.FpVariantRenaming>(export_fn(arg_ptr));
+            };
+        })(),
+        exportFpFlatten: (() => {
+            const export_fn = instance.exports.__fp_gen_export_fp_flatten as any;
+            if (!export_fn) return;
+
+            return (arg: types.FpFlatten) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.FpFlatten>(export_fn(arg_ptr));
+            };
+        })(),
+        exportFpInternallyTagged: (() => {
+            const export_fn = instance.exports.__fp_gen_export_fp_internally_tagged as any;
+            if (!export_fn) return;
+
+            return (arg: types.FpInternallyTagged) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.FpInternallyTagged>(export_fn(arg_ptr));
+            };
+        })(),
+        exportFpStruct: (() => {
+            const export_fn = instance.exports.__fp_gen_export_fp_struct as any;
+            if (!export_fn) return;
+
+            return (arg: types.FpPropertyRenaming) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.FpPropertyRenaming>(export_fn(arg_ptr));
+            };
+        })(),
+        exportFpUntagged: (() => {
+            const export_fn = instance.exports.__fp_gen_export_fp_untagged as any;
+            if (!export_fn) return;
+
+            return (arg: types.FpUntagged) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.FpUntagged>(export_fn(arg_ptr));
+            };
+        })(),
+        exportGenerics: (() => {
+            const export_fn = instance.exports.__fp_gen_export_generics as any;
+            if (!export_fn) return;
+
+            return (arg: types.StructWithGenerics<number>) => {
+                const arg_ptr = serializeObject(arg, denormalizeStructWithGenerics(identity));
+                return parseObject<types.StructWithGenerics<number>>(export_fn(arg_ptr), normalizeStructWithGenerics(identity));
+            };
+        })(),
+        exportGetBytes: (() => {
+            const export_fn = instance.exports.__fp_gen_export_get_bytes as any;
+            if (!export_fn) return;
+
+            return () => parseObject<types.Result<Uint8Array, string>>(export_fn(), normalizeResult(normalizeBytes, identity));
+        })(),
+        exportGetSerdeBytes: (() => {
+            const export_fn = instance.exports.__fp_gen_export_get_serde_bytes as any;
+            if (!export_fn) return;
+
+            return () => parseObject<types.Result<Uint8Array, string>>(export_fn(), normalizeResult(normalizeByteBuf, identity));
+        })(),
+        exportIncrementGlobalState: (() => {
+            const export_fn = instance.exports.__fp_gen_export_increment_global_state as any;
+            if (!export_fn) return;
+
+            return () => promiseFromPtr(export_fn()).then((ptr) => parseObject<void>(ptr));
+        })(),
+        exportMultiplePrimitives: (() => {
+            const export_fn = instance.exports.__fp_gen_export_multiple_primitives as any;
+            if (!export_fn) return;
+
+            return (arg1: number, arg2: string) => {
+                const arg2_ptr = serializeObject(arg2);
+                return interpretBigSign(export_fn(arg1, arg2_ptr), 9223372036854775808n);
+            };
+        })(),
+        exportPrimitiveBoolNegate: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_bool_negate as any;
+            if (!export_fn) return;
+
+            return (arg: boolean) => !!export_fn(arg);
+        })(),
+        exportPrimitiveBoolNegateAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_bool_negate_async as any;
+            if (!export_fn) return;
+
+            return (arg: boolean) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<boolean>(ptr));
+        })(),
+        exportPrimitiveCharToUppercase: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_char_to_uppercase as any;
+            if (!export_fn) return;
+
+            return (arg: string) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<string>(export_fn(arg_ptr));
+            };
+        })(),
+        exportPrimitiveF32AddThree: instance.exports.__fp_gen_export_primitive_f32_add_three as any,
+        exportPrimitiveF32AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_f32_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveF32AddThreeWasmer2: instance.exports.__fp_gen_export_primitive_f32_add_three_wasmer2 as any,
+        exportPrimitiveF64AddThree: instance.exports.__fp_gen_export_primitive_f64_add_three as any,
+        exportPrimitiveF64AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_f64_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveF64AddThreeWasmer2: instance.exports.__fp_gen_export_primitive_f64_add_three_wasmer2 as any,
+        exportPrimitiveI128AddThree: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i128_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: bigint) => {
+                const arg_ptr = serializeObject(arg, denormalizeInt128);
+                return parseObject<bigint>(export_fn(arg_ptr), normalizeI128);
+            };
+        })(),
+        exportPrimitiveI16AddThree: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i16_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: number) => interpretSign(export_fn(arg), 32768);
+        })(),
+        exportPrimitiveI16AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i16_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveI32AddThree: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i32_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: number) => interpretSign(export_fn(arg), 2147483648);
+        })(),
+        exportPrimitiveI32AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i32_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveI64AddThree: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i64_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: bigint) => interpretBigSign(export_fn(arg), 9223372036854775808n);
+        })(),
+        exportPrimitiveI64AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i64_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: bigint) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveI8AddThree: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i8_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: number) => interpretSign(export_fn(arg), 128);
+        })(),
+        exportPrimitiveI8AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i8_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveU128AddThree: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_u128_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: bigint) => {
+                const arg_ptr = serializeObject(arg, denormalizeInt128);
+                return parseObject<bigint>(export_fn(arg_ptr), normalizeU128);
+            };
+        })(),
+        exportPrimitiveU16AddThree: instance.exports.__fp_gen_export_primitive_u16_add_three as any,
+        exportPrimitiveU16AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_u16_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveU32AddThree: instance.exports.__fp_gen_export_primitive_u32_add_three as any,
+        exportPrimitiveU32AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_u32_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveU64AddThree: instance.exports.__fp_gen_export_primitive_u64_add_three as any,
+        exportPrimitiveU64AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_u64_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: bigint) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrimitiveU8AddThree: instance.exports.__fp_gen_export_primitive_u8_add_three as any,
+        exportPrimitiveU8AddThreeAsync: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_u8_add_three_async as any;
+            if (!export_fn) return;
+
+            return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
+        })(),
+        exportPrint: (() => {
+            const export_fn = instance.exports.__fp_gen_export_print as any;
+            if (!export_fn) return;
+
+            return (stdout: string, stderr: string) => {
+                const stdout_ptr = serializeObject(stdout);
+                const stderr_ptr = serializeObject(stderr);
+                export_fn(stdout_ptr, stderr_ptr);
+            };
+        })(),
+        exportReadFile: (() => {
+            const export_fn = instance.exports.__fp_gen_export_read_file as any;
+            if (!export_fn) return;
+
+            return (path: string) => {
+                const path_ptr = serializeObject(path);
+                return parseObject<types.Result<string, string>>(export_fn(path_ptr));
+            };
+        })(),
+        exportResetGlobalState: (() => {
+            const export_fn = instance.exports.__fp_gen_export_reset_global_state as any;
+            if (!export_fn) return;
+
+            return () => promiseFromPtr(export_fn()).then((ptr) => parseObject<void>(ptr));
+        })(),
+        exportSerdeAdjacentlyTagged: (() => {
+            const export_fn = instance.exports.__fp_gen_export_serde_adjacently_tagged as any;
+            if (!export_fn) return;
+
+            return (arg: types.SerdeAdjacentlyTagged) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.SerdeAdjacentlyTagged>(export_fn(arg_ptr));
+            };
+        })(),
+        exportSerdeEnum: (() => {
+            const export_fn = instance.exports.__fp_gen_export_serde_enum as any;
+            if (!export_fn) return;
+
+            return (arg: types.SerdeVariantRenaming) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.SerdeVariantRenaming>(export_fn(arg_ptr));
+            };
+        })(),
+        exportSerdeFlatten: (() => {
+            const export_fn = instance.exports.__fp_gen_export_serde_flatten as any;
+            if (!export_fn) return;
+
+            return (arg: types.SerdeFlatten) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.SerdeFlatten>(export_fn(arg_ptr));
+            };
+        })(),
+        exportSerdeInternallyTagged: (() => {
+            const export_fn = instance.exports.__fp_gen_export_serde_internally_tagged as any;
+            if (!export_fn) return;
+
+            return (arg: types.SerdeInternallyTagged) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.SerdeInternallyTagged>(export_fn(arg_ptr));
+            };
+        })(),
+        exportSerdeStruct: (() => {
+            const export_fn = instance.exports.__fp_gen_export_serde_struct as any;
+            if (!export_fn) return;
+
+            return (arg: types.SerdePropertyRenaming) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.SerdePropertyRenaming>(export_fn(arg_ptr));
+            };
+        })(),
+        exportSerdeUntagged: (() => {
+            const export_fn = instance.exports.__fp_gen_export_serde_untagged as any;
+            if (!export_fn) return;
+
+            return (arg: types.SerdeUntagged) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.SerdeUntagged>(export_fn(arg_ptr));
+            };
+        })(),
+        exportString: (() => {
+            const export_fn = instance.exports.__fp_gen_export_string as any;
+            if (!export_fn) return;
+
+            return (arg: string) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<string>(export_fn(arg_ptr));
+            };
+        })(),
+        exportStructWithOptions: (() => {
+            const export_fn = instance.exports.__fp_gen_export_struct_with_options as any;
+            if (!export_fn) return;
+
+            return (arg: types.StructWithOptions) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<types.StructWithOptions>(export_fn(arg_ptr));
+            };
+        })(),
+        exportTimestamp: (() => {
+            const export_fn = instance.exports.__fp_gen_export_timestamp as any;
+            if (!export_fn) return;
+
+            return (arg: types.MyDateTime) => {
+                const arg_ptr = serializeObject(arg, denormalizeMyDateTime);
+                return parseObject<types.MyDateTime>(export_fn(arg_ptr), normalizeMyDateTime);
+            };
+        })(),
+        exportUppercaseString: (() => {
+            const export_fn = instance.exports.__fp_gen_export_uppercase_string as any;
+            if (!export_fn) return;
+
+            return (arg: string) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<string>(export_fn(arg_ptr));
+            };
+        })(),
+        exportVoidFunction: instance.exports.__fp_gen_export_void_function as any,
+        exportWriteFile: (() => {
+            const export_fn = instance.exports.__fp_gen_export_write_file as any;
+            if (!export_fn) return;
+
+            return (path: string, contents: string) => {
+                const path_ptr = serializeObject(path);
+                const contents_ptr = serializeObject(contents);
+                return parseObject<types.Result<void, string>>(export_fn(path_ptr, contents_ptr));
+            };
+        })(),
+        fetchData: (() => {
+            const export_fn = instance.exports.__fp_gen_fetch_data as any;
+            if (!export_fn) return;
+
+            return (rType: string) => {
+                const type_ptr = serializeObject(rType);
+                return promiseFromPtr(export_fn(type_ptr)).then((ptr) => parseObject<types.Result<string, string>>(ptr));
+            };
+        })(),
+        init: instance.exports.__fp_gen_init as any,
+        reducerBridge: (() => {
+            const export_fn = instance.exports.__fp_gen_reducer_bridge as any;
+            if (!export_fn) return;
+
+            return (action: types.ReduxAction) => {
+                const action_ptr = serializeObject(action);
+                return parseObject<types.StateUpdate>(export_fn(action_ptr));
+            };
+        })(),
+    };
+}
+
+/**
+ * Returns the names of all the exports that are implemented by the plugin.
+ *
+ * @param exports The functions returned by `createRuntime()`.
+ */
+export function implementedExports(exports: Exports): Array<keyof Exports> {
+    return (Object.keys(exports) as Array<keyof Exports>).filter(
+        (name) => typeof exports[name] === "function"
+    );
+}
+
+function fromFatPtr(fatPtr: FatPtr): [ptr: number, len: number] {
+    return [Number(fatPtr >> 32n), Number(fatPtr & 0xff_ffffn)];
+}
+
+/**
+ * Verifies the data the fat pointer refers to is encoded as MessagePack, which
+ * is the only wire format supported by the TypeScript runtime.
+ */
+function checkWireFormat(fatPtr: FatPtr) {
+    const wireFormat = Number((fatPtr >> 24n) & 0xffn);
+    if (wireFormat !== 0) {
+        throw new FPRuntimeError(`Plugin used an unsupported wire format: ${wireFormat}`);
+    }
+}
+
+function toFatPtr(ptr: number, len: number): FatPtr {
+    return (BigInt(ptr) << 32n) | BigInt(len);
+}
+
+function checkProtocolVersion(found: ProtocolVersion) {
+    if (found.hash === PROTOCOL_VERSION.hash) {
+        return;
+    }
+
+    const foundVersion = formatProtocolVersion(found);
+    const expectedVersion = formatProtocolVersion(PROTOCOL_VERSION);
+    const compatible =
+        found.name && PROTOCOL_VERSION.name
+            ? found.name === PROTOCOL_VERSION.name &&
+              isSemverCompatible(found.version, PROTOCOL_VERSION.version)
+            : undefined;
+    if (compatible === undefined) {
+        console.warn(
+            `Plugin was built against protocol ${foundVersion}, which differs from ${expectedVersion}, and cannot be verified to be compatible`
+        );
+    } else if (compatible) {
+        console.warn(
+            `Plugin was built against protocol ${foundVersion}, which differs from ${expectedVersion}, but should be compatible`
+        );
+    } else {
+        throw new FPRuntimeError(
+            `Plugin was built against protocol ${foundVersion}, which is incompatible with ${expectedVersion}`
+        );
+    }
+}
+
+function formatProtocolVersion({ name, version, hash }: ProtocolVersion): string {
+    return `${name} ${version} (${hash})`;
+}
+
+/**
+ * Versions are compatible if their left-most non-zero component is the same.
+ * Returns `undefined` if either is not a valid semantic version.
+ */
+function isSemverCompatible(a: string, b: string): boolean | undefined {
+    const parse = (version: string) => {
+        const match = /^(\d+)\.(\d+)\.(\d+)([-+].*)?$/.exec(version);
+        return match ? [Number(match[1]), Number(match[2]), Number(match[3])] : undefined;
+    };
+
+    const [aVersion, bVersion] = [parse(a), parse(b)];
+    if (!aVersion || !bVersion) {
+        return undefined;
+    }
+
+    const [aMajor, aMinor, aPatch] = aVersion;
+    const [bMajor, bMinor, bPatch] = bVersion;
+    if (aMajor !== bMajor) {
+        return false;
+    } else if (aMajor > 0) {
+        return true;
+    } else if (aMinor !== bMinor) {
+        return false;
+    } else {
+        return aMinor > 0 || aPatch === bPatch;
+    }
+}
+
+/**
+ * Converts a decoded value into the representation used by the runtime, or
+ * back again before encoding.
+ *
+ * Normalizers are needed because integers that don't require 64 bits are
+ * encoded more compactly, and are therefore decoded as `number`, and because
+ * some types are represented differently at runtime than they are encoded.
+ */
+type Normalizer = (value: any) => any;
+
+const identity: Normalizer = (value) => value;
+
+function normalizeInt64(value: any): any {
+    return typeof value === "number" ? BigInt(value) : value;
+}
+
+function normalizeOption(normalize: Normalizer): Normalizer {
+    return (value) => (value === null || value === undefined ? value : normalize(value));
+}
+
+function normalizeList(normalize: Normalizer): Normalizer {
+    return (value) => value.map((item: any) => normalize(item));
+}
+
+function normalizeMap(normalize: Normalizer): Normalizer {
+    return (value) =>
+        Object.fromEntries(Object.entries(value).map(([key, item]) => [key, normalize(item)]));
+}
+
+function normalizeTuple(normalizers: Array<Normalizer>): Normalizer {
+    return (value) => value.map((item: any, index: number) => normalizers[index](item));
+}
+
+function normalizeFields(normalizers: Record<string, Normalizer>): Normalizer {
+    return (value) => {
+        if (typeof value !== "object" || value === null) {
+            return value;
+        }
+
+        const result = { ...value };
+        for (const [key, normalize] of Object.entries(normalizers)) {
+            if (result[key] !== null && result[key] !== undefined) {
+                result[key] = normalize(result[key]);
+            }
+        }
+        return result;
+    };
+}
+
+function normalizeTagged(tagProp: string, normalizers: Record<string, Normalizer>): Normalizer {
+    return (value) => {
+        const normalize = normalizers[value?.[tagProp]];
+        return normalize ? normalize(value) : value;
+    };
+}
+
+function normalizeAll(...normalizers: Array<Normalizer>): Normalizer {
+    return (value) => normalizers.reduce((result, normalize) => normalize(result), value);
+}
+
+function normalizeU128(value: any): any {
+    if (!(value instanceof Uint8Array)) {
+        return BigInt(value);
+    }
+
+    return value.reduce((result, byte) => (result << 8n) | BigInt(byte), 0n);
+}
+
+function normalizeI128(value: any): any {
+    return BigInt.asIntN(128, normalizeU128(value));
+}
+
+/**
+ * Converts a compact struct, which is encoded as an array of its field values,
+ * into an object. Trailing fields that were omitted are left out.
+ */
+function normalizeCompact(fields: Array<string>): Normalizer {
+    return (value) => {
+        if (!Array.isArray(value)) {
+            return value;
+        }
+
+        const result: Record<string, any> = {};
+        fields.forEach((field, index) => {
+            if (index < value.length) {
+                result[field] = value[index];
+            }
+        });
+        return result;
+    };
+}
+
+const normalizeByteBuf: Normalizer = (value: ArrayLike<number>) => (value instanceof Uint8Array ? value : Uint8Array.from(value));
+
+const normalizeBytes: Normalizer = (value: ArrayLike<number>) => (value instanceof Uint8Array ? value : Uint8Array.from(value));
+
+function normalizeCompactPoint(value: any): any {
+    return normalizeCompact(["x", "y", "label"])(value);
+}
+
+function normalizeExplicitBoundPoint(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: normalizeT })(value);
+}
+
+function normalizeMyDateTime(value: any): any {
+    return normalizeOffsetDateTime(value);
+}
+
+const normalizeOffsetDateTime: Normalizer = (value: string) => Temporal.Instant.from(value);
+
+function normalizePoint(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: normalizeT })(value);
+}
+
+function normalizeRequest(value: any): any {
+    return normalizeFields({ url: normalizeUri, body: normalizeOption(normalizeByteBuf) })(value);
+}
+
+function normalizeRequestError(value: any): any {
+    return normalizeTagged("type", { server_error: normalizeFields({ response: normalizeByteBuf }) })(value);
+}
+
+function normalizeResponse(value: any): any {
+    return normalizeFields({ body: normalizeByteBuf })(value);
+}
+
+function normalizeResult(normalizeT: Normalizer, normalizeE: Normalizer): Normalizer {
+    return (value) => normalizeFields({ Ok: normalizeT, Err: normalizeE })(value);
+}
+
+function normalizeStructWithGenerics(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ list: normalizeList(normalizeT), points: normalizeList(normalizePoint(normalizeT)), recursive: normalizeList(normalizePoint(normalizePoint(normalizeT))), optional_timestamp: normalizeOption(normalizeMyDateTime) })(value);
+}
+
+const normalizeUri: Normalizer = (value: string) => new URL(value);
+
+function denormalizeInt128(value: any): any {
+    // 128-bit integers are encoded as 16 bytes in big-endian order, using
+    // two's complement for negative numbers:
+    let remaining = BigInt.asUintN(128, BigInt(value));
+    const bytes = new Uint8Array(16);
+    for (let index = 15; index >= 0; index--) {
+        bytes[index] = Number(remaining & 0xffn);
+        remaining >>= 8n;
+    }
+    return bytes;
+}
+
+/**
+ * Converts an object into the array a compact struct is encoded as. Trailing
+ * fields beyond `minLength` that are `undefined` are omitted, so the plugin
+ * uses their default values.
+ */
+function denormalizeCompact(fields: Array<string>, minLength: number): Normalizer {
+    return (value) => {
+        if (typeof value !== "object" || value === null || Array.isArray(value)) {
+            return value;
+        }
+
+        let length = fields.length;
+        while (length > minLength && value[fields[length - 1]] === undefined) {
+            length--;
+        }
+        return fields.slice(0, length).map((field) => value[field] ?? null);
+    };
+}
+
+function denormalizeCompactPoint(value: any): any {
+    return denormalizeCompact(["x", "y", "label"], 2)(value);
+}
+
+function denormalizeExplicitBoundPoint(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: denormalizeT })(value);
+}
+
+function denormalizeMyDateTime(value: any): any {
+    return denormalizeOffsetDateTime(value);
+}
+
+const denormalizeOffsetDateTime: Normalizer = (value: Temporal.Instant) => value.toString();
+
+function denormalizePoint(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: denormalizeT })(value);
+}
+
+function denormalizeRequest(value: any): any {
+    return normalizeFields({ url: denormalizeUri })(value);
+}
+
+function denormalizeResult(denormalizeT: Normalizer, denormalizeE: Normalizer): Normalizer {
+    return (value) => normalizeFields({ Ok: denormalizeT, Err: denormalizeE })(value);
+}
+
+function denormalizeStructWithGenerics(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ list: normalizeList(denormalizeT), points: normalizeList(denormalizePoint(denormalizeT)), recursive: normalizeList(denormalizePoint(denormalizePoint(denormalizeT))), optional_timestamp: normalizeOption(denormalizeMyDateTime) })(value);
+}
+
+const denormalizeUri: Normalizer = (value: URL) => value.toString();
@@ -0,0 +1,923 @@
+// ============================================= //
+// Types for WebAssembly runtime                 //
+//                                               //
+// This file is generated. PLEASE DO NOT MODIFY. //
+// ============================================= //
+
+export type Body = ArrayBuffer;
+
+export type CompactPoint = {
+    x: number;
+    y: number;
+    label: string | null;
+};
+
+/**
+ * # This is an enum with doc comments.
+ */
+export type DocExampleEnum =
+    /**
+     * Multi-line doc comment with complex characters
+     * & " , \ ! '
+     */
+    | { Variant1: string }
+    /**
+     * Raw identifiers are supported too.
+     */
+    | { Variant2: {
+
+        /**
+         * Variant property.
+         */
+        inner: number;
+    } };
+
+/**
+ * Creates the `Variant1` variant of `DocExampleEnum`.
+ */
+export function createDocExampleEnumVariant1(value: string): DocExampleEnum {
+    return { Variant1: value };
+}
+
+/**
+ * Creates the `Variant2` variant of `DocExampleEnum`.
+ */
+export function createDocExampleEnumVariant2(value: Extract<DocExampleEnum, { Variant2: unknown }>["Variant2"]): DocExampleEnum {
+    return { Variant2: value };
+}
+
+/**
+ * Returns whether the given value is the `Variant1` variant of `DocExampleEnum`.
+ */
+export function isDocExampleEnumVariant1(value: DocExampleEnum): value is Extract<DocExampleEnum, { Variant1: unknown }> {
+    return typeof value === "object" && value !== null && "Variant1" in value;
+}
+
+/**
+ * Returns whether the given value is the `Variant2` variant of `DocExampleEnum`.
+ */
+export function isDocExampleEnumVariant2(value: DocExampleEnum): value is Extract<DocExampleEnum, { Variant2: unknown }> {
+    return typeof value === "object" && value !== null && "Variant2" in value;
+}
+
+/**
+ * Calls the handler for the variant of the given `DocExampleEnum` value, and
+ * returns its result.
+ */
+export function matchDocExampleEnum<TResult>(
+    value: DocExampleEnum,
+    handlers: {
+        Variant1: (value: Extract<DocExampleEnum, { Variant1: unknown }>) => TResult;
+        Variant2: (value: Extract<DocExampleEnum, { Variant2: unknown }>) => TResult;
+    }
+): TResult {
+    if (isDocExampleEnumVariant1(value)) {
+        return handlers.Variant1(value);
+    }
+    if (isDocExampleEnumVariant2(value)) {
+        return handlers.Variant2(value);
+    }
+    throw new Error("Unknown variant of DocExampleEnum");
+}
+
+/**
+ * # This is a struct with doc comments.
+ */
+export type DocExampleStruct = {
+    /**
+     * Multi-line doc comment with complex characters
+     * & " , \ ! '
+     */
+    multi_line: string;
+
+    /**
+     * Raw identifiers are supported too.
+     */
+    type: string;
+};
+
+/**
+ * A point of an arbitrary type, with explicit trait bounds.
+ */
+export type ExplicitBoundPoint<T> = {
+    value: T;
+};
+
+/**
+ * This struct is also not referenced by any function or data structure, but
+ * it will show up because there is an explicit `use` statement for it in the
+ * `fp_import!` macro.
+ */
+export type ExplicitedlyImportedType = {
+    you_will_see_this: boolean;
+};
+
+export type FlattenedStruct = {
+    foo: string;
+    bar: number;
+};
+
+export type FloatingPoint = Point<number>;
+
+export type FpAdjacentlyTagged =
+    | { type: "Foo" }
+    | { type: "Bar"; payload: string }
+    | { type: "Baz"; payload: { a: number; b: number } };
+
+/**
+ * Creates the `Foo` variant of `FpAdjacentlyTagged`.
+ */
+export function createFpAdjacentlyTaggedFoo(): FpAdjacentlyTagged {
+    return { type: "Foo" };
+}
+
+/**
+ * Creates the `Bar` variant of `FpAdjacentlyTagged`.
+ */
+export function createFpAdjacentlyTaggedBar(value: string): FpAdjacentlyTagged {
+    return { type: "Bar", payload: value };
+}
+
+/**
+ * Creates the `Baz` variant of `FpAdjacentlyTagged`.
+ */
+export function createFpAdjacentlyTaggedBaz(value: Extract<FpAdjacentlyTagged, { type: "Baz" }>["payload"]): FpAdjacentlyTagged {
+    return { type: "Baz", payload: value };
+}
+
+/**
+ * Returns whether the given value is the `Foo` variant of `FpAdjacentlyTagged`.
+ */
+export function isFpAdjacentlyTaggedFoo(value: FpAdjacentlyTagged): value is Extract<FpAdjacentlyTagged, { type: "Foo" }> {
+    return value.type === "Foo";
+}
+
+/**
+ * Returns whether the given value is the `Bar` variant of `FpAdjacentlyTagged`.
+ */
+export function isFpAdjacentlyTaggedBar(value: FpAdjacentlyTagged): value is Extract<FpAdjacentlyTagged, { type: "Bar" }> {
+    return value.type === "Bar";
+}
+
+/**
+ * Returns whether the given value is the `Baz` variant of `FpAdjacentlyTagged`.
+ */
+export function isFpAdjacentlyTaggedBaz(value: FpAdjacentlyTagged): value is Extract<FpAdjacentlyTagged, { type: "Baz" }> {
+    return value.type === "Baz";
+}
+
+/**
+ * Calls the handler for the variant of the given `FpAdjacentlyTagged` value, and
+ * returns its result.
+ */
+export function matchFpAdjacentlyTagged<TResult>(
+    value: FpAdjacentlyTagged,
+    handlers: {
+        Foo: (value: Extract<FpAdjacentlyTagged, { type: "Foo" }>) => TResult;
+        Bar: (value: Extract<FpAdjacentlyTagged, { type: "Bar" }>) => TResult;
+        Baz: (value: Extract<FpAdjacentlyTagged, { type: "Baz" }>) => TResult;
+    }
+): TResult {
+    if (isFpAdjacentlyTaggedFoo(value)) {
+        return handlers.Foo(value);
+    }
+    if (isFpAdjacentlyTaggedBar(value)) {
+        return handlers.Bar(value);
+    }
+    if (isFpAdjacentlyTaggedBaz(value)) {
+        return handlers.Baz(value);
+    }
+    throw new Error("Unknown variant of FpAdjacentlyTagged");
+}
+
+export type FpFlatten = {
+} & FlattenedStruct;
+
+export type FpInternallyTagged =
+    | { type: "Foo" }
+    | { type: "Baz"; a: number; b: number };
+
+/**
+ * Creates the `Foo` variant of `FpInternallyTagged`.
+ */
+export function createFpInternallyTaggedFoo(): FpInternallyTagged {
+    return { type: "Foo" };
+}
+
+/**
+ * Creates the `Baz` variant of `FpInternallyTagged`.
+ */
+export function createFpInternallyTaggedBaz(value: Omit<Extract<FpInternallyTagged, { type: "Baz" }>, "type">): FpInternallyTagged {
+    return { type: "Baz", ...value } as FpInternallyTagged;
+}
+
+/**
+ * Returns whether the given value is the `Foo` variant of `FpInternallyTagged`.
+ */
+export function isFpInternallyTaggedFoo(value: FpInternallyTagged): value is Extract<FpInternallyTagged, { type: "Foo" }> {
+    return value.type === "Foo";
+}
+
+/**
+ * Returns whether the given value is the `Baz` variant of `FpInternallyTagged`.
+ */
+export function isFpInternallyTaggedBaz(value: FpInternallyTagged): value is Extract<FpInternallyTagged, { type: "Baz" }> {
+    return value.type === "Baz";
+}
+
+/**
+ * Calls the handler for the variant of the given `FpInternallyTagged` value, and
+ * returns its result.
+ */
+export function matchFpInternallyTagged<TResult>(
+    value: FpInternallyTagged,
+    handlers: {
+        Foo: (value: Extract<FpInternallyTagged, { type: "Foo" }>) => TResult;
+        Baz: (value: Extract<FpInternallyTagged, { type: "Baz" }>) => TResult;
+    }
+): TResult {
+    if (isFpInternallyTaggedFoo(value)) {
+        return handlers.Foo(value);
+    }
+    if (isFpInternallyTaggedBaz(value)) {
+        return handlers.Baz(value);
+    }
+    throw new Error("Unknown variant of FpInternallyTagged");
+}
+
+export type FpPropertyRenaming = {
+    fooBar: string;
+    QUX_BAZ: number;
+    rawStruct: number;
+};
+
+export type FpUntagged =
+    | string
+    | { a: number; b: number; };
+
+/**
+ * Creates the `Bar` variant of `FpUntagged`.
+ */
+export function createFpUntaggedBar(value: string): FpUntagged {
+    return value;
+}
+
+/**
+ * Creates the `Baz` variant of `FpUntagged`.
+ */
+export function createFpUntaggedBaz(value: { a: number; b: number }): FpUntagged {
+    return value;
+}
+
+export type FpVariantRenaming =
+    | "foo_bar"
+    | { QUX_BAZ: {
+
+        /**
+         * Will be renamed to "FOO_BAR" because of the `rename_all` on the
+         * variant.
+         */
+        FOO_BAR: string;
+        qux_baz: number;
+    } };
+
+/**
+ * Creates the `FooBar` variant of `FpVariantRenaming`.
+ */
+export function createFpVariantRenamingFooBar(): FpVariantRenaming {
+    return "foo_bar";
+}
+
+/**
+ * Creates the `QuxBaz` variant of `FpVariantRenaming`.
+ */
+export function createFpVariantRenamingQuxBaz(value: Extract<FpVariantRenaming, { QUX_BAZ: unknown }>["QUX_BAZ"]): FpVariantRenaming {
+    return { QUX_BAZ: value };
+}
+
+/**
+ * Returns whether the given value is the `FooBar` variant of `FpVariantRenaming`.
+ */
+export function isFpVariantRenamingFooBar(value: FpVariantRenaming): value is Extract<FpVariantRenaming, "foo_bar"> {
+    return value === "foo_bar";
+}
+
+/**
+ * Returns whether the given value is the `QuxBaz` variant of `FpVariantRenaming`.
+ */
+export function isFpVariantRenamingQuxBaz(value: FpVariantRenaming): value is Extract<FpVariantRenaming, { QUX_BAZ: unknown }> {
+    return typeof value === "object" && value !== null && "QUX_BAZ" in value;
+}
+
+/**
+ * Calls the handler for the variant of the given `FpVariantRenaming` value, and
+ * returns its result.
+ */
+export function matchFpVariantRenaming<TResult>(
+    value: FpVariantRenaming,
+    handlers: {
+        FooBar: (value: Extract<FpVariantRenaming, "foo_bar">) => TResult;
+        QuxBaz: (value: Extract<FpVariantRenaming, { QUX_BAZ: unknown }>) => TResult;
+    }
+): TResult {
+    if (isFpVariantRenamingFooBar(value)) {
+        return handlers.FooBar(value);
+    }
+    if (isFpVariantRenamingQuxBaz(value)) {
+        return handlers.QuxBaz(value);
+    }
+    throw new Error("Unknown variant of FpVariantRenaming");
+}
+
+export type GroupImportedType1 = {
+    you_will_see_this: boolean;
+};
+
+export type GroupImportedType2 = {
+    you_will_see_this: boolean;
+};
+
+export type HttpResult = Result<Response, RequestError>;
+
+export type Int64 = number | bigint;
+
+export type Method = 
+    | "GET"
+    | "POST"
+    | "PUT"
+    | "DELETE"
+    | "HEAD"
+    | "OPTIONS"
+    | "CONNECT"
+    | "PATCH"
+    | "TRACE";
+
+/**
+ * Our struct for passing date time instances.
+ *
+ * We wrap the `OffsetDateTime` type in a new struct so that the Serde
+ * attributes can be inserted. These are necessary to enable RFC3339
+ * formatting. Without a wrapper type like this, we would not be able to pass
+ * date time instances directly to function arguments and we might run into
+ * trouble embedding them into certain generic types.
+ */
+export type MyDateTime = string;
+
+/**
+ * A point of an arbitrary type.
+ */
+export type Point<T> = {
+    value: T;
+};
+
+/**
+ * Example for representing Redux actions.
+ */
+export type ReduxAction =
+    | { type: "clear_title" }
+    | { type: "update_title"; payload: { title: string } };
+
+/**
+ * Creates the `ClearTitle` variant of `ReduxAction`.
+ */
+export function createReduxActionClearTitle(): ReduxAction {
+    return { type: "clear_title" };
+}
+
+/**
+ * Creates the `UpdateTitle` variant of `ReduxAction`.
+ */
+export function createReduxActionUpdateTitle(value: Extract<ReduxAction, { type: "update_title" }>["payload"]): ReduxAction {
+    return { type: "update_title", payload: value };
+}
+
+/**
+ * Returns whether the given value is the `ClearTitle` variant of `ReduxAction`.
+ */
+export function isReduxActionClearTitle(value: ReduxAction): value is Extract<ReduxAction, { type: "clear_title" }> {
+    return value.type === "clear_title";
+}
+
+/**
+ * Returns whether the given value is the `UpdateTitle` variant of `ReduxAction`.
+ */
+export function isReduxActionUpdateTitle(value: ReduxAction): value is Extract<ReduxAction, { type: "update_title" }> {
+    return value.type === "update_title";
+}
+
+/**
+ * Calls the handler for the variant of the given `ReduxAction` value, and
+ * returns its result.
+ */
+export function matchReduxAction<TResult>(
+    value: ReduxAction,
+    handlers: {
+        ClearTitle: (value: Extract<ReduxAction, { type: "clear_title" }>) => TResult;
+        UpdateTitle: (value: Extract<ReduxAction, { type: "update_title" }>) => TResult;
+    }
+): TResult {
+    if (isReduxActionClearTitle(value)) {
+        return handlers.ClearTitle(value);
+    }
+    if (isReduxActionUpdateTitle(value)) {
+        return handlers.UpdateTitle(value);
+    }
+    throw new Error("Unknown variant of ReduxAction");
+}
+
+/**
+ * Represents an HTTP request to be sent.
+ */
+export type Request = {
+    /**
+     * The URI to submit the request to.
+     */
+    url: string;
+
+    /**
+     * HTTP method to use for the request.
+     */
+    method: Method;
+
+    /**
+     * HTTP headers to submit with the request.
+     */
+    headers: HeaderMap;
+
+    /**
+     * The body to submit with the request.
+     */
+    body?: Body;
+};
+
+/**
+ * Represents an error that occurred while attempting to submit the request.
+ */
+export type RequestError =
+    /**
+     * Used when we know we don't have an active network connection.
+     */
+    | { type: "offline" }
+    | { type: "no_route" }
+    | { type: "connection_refused" }
+    | { type: "timeout" }
+    | {
+        type: "server_error";
+
+        /**
+         * HTTP status code.
+         */
+        status_code: number;
+
+        /**
+         * Response body.
+         */
+        response: Body;
+    }
+    /**
+     * Misc.
+     */
+    | { type: "other/misc"; reason: string };
+
+/**
+ * Creates the `Offline` variant of `RequestError`.
+ */
+export function createRequestErrorOffline(): RequestError {
+    return { type: "offline" };
+}
+
+/**
+ * Creates the `NoRoute` variant of `RequestError`.
+ */
+export function createRequestErrorNoRoute(): RequestError {
+    return { type: "no_route" };
+}
+
+/**
+ * Creates the `ConnectionRefused` variant of `RequestError`.
+ */
+export function createRequestErrorConnectionRefused(): RequestError {
+    return { type: "connection_refused" };
+}
+
+/**
+ * Creates the `Timeout` variant of `RequestError`.
+ */
+export function createRequestErrorTimeout(): RequestError {
+    return { type: "timeout" };
+}
+
+/**
+ * Creates the `ServerError` variant of `RequestError`.
+ */
+export function createRequestErrorServerError(value: Omit<Extract<RequestError, { type: "server_error" }>, "type">): RequestError {
+    return { type: "server_error", ...value } as RequestError;
+}
+
+/**
+ * Creates the `Other` variant of `RequestError`.
+ */
+export function createRequestErrorOther(value: Omit<Extract<RequestError, { type: "other/misc" }>, "type">): RequestError {
+    return { type: "other/misc", ...value } as RequestError;
+}
+
+/**
+ * Returns whether the given value is the `Offline` variant of `RequestError`.
+ */
+export function isRequestErrorOffline(value: RequestError): value is Extract<RequestError, { type: "offline" }> {
+    return value.type === "offline";
+}
+
+/**
+ * Returns whether the given value is the `NoRoute` variant of `RequestError`.
+ */
+export function isRequestErrorNoRoute(value: RequestError): value is Extract<RequestError, { type: "no_route" }> {
+    return value.type === "no_route";
+}
+
+/**
+ * Returns whether the given value is the `ConnectionRefused` variant of `RequestError`.
+ */
+export function isRequestErrorConnectionRefused(value: RequestError): value is Extract<RequestError, { type: "connection_refused" }> {
+    return value.type === "connection_refused";
+}
+
+/**
+ * Returns whether the given value is the `Timeout` variant of `RequestError`.
+ */
+export function isRequestErrorTimeout(value: RequestError): value is Extract<RequestError, { type: "timeout" }> {
+    return value.type === "timeout";
+}
+
+/**
+ * Returns whether the given value is the `ServerError` variant of `RequestError`.
+ */
+export function isRequestErrorServerError(value: RequestError): value is Extract<RequestError, { type: "server_error" }> {
+    return value.type === "server_error";
+}
+
+/**
+ * Returns whether the given value is the `Other` variant of `RequestError`.
+ */
+export function isRequestErrorOther(value: RequestError): value is Extract<RequestError, { type: "other/misc" }> {
+    return value.type === "other/misc";
+}
+
+/**
+ * Calls the handler for the variant of the given `RequestError` value, and
+ * returns its result.
+ */
+export function matchRequestError<TResult>(
+    value: RequestError,
+    handlers: {
+        Offline: (value: Extract<RequestError, { type: "offline" }>) => TResult;
+        NoRoute: (value: Extract<RequestError, { type: "no_route" }>) => TResult;
+        ConnectionRefused: (value: Extract<RequestError, { type: "connection_refused" }>) => TResult;
+        Timeout: (value: Extract<RequestError, { type: "timeout" }>) => TResult;
+        ServerError: (value: Extract<RequestError, { type: "server_error" }>) => TResult;
+        Other: (value: Extract<RequestError, { type: "other/misc" }>) => TResult;
+    }
+): TResult {
+    if (isRequestErrorOffline(value)) {
+        return handlers.Offline(value);
+    }
+    if (isRequestErrorNoRoute(value)) {
+        return handlers.NoRoute(value);
+    }
+    if (isRequestErrorConnectionRefused(value)) {
+        return handlers.ConnectionRefused(value);
+    }
+    if (isRequestErrorTimeout(value)) {
+        return handlers.Timeout(value);
+    }
+    if (isRequestErrorServerError(value)) {
+        return handlers.ServerError(value);
+    }
+    if (isRequestErrorOther(value)) {
+        return handlers.Other(value);
+    }
+    throw new Error("Unknown variant of RequestError");
+}
+
+/**
+ * Represents an HTTP response we received.
+ *
+ * Please note we currently do not support streaming responses.
+ */
+export type Response = {
+    /**
+     * The response body. May be empty.
+     */
+    body: Body;
+
+    /**
+     * HTTP headers that were part of the response.
+     */
+    headers: HeaderMap;
+
+    /**
+     * HTTP status code.
+     */
+    status_code: number;
+};
+
+/**
+ * A result that can be either successful (`Ok`) or represent an error (`Err`).
+ */
+export type Result<T, E> =
+    /**
+     * Represents a successful result.
+     */
+    | { Ok: T }
+    /**
+     * Represents an error.
+     */
+    | { Err: E };
+
+/**
+ * Creates the `Ok` variant of `Result`.
+ */
+export function createResultOk<T, E>(value: T): Result<T, E> {
+    return { Ok: value };
+}
+
+/**
+ * Creates the `Err` variant of `Result`.
+ */
+export function createResultErr<T, E>(value: E): Result<T, E> {
+    return { Err: value };
+}
+
+/**
+ * Returns whether the given value is the `Ok` variant of `Result`.
+ */
+export function isResultOk<T, E>(value: Result<T, E>): value is Extract<Result<T, E>, { Ok: unknown }> {
+    return typeof value === "object" && value !== null && "Ok" in value;
+}
+
+/**
+ * Returns whether the given value is the `Err` variant of `Result`.
+ */
+export function isResultErr<T, E>(value: Result<T, E>): value is Extract<Result<T, E>, { Err: unknown }> {
+    return typeof value === "object" && value !== null && "Err" in value;
+}
+
+/**
+ * Calls the handler for the variant of the given `Result` value, and
+ * returns its result.
+ */
+export function matchResult<T, E, TResult>(
+    value: Result<T, E>,
+    handlers: {
+        Ok: (value: Extract<Result<T, E>, { Ok: unknown }>) => TResult;
+        Err: (value: Extract<Result<T, E>, { Err: unknown }>) => TResult;
+    }
+): TResult {
+    if (isResultOk(value)) {
+        return handlers.Ok(value);
+    }
+    if (isResultErr(value)) {
+        return handlers.Err(value);
+    }
+    throw new Error("Unknown variant of Result");
+}
+
+export type SerdeAdjacentlyTagged =
+    | { type: "Foo" }
+    | { type: "Bar"; payload: string }
+    | { type: "Baz"; payload: { a: number; b: number } };
+
+/**
+ * Creates the `Foo` variant of `SerdeAdjacentlyTagged`.
+ */
+export function createSerdeAdjacentlyTaggedFoo(): SerdeAdjacentlyTagged {
+    return { type: "Foo" };
+}
+
+/**
+ * Creates the `Bar` variant of `SerdeAdjacentlyTagged`.
+ */
+export function createSerdeAdjacentlyTaggedBar(value: string): SerdeAdjacentlyTagged {
+    return { type: "Bar", payload: value };
+}
+
+/**
+ * Creates the `Baz` variant of `SerdeAdjacentlyTagged`.
+ */
+export function createSerdeAdjacentlyTaggedBaz(value: Extract<SerdeAdjacentlyTagged, { type: "Baz" }>["payload"]): SerdeAdjacentlyTagged {
+    return { type: "Baz", payload: value };
+}
+
+/**
+ * Returns whether the given value is the `Foo` variant of `SerdeAdjacentlyTagged`.
+ */
+export function isSerdeAdjacentlyTaggedFoo(value: SerdeAdjacentlyTagged): value is Extract<SerdeAdjacentlyTagged, { type: "Foo" }> {
+    return value.type === "Foo";
+}
+
+/**
+ * Returns whether the given value is the `Bar` variant of `SerdeAdjacentlyTagged`.
+ */
+export function isSerdeAdjacentlyTaggedBar(value: SerdeAdjacentlyTagged): value is Extract<SerdeAdjacentlyTagged, { type: "Bar" }> {
+    return value.type === "Bar";
+}
+
+/**
+ * Returns whether the given value is the `Baz` variant of `SerdeAdjacentlyTagged`.
+ */
+export function isSerdeAdjacentlyTaggedBaz(value: SerdeAdjacentlyTagged): value is Extract<SerdeAdjacentlyTagged, { type: "Baz" }> {
+    return value.type === "Baz";
+}
+
+/**
+ * Calls the handler for the variant of the given `SerdeAdjacentlyTagged` value, and
+ * returns its result.
+ */
+export function matchSerdeAdjacentlyTagged<TResult>(
+    value: SerdeAdjacentlyTagged,
+    handlers: {
+        Foo: (value: Extract<SerdeAdjacentlyTagged, { type: "Foo" }>) => TResult;
+        Bar: (value: Extract<SerdeAdjacentlyTagged, { type: "Bar" }>) => TResult;
+        Baz: (value: Extract<SerdeAdjacentlyTagged, { type: "Baz" }>) => TResult;
+    }
+): TResult {
+    if (isSerdeAdjacentlyTaggedFoo(value)) {
+        return handlers.Foo(value);
+    }
+    if (isSerdeAdjacentlyTaggedBar(value)) {
+        return handlers.Bar(value);
+    }
+    if (isSerdeAdjacentlyTaggedBaz(value)) {
+        return handlers.Baz(value);
+    }
+    throw new Error("Unknown variant of SerdeAdjacentlyTagged");
+}
+
+export type SerdeFlatten = {
+} & FlattenedStruct;
+
+export type SerdeInternallyTagged =
+    | { type: "Foo" }
+    | { type: "Baz"; a: number; b: number };
+
+/**
+ * Creates the `Foo` variant of `SerdeInternallyTagged`.
+ */
+export function createSerdeInternallyTaggedFoo(): SerdeInternallyTagged {
+    return { type: "Foo" };
+}
+
+/**
+ * Creates the `Baz` variant of `SerdeInternallyTagged`.
+ */
+export function createSerdeInternallyTaggedBaz(value: Omit<Extract<SerdeInternallyTagged, { type: "Baz" }>, "type">): SerdeInternallyTagged {
+    return { type: "Baz", ...value } as SerdeInternallyTagged;
+}
+
+/**
+ * Returns whether the given value is the `Foo` variant of `SerdeInternallyTagged`.
+ */
+export function isSerdeInternallyTaggedFoo(value: SerdeInternallyTagged): value is Extract<SerdeInternallyTagged, { type: "Foo" }> {
+    return value.type === "Foo";
+}
+
+/**
+ * Returns whether the given value is the `Baz` variant of `SerdeInternallyTagged`.
+ */
+export function isSerdeInternallyTaggedBaz(value: SerdeInternallyTagged): value is Extract<SerdeInternallyTagged, { type: "Baz" }> {
+    return value.type === "Baz";
+}
+
+/**
+ * Calls the handler for the variant of the given `SerdeInternallyTagged` value, and
+ * returns its result.
+ */
+export function matchSerdeInternallyTagged<TResult>(
+    value: SerdeInternallyTagged,
+    handlers: {
+        Foo: (value: Extract<SerdeInternallyTagged, { type: "Foo" }>) => TResult;
+        Baz: (value: Extract<SerdeInternallyTagged, { type: "Baz" }>) => TResult;
+    }
+): TResult {
+    if (isSerdeInternallyTaggedFoo(value)) {
+        return handlers.Foo(value);
+    }
+    if (isSerdeInternallyTaggedBaz(value)) {
+        return handlers.Baz(value);
+    }
+    throw new Error("Unknown variant of SerdeInternallyTagged");
+}
+
+export type SerdePropertyRenaming = {
+    fooBar: string;
+    QUX_BAZ: number;
+    rawStruct: number;
+};
+
+export type SerdeUntagged =
+    | string
+    | { a: number; b: number; };
+
+/**
+ * Creates the `Bar` variant of `SerdeUntagged`.
+ */
+export function createSerdeUntaggedBar(value: string): SerdeUntagged {
+    return value;
+}
+
+/**
+ * Creates the `Baz` variant of `SerdeUntagged`.
+ */
+export function createSerdeUntaggedBaz(value: { a: number; b: number }): SerdeUntagged {
+    return value;
+}
+
+export type SerdeVariantRenaming =
+    | "foo_bar"
+    | { QUX_BAZ: {
+
+        /**
+         * Will be renamed to "FooBar" because of the `rename_all` on the
+         * variant.
+         */
+        FooBar: string;
+        qux_baz: number;
+    } };
+
+/**
+ * Creates the `FooBar` variant of `SerdeVariantRenaming`.
+ */
+export function createSerdeVariantRenamingFooBar(): SerdeVariantRenaming {
+    return "foo_bar";
+}
+
+/**
+ * Creates the `QuxBaz` variant of `SerdeVariantRenaming`.
+ */
+export function createSerdeVariantRenamingQuxBaz(value: Extract<SerdeVariantRenaming, { QUX_BAZ: unknown }>["QUX_BAZ"]): SerdeVariantRenaming {
+    return { QUX_BAZ: value };
+}
+
+/**
+ * Returns whether the given value is the `FooBar` variant of `SerdeVariantRenaming`.
+ */
+export function isSerdeVariantRenamingFooBar(value: SerdeVariantRenaming): value is Extract<SerdeVariantRenaming, "foo_bar"> {
+    return value === "foo_bar";
+}
+
+/**
+ * Returns whether the given value is the `QuxBaz` variant of `SerdeVariantRenaming`.
+ */
+export function isSerdeVariantRenamingQuxBaz(value: SerdeVariantRenaming): value is Extract<SerdeVariantRenaming, { QUX_BAZ: unknown }> {
+    return typeof value === "object" && value !== null && "QUX_BAZ" in value;
+}
+
+/**
+ * Calls the handler for the variant of the given `SerdeVariantRenaming` value, and
+ * returns its result.
+ */
+export function matchSerdeVariantRenaming<TResult>(
+    value: SerdeVariantRenaming,
+    handlers: {
+        FooBar: (value: Extract<SerdeVariantRenaming, "foo_bar">) => TResult;
+        QuxBaz: (value: Extract<SerdeVariantRenaming, { QUX_BAZ: unknown }>) => TResult;
+    }
+): TResult {
+    if (isSerdeVariantRenamingFooBar(value)) {
+        return handlers.FooBar(value);
+    }
+    if (isSerdeVariantRenamingQuxBaz(value)) {
+        return handlers.QuxBaz(value);
+    }
+    throw new Error("Unknown variant of SerdeVariantRenaming");
+}
+
+/**
+ * A state update to communicate to the Redux host.
+ *
+ * Fields are wrapped in `Option`. If any field is `None` it means it hasn't
+ * changed.
+ */
+export type StateUpdate = {
+    title: string | null;
+    revision: number | null;
+};
+
+export type StructWithGenerics<T> = {
+    list: Array<T>;
+    points: Array<Point<T>>;
+    recursive: Array<Point<Point<T>>>;
+    complex_nested: Record<string, Array<FloatingPoint>> | null;
+    optional_timestamp: MyDateTime | null;
+};
+
+export type StructWithOptions = {
+    filledString?: string;
+    emptyString?: string;
+    filledOptionString?: string;
+    emptyOptionString?: string;
+    neverSkippedFilledOptionString: string | null;
+    neverSkippedEmptyOptionString: string | null;
+};
+
+export type HeaderMap = { [key: string]: Uint8Array };
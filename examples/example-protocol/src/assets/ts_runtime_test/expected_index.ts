@@ -20,6 +20,7 @@ export type Imports = {
     importArrayU16: (arg: Uint16Array) => Uint16Array;
     importArrayU32: (arg: Uint32Array) => Uint32Array;
     importArrayU8: (arg: Uint8Array) => Uint8Array;
+    importBorrowedString: (arg: string) => string;
     importExplicitBoundPoint: (arg: types.ExplicitBoundPoint<number>) => void;
     importFpAdjacentlyTagged: (arg: types.FpAdjacentlyTagged) => types.FpAdjacentlyTagged;
     importFpEnum: (arg: types.FpVariantRenaming) => types.FpVariantRenaming;
@@ -71,6 +72,10 @@ export type Imports = {
     importVoidFunctionEmptyReturn: () => void;
     log: (message: string) => void;
     makeHttpRequest: (request: types.Request) => Promise<types.HttpResult>;
+} & TextImports;
+
+export type TextImports = {
+    importUppercaseString: (arg: string) => string;
 };
 
 export type Exports = {
@@ -83,6 +88,9 @@ export type Exports = {
     exportArrayU32?: (arg: Uint32Array) => Uint32Array;
     exportArrayU8?: (arg: Uint8Array) => Uint8Array;
     exportAsyncStruct?: (arg1: types.FpPropertyRenaming, arg2: bigint) => Promise<types.FpPropertyRenaming>;
+    exportBorrowedBytes?: (arg: Array<number>) => Array<number>;
+    exportBorrowedString?: (arg: string) => string;
+    exportCompactPoint?: (arg: types.CompactPoint) => types.CompactPoint;
     exportFpAdjacentlyTagged?: (arg: types.FpAdjacentlyTagged) => types.FpAdjacentlyTagged;
     exportFpEnum?: (arg: types.FpVariantRenaming) => types.FpVariantRenaming;
     exportFpFlatten?: (arg: types.FpFlatten) => types.FpFlatten;
@@ -96,12 +104,14 @@ export type Exports = {
     exportMultiplePrimitives?: (arg1: number, arg2: string) => bigint;
     exportPrimitiveBoolNegate?: (arg: boolean) => boolean;
     exportPrimitiveBoolNegateAsync?: (arg: boolean) => Promise<boolean>;
+    exportPrimitiveCharToUppercase?: (arg: string) => string;
     exportPrimitiveF32AddThree?: (arg: number) => number;
     exportPrimitiveF32AddThreeAsync?: (arg: number) => Promise<number>;
     exportPrimitiveF32AddThreeWasmer2?: (arg: number) => number;
     exportPrimitiveF64AddThree?: (arg: number) => number;
     exportPrimitiveF64AddThreeAsync?: (arg: number) => Promise<number>;
     exportPrimitiveF64AddThreeWasmer2?: (arg: number) => number;
+    exportPrimitiveI128AddThree?: (arg: bigint) => bigint;
     exportPrimitiveI16AddThree?: (arg: number) => number;
     exportPrimitiveI16AddThreeAsync?: (arg: number) => Promise<number>;
     exportPrimitiveI32AddThree?: (arg: number) => number;
@@ -110,6 +120,7 @@ export type Exports = {
     exportPrimitiveI64AddThreeAsync?: (arg: bigint) => Promise<number>;
     exportPrimitiveI8AddThree?: (arg: number) => number;
     exportPrimitiveI8AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveU128AddThree?: (arg: bigint) => bigint;
     exportPrimitiveU16AddThree?: (arg: number) => number;
     exportPrimitiveU16AddThreeAsync?: (arg: number) => Promise<number>;
     exportPrimitiveU32AddThree?: (arg: number) => number;
@@ -118,6 +129,8 @@ export type Exports = {
     exportPrimitiveU64AddThreeAsync?: (arg: bigint) => Promise<number>;
     exportPrimitiveU8AddThree?: (arg: number) => number;
     exportPrimitiveU8AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrint?: (stdout: string, stderr: string) => void;
+    exportReadFile?: (path: string) => types.Result<string, string>;
     exportResetGlobalState?: () => Promise<void>;
     exportSerdeAdjacentlyTagged?: (arg: types.SerdeAdjacentlyTagged) => types.SerdeAdjacentlyTagged;
     exportSerdeEnum?: (arg: types.SerdeVariantRenaming) => types.SerdeVariantRenaming;
@@ -128,7 +141,9 @@ export type Exports = {
     exportString?: (arg: string) => string;
     exportStructWithOptions?: (arg: types.StructWithOptions) => types.StructWithOptions;
     exportTimestamp?: (arg: types.MyDateTime) => types.MyDateTime;
+    exportUppercaseString?: (arg: string) => string;
     exportVoidFunction?: () => void;
+    exportWriteFile?: (path: string, contents: string) => types.Result<void, string>;
     fetchData?: (rType: string) => Promise<types.Result<string, string>>;
     init?: () => void;
     reducerBridge?: (action: types.ReduxAction) => types.StateUpdate;
@@ -141,6 +156,9 @@ export type Exports = {
     exportArrayU32Raw?: (arg: Uint8Array) => Uint8Array;
     exportArrayU8Raw?: (arg: Uint8Array) => Uint8Array;
     exportAsyncStructRaw?: (arg1: Uint8Array, arg2: bigint) => Promise<Uint8Array>;
+    exportBorrowedBytesRaw?: (arg: Uint8Array) => Uint8Array;
+    exportBorrowedStringRaw?: (arg: Uint8Array) => Uint8Array;
+    exportCompactPointRaw?: (arg: Uint8Array) => Uint8Array;
     exportFpAdjacentlyTaggedRaw?: (arg: Uint8Array) => Uint8Array;
     exportFpEnumRaw?: (arg: Uint8Array) => Uint8Array;
     exportFpFlattenRaw?: (arg: Uint8Array) => Uint8Array;
@@ -154,8 +172,10 @@ export type Exports = {
     exportMultiplePrimitivesRaw?: (arg1: number, arg2: Uint8Array) => bigint;
     exportPrimitiveBoolNegateRaw?: (arg: boolean) => boolean;
     exportPrimitiveBoolNegateAsyncRaw?: (arg: boolean) => Promise<Uint8Array>;
+    exportPrimitiveCharToUppercaseRaw?: (arg: Uint8Array) => Uint8Array;
     exportPrimitiveF32AddThreeAsyncRaw?: (arg: number) => Promise<Uint8Array>;
     exportPrimitiveF64AddThreeAsyncRaw?: (arg: number) => Promise<Uint8Array>;
+    exportPrimitiveI128AddThreeRaw?: (arg: Uint8Array) => Uint8Array;
     exportPrimitiveI16AddThreeRaw?: (arg: number) => number;
     exportPrimitiveI16AddThreeAsyncRaw?: (arg: number) => Promise<Uint8Array>;
     exportPrimitiveI32AddThreeRaw?: (arg: number) => number;
@@ -164,10 +184,13 @@ export type Exports = {
     exportPrimitiveI64AddThreeAsyncRaw?: (arg: bigint) => Promise<Uint8Array>;
     exportPrimitiveI8AddThreeRaw?: (arg: number) => number;
     exportPrimitiveI8AddThreeAsyncRaw?: (arg: number) => Promise<Uint8Array>;
+    exportPrimitiveU128AddThreeRaw?: (arg: Uint8Array) => Uint8Array;
     exportPrimitiveU16AddThreeAsyncRaw?: (arg: number) => Promise<Uint8Array>;
     exportPrimitiveU32AddThreeAsyncRaw?: (arg: number) => Promise<Uint8Array>;
     exportPrimitiveU64AddThreeAsyncRaw?: (arg: bigint) => Promise<Uint8Array>;
     exportPrimitiveU8AddThreeAsyncRaw?: (arg: number) => Promise<Uint8Array>;
+    exportPrintRaw?: (stdout: Uint8Array, stderr: Uint8Array) => void;
+    exportReadFileRaw?: (path: Uint8Array) => Uint8Array;
     exportResetGlobalStateRaw?: () => Promise<Uint8Array>;
     exportSerdeAdjacentlyTaggedRaw?: (arg: Uint8Array) => Uint8Array;
     exportSerdeEnumRaw?: (arg: Uint8Array) => Uint8Array;
@@ -178,6 +201,8 @@ export type Exports = {
     exportStringRaw?: (arg: Uint8Array) => Uint8Array;
     exportStructWithOptionsRaw?: (arg: Uint8Array) => Uint8Array;
     exportTimestampRaw?: (arg: Uint8Array) => Uint8Array;
+    exportUppercaseStringRaw?: (arg: Uint8Array) => Uint8Array;
+    exportWriteFileRaw?: (path: Uint8Array, contents: Uint8Array) => Uint8Array;
     fetchDataRaw?: (rType: Uint8Array) => Promise<Uint8Array>;
     reducerBridgeRaw?: (action: Uint8Array) => Uint8Array;
 };
@@ -193,6 +218,24 @@ export class FPRuntimeError extends Error {
     }
 }
 
+/**
+ * Identifies a specific revision of a protocol.
+ */
+export type ProtocolVersion = {
+    name: string;
+    version: string;
+    hash: string;
+};
+
+/**
+ * The version of the protocol this runtime was generated for.
+ */
+export const PROTOCOL_VERSION: ProtocolVersion = {
+    name: "example-protocol",
+    version: "0.1.0",
+    hash: "5c1bf67516549a9d",
+};
+
 /**
  * Creates a runtime for executing the given plugin.
  *
@@ -231,7 +274,8 @@ export async function createRuntime(
         }
     }
 
-    function parseObject<T>(fatPtr: FatPtr): T {
+    function parseObject<T>(fatPtr: FatPtr, normalize: Normalizer = identity): T {
+        checkWireFormat(fatPtr);
         const [ptr, len] = fromFatPtr(fatPtr);
         const buffer = new Uint8Array(memory.buffer, ptr, len);
         // Without creating a copy of the memory, we risk corruption of any
@@ -240,7 +284,7 @@ export async function createRuntime(
         const copy = new Uint8Array(len);
         copy.set(buffer);
         free(fatPtr);
-        const object = decode(copy) as unknown as T;
+        const object = normalize(decode(copy)) as T;
         return object;
     }
 
@@ -274,8 +318,8 @@ export async function createRuntime(
         }
     }
 
-    function serializeObject<T>(object: T): FatPtr {
-        return exportToMemory(encode(object));
+    function serializeObject<T>(object: T, denormalize: Normalizer = identity): FatPtr {
+        return exportToMemory(encode(denormalize(object)));
     }
 
     function exportToMemory(serialized: Uint8Array): FatPtr {
@@ -287,6 +331,7 @@ export async function createRuntime(
     }
 
     function importFromMemory(fatPtr: FatPtr): Uint8Array {
+        checkWireFormat(fatPtr);
         const [ptr, len] = fromFatPtr(fatPtr);
         const buffer = new Uint8Array(memory.buffer, ptr, len);
         const copy = new Uint8Array(len);
@@ -329,6 +374,10 @@ export async function createRuntime(
                 const arg = parseObject<Uint8Array>(arg_ptr);
                 return serializeObject(importFunctions.importArrayU8(arg));
             },
+            __fp_gen_import_borrowed_string: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<string>(arg_ptr);
+                return serializeObject(importFunctions.importBorrowedString(arg));
+            },
             __fp_gen_import_explicit_bound_point: (arg_ptr: FatPtr) => {
                 const arg = parseObject<types.ExplicitBoundPoint<number>>(arg_ptr);
                 importFunctions.importExplicitBoundPoint(arg);
@@ -660,6 +709,12 @@ export async function createRuntime(
             },
             __fp_host_resolve_async_value: resolvePromise,
         },
+        text: {
+            __fp_gen_import_uppercase_string: (arg_ptr: FatPtr): FatPtr => {
+                const arg = parseObject<string>(arg_ptr);
+                return serializeObject(importFunctions.importUppercaseString(arg));
+            },
+        },
     });
 
     const getExport = <T>(name: string): T => {
@@ -673,6 +728,18 @@ export async function createRuntime(
     const memory = getExport<WebAssembly.Memory>("memory");
     const malloc = getExport<(len: number) => FatPtr>("__fp_malloc");
     const free = getExport<(ptr: FatPtr) => void>("__fp_free");
+
+    const getProtocolVersion = instance.exports.__fp_protocol_version as unknown as
+        | (() => FatPtr)
+        | undefined;
+    if (getProtocolVersion) {
+        checkProtocolVersion(parseObject<ProtocolVersion>(getProtocolVersion()));
+    } else {
+        console.warn(
+            "Plugin does not declare its protocol version, so it cannot be verified to be " +
+                `compatible with ${formatProtocolVersion(PROTOCOL_VERSION)}`
+        );
+    }
     const resolveFuture = getExport<(asyncValuePtr: FatPtr, resultPtr: FatPtr) => void>("__fp_guest_resolve_async_value");
 
     return {
@@ -757,6 +824,33 @@ export async function createRuntime(
                 return promiseFromPtr(export_fn(arg1_ptr, arg2)).then((ptr) => parseObject<types.FpPropertyRenaming>(ptr));
             };
         })(),
+        exportBorrowedBytes: (() => {
+            const export_fn = instance.exports.__fp_gen_export_borrowed_bytes as any;
+            if (!export_fn) return;
+
+            return (arg: Array<number>) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<Array<number>>(export_fn(arg_ptr));
+            };
+        })(),
+        exportBorrowedString: (() => {
+            const export_fn = instance.exports.__fp_gen_export_borrowed_string as any;
+            if (!export_fn) return;
+
+            return (arg: string) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<string>(export_fn(arg_ptr));
+            };
+        })(),
+        exportCompactPoint: (() => {
+            const export_fn = instance.exports.__fp_gen_export_compact_point as any;
+            if (!export_fn) return;
+
+            return (arg: types.CompactPoint) => {
+                const arg_ptr = serializeObject(arg, denormalizeCompactPoint);
+                return parseObject<types.CompactPoint>(export_fn(arg_ptr), normalizeCompactPoint);
+            };
+        })(),
         exportFpAdjacentlyTagged: (() => {
             const export_fn = instance.exports.__fp_gen_export_fp_adjacently_tagged as any;
             if (!export_fn) return;
@@ -859,6 +953,15 @@ export async function createRuntime(
 
             return (arg: boolean) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<boolean>(ptr));
         })(),
+        exportPrimitiveCharToUppercase: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_char_to_uppercase as any;
+            if (!export_fn) return;
+
+            return (arg: string) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<string>(export_fn(arg_ptr));
+            };
+        })(),
         exportPrimitiveF32AddThree: instance.exports.__fp_gen_export_primitive_f32_add_three as any,
         exportPrimitiveF32AddThreeAsync: (() => {
             const export_fn = instance.exports.__fp_gen_export_primitive_f32_add_three_async as any;
@@ -875,6 +978,15 @@ export async function createRuntime(
             return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
         })(),
         exportPrimitiveF64AddThreeWasmer2: instance.exports.__fp_gen_export_primitive_f64_add_three_wasmer2 as any,
+        exportPrimitiveI128AddThree: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i128_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: bigint) => {
+                const arg_ptr = serializeObject(arg, denormalizeInt128);
+                return parseObject<bigint>(export_fn(arg_ptr), normalizeI128);
+            };
+        })(),
         exportPrimitiveI16AddThree: (() => {
             const export_fn = instance.exports.__fp_gen_export_primitive_i16_add_three as any;
             if (!export_fn) return;
@@ -923,6 +1035,15 @@ export async function createRuntime(
 
             return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
         })(),
+        exportPrimitiveU128AddThree: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_u128_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: bigint) => {
+                const arg_ptr = serializeObject(arg, denormalizeInt128);
+                return parseObject<bigint>(export_fn(arg_ptr), normalizeU128);
+            };
+        })(),
         exportPrimitiveU16AddThree: instance.exports.__fp_gen_export_primitive_u16_add_three as any,
         exportPrimitiveU16AddThreeAsync: (() => {
             const export_fn = instance.exports.__fp_gen_export_primitive_u16_add_three_async as any;
@@ -951,6 +1072,25 @@ export async function createRuntime(
 
             return (arg: number) => promiseFromPtr(export_fn(arg)).then((ptr) => parseObject<number>(ptr));
         })(),
+        exportPrint: (() => {
+            const export_fn = instance.exports.__fp_gen_export_print as any;
+            if (!export_fn) return;
+
+            return (stdout: string, stderr: string) => {
+                const stdout_ptr = serializeObject(stdout);
+                const stderr_ptr = serializeObject(stderr);
+                export_fn(stdout_ptr, stderr_ptr);
+            };
+        })(),
+        exportReadFile: (() => {
+            const export_fn = instance.exports.__fp_gen_export_read_file as any;
+            if (!export_fn) return;
+
+            return (path: string) => {
+                const path_ptr = serializeObject(path);
+                return parseObject<types.Result<string, string>>(export_fn(path_ptr));
+            };
+        })(),
         exportResetGlobalState: (() => {
             const export_fn = instance.exports.__fp_gen_export_reset_global_state as any;
             if (!export_fn) return;
@@ -1038,7 +1178,26 @@ export async function createRuntime(
                 return parseObject<types.MyDateTime>(export_fn(arg_ptr));
             };
         })(),
+        exportUppercaseString: (() => {
+            const export_fn = instance.exports.__fp_gen_export_uppercase_string as any;
+            if (!export_fn) return;
+
+            return (arg: string) => {
+                const arg_ptr = serializeObject(arg);
+                return parseObject<string>(export_fn(arg_ptr));
+            };
+        })(),
         exportVoidFunction: instance.exports.__fp_gen_export_void_function as any,
+        exportWriteFile: (() => {
+            const export_fn = instance.exports.__fp_gen_export_write_file as any;
+            if (!export_fn) return;
+
+            return (path: string, contents: string) => {
+                const path_ptr = serializeObject(path);
+                const contents_ptr = serializeObject(contents);
+                return parseObject<types.Result<void, string>>(export_fn(path_ptr, contents_ptr));
+            };
+        })(),
         fetchData: (() => {
             const export_fn = instance.exports.__fp_gen_fetch_data as any;
             if (!export_fn) return;
@@ -1139,6 +1298,33 @@ export async function createRuntime(
                 return promiseFromPtr(export_fn(arg1_ptr, arg2)).then(importFromMemory);
             };
         })(),
+        exportBorrowedBytesRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_borrowed_bytes as any;
+            if (!export_fn) return;
+
+            return (arg: Uint8Array) => {
+                const arg_ptr = exportToMemory(arg);
+                return importFromMemory(export_fn(arg_ptr));
+            };
+        })(),
+        exportBorrowedStringRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_borrowed_string as any;
+            if (!export_fn) return;
+
+            return (arg: Uint8Array) => {
+                const arg_ptr = exportToMemory(arg);
+                return importFromMemory(export_fn(arg_ptr));
+            };
+        })(),
+        exportCompactPointRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_compact_point as any;
+            if (!export_fn) return;
+
+            return (arg: Uint8Array) => {
+                const arg_ptr = exportToMemory(arg);
+                return importFromMemory(export_fn(arg_ptr));
+            };
+        })(),
         exportFpAdjacentlyTaggedRaw: (() => {
             const export_fn = instance.exports.__fp_gen_export_fp_adjacently_tagged as any;
             if (!export_fn) return;
@@ -1241,6 +1427,15 @@ export async function createRuntime(
 
             return (arg: boolean) => promiseFromPtr(export_fn(arg)).then(importFromMemory);
         })(),
+        exportPrimitiveCharToUppercaseRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_char_to_uppercase as any;
+            if (!export_fn) return;
+
+            return (arg: Uint8Array) => {
+                const arg_ptr = exportToMemory(arg);
+                return importFromMemory(export_fn(arg_ptr));
+            };
+        })(),
         exportPrimitiveF32AddThreeAsyncRaw: (() => {
             const export_fn = instance.exports.__fp_gen_export_primitive_f32_add_three_async as any;
             if (!export_fn) return;
@@ -1253,6 +1448,15 @@ export async function createRuntime(
 
             return (arg: number) => promiseFromPtr(export_fn(arg)).then(importFromMemory);
         })(),
+        exportPrimitiveI128AddThreeRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_i128_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: Uint8Array) => {
+                const arg_ptr = exportToMemory(arg);
+                return importFromMemory(export_fn(arg_ptr));
+            };
+        })(),
         exportPrimitiveI16AddThreeRaw: (() => {
             const export_fn = instance.exports.__fp_gen_export_primitive_i16_add_three as any;
             if (!export_fn) return;
@@ -1301,6 +1505,15 @@ export async function createRuntime(
 
             return (arg: number) => promiseFromPtr(export_fn(arg)).then(importFromMemory);
         })(),
+        exportPrimitiveU128AddThreeRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_primitive_u128_add_three as any;
+            if (!export_fn) return;
+
+            return (arg: Uint8Array) => {
+                const arg_ptr = exportToMemory(arg);
+                return importFromMemory(export_fn(arg_ptr));
+            };
+        })(),
         exportPrimitiveU16AddThreeAsyncRaw: (() => {
             const export_fn = instance.exports.__fp_gen_export_primitive_u16_add_three_async as any;
             if (!export_fn) return;
@@ -1325,6 +1538,25 @@ export async function createRuntime(
 
             return (arg: number) => promiseFromPtr(export_fn(arg)).then(importFromMemory);
         })(),
+        exportPrintRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_print as any;
+            if (!export_fn) return;
+
+            return (stdout: Uint8Array, stderr: Uint8Array) => {
+                const stdout_ptr = exportToMemory(stdout);
+                const stderr_ptr = exportToMemory(stderr);
+                export_fn(stdout_ptr, stderr_ptr);
+            };
+        })(),
+        exportReadFileRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_read_file as any;
+            if (!export_fn) return;
+
+            return (path: Uint8Array) => {
+                const path_ptr = exportToMemory(path);
+                return importFromMemory(export_fn(path_ptr));
+            };
+        })(),
         exportResetGlobalStateRaw: (() => {
             const export_fn = instance.exports.__fp_gen_export_reset_global_state as any;
             if (!export_fn) return;
@@ -1412,6 +1644,25 @@ export async function createRuntime(
                 return importFromMemory(export_fn(arg_ptr));
             };
         })(),
+        exportUppercaseStringRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_uppercase_string as any;
+            if (!export_fn) return;
+
+            return (arg: Uint8Array) => {
+                const arg_ptr = exportToMemory(arg);
+                return importFromMemory(export_fn(arg_ptr));
+            };
+        })(),
+        exportWriteFileRaw: (() => {
+            const export_fn = instance.exports.__fp_gen_export_write_file as any;
+            if (!export_fn) return;
+
+            return (path: Uint8Array, contents: Uint8Array) => {
+                const path_ptr = exportToMemory(path);
+                const contents_ptr = exportToMemory(contents);
+                return importFromMemory(export_fn(path_ptr, contents_ptr));
+            };
+        })(),
         fetchDataRaw: (() => {
             const export_fn = instance.exports.__fp_gen_fetch_data as any;
             if (!export_fn) return;
@@ -1433,10 +1684,254 @@ export async function createRuntime(
     };
 }
 
+/**
+ * Returns the names of all the exports that are implemented by the plugin.
+ *
+ * @param exports The functions returned by `createRuntime()`.
+ */
+export function implementedExports(exports: Exports): Array<keyof Exports> {
+    return (Object.keys(exports) as Array<keyof Exports>).filter(
+        (name) => typeof exports[name] === "function"
+    );
+}
+
 function fromFatPtr(fatPtr: FatPtr): [ptr: number, len: number] {
-    return [Number(fatPtr >> 32n), Number(fatPtr & 0xffff_ffffn)];
+    return [Number(fatPtr >> 32n), Number(fatPtr & 0xff_ffffn)];
+}
+
+/**
+ * Verifies the data the fat pointer refers to is encoded as MessagePack, which
+ * is the only wire format supported by the TypeScript runtime.
+ */
+function checkWireFormat(fatPtr: FatPtr) {
+    const wireFormat = Number((fatPtr >> 24n) & 0xffn);
+    if (wireFormat !== 0) {
+        throw new FPRuntimeError(`Plugin used an unsupported wire format: ${wireFormat}`);
+    }
 }
 
 function toFatPtr(ptr: number, len: number): FatPtr {
     return (BigInt(ptr) << 32n) | BigInt(len);
 }
+
+function checkProtocolVersion(found: ProtocolVersion) {
+    if (found.hash === PROTOCOL_VERSION.hash) {
+        return;
+    }
+
+    const foundVersion = formatProtocolVersion(found);
+    const expectedVersion = formatProtocolVersion(PROTOCOL_VERSION);
+    const compatible =
+        found.name && PROTOCOL_VERSION.name
+            ? found.name === PROTOCOL_VERSION.name &&
+              isSemverCompatible(found.version, PROTOCOL_VERSION.version)
+            : undefined;
+    if (compatible === undefined) {
+        console.warn(
+            `Plugin was built against protocol ${foundVersion}, which differs from ${expectedVersion}, and cannot be verified to be compatible`
+        );
+    } else if (compatible) {
+        console.warn(
+            `Plugin was built against protocol ${foundVersion}, which differs from ${expectedVersion}, but should be compatible`
+        );
+    } else {
+        throw new FPRuntimeError(
+            `Plugin was built against protocol ${foundVersion}, which is incompatible with ${expectedVersion}`
+        );
+    }
+}
+
+function formatProtocolVersion({ name, version, hash }: ProtocolVersion): string {
+    return `${name} ${version} (${hash})`;
+}
+
+/**
+ * Versions are compatible if their left-most non-zero component is the same.
+ * Returns `undefined` if either is not a valid semantic version.
+ */
+function isSemverCompatible(a: string, b: string): boolean | undefined {
+    const parse = (version: string) => {
+        const match = /^(\d+)\.(\d+)\.(\d+)([-+].*)?$/.exec(version);
+        return match ? [Number(match[1]), Number(match[2]), Number(match[3])] : undefined;
+    };
+
+    const [aVersion, bVersion] = [parse(a), parse(b)];
+    if (!aVersion || !bVersion) {
+        return undefined;
+    }
+
+    const [aMajor, aMinor, aPatch] = aVersion;
+    const [bMajor, bMinor, bPatch] = bVersion;
+    if (aMajor !== bMajor) {
+        return false;
+    } else if (aMajor > 0) {
+        return true;
+    } else if (aMinor !== bMinor) {
+        return false;
+    } else {
+        return aMinor > 0 || aPatch === bPatch;
+    }
+}
+
+/**
+ * Converts a decoded value into the representation used by the runtime, or
+ * back again before encoding.
+ *
+ * Normalizers are needed because integers that don't require 64 bits are
+ * encoded more compactly, and are therefore decoded as `number`, and because
+ * some types are represented differently at runtime than they are encoded.
+ */
+type Normalizer = (value: any) => any;
+
+const identity: Normalizer = (value) => value;
+
+function normalizeInt64(value: any): any {
+    return typeof value === "number" ? BigInt(value) : value;
+}
+
+function normalizeOption(normalize: Normalizer): Normalizer {
+    return (value) => (value === null || value === undefined ? value : normalize(value));
+}
+
+function normalizeList(normalize: Normalizer): Normalizer {
+    return (value) => value.map((item: any) => normalize(item));
+}
+
+function normalizeMap(normalize: Normalizer): Normalizer {
+    return (value) =>
+        Object.fromEntries(Object.entries(value).map(([key, item]) => [key, normalize(item)]));
+}
+
+function normalizeTuple(normalizers: Array<Normalizer>): Normalizer {
+    return (value) => value.map((item: any, index: number) => normalizers[index](item));
+}
+
+function normalizeFields(normalizers: Record<string, Normalizer>): Normalizer {
+    return (value) => {
+        if (typeof value !== "object" || value === null) {
+            return value;
+        }
+
+        const result = { ...value };
+        for (const [key, normalize] of Object.entries(normalizers)) {
+            if (result[key] !== null && result[key] !== undefined) {
+                result[key] = normalize(result[key]);
+            }
+        }
+        return result;
+    };
+}
+
+function normalizeTagged(tagProp: string, normalizers: Record<string, Normalizer>): Normalizer {
+    return (value) => {
+        const normalize = normalizers[value?.[tagProp]];
+        return normalize ? normalize(value) : value;
+    };
+}
+
+function normalizeAll(...normalizers: Array<Normalizer>): Normalizer {
+    return (value) => normalizers.reduce((result, normalize) => normalize(result), value);
+}
+
+function normalizeU128(value: any): any {
+    if (!(value instanceof Uint8Array)) {
+        return BigInt(value);
+    }
+
+    return value.reduce((result, byte) => (result << 8n) | BigInt(byte), 0n);
+}
+
+function normalizeI128(value: any): any {
+    return BigInt.asIntN(128, normalizeU128(value));
+}
+
+/**
+ * Converts a compact struct, which is encoded as an array of its field values,
+ * into an object. Trailing fields that were omitted are left out.
+ */
+function normalizeCompact(fields: Array<string>): Normalizer {
+    return (value) => {
+        if (!Array.isArray(value)) {
+            return value;
+        }
+
+        const result: Record<string, any> = {};
+        fields.forEach((field, index) => {
+            if (index < value.length) {
+                result[field] = value[index];
+            }
+        });
+        return result;
+    };
+}
+
+function normalizeCompactPoint(value: any): any {
+    return normalizeCompact(["x", "y", "label"])(value);
+}
+
+function normalizeExplicitBoundPoint(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: normalizeT })(value);
+}
+
+function normalizePoint(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: normalizeT })(value);
+}
+
+function normalizeResult(normalizeT: Normalizer, normalizeE: Normalizer): Normalizer {
+    return (value) => normalizeFields({ Ok: normalizeT, Err: normalizeE })(value);
+}
+
+function normalizeStructWithGenerics(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ list: normalizeList(normalizeT), points: normalizeList(normalizePoint(normalizeT)), recursive: normalizeList(normalizePoint(normalizePoint(normalizeT))) })(value);
+}
+
+function denormalizeInt128(value: any): any {
+    // 128-bit integers are encoded as 16 bytes in big-endian order, using
+    // two's complement for negative numbers:
+    let remaining = BigInt.asUintN(128, BigInt(value));
+    const bytes = new Uint8Array(16);
+    for (let index = 15; index >= 0; index--) {
+        bytes[index] = Number(remaining & 0xffn);
+        remaining >>= 8n;
+    }
+    return bytes;
+}
+
+/**
+ * Converts an object into the array a compact struct is encoded as. Trailing
+ * fields beyond `minLength` that are `undefined` are omitted, so the plugin
+ * uses their default values.
+ */
+function denormalizeCompact(fields: Array<string>, minLength: number): Normalizer {
+    return (value) => {
+        if (typeof value !== "object" || value === null || Array.isArray(value)) {
+            return value;
+        }
+
+        let length = fields.length;
+        while (length > minLength && value[fields[length - 1]] === undefined) {
+            length--;
+        }
+        return fields.slice(0, length).map((field) => value[field] ?? null);
+    };
+}
+
+function denormalizeCompactPoint(value: any): any {
+    return denormalizeCompact(["x", "y", "label"], 2)(value);
+}
+
+function denormalizeExplicitBoundPoint(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: denormalizeT })(value);
+}
+
+function denormalizePoint(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: denormalizeT })(value);
+}
+
+function denormalizeResult(denormalizeT: Normalizer, denormalizeE: Normalizer): Normalizer {
+    return (value) => normalizeFields({ Ok: denormalizeT, Err: denormalizeE })(value);
+}
+
+function denormalizeStructWithGenerics(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ list: normalizeList(denormalizeT), points: normalizeList(denormalizePoint(denormalizeT)), recursive: normalizeList(denormalizePoint(denormalizePoint(denormalizeT))) })(value);
+}
@@ -6,6 +6,12 @@
 
 export type Body = ArrayBuffer;
 
+export type CompactPoint = {
+    x: number;
+    y: number;
+    label: string | null;
+};
+
 /**
  * # This is an enum with doc comments.
  */
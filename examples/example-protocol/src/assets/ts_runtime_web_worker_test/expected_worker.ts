@@ -0,0 +1,182 @@
+// ============================================= //
+// Web Worker entry point for TypeScript         //
+//                                               //
+// This file is generated. PLEASE DO NOT MODIFY. //
+// ============================================= //
+// deno-lint-ignore-file no-explicit-any no-unused-vars
+
+import { createRuntime, implementedExports, FPRuntimeError } from "./index";
+import type { Exports, Imports, RuntimeOptions } from "./index";
+import { callSyncImport, formatError } from "./workerRuntime";
+import type { WorkerRequest, WorkerResponse } from "./workerRuntime";
+
+type WorkerScope = {
+    onmessage: ((event: MessageEvent<WorkerRequest>) => void) | null;
+    postMessage(message: WorkerResponse, options?: { transfer?: Array<Transferable> }): void;
+};
+
+const scope = self as unknown as WorkerScope;
+
+const syncImports: Array<keyof Imports> = [
+    "importArrayF32",
+    "importArrayF64",
+    "importArrayI16",
+    "importArrayI32",
+    "importArrayI8",
+    "importArrayU16",
+    "importArrayU32",
+    "importArrayU8",
+    "importBorrowedString",
+    "importExplicitBoundPoint",
+    "importFpAdjacentlyTagged",
+    "importFpEnum",
+    "importFpFlatten",
+    "importFpInternallyTagged",
+    "importFpStruct",
+    "importFpUntagged",
+    "importGenerics",
+    "importGetBytes",
+    "importGetSerdeBytes",
+    "importMultiplePrimitives",
+    "importPrimitiveBoolNegate",
+    "importPrimitiveF32AddOne",
+    "importPrimitiveF32AddOneWasmer2",
+    "importPrimitiveF64AddOne",
+    "importPrimitiveF64AddOneWasmer2",
+    "importPrimitiveI16AddOne",
+    "importPrimitiveI32AddOne",
+    "importPrimitiveI64AddOne",
+    "importPrimitiveI8AddOne",
+    "importPrimitiveU16AddOne",
+    "importPrimitiveU32AddOne",
+    "importPrimitiveU64AddOne",
+    "importPrimitiveU8AddOne",
+    "importSerdeAdjacentlyTagged",
+    "importSerdeEnum",
+    "importSerdeFlatten",
+    "importSerdeInternallyTagged",
+    "importSerdeStruct",
+    "importSerdeUntagged",
+    "importString",
+    "importStructWithOptions",
+    "importTimestamp",
+    "importUppercaseString",
+    "importVoidFunction",
+    "importVoidFunctionEmptyResult",
+    "importVoidFunctionEmptyReturn",
+    "log",
+];
+
+const asyncImports: Array<keyof Imports> = [
+    "importIncrementGlobalState",
+    "importPrimitiveBoolNegateAsync",
+    "importPrimitiveF32AddOneAsync",
+    "importPrimitiveF64AddOneAsync",
+    "importPrimitiveI16AddOneAsync",
+    "importPrimitiveI32AddOneAsync",
+    "importPrimitiveI64AddOneAsync",
+    "importPrimitiveI8AddOneAsync",
+    "importPrimitiveU16AddOneAsync",
+    "importPrimitiveU32AddOneAsync",
+    "importPrimitiveU64AddOneAsync",
+    "importPrimitiveU8AddOneAsync",
+    "importResetGlobalState",
+    "makeHttpRequest",
+];
+
+let exports: Exports | undefined;
+let syncImportBuffer: SharedArrayBuffer | undefined;
+
+let nextImportId = 0;
+const pendingImports = new Map<
+    number,
+    { resolve: (value: any) => void; reject: (error: Error) => void }
+>();
+
+scope.onmessage = (event) => {
+    const message = event.data;
+    switch (message.type) {
+        case "init":
+            syncImportBuffer = message.syncImportBuffer;
+            respond(message.id, () => initialize(message.source, message.options));
+            break;
+        case "call":
+            respond(message.id, () => callExport(message.name, message.args));
+            break;
+        case "importResult": {
+            const pendingImport = pendingImports.get(message.id);
+            if (pendingImport) {
+                pendingImports.delete(message.id);
+                if (message.error !== undefined) {
+                    pendingImport.reject(new FPRuntimeError(message.error));
+                } else {
+                    pendingImport.resolve(message.value);
+                }
+            }
+            break;
+        }
+    }
+};
+
+async function initialize(source: ArrayBuffer, options?: RuntimeOptions): Promise<Array<string>> {
+    exports = await createRuntime(
+        new Response(source, { headers: { "Content-Type": "application/wasm" } }),
+        createImportFunctions(), options
+    );
+    return implementedExports(exports);
+}
+
+function callExport(name: string, args: Array<unknown>): unknown {
+    const exportFunction = exports?.[name as keyof Exports] as
+        | ((...args: Array<unknown>) => unknown)
+        | undefined;
+    if (!exportFunction) {
+        throw new FPRuntimeError(`Plugin does not implement export: "${name}"`);
+    }
+    return exportFunction(...args);
+}
+
+/**
+ * Sends the result of the given handler back to the main thread. Raw
+ * MessagePack data is transferred rather than copied.
+ */
+function respond(id: number, handler: () => unknown) {
+    Promise.resolve()
+        .then(handler)
+        .then(
+            (value) =>
+                scope.postMessage(
+                    { type: "result", id, value },
+                    { transfer: value instanceof Uint8Array ? [value.buffer] : [] }
+                ),
+            (error) => scope.postMessage({ type: "result", id, error: formatError(error) })
+        );
+}
+
+/**
+ * Creates import functions that forward their calls to the main thread.
+ */
+function createImportFunctions(): Imports {
+    const importFunctions: Record<string, (...args: Array<unknown>) => unknown> = {};
+    for (const name of syncImports) {
+        importFunctions[name] = (...args) => {
+            if (!syncImportBuffer) {
+                throw new FPRuntimeError(
+                    `Cannot call synchronous import function "${name}" without SharedArrayBuffer`
+                );
+            }
+            return callSyncImport(syncImportBuffer, () =>
+                scope.postMessage({ type: "syncImport", name, args })
+            );
+        };
+    }
+    for (const name of asyncImports) {
+        importFunctions[name] = (...args) =>
+            new Promise((resolve, reject) => {
+                const id = nextImportId++;
+                pendingImports.set(id, { resolve, reject });
+                scope.postMessage({ type: "asyncImport", id, name, args });
+            });
+    }
+    return importFunctions as unknown as Imports;
+}
@@ -0,0 +1,1107 @@
+// ============================================= //
+// Web Worker runtime for TypeScript             //
+//                                               //
+// This file is generated. PLEASE DO NOT MODIFY. //
+// ============================================= //
+// deno-lint-ignore-file no-explicit-any no-unused-vars
+
+import { encode, decode } from "@msgpack/msgpack";
+
+import { FPRuntimeError, ValidationError } from "./index";
+import type { Imports, RuntimeOptions } from "./index";
+import type * as types from "./types";
+
+/**
+ * The functions exported by a plugin that runs inside a worker.
+ *
+ * These are the same as those returned by `createRuntime()`, except that they
+ * always return a `Promise`.
+ */
+export type WorkerExports = {
+    exportArrayF32?: (arg: Float32Array) => Promise<Float32Array>;
+    exportArrayF64?: (arg: Float64Array) => Promise<Float64Array>;
+    exportArrayI16?: (arg: Int16Array) => Promise<Int16Array>;
+    exportArrayI32?: (arg: Int32Array) => Promise<Int32Array>;
+    exportArrayI8?: (arg: Int8Array) => Promise<Int8Array>;
+    exportArrayU16?: (arg: Uint16Array) => Promise<Uint16Array>;
+    exportArrayU32?: (arg: Uint32Array) => Promise<Uint32Array>;
+    exportArrayU8?: (arg: Uint8Array) => Promise<Uint8Array>;
+    exportAsyncStruct?: (arg1: types.FpPropertyRenaming, arg2: bigint) => Promise<types.FpPropertyRenaming>;
+    exportBorrowedBytes?: (arg: Array<number>) => Promise<Array<number>>;
+    exportBorrowedString?: (arg: string) => Promise<string>;
+    exportCompactPoint?: (arg: types.CompactPoint) => Promise<types.CompactPoint>;
+    exportFpAdjacentlyTagged?: (arg: types.FpAdjacentlyTagged) => Promise<types.FpAdjacentlyTagged>;
+    exportFpEnum?: (arg: types.FpVariantRenaming) => Promise<types.FpVariantRenaming>;
+    exportFpFlatten?: (arg: types.FpFlatten) => Promise<types.FpFlatten>;
+    exportFpInternallyTagged?: (arg: types.FpInternallyTagged) => Promise<types.FpInternallyTagged>;
+    exportFpStruct?: (arg: types.FpPropertyRenaming) => Promise<types.FpPropertyRenaming>;
+    exportFpUntagged?: (arg: types.FpUntagged) => Promise<types.FpUntagged>;
+    exportGenerics?: (arg: types.StructWithGenerics<number>) => Promise<types.StructWithGenerics<number>>;
+    exportGetBytes?: () => Promise<types.Result<Uint8Array, string>>;
+    exportGetSerdeBytes?: () => Promise<types.Result<ArrayBuffer, string>>;
+    exportIncrementGlobalState?: () => Promise<void>;
+    exportMultiplePrimitives?: (arg1: number, arg2: string) => Promise<bigint>;
+    exportPrimitiveBoolNegate?: (arg: boolean) => Promise<boolean>;
+    exportPrimitiveBoolNegateAsync?: (arg: boolean) => Promise<boolean>;
+    exportPrimitiveCharToUppercase?: (arg: string) => Promise<string>;
+    exportPrimitiveF32AddThree?: (arg: number) => Promise<number>;
+    exportPrimitiveF32AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveF32AddThreeWasmer2?: (arg: number) => Promise<number>;
+    exportPrimitiveF64AddThree?: (arg: number) => Promise<number>;
+    exportPrimitiveF64AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveF64AddThreeWasmer2?: (arg: number) => Promise<number>;
+    exportPrimitiveI128AddThree?: (arg: bigint) => Promise<bigint>;
+    exportPrimitiveI16AddThree?: (arg: number) => Promise<number>;
+    exportPrimitiveI16AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveI32AddThree?: (arg: number) => Promise<number>;
+    exportPrimitiveI32AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveI64AddThree?: (arg: bigint) => Promise<bigint>;
+    exportPrimitiveI64AddThreeAsync?: (arg: bigint) => Promise<number>;
+    exportPrimitiveI8AddThree?: (arg: number) => Promise<number>;
+    exportPrimitiveI8AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveU128AddThree?: (arg: bigint) => Promise<bigint>;
+    exportPrimitiveU16AddThree?: (arg: number) => Promise<number>;
+    exportPrimitiveU16AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveU32AddThree?: (arg: number) => Promise<number>;
+    exportPrimitiveU32AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrimitiveU64AddThree?: (arg: bigint) => Promise<bigint>;
+    exportPrimitiveU64AddThreeAsync?: (arg: bigint) => Promise<number>;
+    exportPrimitiveU8AddThree?: (arg: number) => Promise<number>;
+    exportPrimitiveU8AddThreeAsync?: (arg: number) => Promise<number>;
+    exportPrint?: (stdout: string, stderr: string) => Promise<void>;
+    exportReadFile?: (path: string) => Promise<types.Result<string, string>>;
+    exportResetGlobalState?: () => Promise<void>;
+    exportSerdeAdjacentlyTagged?: (arg: types.SerdeAdjacentlyTagged) => Promise<types.SerdeAdjacentlyTagged>;
+    exportSerdeEnum?: (arg: types.SerdeVariantRenaming) => Promise<types.SerdeVariantRenaming>;
+    exportSerdeFlatten?: (arg: types.SerdeFlatten) => Promise<types.SerdeFlatten>;
+    exportSerdeInternallyTagged?: (arg: types.SerdeInternallyTagged) => Promise<types.SerdeInternallyTagged>;
+    exportSerdeStruct?: (arg: types.SerdePropertyRenaming) => Promise<types.SerdePropertyRenaming>;
+    exportSerdeUntagged?: (arg: types.SerdeUntagged) => Promise<types.SerdeUntagged>;
+    exportString?: (arg: string) => Promise<string>;
+    exportStructWithOptions?: (arg: types.StructWithOptions) => Promise<types.StructWithOptions>;
+    exportTimestamp?: (arg: types.MyDateTime) => Promise<types.MyDateTime>;
+    exportUppercaseString?: (arg: string) => Promise<string>;
+    exportVoidFunction?: () => Promise<void>;
+    exportWriteFile?: (path: string, contents: string) => Promise<types.Result<void, string>>;
+    fetchData?: (rType: string) => Promise<types.Result<string, string>>;
+    init?: () => Promise<void>;
+    reducerBridge?: (action: types.ReduxAction) => Promise<types.StateUpdate>;
+};
+
+/**
+ * Options for creating a worker runtime.
+ */
+export type WorkerRuntimeOptions = RuntimeOptions & {
+    /**
+     * Size of the buffer through which the results of synchronous import
+     * functions are passed to the worker, in bytes. Defaults to 1 MiB, and
+     * cannot be less than 1 KiB.
+     */
+    syncImportBufferSize?: number;
+};
+
+/**
+ * Messages sent from the main thread to the worker.
+ */
+export type WorkerRequest =
+    | {
+          type: "init";
+          id: number;
+          source: ArrayBuffer;
+          options: RuntimeOptions;
+          syncImportBuffer?: SharedArrayBuffer;
+      }
+    | { type: "call"; id: number; name: string; args: Array<unknown> }
+    | { type: "importResult"; id: number; value?: unknown; error?: string };
+
+/**
+ * Messages sent from the worker to the main thread.
+ */
+export type WorkerResponse =
+    | { type: "result"; id: number; value?: unknown; error?: string }
+    | { type: "syncImport"; name: string; args: Array<unknown> }
+    | { type: "asyncImport"; id: number; name: string; args: Array<unknown> };
+
+/**
+ * The result of a synchronous import function, as passed to the worker.
+ */
+export type SyncImportResult = { value: unknown } | { error: string };
+
+type PendingRequest = { resolve: (value: any) => void; reject: (error: Error) => void };
+
+/**
+ * Creates a runtime that executes the given plugin inside a Web Worker.
+ *
+ * The worker must be created from the `worker.ts` module that is generated
+ * alongside this one, for instance:
+ *
+ * ```ts
+ * const worker = new Worker(new URL("./worker.ts", import.meta.url), { type: "module" });
+ * ```
+ *
+ * @param worker The worker in which to run the plugin.
+ * @param source The response for fetching the WASM plugin.
+ * @param importFunctions The host functions that may be imported by the plugin.
+ * @param options Options for the runtime.
+ * @returns The functions that may be exported by the plugin.
+ */
+export async function createWorkerRuntime(
+    worker: Worker,
+    source: Response | Promise<Response>,
+    importFunctions: Imports,
+    options: WorkerRuntimeOptions = {}
+): Promise<WorkerExports> {
+    let nextRequestId = 0;
+    const pendingRequests = new Map<number, PendingRequest>();
+
+    // Synchronous import functions block the worker until their result has
+    // been written to this buffer, which requires `SharedArrayBuffer`:
+    const syncImportBuffer =
+        typeof SharedArrayBuffer !== "undefined"
+            ? new SharedArrayBuffer(
+                  SYNC_DATA_OFFSET + Math.max(options.syncImportBufferSize ?? 1024 * 1024, 1024)
+              )
+            : undefined;
+
+    function request<T>(message: WorkerRequest, transfer: Array<Transferable> = []): Promise<T> {
+        return new Promise((resolve, reject) => {
+            pendingRequests.set(message.id, { resolve, reject });
+            worker.postMessage(message, { transfer });
+        });
+    }
+
+    function call<T>(name: string, args: Array<unknown>): Promise<T> {
+        const transfer = args.flatMap((arg) => (arg instanceof Uint8Array ? [arg.buffer] : []));
+        return request({ type: "call", id: nextRequestId++, name, args }, transfer);
+    }
+
+    function callImport(name: string, args: Array<unknown>): unknown {
+        const importFunction = importFunctions[name as keyof Imports] as unknown as
+            | ((...args: Array<unknown>) => unknown)
+            | undefined;
+        if (!importFunction) {
+            throw new FPRuntimeError(`Plugin called unknown import function: "${name}"`);
+        }
+        return importFunction(...args);
+    }
+
+    function parseObject<T>(copy: Uint8Array, validate: Validator = validateAny, path = "value", normalize: Normalizer = identity): T {
+        const object = decode(copy);
+        if (options.validate !== false) {
+            validate(object, path);
+        }
+        return normalize(object) as T;
+    }
+
+    function serializeObject<T>(object: T, denormalize: Normalizer = identity): Uint8Array {
+        // The encoded data is copied, so we can be sure the buffer we
+        // transfer to the worker isn't shared with anything else:
+        return encode(denormalize(object)).slice();
+    }
+
+    worker.onmessage = (event: MessageEvent<WorkerResponse>) => {
+        const message = event.data;
+        switch (message.type) {
+            case "result": {
+                const pendingRequest = pendingRequests.get(message.id);
+                if (pendingRequest) {
+                    pendingRequests.delete(message.id);
+                    if (message.error !== undefined) {
+                        pendingRequest.reject(new FPRuntimeError(message.error));
+                    } else {
+                        pendingRequest.resolve(message.value);
+                    }
+                }
+                break;
+            }
+            case "syncImport": {
+                let result: SyncImportResult;
+                try {
+                    result = { value: callImport(message.name, message.args) };
+                } catch (error) {
+                    result = { error: formatError(error) };
+                }
+                writeSyncImportResult(syncImportBuffer!, result);
+                break;
+            }
+            case "asyncImport": {
+                const { id } = message;
+                Promise.resolve()
+                    .then(() => callImport(message.name, message.args))
+                    .then(
+                        (value) => worker.postMessage({ type: "importResult", id, value }),
+                        (error) =>
+                            worker.postMessage({
+                                type: "importResult",
+                                id,
+                                error: formatError(error),
+                            })
+                    );
+                break;
+            }
+        }
+    };
+
+    worker.onerror = (event: ErrorEvent) => {
+        const error = new FPRuntimeError(`Error in plugin worker: ${event.message}`);
+        for (const pendingRequest of pendingRequests.values()) {
+            pendingRequest.reject(error);
+        }
+        pendingRequests.clear();
+    };
+
+    const buffer = await (await source).arrayBuffer();
+    const implemented = await request<Array<string>>(
+        {
+            type: "init",
+            id: nextRequestId++,
+            source: buffer,
+            options,
+            syncImportBuffer,
+        },
+        [buffer]
+    );
+
+    const exports: WorkerExports = {
+        exportArrayF32: async (arg: Float32Array) => {
+            const result = await call<Uint8Array>("exportArrayF32Raw", [serializeObject(Array.from(arg))]);
+            return parseObject<Float32Array>(result, validateArray(3), "exportArrayF32()");
+        },
+        exportArrayF64: async (arg: Float64Array) => {
+            const result = await call<Uint8Array>("exportArrayF64Raw", [serializeObject(Array.from(arg))]);
+            return parseObject<Float64Array>(result, validateArray(3), "exportArrayF64()");
+        },
+        exportArrayI16: async (arg: Int16Array) => {
+            const result = await call<Uint8Array>("exportArrayI16Raw", [serializeObject(Array.from(arg))]);
+            return parseObject<Int16Array>(result, validateArray(3), "exportArrayI16()");
+        },
+        exportArrayI32: async (arg: Int32Array) => {
+            const result = await call<Uint8Array>("exportArrayI32Raw", [serializeObject(Array.from(arg))]);
+            return parseObject<Int32Array>(result, validateArray(3), "exportArrayI32()");
+        },
+        exportArrayI8: async (arg: Int8Array) => {
+            const result = await call<Uint8Array>("exportArrayI8Raw", [serializeObject(Array.from(arg))]);
+            return parseObject<Int8Array>(result, validateArray(3), "exportArrayI8()");
+        },
+        exportArrayU16: async (arg: Uint16Array) => {
+            const result = await call<Uint8Array>("exportArrayU16Raw", [serializeObject(Array.from(arg))]);
+            return parseObject<Uint16Array>(result, validateArray(3), "exportArrayU16()");
+        },
+        exportArrayU32: async (arg: Uint32Array) => {
+            const result = await call<Uint8Array>("exportArrayU32Raw", [serializeObject(Array.from(arg))]);
+            return parseObject<Uint32Array>(result, validateArray(3), "exportArrayU32()");
+        },
+        exportArrayU8: async (arg: Uint8Array) => {
+            const result = await call<Uint8Array>("exportArrayU8Raw", [serializeObject(Array.from(arg))]);
+            return parseObject<Uint8Array>(result, validateArray(3), "exportArrayU8()");
+        },
+        exportAsyncStruct: async (arg1: types.FpPropertyRenaming, arg2: bigint) => {
+            const result = await call<Uint8Array>("exportAsyncStructRaw", [serializeObject(arg1), arg2]);
+            return parseObject<types.FpPropertyRenaming>(result, validateFpPropertyRenaming, "exportAsyncStruct()");
+        },
+        exportBorrowedBytes: async (arg: Array<number>) => {
+            const result = await call<Uint8Array>("exportBorrowedBytesRaw", [serializeObject(arg)]);
+            return parseObject<Array<number>>(result, validateList(validateU8), "exportBorrowedBytes()");
+        },
+        exportBorrowedString: async (arg: string) => {
+            const result = await call<Uint8Array>("exportBorrowedStringRaw", [serializeObject(arg)]);
+            return parseObject<string>(result, validateString, "exportBorrowedString()");
+        },
+        exportCompactPoint: async (arg: types.CompactPoint) => {
+            const result = await call<Uint8Array>("exportCompactPointRaw", [serializeObject(arg, denormalizeCompactPoint)]);
+            return parseObject<types.CompactPoint>(result, validateCompactPoint, "exportCompactPoint()", normalizeCompactPoint);
+        },
+        exportFpAdjacentlyTagged: async (arg: types.FpAdjacentlyTagged) => {
+            const result = await call<Uint8Array>("exportFpAdjacentlyTaggedRaw", [serializeObject(arg)]);
+            return parseObject<types.FpAdjacentlyTagged>(result, validateFpAdjacentlyTagged, "exportFpAdjacentlyTagged()");
+        },
+        exportFpEnum: async (arg: types.FpVariantRenaming) => {
+            const result = await call<Uint8Array>("exportFpEnumRaw", [serializeObject(arg)]);
+            return parseObject<types.FpVariantRenaming>(result, validateFpVariantRenaming, "exportFpEnum()");
+        },
+        exportFpFlatten: async (arg: types.FpFlatten) => {
+            const result = await call<Uint8Array>("exportFpFlattenRaw", [serializeObject(arg)]);
+            return parseObject<types.FpFlatten>(result, validateFpFlatten, "exportFpFlatten()");
+        },
+        exportFpInternallyTagged: async (arg: types.FpInternallyTagged) => {
+            const result = await call<Uint8Array>("exportFpInternallyTaggedRaw", [serializeObject(arg)]);
+            return parseObject<types.FpInternallyTagged>(result, validateFpInternallyTagged, "exportFpInternallyTagged()");
+        },
+        exportFpStruct: async (arg: types.FpPropertyRenaming) => {
+            const result = await call<Uint8Array>("exportFpStructRaw", [serializeObject(arg)]);
+            return parseObject<types.FpPropertyRenaming>(result, validateFpPropertyRenaming, "exportFpStruct()");
+        },
+        exportFpUntagged: async (arg: types.FpUntagged) => {
+            const result = await call<Uint8Array>("exportFpUntaggedRaw", [serializeObject(arg)]);
+            return parseObject<types.FpUntagged>(result, validateFpUntagged, "exportFpUntagged()");
+        },
+        exportGenerics: async (arg: types.StructWithGenerics<number>) => {
+            const result = await call<Uint8Array>("exportGenericsRaw", [serializeObject(arg)]);
+            return parseObject<types.StructWithGenerics<number>>(result, validateStructWithGenerics(validateU64), "exportGenerics()");
+        },
+        exportGetBytes: async () => {
+            const result = await call<Uint8Array>("exportGetBytesRaw", []);
+            return parseObject<types.Result<Uint8Array, string>>(result, validateResult(validateAny, validateString), "exportGetBytes()");
+        },
+        exportGetSerdeBytes: async () => {
+            const result = await call<Uint8Array>("exportGetSerdeBytesRaw", []);
+            return parseObject<types.Result<ArrayBuffer, string>>(result, validateResult(validateAny, validateString), "exportGetSerdeBytes()");
+        },
+        exportIncrementGlobalState: async () => {
+            const result = await call<Uint8Array>("exportIncrementGlobalStateRaw", []);
+            return parseObject<void>(result, validateUnit, "exportIncrementGlobalState()");
+        },
+        exportMultiplePrimitives: (arg1: number, arg2: string) => call<bigint>("exportMultiplePrimitivesRaw", [arg1, serializeObject(arg2)]),
+        exportPrimitiveBoolNegate: (arg: boolean) => call<boolean>("exportPrimitiveBoolNegateRaw", [arg]),
+        exportPrimitiveBoolNegateAsync: async (arg: boolean) => {
+            const result = await call<Uint8Array>("exportPrimitiveBoolNegateAsyncRaw", [arg]);
+            return parseObject<boolean>(result, validateBoolean, "exportPrimitiveBoolNegateAsync()");
+        },
+        exportPrimitiveCharToUppercase: async (arg: string) => {
+            const result = await call<Uint8Array>("exportPrimitiveCharToUppercaseRaw", [serializeObject(arg)]);
+            return parseObject<string>(result, validateChar, "exportPrimitiveCharToUppercase()");
+        },
+        exportPrimitiveF32AddThree: (arg: number) => call<number>("exportPrimitiveF32AddThree", [arg]),
+        exportPrimitiveF32AddThreeAsync: async (arg: number) => {
+            const result = await call<Uint8Array>("exportPrimitiveF32AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateNumber, "exportPrimitiveF32AddThreeAsync()");
+        },
+        exportPrimitiveF32AddThreeWasmer2: (arg: number) => call<number>("exportPrimitiveF32AddThreeWasmer2", [arg]),
+        exportPrimitiveF64AddThree: (arg: number) => call<number>("exportPrimitiveF64AddThree", [arg]),
+        exportPrimitiveF64AddThreeAsync: async (arg: number) => {
+            const result = await call<Uint8Array>("exportPrimitiveF64AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateNumber, "exportPrimitiveF64AddThreeAsync()");
+        },
+        exportPrimitiveF64AddThreeWasmer2: (arg: number) => call<number>("exportPrimitiveF64AddThreeWasmer2", [arg]),
+        exportPrimitiveI128AddThree: async (arg: bigint) => {
+            const result = await call<Uint8Array>("exportPrimitiveI128AddThreeRaw", [serializeObject(arg, denormalizeInt128)]);
+            return parseObject<bigint>(result, validateI128, "exportPrimitiveI128AddThree()", normalizeI128);
+        },
+        exportPrimitiveI16AddThree: (arg: number) => call<number>("exportPrimitiveI16AddThreeRaw", [arg]),
+        exportPrimitiveI16AddThreeAsync: async (arg: number) => {
+            const result = await call<Uint8Array>("exportPrimitiveI16AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateI16, "exportPrimitiveI16AddThreeAsync()");
+        },
+        exportPrimitiveI32AddThree: (arg: number) => call<number>("exportPrimitiveI32AddThreeRaw", [arg]),
+        exportPrimitiveI32AddThreeAsync: async (arg: number) => {
+            const result = await call<Uint8Array>("exportPrimitiveI32AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateI32, "exportPrimitiveI32AddThreeAsync()");
+        },
+        exportPrimitiveI64AddThree: (arg: bigint) => call<bigint>("exportPrimitiveI64AddThreeRaw", [arg]),
+        exportPrimitiveI64AddThreeAsync: async (arg: bigint) => {
+            const result = await call<Uint8Array>("exportPrimitiveI64AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateI64, "exportPrimitiveI64AddThreeAsync()");
+        },
+        exportPrimitiveI8AddThree: (arg: number) => call<number>("exportPrimitiveI8AddThreeRaw", [arg]),
+        exportPrimitiveI8AddThreeAsync: async (arg: number) => {
+            const result = await call<Uint8Array>("exportPrimitiveI8AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateI8, "exportPrimitiveI8AddThreeAsync()");
+        },
+        exportPrimitiveU128AddThree: async (arg: bigint) => {
+            const result = await call<Uint8Array>("exportPrimitiveU128AddThreeRaw", [serializeObject(arg, denormalizeInt128)]);
+            return parseObject<bigint>(result, validateU128, "exportPrimitiveU128AddThree()", normalizeU128);
+        },
+        exportPrimitiveU16AddThree: (arg: number) => call<number>("exportPrimitiveU16AddThree", [arg]),
+        exportPrimitiveU16AddThreeAsync: async (arg: number) => {
+            const result = await call<Uint8Array>("exportPrimitiveU16AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateU16, "exportPrimitiveU16AddThreeAsync()");
+        },
+        exportPrimitiveU32AddThree: (arg: number) => call<number>("exportPrimitiveU32AddThree", [arg]),
+        exportPrimitiveU32AddThreeAsync: async (arg: number) => {
+            const result = await call<Uint8Array>("exportPrimitiveU32AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateU32, "exportPrimitiveU32AddThreeAsync()");
+        },
+        exportPrimitiveU64AddThree: (arg: bigint) => call<bigint>("exportPrimitiveU64AddThree", [arg]),
+        exportPrimitiveU64AddThreeAsync: async (arg: bigint) => {
+            const result = await call<Uint8Array>("exportPrimitiveU64AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateU64, "exportPrimitiveU64AddThreeAsync()");
+        },
+        exportPrimitiveU8AddThree: (arg: number) => call<number>("exportPrimitiveU8AddThree", [arg]),
+        exportPrimitiveU8AddThreeAsync: async (arg: number) => {
+            const result = await call<Uint8Array>("exportPrimitiveU8AddThreeAsyncRaw", [arg]);
+            return parseObject<number>(result, validateU8, "exportPrimitiveU8AddThreeAsync()");
+        },
+        exportPrint: (stdout: string, stderr: string) => call<void>("exportPrintRaw", [serializeObject(stdout), serializeObject(stderr)]),
+        exportReadFile: async (path: string) => {
+            const result = await call<Uint8Array>("exportReadFileRaw", [serializeObject(path)]);
+            return parseObject<types.Result<string, string>>(result, validateResult(validateString, validateString), "exportReadFile()");
+        },
+        exportResetGlobalState: async () => {
+            const result = await call<Uint8Array>("exportResetGlobalStateRaw", []);
+            return parseObject<void>(result, validateUnit, "exportResetGlobalState()");
+        },
+        exportSerdeAdjacentlyTagged: async (arg: types.SerdeAdjacentlyTagged) => {
+            const result = await call<Uint8Array>("exportSerdeAdjacentlyTaggedRaw", [serializeObject(arg)]);
+            return parseObject<types.SerdeAdjacentlyTagged>(result, validateSerdeAdjacentlyTagged, "exportSerdeAdjacentlyTagged()");
+        },
+        exportSerdeEnum: async (arg: types.SerdeVariantRenaming) => {
+            const result = await call<Uint8Array>("exportSerdeEnumRaw", [serializeObject(arg)]);
+            return parseObject<types.SerdeVariantRenaming>(result, validateSerdeVariantRenaming, "exportSerdeEnum()");
+        },
+        exportSerdeFlatten: async (arg: types.SerdeFlatten) => {
+            const result = await call<Uint8Array>("exportSerdeFlattenRaw", [serializeObject(arg)]);
+            return parseObject<types.SerdeFlatten>(result, validateSerdeFlatten, "exportSerdeFlatten()");
+        },
+        exportSerdeInternallyTagged: async (arg: types.SerdeInternallyTagged) => {
+            const result = await call<Uint8Array>("exportSerdeInternallyTaggedRaw", [serializeObject(arg)]);
+            return parseObject<types.SerdeInternallyTagged>(result, validateSerdeInternallyTagged, "exportSerdeInternallyTagged()");
+        },
+        exportSerdeStruct: async (arg: types.SerdePropertyRenaming) => {
+            const result = await call<Uint8Array>("exportSerdeStructRaw", [serializeObject(arg)]);
+            return parseObject<types.SerdePropertyRenaming>(result, validateSerdePropertyRenaming, "exportSerdeStruct()");
+        },
+        exportSerdeUntagged: async (arg: types.SerdeUntagged) => {
+            const result = await call<Uint8Array>("exportSerdeUntaggedRaw", [serializeObject(arg)]);
+            return parseObject<types.SerdeUntagged>(result, validateSerdeUntagged, "exportSerdeUntagged()");
+        },
+        exportString: async (arg: string) => {
+            const result = await call<Uint8Array>("exportStringRaw", [serializeObject(arg)]);
+            return parseObject<string>(result, validateString, "exportString()");
+        },
+        exportStructWithOptions: async (arg: types.StructWithOptions) => {
+            const result = await call<Uint8Array>("exportStructWithOptionsRaw", [serializeObject(arg)]);
+            return parseObject<types.StructWithOptions>(result, validateStructWithOptions, "exportStructWithOptions()");
+        },
+        exportTimestamp: async (arg: types.MyDateTime) => {
+            const result = await call<Uint8Array>("exportTimestampRaw", [serializeObject(arg)]);
+            return parseObject<types.MyDateTime>(result, validateMyDateTime, "exportTimestamp()");
+        },
+        exportUppercaseString: async (arg: string) => {
+            const result = await call<Uint8Array>("exportUppercaseStringRaw", [serializeObject(arg)]);
+            return parseObject<string>(result, validateString, "exportUppercaseString()");
+        },
+        exportVoidFunction: () => call<void>("exportVoidFunction", []),
+        exportWriteFile: async (path: string, contents: string) => {
+            const result = await call<Uint8Array>("exportWriteFileRaw", [serializeObject(path), serializeObject(contents)]);
+            return parseObject<types.Result<void, string>>(result, validateResult(validateUnit, validateString), "exportWriteFile()");
+        },
+        fetchData: async (rType: string) => {
+            const result = await call<Uint8Array>("fetchDataRaw", [serializeObject(rType)]);
+            return parseObject<types.Result<string, string>>(result, validateResult(validateString, validateString), "fetchData()");
+        },
+        init: () => call<void>("init", []),
+        reducerBridge: async (action: types.ReduxAction) => {
+            const result = await call<Uint8Array>("reducerBridgeRaw", [serializeObject(action)]);
+            return parseObject<types.StateUpdate>(result, validateStateUpdate, "reducerBridge()");
+        },
+    };
+
+    return Object.fromEntries(
+        Object.entries(exports).filter(([name]) => implemented.includes(name))
+    ) as WorkerExports;
+}
+
+/**
+ * Formats an error for passing it between threads.
+ */
+export function formatError(error: unknown): string {
+    return error instanceof Error ? error.message : String(error);
+}
+
+// The buffer for the results of synchronous import functions starts with a
+// header of 32-bit integers, followed by a slot for `bigint` values and the
+// MessagePack encoding of all other values.
+const SYNC_HEADER_LENGTH = 4;
+const SYNC_STATE_INDEX = 0;
+const SYNC_KIND_INDEX = 1;
+const SYNC_LENGTH_INDEX = 2;
+const SYNC_BIGINT_OFFSET = 16;
+const SYNC_DATA_OFFSET = 24;
+
+const SYNC_STATE_PENDING = 0;
+const SYNC_STATE_DONE = 1;
+
+const SYNC_KIND_UNDEFINED = 0;
+const SYNC_KIND_BIGINT = 1;
+const SYNC_KIND_ENCODED = 2;
+const SYNC_KIND_ERROR = 3;
+
+/**
+ * Calls a synchronous import function from the worker, by sending a request
+ * to the main thread and blocking until it has written the result.
+ *
+ * @param buffer The buffer shared with the main thread.
+ * @param sendRequest Sends the request for calling the import function.
+ */
+export function callSyncImport(buffer: SharedArrayBuffer, sendRequest: () => void): unknown {
+    const header = new Int32Array(buffer, 0, SYNC_HEADER_LENGTH);
+    Atomics.store(header, SYNC_STATE_INDEX, SYNC_STATE_PENDING);
+    sendRequest();
+    Atomics.wait(header, SYNC_STATE_INDEX, SYNC_STATE_PENDING);
+
+    const readData = () =>
+        new Uint8Array(buffer, SYNC_DATA_OFFSET, header[SYNC_LENGTH_INDEX]).slice();
+    switch (header[SYNC_KIND_INDEX]) {
+        case SYNC_KIND_BIGINT:
+            return new BigInt64Array(buffer, SYNC_BIGINT_OFFSET, 1)[0];
+        case SYNC_KIND_ENCODED:
+            return decode(readData());
+        case SYNC_KIND_ERROR:
+            throw new FPRuntimeError(decode(readData()) as string);
+        default:
+            return undefined;
+    }
+}
+
+/**
+ * Writes the result of a synchronous import function for the worker, and
+ * wakes it up.
+ *
+ * @param buffer The buffer shared with the worker.
+ * @param result The result of the import function.
+ */
+export function writeSyncImportResult(buffer: SharedArrayBuffer, result: SyncImportResult) {
+    const header = new Int32Array(buffer, 0, SYNC_HEADER_LENGTH);
+    const writeData = (kind: number, data: Uint8Array) => {
+        const capacity = buffer.byteLength - SYNC_DATA_OFFSET;
+        if (data.length > capacity) {
+            kind = SYNC_KIND_ERROR;
+            data = encode(`Result of synchronous import function exceeds ${capacity} bytes`);
+        }
+        new Uint8Array(buffer, SYNC_DATA_OFFSET, data.length).set(data);
+        header[SYNC_KIND_INDEX] = kind;
+        header[SYNC_LENGTH_INDEX] = data.length;
+    };
+
+    if ("error" in result) {
+        writeData(SYNC_KIND_ERROR, encode(result.error));
+    } else if (result.value === undefined) {
+        header[SYNC_KIND_INDEX] = SYNC_KIND_UNDEFINED;
+    } else if (typeof result.value === "bigint") {
+        // Primitive 64-bit integers are always represented as `bigint`, even
+        // if MessagePack values are not:
+        new BigInt64Array(buffer, SYNC_BIGINT_OFFSET, 1)[0] = BigInt.asIntN(64, result.value);
+        header[SYNC_KIND_INDEX] = SYNC_KIND_BIGINT;
+    } else {
+        writeData(SYNC_KIND_ENCODED, encode(result.value));
+    }
+
+    Atomics.store(header, SYNC_STATE_INDEX, SYNC_STATE_DONE);
+    Atomics.notify(header, SYNC_STATE_INDEX);
+}
+
+/**
+ * Converts a decoded value into the representation used by the runtime, or
+ * back again before encoding.
+ *
+ * Normalizers are needed because integers that don't require 64 bits are
+ * encoded more compactly, and are therefore decoded as `number`, and because
+ * some types are represented differently at runtime than they are encoded.
+ */
+type Normalizer = (value: any) => any;
+
+const identity: Normalizer = (value) => value;
+
+function normalizeInt64(value: any): any {
+    return typeof value === "number" ? BigInt(value) : value;
+}
+
+function normalizeOption(normalize: Normalizer): Normalizer {
+    return (value) => (value === null || value === undefined ? value : normalize(value));
+}
+
+function normalizeList(normalize: Normalizer): Normalizer {
+    return (value) => value.map((item: any) => normalize(item));
+}
+
+function normalizeMap(normalize: Normalizer): Normalizer {
+    return (value) =>
+        Object.fromEntries(Object.entries(value).map(([key, item]) => [key, normalize(item)]));
+}
+
+function normalizeTuple(normalizers: Array<Normalizer>): Normalizer {
+    return (value) => value.map((item: any, index: number) => normalizers[index](item));
+}
+
+function normalizeFields(normalizers: Record<string, Normalizer>): Normalizer {
+    return (value) => {
+        if (typeof value !== "object" || value === null) {
+            return value;
+        }
+
+        const result = { ...value };
+        for (const [key, normalize] of Object.entries(normalizers)) {
+            if (result[key] !== null && result[key] !== undefined) {
+                result[key] = normalize(result[key]);
+            }
+        }
+        return result;
+    };
+}
+
+function normalizeTagged(tagProp: string, normalizers: Record<string, Normalizer>): Normalizer {
+    return (value) => {
+        const normalize = normalizers[value?.[tagProp]];
+        return normalize ? normalize(value) : value;
+    };
+}
+
+function normalizeAll(...normalizers: Array<Normalizer>): Normalizer {
+    return (value) => normalizers.reduce((result, normalize) => normalize(result), value);
+}
+
+function normalizeU128(value: any): any {
+    if (!(value instanceof Uint8Array)) {
+        return BigInt(value);
+    }
+
+    return value.reduce((result, byte) => (result << 8n) | BigInt(byte), 0n);
+}
+
+function normalizeI128(value: any): any {
+    return BigInt.asIntN(128, normalizeU128(value));
+}
+
+/**
+ * Converts a compact struct, which is encoded as an array of its field values,
+ * into an object. Trailing fields that were omitted are left out.
+ */
+function normalizeCompact(fields: Array<string>): Normalizer {
+    return (value) => {
+        if (!Array.isArray(value)) {
+            return value;
+        }
+
+        const result: Record<string, any> = {};
+        fields.forEach((field, index) => {
+            if (index < value.length) {
+                result[field] = value[index];
+            }
+        });
+        return result;
+    };
+}
+
+function normalizeCompactPoint(value: any): any {
+    return normalizeCompact(["x", "y", "label"])(value);
+}
+
+function normalizeExplicitBoundPoint(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: normalizeT })(value);
+}
+
+function normalizePoint(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: normalizeT })(value);
+}
+
+function normalizeResult(normalizeT: Normalizer, normalizeE: Normalizer): Normalizer {
+    return (value) => normalizeFields({ Ok: normalizeT, Err: normalizeE })(value);
+}
+
+function normalizeStructWithGenerics(normalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ list: normalizeList(normalizeT), points: normalizeList(normalizePoint(normalizeT)), recursive: normalizeList(normalizePoint(normalizePoint(normalizeT))) })(value);
+}
+
+function denormalizeInt128(value: any): any {
+    // 128-bit integers are encoded as 16 bytes in big-endian order, using
+    // two's complement for negative numbers:
+    let remaining = BigInt.asUintN(128, BigInt(value));
+    const bytes = new Uint8Array(16);
+    for (let index = 15; index >= 0; index--) {
+        bytes[index] = Number(remaining & 0xffn);
+        remaining >>= 8n;
+    }
+    return bytes;
+}
+
+/**
+ * Converts an object into the array a compact struct is encoded as. Trailing
+ * fields beyond `minLength` that are `undefined` are omitted, so the plugin
+ * uses their default values.
+ */
+function denormalizeCompact(fields: Array<string>, minLength: number): Normalizer {
+    return (value) => {
+        if (typeof value !== "object" || value === null || Array.isArray(value)) {
+            return value;
+        }
+
+        let length = fields.length;
+        while (length > minLength && value[fields[length - 1]] === undefined) {
+            length--;
+        }
+        return fields.slice(0, length).map((field) => value[field] ?? null);
+    };
+}
+
+function denormalizeCompactPoint(value: any): any {
+    return denormalizeCompact(["x", "y", "label"], 2)(value);
+}
+
+function denormalizeExplicitBoundPoint(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: denormalizeT })(value);
+}
+
+function denormalizePoint(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ value: denormalizeT })(value);
+}
+
+function denormalizeResult(denormalizeT: Normalizer, denormalizeE: Normalizer): Normalizer {
+    return (value) => normalizeFields({ Ok: denormalizeT, Err: denormalizeE })(value);
+}
+
+function denormalizeStructWithGenerics(denormalizeT: Normalizer): Normalizer {
+    return (value) => normalizeFields({ list: normalizeList(denormalizeT), points: normalizeList(denormalizePoint(denormalizeT)), recursive: normalizeList(denormalizePoint(denormalizePoint(denormalizeT))) })(value);
+}
+
+/**
+ * Verifies the value at the given path matches the expected type, and throws a
+ * `ValidationError` otherwise.
+ */
+type Validator = (value: any, path: string) => void;
+
+function describeValue(value: unknown): string {
+    if (value === null || value === undefined) {
+        return String(value);
+    } else if (typeof value === "string") {
+        return JSON.stringify(value);
+    } else if (typeof value === "number" || typeof value === "boolean") {
+        return String(value);
+    } else if (typeof value === "bigint") {
+        return `${value}n`;
+    } else if (Array.isArray(value)) {
+        return `array of length ${value.length}`;
+    } else {
+        return typeof value;
+    }
+}
+
+const validateAny: Validator = () => {};
+
+function validateBoolean(value: any, path: string) {
+    if (typeof value !== "boolean") {
+        throw new ValidationError(path, "boolean", value);
+    }
+}
+
+function validateNumber(value: any, path: string) {
+    if (typeof value !== "number") {
+        throw new ValidationError(path, "number", value);
+    }
+}
+
+function validateInteger(name: string, min: bigint, max: bigint): Validator {
+    return (value, path) => {
+        const isInteger = typeof value === "bigint" || Number.isInteger(value);
+        if (!isInteger || BigInt(value) < min || BigInt(value) > max) {
+            throw new ValidationError(path, name, value);
+        }
+    };
+}
+
+const validateI8 = validateInteger("i8", -(2n ** 7n), 2n ** 7n - 1n);
+const validateI16 = validateInteger("i16", -(2n ** 15n), 2n ** 15n - 1n);
+const validateI32 = validateInteger("i32", -(2n ** 31n), 2n ** 31n - 1n);
+const validateI64 = validateInteger("i64", -(2n ** 63n), 2n ** 63n - 1n);
+const validateU8 = validateInteger("u8", 0n, 2n ** 8n - 1n);
+const validateU16 = validateInteger("u16", 0n, 2n ** 16n - 1n);
+const validateU32 = validateInteger("u32", 0n, 2n ** 32n - 1n);
+const validateU64 = validateInteger("u64", 0n, 2n ** 64n - 1n);
+
+function validateInteger128(name: string, min: bigint, max: bigint): Validator {
+    const validate = validateInteger(name, min, max);
+    return (value, path) => {
+        // 128-bit integers are encoded as 16 bytes, although they may also be
+        // encoded as regular integers if they fit:
+        if (!(value instanceof Uint8Array)) {
+            validate(value, path);
+        } else if (value.length !== 16) {
+            throw new ValidationError(path, name, value);
+        }
+    };
+}
+
+const validateI128 = validateInteger128("i128", -(2n ** 127n), 2n ** 127n - 1n);
+const validateU128 = validateInteger128("u128", 0n, 2n ** 128n - 1n);
+
+function validateChar(value: any, path: string) {
+    if (typeof value !== "string" || [...value].length !== 1) {
+        throw new ValidationError(path, "char", value);
+    }
+}
+
+function validateString(value: any, path: string) {
+    if (typeof value !== "string") {
+        throw new ValidationError(path, "string", value);
+    }
+}
+
+function validateUnit(value: any, path: string) {
+    if (value !== null && value !== undefined) {
+        throw new ValidationError(path, "null", value);
+    }
+}
+
+function validateCallback(value: any, path: string) {
+    if (typeof value !== "function") {
+        throw new ValidationError(path, "callback", value);
+    }
+}
+
+function validateOption(validate: Validator): Validator {
+    return (value, path) => {
+        if (value !== null && value !== undefined) {
+            validate(value, path);
+        }
+    };
+}
+
+function validateOptional(validate: Validator): Validator {
+    return (value, path) => {
+        if (value !== undefined) {
+            validate(value, path);
+        }
+    };
+}
+
+function validateArray(size: number): Validator {
+    return (value, path) => {
+        const isArray = Array.isArray(value) || ArrayBuffer.isView(value);
+        if (!isArray || (value as ArrayLike<unknown>).length !== size) {
+            throw new ValidationError(path, `array of length ${size}`, value);
+        }
+    };
+}
+
+function validateList(validate: Validator): Validator {
+    return (value, path) => {
+        if (!Array.isArray(value)) {
+            throw new ValidationError(path, "array", value);
+        }
+
+        value.forEach((item, index) => validate(item, `${path}[${index}]`));
+    };
+}
+
+function validateMap(validate: Validator): Validator {
+    return (value, path) => {
+        validateObject(value, path);
+        for (const [key, item] of Object.entries(value)) {
+            validate(item, `${path}[${JSON.stringify(key)}]`);
+        }
+    };
+}
+
+function validateTuple(validators: Array<Validator>): Validator {
+    return (value, path) => {
+        if (!Array.isArray(value) || value.length !== validators.length) {
+            throw new ValidationError(path, `tuple of length ${validators.length}`, value);
+        }
+
+        validators.forEach((validate, index) => validate(value[index], `${path}[${index}]`));
+    };
+}
+
+function validateObject(value: any, path: string) {
+    if (typeof value !== "object" || value === null || Array.isArray(value)) {
+        throw new ValidationError(path, "object", value);
+    }
+}
+
+function validateFields(validators: Record<string, Validator>): Validator {
+    return (value, path) => {
+        validateObject(value, path);
+        for (const [key, validate] of Object.entries(validators)) {
+            validate(value[key], `${path}.${key}`);
+        }
+    };
+}
+
+function validateCompact(validators: Record<string, Validator>, minLength: number): Validator {
+    const fields = Object.entries(validators);
+    return (value, path) => {
+        if (!Array.isArray(value) || value.length < minLength || value.length > fields.length) {
+            throw new ValidationError(
+                path,
+                `array of ${minLength} to ${fields.length} field values`,
+                value
+            );
+        }
+
+        fields.forEach(([key, validate], index) => {
+            if (index < value.length) {
+                validate(value[index], `${path}.${key}`);
+            }
+        });
+    };
+}
+
+function validateExternallyTagged(
+    unitVariants: Array<string>,
+    validators: Record<string, Validator>
+): Validator {
+    const variants = [...unitVariants, ...Object.keys(validators)];
+    const expected = `one of ${variants.map((variant) => JSON.stringify(variant)).join(", ")}`;
+    return (value, path) => {
+        if (typeof value === "string") {
+            if (!unitVariants.includes(value)) {
+                throw new ValidationError(path, expected, value);
+            }
+            return;
+        }
+
+        validateObject(value, path);
+        const keys = Object.keys(value);
+        if (keys.length !== 1 || !Object.prototype.hasOwnProperty.call(validators, keys[0])) {
+            throw new ValidationError(path, `object with ${expected} as its only key`, value);
+        }
+
+        validators[keys[0]](value[keys[0]], `${path}.${keys[0]}`);
+    };
+}
+
+function validateTagged(tagProp: string, validators: Record<string, Validator>): Validator {
+    const variants = Object.keys(validators);
+    const expected = `one of ${variants.map((variant) => JSON.stringify(variant)).join(", ")}`;
+    return (value, path) => {
+        validateObject(value, path);
+        const tag = value[tagProp];
+        if (typeof tag !== "string" || !Object.prototype.hasOwnProperty.call(validators, tag)) {
+            throw new ValidationError(`${path}.${tagProp}`, expected, tag);
+        }
+
+        validators[tag](value, path);
+    };
+}
+
+function validateUntagged(name: string, validators: Array<Validator>): Validator {
+    return (value, path) => {
+        const matches = validators.some((validate) => {
+            try {
+                validate(value, path);
+                return true;
+            } catch (error) {
+                if (error instanceof ValidationError) {
+                    return false;
+                }
+                throw error;
+            }
+        });
+        if (!matches) {
+            throw new ValidationError(path, `any variant of ${name}`, value);
+        }
+    };
+}
+
+function validateAll(...validators: Array<Validator>): Validator {
+    return (value, path) => validators.forEach((validate) => validate(value, path));
+}
+
+function validateCompactPoint(value: any, path: string) {
+    validateCompact({ x: validateI32, y: validateI32, label: validateOption(validateString) }, 2)(value, path);
+}
+
+function validateDocExampleEnum(value: any, path: string) {
+    validateExternallyTagged([], { Variant1: validateString, Variant2: validateFields({ inner: validateI8 }) })(value, path);
+}
+
+function validateDocExampleStruct(value: any, path: string) {
+    validateFields({ multi_line: validateString, type: validateString })(value, path);
+}
+
+function validateExplicitBoundPoint(validateT: Validator): Validator {
+    return (value, path) => validateFields({ value: validateT })(value, path);
+}
+
+function validateExplicitedlyImportedType(value: any, path: string) {
+    validateFields({ you_will_see_this: validateBoolean })(value, path);
+}
+
+function validateFlattenedStruct(value: any, path: string) {
+    validateFields({ foo: validateString, bar: validateI64 })(value, path);
+}
+
+function validateFpAdjacentlyTagged(value: any, path: string) {
+    validateTagged("type", { Foo: validateAny, Bar: validateFields({ payload: validateString }), Baz: validateFields({ payload: validateFields({ a: validateI8, b: validateU64 }) }) })(value, path);
+}
+
+function validateFpFlatten(value: any, path: string) {
+    validateFlattenedStruct(value, path);
+}
+
+function validateFpInternallyTagged(value: any, path: string) {
+    validateTagged("type", { Foo: validateAny, Baz: validateFields({ a: validateI8, b: validateU64 }) })(value, path);
+}
+
+function validateFpPropertyRenaming(value: any, path: string) {
+    validateFields({ fooBar: validateString, QUX_BAZ: validateNumber, rawStruct: validateI32 })(value, path);
+}
+
+function validateFpUntagged(value: any, path: string) {
+    validateUntagged("FpUntagged", [validateString, validateFields({ a: validateI8, b: validateU64 })])(value, path);
+}
+
+function validateFpVariantRenaming(value: any, path: string) {
+    validateExternallyTagged(["foo_bar"], { QUX_BAZ: validateFields({ FOO_BAR: validateString, qux_baz: validateNumber }) })(value, path);
+}
+
+function validateGroupImportedType1(value: any, path: string) {
+    validateFields({ you_will_see_this: validateBoolean })(value, path);
+}
+
+function validateGroupImportedType2(value: any, path: string) {
+    validateFields({ you_will_see_this: validateBoolean })(value, path);
+}
+
+function validateMyDateTime(value: any, path: string) {
+    validateAny(value, path);
+}
+
+function validatePoint(validateT: Validator): Validator {
+    return (value, path) => validateFields({ value: validateT })(value, path);
+}
+
+function validateReduxAction(value: any, path: string) {
+    validateTagged("type", { clear_title: validateAny, update_title: validateFields({ payload: validateFields({ title: validateString }) }) })(value, path);
+}
+
+function validateRequest(value: any, path: string) {
+    validateFields({ url: validateAny, method: validateAny, headers: validateAny, body: validateOptional(validateOption(validateAny)) })(value, path);
+}
+
+function validateRequestError(value: any, path: string) {
+    validateTagged("type", { offline: validateAny, no_route: validateAny, connection_refused: validateAny, timeout: validateAny, server_error: validateFields({ status_code: validateU16, response: validateAny }), "other/misc": validateFields({ reason: validateString }) })(value, path);
+}
+
+function validateResponse(value: any, path: string) {
+    validateFields({ body: validateAny, headers: validateAny, status_code: validateU16 })(value, path);
+}
+
+function validateResult(validateT: Validator, validateE: Validator): Validator {
+    return (value, path) => validateExternallyTagged([], { Ok: validateT, Err: validateE })(value, path);
+}
+
+function validateSerdeAdjacentlyTagged(value: any, path: string) {
+    validateTagged("type", { Foo: validateAny, Bar: validateFields({ payload: validateString }), Baz: validateFields({ payload: validateFields({ a: validateI8, b: validateU64 }) }) })(value, path);
+}
+
+function validateSerdeFlatten(value: any, path: string) {
+    validateFlattenedStruct(value, path);
+}
+
+function validateSerdeInternallyTagged(value: any, path: string) {
+    validateTagged("type", { Foo: validateAny, Baz: validateFields({ a: validateI8, b: validateU64 }) })(value, path);
+}
+
+function validateSerdePropertyRenaming(value: any, path: string) {
+    validateFields({ fooBar: validateString, QUX_BAZ: validateNumber, rawStruct: validateI32 })(value, path);
+}
+
+function validateSerdeUntagged(value: any, path: string) {
+    validateUntagged("SerdeUntagged", [validateString, validateFields({ a: validateI8, b: validateU64 })])(value, path);
+}
+
+function validateSerdeVariantRenaming(value: any, path: string) {
+    validateExternallyTagged(["foo_bar"], { QUX_BAZ: validateFields({ FooBar: validateString, qux_baz: validateNumber }) })(value, path);
+}
+
+function validateStateUpdate(value: any, path: string) {
+    validateFields({ title: validateOption(validateString), revision: validateOption(validateU16) })(value, path);
+}
+
+function validateStructWithGenerics(validateT: Validator): Validator {
+    return (value, path) => validateFields({ list: validateList(validateT), points: validateList(validatePoint(validateT)), recursive: validateList(validatePoint(validatePoint(validateT))), complex_nested: validateOption(validateMap(validateList(validatePoint(validateNumber)))), optional_timestamp: validateOption(validateMyDateTime) })(value, path);
+}
+
+function validateStructWithOptions(value: any, path: string) {
+    validateFields({ filledString: validateOptional(validateString), emptyString: validateOptional(validateString), filledOptionString: validateOptional(validateOption(validateString)), emptyOptionString: validateOptional(validateOption(validateString)), neverSkippedFilledOptionString: validateOption(validateString), neverSkippedEmptyOptionString: validateOption(validateString) })(value, path);
+}
@@ -81,6 +81,15 @@ fp_import! {
     // Passing strings:
     fn import_string(arg: String) -> String;
 
+    // Passing borrowed arguments:
+    fn import_borrowed_string(arg: &str) -> String;
+
+    // Functions in a named module are imported from a Wasm module of the same
+    // name, instead of the default `fp` module:
+    mod text {
+        fn import_uppercase_string(arg: String) -> String;
+    }
+
     // Multiple arguments:
     fn import_multiple_primitives(arg1: i8, arg2: String) -> i64;
 
@@ -142,9 +151,6 @@ fp_import! {
     async fn import_reset_global_state() -> ();
     async fn import_increment_global_state();
 
-    // Async function:
-    async fn import_fp_struct(arg1: FpPropertyRenaming, arg2: u64) -> FpPropertyRenaming;
-
     /// Logs a message to the (development) console.
     fn log(message: String);
 
@@ -177,6 +183,11 @@ fp_export! {
     fn export_primitive_u32_add_three(arg: u32) -> u32;
     fn export_primitive_u64_add_three(arg: u64) -> u64;
 
+    // Passing primitives that require serialization:
+    fn export_primitive_i128_add_three(arg: i128) -> i128;
+    fn export_primitive_u128_add_three(arg: u128) -> u128;
+    fn export_primitive_char_to_uppercase(arg: char) -> char;
+
     // Passing arrays:
     fn export_array_u8(arg: [u8; 3]) -> [u8; 3];
     fn export_array_u16(arg: [u16; 3]) -> [u16; 3];
@@ -190,6 +201,13 @@ fp_export! {
     // Passing strings:
     fn export_string(arg: String) -> String;
 
+    // Passing borrowed arguments:
+    fn export_borrowed_string(arg: &str) -> String;
+    fn export_borrowed_bytes(arg: &[u8]) -> Vec<u8>;
+
+    // Calling a function from a named import module:
+    fn export_uppercase_string(arg: String) -> String;
+
     // Multiple arguments:
     fn export_multiple_primitives(arg1: i8, arg2: String) -> i64;
 
@@ -210,6 +228,17 @@ fp_export! {
     // Options
     fn export_struct_with_options(arg: StructWithOptions) -> StructWithOptions;
 
+    // Compact structs.
+    //
+    // See `types/compact.rs` for more info.
+    fn export_compact_point(arg: CompactPoint) -> CompactPoint;
+
+    // Access to stdout, stderr and the file system, which plugins only have
+    // when they are built for WASI.
+    fn export_print(stdout: String, stderr: String);
+    fn export_read_file(path: String) -> Result<String, String>;
+    fn export_write_file(path: String, contents: String) -> Result<(), String>;
+
     // Custom type in a generic position.
     fn export_get_bytes() -> Result<Bytes, String>;
     fn export_get_serde_bytes() -> Result<ByteBuf, String>;
@@ -294,6 +323,7 @@ static PLUGIN_DEPENDENCIES: Lazy<BTreeMap<&str, CargoDependency>> = Lazy::new(||
 
 fn main() {
     for bindings_type in [
+        BindingsType::ProtocolSchema,
         BindingsType::RustPlugin(
             RustPluginConfig::builder()
                 .name(NAME)
@@ -323,6 +353,56 @@ fn main() {
     }
 }
 
+#[test]
+fn test_generate_protocol_schema() {
+    static FILES: &[(&str, &[u8])] = &[
+        (
+            "bindings/protocol-schema/protocol.json",
+            include_bytes!("assets/protocol_schema_test/expected_protocol.json"),
+        ),
+        (
+            "bindings/protocol-schema/schema.json",
+            include_bytes!("assets/protocol_schema_test/expected_schema.json"),
+        ),
+    ];
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::ProtocolSchema,
+        path: "bindings/protocol-schema",
+    });
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
+#[test]
+fn test_parse_protocol_description() {
+    static FILES: &[(&str, &[u8])] = &[
+        (
+            "bindings/protocol-schema-parsed/protocol.json",
+            include_bytes!("assets/protocol_schema_test/expected_protocol.json"),
+        ),
+        (
+            "bindings/protocol-schema-parsed/schema.json",
+            include_bytes!("assets/protocol_schema_test/expected_schema.json"),
+        ),
+    ];
+    let protocol = Protocol::from_description(include_str!(
+        "assets/protocol_schema_test/expected_protocol.json"
+    ))
+    .expect("Could not parse protocol description");
+    fp_bindgen::generate_protocol_bindings(
+        protocol,
+        BindingConfig {
+            bindings_type: BindingsType::ProtocolSchema,
+            path: "bindings/protocol-schema-parsed",
+        },
+    )
+    .expect("Could not generate bindings");
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
 #[test]
 fn test_generate_rust_plugin() {
     static FILES: &[(&str, &[u8])] = &[
@@ -457,6 +537,217 @@ fn test_generate_ts_runtime() {
     }
 }
 
+#[test]
+fn test_generate_ts_runtime_with_bigint() {
+    static FILES: &[(&str, &[u8])] = &[
+        (
+            "bindings/ts-runtime-bigint/types.ts",
+            include_bytes!("assets/ts_runtime_bigint_test/expected_types.ts"),
+        ),
+        (
+            "bindings/ts-runtime-bigint/index.ts",
+            include_bytes!("assets/ts_runtime_bigint_test/expected_index.ts"),
+        ),
+    ];
+
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::TsRuntime(
+            TsRuntimeConfig::new()
+                .with_msgpack_module("https://unpkg.com/@msgpack/msgpack@2.8.0/mod.ts")
+                .with_bigint_for_64bit_integers()
+        ),
+        path: "bindings/ts-runtime-bigint",
+    });
+
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
+#[test]
+fn test_generate_ts_runtime_with_validators() {
+    static FILES: &[(&str, &[u8])] = &[
+        (
+            "bindings/ts-runtime-validators/types.ts",
+            include_bytes!("assets/ts_runtime_validators_test/expected_types.ts"),
+        ),
+        (
+            "bindings/ts-runtime-validators/index.ts",
+            include_bytes!("assets/ts_runtime_validators_test/expected_index.ts"),
+        ),
+    ];
+
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::TsRuntime(
+            TsRuntimeConfig::new()
+                .with_msgpack_module("https://unpkg.com/@msgpack/msgpack@2.7.2/mod.ts")
+                .with_validators()
+        ),
+        path: "bindings/ts-runtime-validators",
+    });
+
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
+#[test]
+fn test_generate_ts_runtime_with_wasi() {
+    static FILES: &[(&str, &[u8])] = &[(
+        "bindings/ts-runtime-wasi/index.ts",
+        include_bytes!("assets/ts_runtime_wasi_test/expected_index.ts"),
+    )];
+
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::TsRuntime(
+            TsRuntimeConfig::new()
+                .with_wasi(TsWasiShim::Node)
+                .without_streaming_instantiation()
+        ),
+        path: "bindings/ts-runtime-wasi",
+    });
+
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
+#[test]
+fn test_generate_ts_runtime_with_deno_wasi() {
+    static FILES: &[(&str, &[u8])] = &[(
+        "bindings/ts-runtime-deno-wasi/index.ts",
+        include_bytes!("assets/ts_runtime_deno_wasi_test/expected_index.ts"),
+    )];
+
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::TsRuntime(
+            TsRuntimeConfig::new()
+                .with_wasi(TsWasiShim::Deno(
+                    "https://deno.land/std@0.205.0/wasi/snapshot_preview1.ts".to_owned()
+                ))
+                .without_streaming_instantiation()
+        ),
+        path: "bindings/ts-runtime-deno-wasi",
+    });
+
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
+#[test]
+fn test_generate_ts_runtime_npm_package() {
+    static FILES: &[(&str, &[u8])] = &[
+        (
+            "bindings/ts-runtime-npm/package.json",
+            include_bytes!("assets/ts_runtime_npm_test/expected_package.json"),
+        ),
+        (
+            "bindings/ts-runtime-npm/tsconfig.json",
+            include_bytes!("assets/ts_runtime_npm_test/expected_tsconfig.json"),
+        ),
+        (
+            "bindings/ts-runtime-npm/tsconfig.cjs.json",
+            include_bytes!("assets/ts_runtime_npm_test/expected_tsconfig.cjs.json"),
+        ),
+        (
+            "bindings/ts-runtime-npm/src/index.ts",
+            include_bytes!("assets/ts_runtime_npm_test/expected_index.ts"),
+        ),
+    ];
+
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::TsRuntime(
+            TsRuntimeConfig::new().with_npm_package(
+                TsNpmPackageConfig::new("@fp-bindgen/example-runtime", "1.0.0")
+                    .with_description("Runtime for the example protocol")
+                    .with_license("MIT OR Apache-2.0")
+            )
+        ),
+        path: "bindings/ts-runtime-npm",
+    });
+
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
+#[test]
+fn test_generate_ts_runtime_with_web_worker() {
+    static FILES: &[(&str, &[u8])] = &[
+        (
+            "bindings/ts-runtime-web-worker/worker.ts",
+            include_bytes!("assets/ts_runtime_web_worker_test/expected_worker.ts"),
+        ),
+        (
+            "bindings/ts-runtime-web-worker/workerRuntime.ts",
+            include_bytes!("assets/ts_runtime_web_worker_test/expected_workerRuntime.ts"),
+        ),
+    ];
+
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::TsRuntime(
+            TsRuntimeConfig::new().with_web_worker().with_validators()
+        ),
+        path: "bindings/ts-runtime-web-worker",
+    });
+
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
+#[test]
+fn test_generate_ts_runtime_with_custom_type_conversions() {
+    static FILES: &[(&str, &[u8])] = &[
+        (
+            "bindings/ts-runtime-custom-types/index.ts",
+            include_bytes!("assets/ts_runtime_custom_types_test/expected_index.ts"),
+        ),
+        (
+            "bindings/ts-runtime-custom-types/types.ts",
+            include_bytes!("assets/ts_runtime_custom_types_test/expected_types.ts"),
+        ),
+    ];
+
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::TsRuntime(
+            TsRuntimeConfig::new()
+                .with_custom_type_conversions()
+                .with_custom_type_conversion(
+                    "OffsetDateTime",
+                    TsTypeConversion::new(
+                        "Temporal.Instant",
+                        "(value: string) => Temporal.Instant.from(value)"
+                    )
+                    .with_encode("(value: Temporal.Instant) => value.toString()")
+                )
+        ),
+        path: "bindings/ts-runtime-custom-types",
+    });
+
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
+#[test]
+fn test_generate_ts_runtime_with_enum_helpers() {
+    static FILES: &[(&str, &[u8])] = &[(
+        "bindings/ts-runtime-enum-helpers/types.ts",
+        include_bytes!("assets/ts_runtime_enum_helpers_test/expected_types.ts"),
+    )];
+
+    fp_bindgen!(BindingConfig {
+        bindings_type: BindingsType::TsRuntime(TsRuntimeConfig::new().with_enum_helpers()),
+        path: "bindings/ts-runtime-enum-helpers",
+    });
+
+    for (path, expected) in FILES {
+        tests::assert_file_eq(path, expected)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::Path;
@@ -0,0 +1,37 @@
+use fp_bindgen::prelude::{Serializable, SerializeCompact};
+use serde::Deserialize;
+
+// Compact structs are encoded as an array of their field values, instead of a
+// map from field names to values.
+//
+// If a compact struct is shared with the host, it should derive
+// `SerializeCompact` instead of Serde's `Serialize`, so the host encodes it
+// the same way as the generated bindings do. Only fields at the end that have
+// a default value may be omitted by the sender.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serializable, SerializeCompact)]
+#[fp(compact)]
+pub struct CompactPoint {
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[test]
+fn serializes_compact_structs_as_arrays() {
+    let point = CompactPoint {
+        x: 1,
+        y: 2,
+        label: Some("origin".to_owned()),
+    };
+
+    let serialized = rmp_serde::to_vec(&point).unwrap();
+    assert_eq!(serialized, rmp_serde::to_vec(&(1, 2, "origin")).unwrap());
+
+    let deserialized: CompactPoint = rmp_serde::from_slice(&serialized).unwrap();
+    assert_eq!(deserialized, point);
+
+    let deserialized: CompactPoint =
+        rmp_serde::from_slice(&rmp_serde::to_vec(&(1, 2)).unwrap()).unwrap();
+    assert_eq!(deserialized.label, None);
+}
@@ -1,6 +1,9 @@
 mod aliases;
 pub use aliases::*;
 
+mod compact;
+pub use compact::*;
+
 mod flattening;
 pub use flattening::*;
 
@@ -84,6 +84,17 @@ fn import_string(_arg: String) -> String {
     todo!()
 }
 
+fn import_borrowed_string(arg: String) -> String {
+    assert_eq!(arg, "Hello");
+    "Hello, world!".to_owned()
+}
+
+mod text {
+    pub fn import_uppercase_string(arg: String) -> String {
+        arg.to_uppercase()
+    }
+}
+
 fn import_multiple_primitives(_arg1: i8, _arg2: String) -> i64 {
     todo!()
 }
@@ -37,6 +37,16 @@ fn primitives() -> Result<()> {
     assert_eq!(rt.export_primitive_i32_add_three(-32)?, -32 + 3);
     assert_eq!(rt.export_primitive_i64_add_three(-64)?, -64 + 3);
 
+    assert_eq!(
+        rt.export_primitive_i128_add_three(-(1 << 100))?,
+        -(1 << 100) + 3
+    );
+    assert_eq!(
+        rt.export_primitive_u128_add_three(u128::MAX - 3)?,
+        u128::MAX
+    );
+    assert_eq!(rt.export_primitive_char_to_uppercase('a')?, 'A');
+
     assert_eq!(
         rt.export_multiple_primitives(-8, "Hello, 🇳🇱!".to_string())?,
         -64
@@ -83,6 +93,49 @@ fn string() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn borrowed_arguments() -> Result<()> {
+    let rt = new_runtime()?;
+    assert_eq!(rt.export_borrowed_string("Hello, plugin!")?, "Hello, world!");
+    assert_eq!(rt.export_borrowed_bytes(&[1, 2, 3])?, [3, 2, 1]);
+
+    Ok(())
+}
+
+#[test]
+fn named_import_module() -> Result<()> {
+    let rt = new_runtime()?;
+    assert_eq!(rt.export_uppercase_string("Hello, plugin!".to_string())?, "HELLO, PLUGIN!");
+
+    Ok(())
+}
+
+#[test]
+fn compact_structs() -> Result<()> {
+    let rt = new_runtime()?;
+    assert_eq!(
+        rt.export_compact_point(CompactPoint {
+            x: 1,
+            y: 2,
+            label: None
+        })?,
+        CompactPoint {
+            x: 2,
+            y: 1,
+            label: Some("swapped".to_owned())
+        }
+    );
+
+    // Compact structs are encoded as arrays in both directions, and fields at
+    // the end that have a default value may be omitted:
+    assert_eq!(
+        rt.export_compact_point_raw(rmp_serde::to_vec(&(1, 2))?)?,
+        rmp_serde::to_vec(&(2, 1, "swapped"))?
+    );
+
+    Ok(())
+}
+
 #[test]
 fn timestamp() -> Result<()> {
     let rt = new_runtime()?;
@@ -318,6 +371,73 @@ fn bytes() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn implemented_exports() -> Result<()> {
+    let rt = new_runtime()?;
+
+    assert!(rt.has_export_string());
+    assert!(!rt.has_export_void_function());
+
+    let exports = rt.implemented_exports();
+    assert!(exports.contains(&"export_string"));
+    assert!(!exports.contains(&"export_void_function"));
+
+    Ok(())
+}
+
+#[cfg(feature = "wasi")]
+#[test]
+fn wasi_config() -> Result<()> {
+    use crate::wasi_spec::bindings::{OutputBuffer, WasiConfig};
+    use std::sync::{Arc, Mutex};
+
+    let dir = std::env::temp_dir().join(format!("fp-bindgen-wasi-{}", std::process::id()));
+    let data_dir = dir.join("data");
+    let config_dir = dir.join("config");
+    std::fs::create_dir_all(&data_dir)?;
+    std::fs::create_dir_all(&config_dir)?;
+    std::fs::write(config_dir.join("input.txt"), "Hello from the host")?;
+
+    let stdout = Arc::new(Mutex::new(Vec::new()));
+    let stderr = OutputBuffer::new();
+    let config = WasiConfig::new()
+        .map_dir("/data", &data_dir)
+        .map_dir_read_only("/config", &config_dir)
+        .stdout({
+            let stdout = stdout.clone();
+            move |bytes| stdout.lock().unwrap().extend_from_slice(bytes)
+        })
+        .stderr(stderr.writer());
+    let rt = Runtime::with_wasi_config(WASM_BYTES, config)?;
+    rt.init()?;
+
+    rt.export_print("Hello, stdout!".to_string(), "Hello, stderr!".to_string())?;
+    assert_eq!(stdout.lock().unwrap().as_slice(), b"Hello, stdout!");
+    assert_eq!(stderr.take(), b"Hello, stderr!");
+    assert_eq!(stderr.take(), b"");
+
+    assert_eq!(
+        rt.export_read_file("/config/input.txt".to_string())?,
+        Ok("Hello from the host".to_string())
+    );
+    assert!(rt
+        .export_write_file("/config/output.txt".to_string(), "Nope".to_string())?
+        .is_err());
+    assert!(!config_dir.join("output.txt").exists());
+
+    assert_eq!(
+        rt.export_write_file("/data/output.txt".to_string(), "Hello from the plugin".to_string())?,
+        Ok(())
+    );
+    assert_eq!(
+        std::fs::read_to_string(data_dir.join("output.txt"))?,
+        "Hello from the plugin"
+    );
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
 fn new_runtime() -> Result<Runtime> {
     let rt = Runtime::new(WASM_BYTES)?;
     rt.init()?;
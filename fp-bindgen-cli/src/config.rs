@@ -0,0 +1,540 @@
+use crate::generate::Target;
+use anyhow::{bail, Context, Result};
+use fp_bindgen::{
+    prelude::Protocol,
+    types::{CargoDependency, TsTypeConversion},
+    BindingsType, RustPluginConfig, TsNpmPackageConfig, TsRuntimeConfig, TsWasiShim,
+};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the config file that is used if none is specified explicitly.
+pub const DEFAULT_CONFIG_FILE: &str = "fp-bindgen.toml";
+
+/// Contents of an `fp-bindgen.toml` config file.
+///
+/// Every bindings type has its own (optional) section. Bindings types for
+/// which a section is present are generated by default.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Path to the crate that defines the protocol, or to a protocol
+    /// description.
+    pub protocol: Option<PathBuf>,
+
+    /// Directory in which to write the bindings.
+    pub out: Option<PathBuf>,
+
+    pub protocol_schema: Option<TargetConfig>,
+    pub rust_plugin: Option<RustPluginTargetConfig>,
+    pub rust_wasmer2_runtime: Option<TargetConfig>,
+    pub rust_wasmer2_wasi_runtime: Option<TargetConfig>,
+    pub ts_runtime: Option<TsRuntimeTargetConfig>,
+}
+
+impl Config {
+    /// Reads the config from the given file.
+    ///
+    /// Relative paths inside the config file are resolved relative to the
+    /// directory that contains the file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read config file `{}`", path.display()))?;
+        let config = Self::from_toml(&contents)
+            .with_context(|| format!("Could not parse config file `{}`", path.display()))?;
+
+        Ok(config.with_base_dir(path.parent().unwrap_or_else(|| Path::new(""))))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    fn with_base_dir(mut self, base_dir: &Path) -> Self {
+        let resolve = |path: &mut Option<PathBuf>| {
+            if let Some(path) = path {
+                *path = base_dir.join(&path);
+            }
+        };
+
+        resolve(&mut self.protocol);
+        resolve(&mut self.out);
+        for path in [
+            self.protocol_schema.as_mut().map(|config| &mut config.path),
+            self.rust_plugin.as_mut().map(|config| &mut config.path),
+            self.rust_wasmer2_runtime
+                .as_mut()
+                .map(|config| &mut config.path),
+            self.rust_wasmer2_wasi_runtime
+                .as_mut()
+                .map(|config| &mut config.path),
+            self.ts_runtime.as_mut().map(|config| &mut config.path),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            resolve(path);
+        }
+        self
+    }
+
+    /// Returns the bindings types for which a section is present.
+    pub fn configured_targets(&self) -> Vec<Target> {
+        let mut targets = Vec::new();
+        if self.protocol_schema.is_some() {
+            targets.push(Target::ProtocolSchema);
+        }
+        if self.rust_plugin.is_some() {
+            targets.push(Target::RustPlugin);
+        }
+        if self.rust_wasmer2_runtime.is_some() {
+            targets.push(Target::RustWasmer2Runtime);
+        }
+        if self.rust_wasmer2_wasi_runtime.is_some() {
+            targets.push(Target::RustWasmer2WasiRuntime);
+        }
+        if self.ts_runtime.is_some() {
+            targets.push(Target::TsRuntime);
+        }
+        targets
+    }
+
+    /// Returns the output path configured for the given bindings type, if any.
+    pub fn target_path(&self, target: Target) -> Option<&Path> {
+        let path = match target {
+            Target::ProtocolSchema => self.protocol_schema.as_ref().map(|c| &c.path),
+            Target::RustPlugin => self.rust_plugin.as_ref().map(|c| &c.path),
+            Target::RustWasmer2Runtime => self.rust_wasmer2_runtime.as_ref().map(|c| &c.path),
+            Target::RustWasmer2WasiRuntime => {
+                self.rust_wasmer2_wasi_runtime.as_ref().map(|c| &c.path)
+            }
+            Target::TsRuntime => self.ts_runtime.as_ref().map(|c| &c.path),
+        };
+        path.and_then(Option::as_deref)
+    }
+
+    /// Returns the bindings type to generate for the given target, including
+    /// the generator options from the config.
+    pub fn bindings_type(&self, target: Target, protocol: &Protocol) -> Result<BindingsType> {
+        let bindings_type = match target {
+            Target::ProtocolSchema => BindingsType::ProtocolSchema,
+            Target::RustPlugin => BindingsType::RustPlugin(
+                self.rust_plugin
+                    .clone()
+                    .unwrap_or_default()
+                    .into_rust_plugin_config(protocol)?,
+            ),
+            Target::RustWasmer2Runtime => BindingsType::RustWasmer2Runtime,
+            Target::RustWasmer2WasiRuntime => BindingsType::RustWasmer2WasiRuntime,
+            Target::TsRuntime => BindingsType::TsRuntime(
+                self.ts_runtime
+                    .clone()
+                    .unwrap_or_default()
+                    .into_ts_runtime_config(protocol)?,
+            ),
+        };
+        Ok(bindings_type)
+    }
+}
+
+/// Config section for bindings types that have no generator options.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TargetConfig {
+    /// Directory in which to write the bindings.
+    pub path: Option<PathBuf>,
+}
+
+/// Config section for the `rust-plugin` bindings type.
+///
+/// See [RustPluginConfig] for the meaning of the options.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct RustPluginTargetConfig {
+    pub path: Option<PathBuf>,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub authors: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub readme: Option<String>,
+    pub license: Option<String>,
+    pub publish: Option<bool>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, DependencyConfig>,
+}
+
+impl RustPluginTargetConfig {
+    fn into_rust_plugin_config(self, protocol: &Protocol) -> Result<RustPluginConfig> {
+        // Without an explicit name, we name the plugin crate after the
+        // protocol:
+        let name = match self.name {
+            Some(name) => name,
+            None if !protocol.name.is_empty() => format!("{}-bindings", protocol.name),
+            None => bail!(
+                "The protocol has no name, so a name for the plugin crate must be specified \
+                using the `name` setting in the `[rust-plugin]` section of the config file"
+            ),
+        };
+
+        let mut builder = RustPluginConfig::builder().name(name);
+        if let Some(version) = self.version {
+            builder = builder.version(version);
+        }
+        if let Some(authors) = self.authors {
+            builder = builder.authors(authors);
+        }
+        if let Some(description) = self.description {
+            builder = builder.description(description);
+        }
+        if let Some(readme) = self.readme {
+            builder = builder.readme(readme);
+        }
+        if let Some(license) = self.license {
+            builder = builder.license(license);
+        }
+        if let Some(publish) = self.publish {
+            builder = builder.publish(publish);
+        }
+        for (name, dependency) in self.dependencies {
+            builder = builder.dependency(name, dependency.into_cargo_dependency());
+        }
+        Ok(builder.build())
+    }
+}
+
+/// A dependency for the plugin crate, using the same syntax as in
+/// `Cargo.toml`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum DependencyConfig {
+    Version(String),
+    Detailed(DetailedDependencyConfig),
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DetailedDependencyConfig {
+    pub branch: Option<String>,
+    pub default_features: Option<bool>,
+    #[serde(default)]
+    pub features: BTreeSet<String>,
+    pub git: Option<String>,
+    pub path: Option<String>,
+    pub registry: Option<String>,
+    pub version: Option<String>,
+    pub workspace: Option<bool>,
+}
+
+impl DependencyConfig {
+    fn into_cargo_dependency(self) -> CargoDependency {
+        let config = match self {
+            Self::Version(version) => DetailedDependencyConfig {
+                version: Some(version),
+                ..Default::default()
+            },
+            Self::Detailed(config) => config,
+        };
+
+        let mut dependency = CargoDependency::default();
+        dependency.branch = config.branch.map(leak);
+        dependency.default_features = config.default_features;
+        dependency.features = config.features.into_iter().map(leak).collect();
+        dependency.git = config.git.map(leak);
+        dependency.path = config.path.map(leak);
+        dependency.registry = config.registry.map(leak);
+        dependency.version = config.version.map(leak);
+        dependency.workspace = config.workspace;
+        dependency
+    }
+}
+
+/// Turns a string from the config into one that can be used in a
+/// [CargoDependency], which only holds static strings. The config is only read
+/// once per run, so the leaked memory doesn't add up.
+fn leak(string: String) -> &'static str {
+    Box::leak(string.into_boxed_str())
+}
+
+/// Config section for the `ts-runtime` bindings type.
+///
+/// See [TsRuntimeConfig] for the meaning of the options.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TsRuntimeTargetConfig {
+    pub path: Option<PathBuf>,
+    pub msgpack_module: Option<String>,
+    #[serde(default)]
+    pub raw_export_wrappers: bool,
+    pub streaming_instantiation: Option<bool>,
+    #[serde(default)]
+    pub bigint_for_64bit_integers: bool,
+    #[serde(default)]
+    pub validators: bool,
+    pub wasi: Option<WasiShimConfig>,
+    #[serde(default)]
+    pub web_worker: bool,
+    #[serde(default)]
+    pub custom_type_conversions: bool,
+    #[serde(default)]
+    pub enum_helpers: bool,
+    #[serde(default)]
+    pub custom_types: BTreeMap<String, TsTypeConversionConfig>,
+    pub import_path_extension: Option<String>,
+    pub npm_package: Option<NpmPackageConfig>,
+}
+
+/// The WASI implementation to use, either `"node"` or
+/// `{ deno = "<module URL>" }`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub enum WasiShimConfig {
+    Node,
+    Deno(String),
+}
+
+/// Config section for the TypeScript conversion of a custom type, keyed by the
+/// name of the custom type.
+///
+/// See [TsTypeConversion] for the meaning of the options.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TsTypeConversionConfig {
+    pub ty: String,
+    pub decode: String,
+    pub encode: Option<String>,
+}
+
+impl TsTypeConversionConfig {
+    fn into_ts_type_conversion(self) -> TsTypeConversion {
+        let conversion = TsTypeConversion::new(self.ty, self.decode);
+        match self.encode {
+            Some(encode) => conversion.with_encode(encode),
+            None => conversion,
+        }
+    }
+}
+
+/// Config section for generating the TypeScript runtime as an npm package.
+///
+/// See [TsNpmPackageConfig] for the meaning of the options.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct NpmPackageConfig {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub msgpack_version: Option<String>,
+}
+
+impl NpmPackageConfig {
+    fn into_npm_package_config(self, protocol: &Protocol) -> Result<TsNpmPackageConfig> {
+        // Like the plugin crate, the package is named after the protocol
+        // unless a name is given:
+        let name = match self.name {
+            Some(name) => name,
+            None if !protocol.name.is_empty() => format!("{}-runtime", protocol.name),
+            None => bail!(
+                "The protocol has no name, so a name for the npm package must be specified \
+                using the `name` setting in the `[ts-runtime.npm-package]` section of the config \
+                file"
+            ),
+        };
+        let version = match self.version {
+            Some(version) => version,
+            None if !protocol.version.is_empty() => protocol.version.clone(),
+            None => "0.1.0".to_owned(),
+        };
+
+        let mut package = TsNpmPackageConfig::new(name, version);
+        if let Some(description) = self.description {
+            package = package.with_description(description);
+        }
+        if let Some(license) = self.license {
+            package = package.with_license(license);
+        }
+        if let Some(msgpack_version) = self.msgpack_version {
+            package = package.with_msgpack_version(msgpack_version);
+        }
+        Ok(package)
+    }
+}
+
+impl TsRuntimeTargetConfig {
+    fn into_ts_runtime_config(self, protocol: &Protocol) -> Result<TsRuntimeConfig> {
+        let mut config = TsRuntimeConfig::new();
+        if let Some(msgpack_module) = self.msgpack_module {
+            config = config.with_msgpack_module(&msgpack_module);
+        }
+        if self.raw_export_wrappers {
+            config = config.with_raw_export_wrappers();
+        }
+        if self.streaming_instantiation == Some(false) {
+            config = config.without_streaming_instantiation();
+        }
+        if self.bigint_for_64bit_integers {
+            config = config.with_bigint_for_64bit_integers();
+        }
+        if self.validators {
+            config = config.with_validators();
+        }
+        match self.wasi {
+            Some(WasiShimConfig::Node) => config = config.with_wasi(TsWasiShim::Node),
+            Some(WasiShimConfig::Deno(module)) => {
+                config = config.with_wasi(TsWasiShim::Deno(module))
+            }
+            None => {}
+        }
+        if self.web_worker {
+            config = config.with_web_worker();
+        }
+        if self.custom_type_conversions {
+            config = config.with_custom_type_conversions();
+        }
+        if self.enum_helpers {
+            config = config.with_enum_helpers();
+        }
+        for (type_name, conversion) in self.custom_types {
+            config = config
+                .with_custom_type_conversion(&type_name, conversion.into_ts_type_conversion());
+        }
+        if let Some(extension) = self.import_path_extension {
+            config = config.with_import_path_extension(&extension);
+        }
+        if let Some(package) = self.npm_package {
+            config = config.with_npm_package(package.into_npm_package_config(protocol)?);
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = Config::from_toml(
+            r#"
+            protocol = "../my-protocol"
+
+            [rust-plugin]
+            name = "my-bindings"
+            authors = ["Me <me@example.com>"]
+            path = "plugin-bindings"
+
+            [rust-plugin.dependencies]
+            serde = "1"
+            time = { version = "0.3", features = ["serde"] }
+
+            [ts-runtime]
+            msgpack-module = "https://unpkg.com/@msgpack/msgpack@2.7.2/mod.ts"
+            streaming-instantiation = false
+            wasi = { deno = "https://deno.land/std@0.205.0/wasi/snapshot_preview1.ts" }
+            custom-type-conversions = true
+
+            [ts-runtime.custom-types.OffsetDateTime]
+            ty = "Temporal.Instant"
+            decode = "(value: string) => Temporal.Instant.from(value)"
+            encode = "(value: Temporal.Instant) => value.toString()"
+            "#,
+        )
+        .unwrap()
+        .with_base_dir(Path::new("/config"));
+
+        assert_eq!(
+            config.protocol,
+            Some(PathBuf::from("/config/../my-protocol"))
+        );
+        assert_eq!(
+            config.configured_targets(),
+            vec![Target::RustPlugin, Target::TsRuntime]
+        );
+        assert_eq!(
+            config.target_path(Target::RustPlugin),
+            Some(Path::new("/config/plugin-bindings"))
+        );
+        assert_eq!(config.target_path(Target::TsRuntime), None);
+
+        let protocol = Protocol::default();
+        let BindingsType::RustPlugin(plugin_config) =
+            config.bindings_type(Target::RustPlugin, &protocol).unwrap()
+        else {
+            panic!("Expected Rust plugin bindings");
+        };
+        let time = &plugin_config.dependencies["time"];
+        assert_eq!(time.version, Some("0.3"));
+        assert_eq!(time.features, BTreeSet::from(["serde"]));
+
+        let BindingsType::TsRuntime(runtime_config) =
+            config.bindings_type(Target::TsRuntime, &protocol).unwrap()
+        else {
+            panic!("Expected TypeScript runtime bindings");
+        };
+        assert!(!runtime_config.streaming_instantiation);
+        assert_eq!(
+            runtime_config.wasi,
+            Some(TsWasiShim::Deno(
+                "https://deno.land/std@0.205.0/wasi/snapshot_preview1.ts".to_owned()
+            ))
+        );
+        assert!(runtime_config.convert_custom_types);
+        assert_eq!(
+            runtime_config.custom_type_conversions["OffsetDateTime"],
+            TsTypeConversion::new(
+                "Temporal.Instant",
+                "(value: string) => Temporal.Instant.from(value)"
+            )
+            .with_encode("(value: Temporal.Instant) => value.toString()")
+        );
+    }
+
+    #[test]
+    fn npm_package_defaults_to_protocol() {
+        let config = Config::from_toml(
+            r#"
+            [ts-runtime.npm-package]
+            license = "MIT"
+            "#,
+        )
+        .unwrap();
+
+        let protocol = Protocol::default().with_version("my-protocol", "1.2.3");
+        let BindingsType::TsRuntime(runtime_config) =
+            config.bindings_type(Target::TsRuntime, &protocol).unwrap()
+        else {
+            panic!("Expected TypeScript runtime bindings");
+        };
+        let package = runtime_config.npm_package.unwrap();
+        assert_eq!(package.name, "my-protocol-runtime");
+        assert_eq!(package.version, "1.2.3");
+        assert_eq!(package.license.as_deref(), Some("MIT"));
+
+        assert!(config
+            .bindings_type(Target::TsRuntime, &Protocol::default())
+            .is_err());
+    }
+
+    #[test]
+    fn rust_plugin_requires_name() {
+        let config = Config::from_toml("[rust-plugin]").unwrap();
+        assert!(config
+            .bindings_type(Target::RustPlugin, &Protocol::default())
+            .is_err());
+
+        let protocol = Protocol::default().with_version("my-protocol", "1.0.0");
+        let BindingsType::RustPlugin(plugin_config) =
+            config.bindings_type(Target::RustPlugin, &protocol).unwrap()
+        else {
+            panic!("Expected Rust plugin bindings");
+        };
+        assert!(matches!(
+            plugin_config.name,
+            Some(fp_bindgen::RustPluginConfigValue::String(name)) if name == "my-protocol-bindings"
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    config::{Config, DEFAULT_CONFIG_FILE},
+    protocol::load_protocol,
+};
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use fp_bindgen::{BindingConfig, BindingsError};
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+/// Directory in which to write the bindings if none is specified.
+const DEFAULT_OUT_DIR: &str = "bindings";
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    /// Path to the crate that defines the protocol, or to a protocol
+    /// description (`protocol.json`) generated by the `protocol-schema` target
+    #[clap(long)]
+    protocol: Option<PathBuf>,
+
+    /// Bindings type to generate. May be specified multiple times. Defaults to
+    /// the bindings types that are configured in the config file
+    #[clap(long = "target", value_enum, use_value_delimiter = true)]
+    targets: Vec<Target>,
+
+    /// Directory in which to write the bindings. Every bindings type is
+    /// written to a subdirectory named after it [default: bindings]
+    #[clap(long)]
+    out: Option<PathBuf>,
+
+    /// Path to the config file [default: fp-bindgen.toml, if it exists]
+    #[clap(long)]
+    config: Option<PathBuf>,
+
+    /// Checks that the bindings on disk are up-to-date, without writing any
+    /// files. Exits with an error if any of them would change
+    #[clap(long)]
+    check: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Target {
+    ProtocolSchema,
+    RustPlugin,
+    RustWasmer2Runtime,
+    RustWasmer2WasiRuntime,
+    TsRuntime,
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ProtocolSchema => "protocol-schema",
+            Self::RustPlugin => "rust-plugin",
+            Self::RustWasmer2Runtime => "rust-wasmer2-runtime",
+            Self::RustWasmer2WasiRuntime => "rust-wasmer2-wasi-runtime",
+            Self::TsRuntime => "ts-runtime",
+        })
+    }
+}
+
+pub fn generate(args: GenerateArgs) -> Result<()> {
+    let config = match &args.config {
+        Some(path) => Config::from_file(path)?,
+        None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+            Config::from_file(Path::new(DEFAULT_CONFIG_FILE))?
+        }
+        None => Config::default(),
+    };
+
+    let Some(protocol_path) = args.protocol.as_ref().or(config.protocol.as_ref()) else {
+        bail!("No protocol specified. Use `--protocol` or set `protocol` in the config file.");
+    };
+    let protocol = load_protocol(protocol_path)?;
+
+    let targets = if args.targets.is_empty() {
+        config.configured_targets()
+    } else {
+        args.targets
+    };
+    if targets.is_empty() {
+        bail!(
+            "No bindings types specified. Use `--target` or add a section for the bindings type \
+            to the config file."
+        );
+    }
+
+    let mut outdated_targets = Vec::new();
+    for target in targets {
+        // Paths given on the command line take precedence over those in the
+        // config file:
+        let path = match (&args.out, config.target_path(target), &config.out) {
+            (Some(out), _, _) => out.join(target.to_string()),
+            (None, Some(path), _) => path.to_owned(),
+            (None, None, Some(out)) => out.join(target.to_string()),
+            (None, None, None) => Path::new(DEFAULT_OUT_DIR).join(target.to_string()),
+        };
+        let path_str = path
+            .to_str()
+            .with_context(|| format!("Output path is not valid UTF-8: {}", path.display()))?;
+
+        let bindings_config = BindingConfig {
+            bindings_type: config.bindings_type(target, &protocol)?,
+            path: path_str,
+        };
+        if !args.check {
+            fp_bindgen::generate_protocol_bindings(protocol.clone(), bindings_config)?;
+            println!("Generated {target} bindings written to `{path_str}/`.");
+            continue;
+        }
+
+        match fp_bindgen::check_protocol_bindings(protocol.clone(), bindings_config) {
+            Ok(()) => println!("Generated {target} bindings in `{path_str}/` are up-to-date."),
+            Err(BindingsError::OutdatedFiles(paths)) => {
+                println!("Generated {target} bindings in `{path_str}/` are out of date:");
+                for path in paths {
+                    println!("  - {path}");
+                }
+                outdated_targets.push(target.to_string());
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    if !outdated_targets.is_empty() {
+        bail!(
+            "Bindings are out of date for: {}. Run `fp-bindgen generate` without `--check` to \
+            update them.",
+            outdated_targets.join(", ")
+        );
+    }
+
+    Ok(())
+}
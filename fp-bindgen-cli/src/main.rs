@@ -0,0 +1,37 @@
+mod config;
+mod generate;
+mod protocol;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[clap(name = "fp-bindgen", version, about)]
+#[clap(arg_required_else_help(true))]
+struct Cli {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Generates bindings for a protocol
+    Generate(generate::GenerateArgs),
+}
+
+fn main() {
+    if let Err(error) = handle_cli() {
+        eprintln!("Error: {error:#}");
+        std::process::exit(1);
+    }
+}
+
+fn handle_cli() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Commands::Generate(args)) => generate::generate(args)?,
+        None => {}
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anyhow::{anyhow, bail, Context, Result};
+use fp_bindgen::{prelude::Protocol, PROTOCOL_DESCRIPTION_DIR_ENV_VAR};
+use std::{
+    env, fs,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Loads a protocol from the given path.
+///
+/// The path may point to either a protocol description (`protocol.json`), a
+/// directory containing one, or the crate that defines the protocol.
+pub fn load_protocol(path: &Path) -> Result<Protocol> {
+    if path.is_dir() {
+        if path.join("Cargo.toml").exists() {
+            load_protocol_from_crate(path)
+        } else {
+            read_protocol_description(&path.join("protocol.json"))
+        }
+    } else {
+        read_protocol_description(path)
+    }
+}
+
+/// Extracts the protocol by running the crate that defines it, which is
+/// expected to invoke `fp_bindgen!` when run.
+fn load_protocol_from_crate(path: &Path) -> Result<Protocol> {
+    // A fresh directory makes sure we never read a stale description, and it
+    // is removed again when it goes out of scope, even if loading fails.
+    let description_dir = tempfile::Builder::new()
+        .prefix("fp-bindgen-")
+        .tempdir()
+        .context("Could not create a temporary directory")?;
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(path.join("Cargo.toml"))
+        .env(PROTOCOL_DESCRIPTION_DIR_ENV_VAR, description_dir.path())
+        .stdout(Stdio::null())
+        .status()
+        .context("Could not run cargo")?;
+    if !status.success() {
+        bail!("Could not run the protocol crate at `{}`", path.display());
+    }
+
+    let description_path = description_dir.path().join("protocol.json");
+    if !description_path.exists() {
+        bail!(
+            "The protocol crate at `{}` did not generate a protocol description. Make sure \
+            running it invokes `fp_bindgen!`.",
+            path.display()
+        );
+    }
+
+    read_protocol_description(&description_path)
+}
+
+fn read_protocol_description(path: &Path) -> Result<Protocol> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Could not read protocol description `{}`", path.display()))?;
+    Protocol::from_description(&json).map_err(|error| {
+        anyhow!(
+            "Could not parse protocol description `{}`: {error}",
+            path.display()
+        )
+    })
+}
@@ -0,0 +1,101 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_bytes::ByteBuf;
+
+/// MessagePack extension type used to encode references to callbacks.
+pub const CALLBACK_EXT_TYPE: i8 = 1;
+
+/// The side of the bridge that owns a callback, and therefore runs it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CallbackOwner {
+    Host = 0,
+    Plugin = 1,
+}
+
+/// Reference to a callback, as it is passed between host and plugin.
+///
+/// Callbacks are identified by the ID they have in the callback table of
+/// their owner. They are encoded as a MessagePack extension value of type
+/// [CALLBACK_EXT_TYPE], consisting of the owner as a single byte, followed by
+/// the ID as a big-endian 32-bit integer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CallbackRef {
+    pub owner: CallbackOwner,
+    pub id: u32,
+}
+
+/// Tells `rmp-serde` to encode the tuple as an extension value.
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "_ExtStruct")]
+struct ExtStruct((i8, ByteBuf));
+
+impl Serialize for CallbackRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut data = Vec::with_capacity(5);
+        data.push(self.owner as u8);
+        data.extend_from_slice(&self.id.to_be_bytes());
+        ExtStruct((CALLBACK_EXT_TYPE, ByteBuf::from(data))).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CallbackRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ExtStruct((ext_type, data)) = ExtStruct::deserialize(deserializer)?;
+        if ext_type != CALLBACK_EXT_TYPE {
+            return Err(de::Error::custom(format!(
+                "expected a callback, found extension type {ext_type}"
+            )));
+        }
+
+        let owner = match data.first() {
+            Some(0) => CallbackOwner::Host,
+            Some(1) => CallbackOwner::Plugin,
+            _ => return Err(de::Error::custom("invalid callback owner")),
+        };
+        let id = data
+            .get(1..)
+            .and_then(|id| <[u8; 4]>::try_from(id).ok())
+            .map(u32::from_be_bytes)
+            .ok_or_else(|| de::Error::custom("invalid callback ID"))?;
+
+        Ok(Self { owner, id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_callback_refs_as_extension_values() {
+        let callback = CallbackRef {
+            owner: CallbackOwner::Plugin,
+            id: 258,
+        };
+
+        let encoded = rmp_serde::to_vec(&callback).unwrap();
+        // ext 8, with a length of 5 and a type of 1:
+        assert_eq!(encoded, [0xc7, 5, 1, 1, 0, 0, 1, 2]);
+
+        let decoded: CallbackRef = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(decoded, callback);
+
+        let error = rmp_serde::from_slice::<CallbackRef>(&[0xc7, 5, 2, 1, 0, 0, 1, 2]);
+        assert!(error.is_err());
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn encodes_callback_refs_with_postcard() {
+        let callback = CallbackRef {
+            owner: CallbackOwner::Host,
+            id: 258,
+        };
+
+        let encoded = postcard::to_allocvec(&callback).unwrap();
+        // The type, followed by the length-prefixed data:
+        assert_eq!(encoded, [1, 5, 0, 0, 0, 1, 2]);
+
+        let decoded: CallbackRef = postcard::from_bytes(&encoded).unwrap();
+        assert_eq!(decoded, callback);
+    }
+}
@@ -0,0 +1,222 @@
+use super::mem::FatPtr;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Bits in the length of a fat pointer that are reserved for extensions. The
+/// lowest byte of these holds the ID of the wire format used for the data the
+/// pointer refers to.
+pub const FAT_PTR_EXTENSION_BITS: u32 = 0xff000000;
+
+/// Format used for serializing the values that are passed between host and
+/// plugin.
+///
+/// MessagePack is always supported, and is what both sides fall back to if
+/// the other side doesn't support the format of the protocol. Other formats
+/// require enabling the Cargo feature of the same name.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum WireFormat {
+    /// MessagePack, with structs encoded as maps, unless they are compact.
+    #[default]
+    MessagePack,
+
+    /// JSON, which is mainly useful for debugging.
+    Json,
+
+    /// Postcard, a compact binary format that is not self-describing, which
+    /// makes it faster than MessagePack for hosts and plugins written in Rust.
+    Postcard,
+}
+
+impl WireFormat {
+    /// Returns the ID by which the format is identified in fat pointers.
+    pub const fn id(self) -> u8 {
+        match self {
+            Self::MessagePack => 0,
+            Self::Json => 1,
+            Self::Postcard => 2,
+        }
+    }
+
+    /// Returns the format with the given ID, if it is known.
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::MessagePack),
+            1 => Some(Self::Json),
+            2 => Some(Self::Postcard),
+            _ => None,
+        }
+    }
+
+    /// Returns the format of the data the given fat pointer refers to.
+    pub fn of_fat_ptr(fat_ptr: FatPtr) -> Result<Self, WireFormatError> {
+        let id = format_id_of_fat_ptr(fat_ptr);
+        Self::from_id(id).ok_or(WireFormatError::UnknownFormat(id))
+    }
+
+    /// Returns whether this build of `fp-bindgen-support` can (de)serialize
+    /// values using the format.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::MessagePack => true,
+            Self::Json => cfg!(feature = "json"),
+            Self::Postcard => cfg!(feature = "postcard"),
+        }
+    }
+
+    /// Serializes the given value using the format.
+    pub fn serialize<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>, WireFormatError> {
+        match self {
+            Self::MessagePack => {
+                let mut buffer = Vec::new();
+                let mut serializer = rmp_serde::Serializer::new(&mut buffer)
+                    .with_struct_map()
+                    .with_human_readable();
+                value
+                    .serialize(&mut serializer)
+                    .map_err(|error| WireFormatError::Serialization(error.to_string()))?;
+                Ok(buffer)
+            }
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::to_vec(value)
+                .map_err(|error| WireFormatError::Serialization(error.to_string())),
+            #[cfg(not(feature = "json"))]
+            Self::Json => Err(WireFormatError::Unsupported(self)),
+            #[cfg(feature = "postcard")]
+            Self::Postcard => postcard::to_allocvec(value)
+                .map_err(|error| WireFormatError::Serialization(error.to_string())),
+            #[cfg(not(feature = "postcard"))]
+            Self::Postcard => Err(WireFormatError::Unsupported(self)),
+        }
+    }
+
+    /// Deserializes a value from the given slice using the format.
+    pub fn deserialize<'de, T: Deserialize<'de>>(
+        self,
+        slice: &'de [u8],
+    ) -> Result<T, WireFormatError> {
+        match self {
+            Self::MessagePack => {
+                let mut deserializer = rmp_serde::Deserializer::new(slice).with_human_readable();
+                T::deserialize(&mut deserializer)
+                    .map_err(|error| WireFormatError::Deserialization(error.to_string()))
+            }
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::from_slice(slice)
+                .map_err(|error| WireFormatError::Deserialization(error.to_string())),
+            #[cfg(not(feature = "json"))]
+            Self::Json => Err(WireFormatError::Unsupported(self)),
+            #[cfg(feature = "postcard")]
+            Self::Postcard => postcard::from_bytes(slice)
+                .map_err(|error| WireFormatError::Deserialization(error.to_string())),
+            #[cfg(not(feature = "postcard"))]
+            Self::Postcard => Err(WireFormatError::Unsupported(self)),
+        }
+    }
+
+    /// Returns the Cargo feature that enables support for the format.
+    fn feature_name(self) -> &'static str {
+        match self {
+            Self::MessagePack => "default",
+            Self::Json => "json",
+            Self::Postcard => "postcard",
+        }
+    }
+
+    /// Marks the given fat pointer as referring to data in this format.
+    pub fn tag_fat_ptr(self, fat_ptr: FatPtr) -> FatPtr {
+        untag_fat_ptr(fat_ptr) | (self.id() as FatPtr) << 24
+    }
+}
+
+impl Display for WireFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::MessagePack => "MessagePack",
+            Self::Json => "JSON",
+            Self::Postcard => "Postcard",
+        })
+    }
+}
+
+/// Returns the ID of the format of the data the given fat pointer refers to,
+/// which may not be the ID of a known format.
+pub fn format_id_of_fat_ptr(fat_ptr: FatPtr) -> u8 {
+    (fat_ptr >> 24) as u8
+}
+
+/// Removes the extension bits from the given fat pointer, so that it only
+/// contains the pointer and length.
+pub fn untag_fat_ptr(fat_ptr: FatPtr) -> FatPtr {
+    fat_ptr & !(FAT_PTR_EXTENSION_BITS as FatPtr)
+}
+
+/// Error returned when a value cannot be passed using a wire format.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WireFormatError {
+    Deserialization(String),
+    Serialization(String),
+    UnknownFormat(u8),
+    Unsupported(WireFormat),
+}
+
+impl Display for WireFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deserialization(error) => write!(f, "deserialization error: {error}"),
+            Self::Serialization(error) => write!(f, "serialization error: {error}"),
+            Self::UnknownFormat(id) => write!(f, "unknown wire format: {id}"),
+            Self::Unsupported(format) => write!(
+                f,
+                "the {format} wire format requires the `{}` feature of fp-bindgen-support",
+                format.feature_name()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WireFormatError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mem::to_fat_ptr;
+
+    #[test]
+    fn tags_fat_ptrs() {
+        let fat_ptr = to_fat_ptr(0x1000 as *const u8, 42);
+        assert_eq!(WireFormat::of_fat_ptr(fat_ptr), Ok(WireFormat::MessagePack));
+
+        let tagged = WireFormat::Json.tag_fat_ptr(fat_ptr);
+        assert_eq!(WireFormat::of_fat_ptr(tagged), Ok(WireFormat::Json));
+        assert_eq!(untag_fat_ptr(tagged), fat_ptr);
+        assert_eq!(
+            WireFormat::of_fat_ptr(fat_ptr | 0x7f << 24),
+            Err(WireFormatError::UnknownFormat(0x7f))
+        );
+    }
+
+    #[test]
+    fn round_trips_values() {
+        let value = (String::from("value"), vec![1u32, 2, 3]);
+        for format in [
+            WireFormat::MessagePack,
+            WireFormat::Json,
+            WireFormat::Postcard,
+        ] {
+            if !format.is_supported() {
+                assert_eq!(
+                    format.serialize(&value),
+                    Err(WireFormatError::Unsupported(format))
+                );
+                continue;
+            }
+
+            let serialized = format.serialize(&value).unwrap();
+            assert_eq!(
+                format.deserialize::<(String, Vec<u32>)>(&serialized),
+                Ok(value.clone())
+            );
+        }
+    }
+}
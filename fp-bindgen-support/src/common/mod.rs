@@ -1,4 +1,7 @@
 pub mod abi;
 #[cfg(feature = "async")]
 pub mod r#async;
+pub mod callback;
+pub mod format;
 pub mod mem;
+pub mod protocol;
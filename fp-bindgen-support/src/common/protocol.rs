@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Identifies a specific revision of a protocol.
+///
+/// Plugins export the version of the protocol they were built against, which
+/// runtimes compare against their own version when loading the plugin.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProtocolVersion {
+    /// Name of the protocol, usually the name of the crate that defines it.
+    pub name: String,
+
+    /// Semantic version of the protocol.
+    pub version: String,
+
+    /// Hash of all the function signatures and type definitions in the
+    /// protocol.
+    pub hash: String,
+}
+
+impl ProtocolVersion {
+    /// Determines whether a plugin built against the `other` version of the
+    /// protocol can be used with this one.
+    pub fn compatibility_with(&self, other: &ProtocolVersion) -> ProtocolCompatibility {
+        if self.hash == other.hash {
+            return ProtocolCompatibility::Identical;
+        } else if self.name.is_empty() || other.name.is_empty() {
+            return ProtocolCompatibility::Unknown;
+        } else if self.name != other.name {
+            return ProtocolCompatibility::Incompatible;
+        }
+
+        match (parse_semver(&self.version), parse_semver(&other.version)) {
+            (Some(a), Some(b)) if is_semver_compatible(a, b) => ProtocolCompatibility::Compatible,
+            (Some(_), Some(_)) => ProtocolCompatibility::Incompatible,
+            _ => ProtocolCompatibility::Unknown,
+        }
+    }
+}
+
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} ({})", self.name, self.version, self.hash)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProtocolCompatibility {
+    /// Both versions have the exact same signatures.
+    Identical,
+
+    /// The signatures differ, but the semantic versions indicate the versions
+    /// should be compatible.
+    Compatible,
+
+    /// The versions are for different protocols, or their semantic versions
+    /// indicate they are not compatible.
+    Incompatible,
+
+    /// The signatures differ, but either version lacks a name or a valid
+    /// semantic version, so there is no telling whether they are compatible.
+    /// This is the case for protocols whose bindings are generated without
+    /// specifying their version.
+    Unknown,
+}
+
+/// Determines whether two semantic versions are compatible, using the same
+/// rules as Cargo: versions are compatible if their left-most non-zero
+/// component is the same.
+fn is_semver_compatible(a: (u64, u64, u64), b: (u64, u64, u64)) -> bool {
+    match (a, b) {
+        ((0, 0, a_patch), (0, 0, b_patch)) => a_patch == b_patch,
+        ((0, a_minor, _), (0, b_minor, _)) => a_minor == b_minor,
+        ((a_major, _, _), (b_major, _, _)) => a_major == b_major,
+    }
+}
+
+fn parse_semver(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.split(['-', '+']).next()?;
+    let mut components = version.split('.').map(|component| component.parse().ok());
+    let major = components.next()??;
+    let minor = components.next()??;
+    let patch = components.next()??;
+    match components.next() {
+        Some(_) => None,
+        None => Some((major, minor, patch)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(version: &str, hash: &str) -> ProtocolVersion {
+        ProtocolVersion {
+            name: "my-protocol".to_owned(),
+            version: version.to_owned(),
+            hash: hash.to_owned(),
+        }
+    }
+
+    #[test]
+    fn protocol_compatibility() {
+        use ProtocolCompatibility::*;
+
+        let host = version("1.2.0", "a");
+        assert_eq!(host.compatibility_with(&version("0.1.0", "a")), Identical);
+        assert_eq!(host.compatibility_with(&version("1.0.3", "b")), Compatible);
+        assert_eq!(
+            host.compatibility_with(&version("2.0.0", "b")),
+            Incompatible
+        );
+        assert_eq!(host.compatibility_with(&version("1.x", "b")), Unknown);
+        assert_eq!(host.compatibility_with(&version("", "b")), Unknown);
+
+        let other_protocol = ProtocolVersion {
+            name: "other-protocol".to_owned(),
+            ..version("1.2.0", "b")
+        };
+        assert_eq!(host.compatibility_with(&other_protocol), Incompatible);
+
+        let unnamed = ProtocolVersion {
+            name: String::new(),
+            ..version("", "b")
+        };
+        assert_eq!(host.compatibility_with(&unnamed), Unknown);
+        assert_eq!(unnamed.compatibility_with(&host), Unknown);
+
+        let host = version("0.3.1", "a");
+        assert_eq!(
+            host.compatibility_with(&version("0.3.0-beta.1", "b")),
+            Compatible
+        );
+        assert_eq!(
+            host.compatibility_with(&version("0.4.0", "b")),
+            Incompatible
+        );
+    }
+}
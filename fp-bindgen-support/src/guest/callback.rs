@@ -0,0 +1,299 @@
+use super::{
+    handle::{
+        __fp_host_release_handle, __fp_host_retain_handle, transfer_callback, transfer_handle,
+    },
+    io::{export_value_to_host, import_value_from_host},
+};
+use crate::common::{
+    callback::{CallbackOwner, CallbackRef},
+    mem::FatPtr,
+};
+use serde::{de, de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use std::{any::Any, cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+type Function<A, R> = Rc<dyn Fn(A) -> R>;
+
+thread_local! {
+    static CALLBACKS: RefCell<CallbackTable> = RefCell::new(CallbackTable::default());
+}
+
+/// Callback that takes an argument of type `A` and returns a value of type
+/// `R`, which can be passed between the plugin and the host.
+///
+/// Callbacks are either created by the plugin using [Callback::new()], in
+/// which case the host can invoke them, or they are received from the host, in
+/// which case invoking them calls into the host. Either way, the callback stays
+/// alive until both sides have dropped all references to it.
+pub struct Callback<A, R> {
+    inner: Inner<A, R>,
+}
+
+enum Inner<A, R> {
+    Plugin {
+        id: Option<u32>,
+        function: Function<A, R>,
+        entry: Rc<CallbackEntry>,
+    },
+    Host(Rc<HostCallback>),
+}
+
+/// The form in which plugin callbacks are stored in the callback table.
+struct CallbackEntry {
+    invoke: Box<dyn Fn(FatPtr) -> FatPtr>,
+    function: Box<dyn Any>,
+}
+
+/// Reference to a callback owned by the host, which is released when the last
+/// copy in the plugin is dropped.
+struct HostCallback {
+    id: u32,
+}
+
+impl Drop for HostCallback {
+    fn drop(&mut self) {
+        unsafe { __fp_host_release_handle(self.id) };
+    }
+}
+
+/// Table of the callbacks the host holds references to.
+#[derive(Default)]
+struct CallbackTable {
+    entries: HashMap<u32, (Rc<CallbackEntry>, u32)>,
+    last_id: u32,
+}
+
+impl CallbackTable {
+    fn get(&self, id: u32) -> Option<Rc<CallbackEntry>> {
+        self.entries.get(&id).map(|(entry, _)| entry.clone())
+    }
+
+    fn insert(&mut self, entry: Rc<CallbackEntry>) -> u32 {
+        loop {
+            self.last_id = self.last_id.wrapping_add(1);
+            if self.last_id != 0 && !self.entries.contains_key(&self.last_id) {
+                break;
+            }
+        }
+
+        self.entries.insert(self.last_id, (entry, 1));
+        self.last_id
+    }
+
+    fn retain(&mut self, id: u32) {
+        if let Some((_, refs)) = self.entries.get_mut(&id) {
+            *refs += 1;
+        }
+    }
+
+    /// Returns whether the given ID still refers to the given entry.
+    fn refers_to(&self, id: u32, entry: &Rc<CallbackEntry>) -> bool {
+        matches!(self.entries.get(&id), Some((existing, _)) if Rc::ptr_eq(existing, entry))
+    }
+
+    /// Removes a reference to the given entry. Returns the entry if this was
+    /// the last reference, so the caller can drop it after releasing the
+    /// borrow on the table.
+    #[must_use]
+    fn release(&mut self, id: u32) -> Option<Rc<CallbackEntry>> {
+        let (_, refs) = self.entries.get_mut(&id)?;
+        *refs -= 1;
+        if *refs == 0 {
+            self.entries.remove(&id).map(|(entry, _)| entry)
+        } else {
+            None
+        }
+    }
+}
+
+impl<A, R> Callback<A, R>
+where
+    A: DeserializeOwned + Serialize + 'static,
+    R: DeserializeOwned + Serialize + 'static,
+{
+    pub fn new(function: impl Fn(A) -> R + 'static) -> Self {
+        let function: Function<A, R> = Rc::new(function);
+        let invoke = {
+            let function = function.clone();
+            move |args: FatPtr| {
+                let arg = unsafe { import_value_from_host(args) };
+                export_value_to_host(&function(arg))
+            }
+        };
+        let entry = Rc::new(CallbackEntry {
+            invoke: Box::new(invoke),
+            function: Box::new(function.clone()),
+        });
+
+        Self {
+            inner: Inner::Plugin {
+                id: None,
+                function,
+                entry,
+            },
+        }
+    }
+
+    /// Invokes the callback. If the callback is owned by the host, this calls
+    /// into the host.
+    pub fn call(&self, arg: A) -> R {
+        match &self.inner {
+            Inner::Plugin { function, .. } => function(arg),
+            Inner::Host(callback) => unsafe {
+                let args = export_value_to_host(&arg);
+                import_value_from_host(__fp_host_invoke_callback(callback.id, args))
+            },
+        }
+    }
+}
+
+impl<A, R> Clone for Callback<A, R> {
+    fn clone(&self) -> Self {
+        let inner = match &self.inner {
+            Inner::Plugin {
+                id,
+                function,
+                entry,
+            } => Inner::Plugin {
+                id: *id,
+                function: function.clone(),
+                entry: entry.clone(),
+            },
+            Inner::Host(callback) => Inner::Host(callback.clone()),
+        };
+        Self { inner }
+    }
+}
+
+impl<A, R> fmt::Debug for Callback<A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.inner {
+            Inner::Plugin { id, .. } => f.debug_tuple("PluginCallback").field(id).finish(),
+            Inner::Host(callback) => f.debug_tuple("HostCallback").field(&callback.id).finish(),
+        }
+    }
+}
+
+impl<A, R> PartialEq for Callback<A, R> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.inner, &other.inner) {
+            (Inner::Plugin { entry, .. }, Inner::Plugin { entry: other, .. }) => {
+                Rc::ptr_eq(entry, other)
+            }
+            (Inner::Host(callback), Inner::Host(other)) => callback.id == other.id,
+            _ => false,
+        }
+    }
+}
+
+impl<A, R> Serialize for Callback<A, R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // The value carries a reference to the callback, so the host can
+        // release it again once it has received the value.
+        let callback = match &self.inner {
+            Inner::Plugin { id, entry, .. } => CALLBACKS.with(|table| {
+                let mut table = table.borrow_mut();
+                let id = match *id {
+                    Some(id) if table.refers_to(id, entry) => {
+                        if transfer_callback(id) {
+                            table.retain(id);
+                        }
+                        id
+                    }
+                    _ => {
+                        let id = table.insert(entry.clone());
+                        transfer_callback(id);
+                        id
+                    }
+                };
+                CallbackRef {
+                    owner: CallbackOwner::Plugin,
+                    id,
+                }
+            }),
+            Inner::Host(callback) => {
+                if transfer_handle(callback.id) {
+                    unsafe { __fp_host_retain_handle(callback.id) };
+                }
+                CallbackRef {
+                    owner: CallbackOwner::Host,
+                    id: callback.id,
+                }
+            }
+        };
+        callback.serialize(serializer)
+    }
+}
+
+impl<'de, A, R> Deserialize<'de> for Callback<A, R>
+where
+    A: 'static,
+    R: 'static,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let CallbackRef { owner, id } = CallbackRef::deserialize(deserializer)?;
+
+        let inner = match owner {
+            CallbackOwner::Plugin => {
+                // The reference the host passed along is released once the
+                // whole value has been received, since we can refer to the
+                // function directly.
+                transfer_callback(id);
+                let entry = CALLBACKS.with(|table| table.borrow().get(id));
+
+                let entry = entry
+                    .ok_or_else(|| de::Error::custom(format!("callback {id} does not exist")))?;
+                let function = entry
+                    .function
+                    .downcast_ref::<Function<A, R>>()
+                    .cloned()
+                    .ok_or_else(|| {
+                        de::Error::custom(format!("callback {id} has a different signature"))
+                    })?;
+                Inner::Plugin {
+                    id: Some(id),
+                    function,
+                    entry,
+                }
+            }
+            CallbackOwner::Host => {
+                // Every deserialized callback owns a reference, so temporary
+                // copies that Serde creates along the way can be dropped
+                // safely. The reference carried by the value is released once
+                // the whole value has been received.
+                transfer_handle(id);
+                unsafe { __fp_host_retain_handle(id) };
+                Inner::Host(Rc::new(HostCallback { id }))
+            }
+        };
+        Ok(Self { inner })
+    }
+}
+
+#[doc(hidden)]
+#[no_mangle]
+pub fn __fp_guest_invoke_callback(id: u32, args: FatPtr) -> FatPtr {
+    let entry = CALLBACKS
+        .with(|table| table.borrow().get(id))
+        .unwrap_or_else(|| panic!("Host invoked callback {id}, which does not exist"));
+    (entry.invoke)(args)
+}
+
+#[doc(hidden)]
+#[no_mangle]
+pub fn __fp_guest_release_callback(id: u32) {
+    let released = CALLBACKS.with(|table| table.borrow_mut().release(id));
+    // Dropping the callback may call into the host, so this needs to happen
+    // after the table is no longer borrowed.
+    drop(released);
+}
+
+#[doc(hidden)]
+#[no_mangle]
+pub fn __fp_guest_retain_callback(id: u32) {
+    CALLBACKS.with(|table| table.borrow_mut().retain(id));
+}
+
+#[link(wasm_import_module = "fp")]
+extern "C" {
+    fn __fp_host_invoke_callback(id: u32, args: FatPtr) -> FatPtr;
+}
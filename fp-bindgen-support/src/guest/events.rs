@@ -0,0 +1,65 @@
+use super::{
+    io::{__fp_free, export_value_to_host, import_value_from_host},
+    r#async::task::Task,
+};
+use crate::common::mem::FatPtr;
+use serde::de::DeserializeOwned;
+use std::{cell::RefCell, collections::HashMap, future::Future, pin::Pin, rc::Rc};
+
+type Handler = Rc<dyn Fn(FatPtr) -> Pin<Box<dyn Future<Output = ()>>>>;
+
+thread_local! {
+    static HANDLERS: RefCell<HashMap<&'static str, Handler>> = RefCell::new(HashMap::new());
+}
+
+/// Subscribes to the given event, so the host starts delivering it to the
+/// plugin. If the plugin was already subscribed, the handler replaces the
+/// previous one.
+pub fn subscribe<T, F, FUT>(event: &'static str, handler: F)
+where
+    T: DeserializeOwned + 'static,
+    F: Fn(T) -> FUT + 'static,
+    FUT: Future<Output = ()> + 'static,
+{
+    let handler: Handler = Rc::new(move |value| {
+        let value = unsafe { import_value_from_host::<T>(value) };
+        Box::pin(handler(value))
+    });
+
+    let previous = HANDLERS.with(|handlers| handlers.borrow_mut().insert(event, handler));
+    if previous.is_none() {
+        unsafe { __fp_host_subscribe(export_value_to_host(&event)) };
+    }
+}
+
+/// Unsubscribes from the given event, so the host stops delivering it to the
+/// plugin.
+pub fn unsubscribe(event: &'static str) {
+    let previous = HANDLERS.with(|handlers| handlers.borrow_mut().remove(event));
+    if previous.is_some() {
+        unsafe { __fp_host_unsubscribe(export_value_to_host(&event)) };
+    }
+}
+
+/// Passes an event delivered by the host to the subscribed handler, and
+/// returns a pointer to the `AsyncValue` that is resolved once the handler
+/// has finished.
+#[doc(hidden)]
+pub fn dispatch(event: &'static str, value: FatPtr) -> FatPtr {
+    let handler = HANDLERS.with(|handlers| handlers.borrow().get(event).cloned());
+    match handler {
+        Some(handler) => Task::alloc_and_spawn(handler(value)),
+        None => {
+            // Events may still arrive shortly after unsubscribing, in which
+            // case they are simply ignored.
+            unsafe { __fp_free(value) };
+            Task::alloc_and_spawn(async {})
+        }
+    }
+}
+
+#[link(wasm_import_module = "fp")]
+extern "C" {
+    fn __fp_host_subscribe(event: FatPtr);
+    fn __fp_host_unsubscribe(event: FatPtr);
+}
@@ -0,0 +1,152 @@
+use super::callback::__fp_guest_release_callback;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{cell::RefCell, collections::BTreeSet, fmt, marker::PhantomData};
+
+thread_local! {
+    /// References carried by the value that is being passed to or received
+    /// from the host, if any.
+    static TRANSFER: RefCell<Option<Transfer>> = const { RefCell::new(None) };
+}
+
+/// References carried by a value that is passed to or received from the host.
+#[derive(Default)]
+pub(crate) struct Transfer {
+    /// IDs of the handles and callbacks owned by the host.
+    handles: BTreeSet<u32>,
+
+    /// IDs of the callbacks owned by the plugin.
+    callbacks: BTreeSet<u32>,
+}
+
+/// Handle to a resource of type `T` that is owned by the host.
+///
+/// The resource stays alive for as long as the plugin holds a handle to it.
+/// Cloning a handle tells the host another reference was made, while dropping
+/// it tells the host the reference is gone. Passing a handle to an imported
+/// function by value gives up the reference after the call; pass it by
+/// reference to keep it.
+///
+/// A value that is passed to the host carries a reference to every handle in
+/// it, which the host releases once it has received the value. This way, a
+/// handle that is returned from an export stays alive even though the plugin
+/// drops its copy before the host gets to read the result.
+pub struct Handle<T> {
+    id: u32,
+    _resource: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    /// Returns the ID by which the host refers to the resource.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        unsafe { __fp_host_retain_handle(self.id) };
+        Self {
+            id: self.id,
+            _resource: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for Handle<T> {
+    fn drop(&mut self) {
+        unsafe { __fp_host_release_handle(self.id) };
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Handle").field(&self.id).finish()
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Serialize for Handle<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if transfer_handle(self.id) {
+            unsafe { __fp_host_retain_handle(self.id) };
+        }
+        serializer.serialize_u32(self.id)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Handle<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = u32::deserialize(deserializer)?;
+        // Every deserialized handle owns a reference, so temporary copies that
+        // Serde creates along the way can be dropped safely. The reference
+        // carried by the value is released once the whole value was received.
+        transfer_handle(id);
+        unsafe { __fp_host_retain_handle(id) };
+        Ok(Self {
+            id,
+            _resource: PhantomData,
+        })
+    }
+}
+
+/// Runs the given closure, which passes a value to or receives a value from
+/// the host, and returns the references carried by the value.
+///
+/// A value carries a single reference to each of the handles and callbacks in
+/// it, no matter how often they occur in the value, or how often Serde
+/// deserializes them while trying the variants of an untagged enum, for
+/// instance. The receiver releases these references once it has received the
+/// whole value.
+pub(crate) fn with_transferred_handles<R>(f: impl FnOnce() -> R) -> (R, Transfer) {
+    let previous = TRANSFER.with(|transfer| transfer.replace(Some(Transfer::default())));
+    let result = f();
+    let transfer = TRANSFER.with(|transfer| transfer.replace(previous));
+    (result, transfer.unwrap_or_default())
+}
+
+/// Adds the given handle or host callback to the value that is currently
+/// being transferred. Returns whether the value did not carry a reference to
+/// it yet.
+pub(crate) fn transfer_handle(id: u32) -> bool {
+    TRANSFER.with(|transfer| {
+        transfer
+            .borrow_mut()
+            .as_mut()
+            .is_some_and(|transfer| transfer.handles.insert(id))
+    })
+}
+
+/// Adds the given plugin callback to the value that is currently being
+/// transferred. Returns whether the value did not carry a reference to the
+/// callback yet.
+pub(crate) fn transfer_callback(id: u32) -> bool {
+    TRANSFER.with(|transfer| {
+        transfer
+            .borrow_mut()
+            .as_mut()
+            .is_some_and(|transfer| transfer.callbacks.insert(id))
+    })
+}
+
+/// Releases the references carried by a value that was received.
+pub(crate) fn release_handles(transfer: Transfer) {
+    for id in transfer.handles {
+        unsafe { __fp_host_release_handle(id) };
+    }
+    for id in transfer.callbacks {
+        __fp_guest_release_callback(id);
+    }
+}
+
+#[link(wasm_import_module = "fp")]
+extern "C" {
+    pub(crate) fn __fp_host_release_handle(id: u32);
+    pub(crate) fn __fp_host_retain_handle(id: u32);
+}
@@ -1,18 +1,40 @@
-use crate::common::mem::*;
-use rmp_serde::{Deserializer, Serializer};
+use super::handle::{release_handles, with_transferred_handles};
+use crate::common::{
+    format::{untag_fat_ptr, WireFormat, FAT_PTR_EXTENSION_BITS},
+    mem::*,
+};
 use serde::{Deserialize, Serialize};
 use std::alloc::Layout;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// ID of the wire format the host negotiated with us.
+static WIRE_FORMAT: AtomicU8 = AtomicU8::new(WireFormat::MessagePack.id());
+
+/// Returns the format in which values are passed to and from the host.
+pub fn wire_format() -> WireFormat {
+    WireFormat::from_id(WIRE_FORMAT.load(Ordering::Relaxed)).unwrap_or_default()
+}
+
+/// Called by the host to request the wire format with the given ID. Returns
+/// the ID of the format we will use from now on, which is the ID of
+/// MessagePack if the requested format is not supported.
+#[doc(hidden)]
+#[no_mangle]
+pub fn __fp_negotiate_wire_format(id: u32) -> u32 {
+    let format = u8::try_from(id)
+        .ok()
+        .and_then(WireFormat::from_id)
+        .filter(|format| format.is_supported())
+        .unwrap_or_default();
+    WIRE_FORMAT.store(format.id(), Ordering::Relaxed);
+    format.id() as u32
+}
 
 #[doc(hidden)]
 pub fn export_value_to_host<T: Serialize>(value: &T) -> FatPtr {
-    let mut buffer = Vec::new();
-    value
-        .serialize(
-            &mut Serializer::new(&mut buffer)
-                .with_struct_map()
-                .with_human_readable(),
-        )
-        .expect("Serialization error");
+    let format = wire_format();
+    let (buffer, _) = with_transferred_handles(|| format.serialize(value));
+    let mut buffer = buffer.expect("Serialization error");
 
     let len = buffer.len();
 
@@ -32,13 +54,13 @@ pub fn export_value_to_host<T: Serialize>(value: &T) -> FatPtr {
     }
 
     // Make sure the length marker does not run into our extension bits:
-    if len & 0xff000000 != 0 {
+    if len & FAT_PTR_EXTENSION_BITS as usize != 0 {
         panic!("Buffer too large ({} bytes)", len);
     }
 
     let ptr = buffer.as_ptr();
     std::mem::forget(buffer);
-    to_fat_ptr(ptr, len as u32)
+    format.tag_fat_ptr(to_fat_ptr(ptr, len as u32))
 }
 
 /// # Safety
@@ -47,17 +69,17 @@ pub fn export_value_to_host<T: Serialize>(value: &T) -> FatPtr {
 /// host. After this call, the pointer is no longer valid.
 #[doc(hidden)]
 pub unsafe fn import_value_from_host<'de, T: Deserialize<'de>>(fat_ptr: FatPtr) -> T {
-    let (ptr, len) = from_fat_ptr(fat_ptr);
-    if len & 0xff000000 != 0 {
-        panic!("Unknown extension bits");
-    }
+    let format = WireFormat::of_fat_ptr(fat_ptr).expect("Unknown extension bits");
+    let (ptr, len) = from_fat_ptr(untag_fat_ptr(fat_ptr));
 
     let slice = std::slice::from_raw_parts(ptr, len as usize);
-    let mut deserializer = Deserializer::new(slice).with_human_readable();
-    let value = T::deserialize(&mut deserializer).unwrap();
+    let (value, handles) = with_transferred_handles(|| format.deserialize(slice));
+    let value = value.unwrap();
 
     __fp_free(fat_ptr);
 
+    release_handles(handles);
+
     value
 }
 
@@ -87,13 +109,8 @@ pub fn __fp_malloc(len: u32) -> FatPtr {
 #[doc(hidden)]
 #[no_mangle]
 pub unsafe fn __fp_free(ptr: FatPtr) {
-    let (ptr, len) = from_fat_ptr(ptr);
-
-    assert_eq!(
-        len & 0xff000000,
-        0,
-        "__fp_free() failed: unknown extension bits"
-    );
+    // The extension bits don't affect the allocation, so we ignore them:
+    let (ptr, len) = from_fat_ptr(untag_fat_ptr(ptr));
 
     std::alloc::dealloc(
         ptr as *mut u8,
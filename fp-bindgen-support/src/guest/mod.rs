@@ -1,3 +1,7 @@
 #[cfg(feature = "async")]
 pub mod r#async;
+pub mod callback;
+#[cfg(feature = "async")]
+pub mod events;
+pub mod handle;
 pub mod io;
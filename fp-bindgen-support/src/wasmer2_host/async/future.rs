@@ -3,6 +3,7 @@ use crate::common::{
     r#async::{FUTURE_STATUS_PENDING, FUTURE_STATUS_READY},
 };
 use crate::wasmer2_host::{
+    errors::InvocationError,
     io::{to_fat_ptr, to_wasm_ptr},
     mem::import_from_guest_raw,
     runtime::RuntimeInstanceData,
@@ -23,7 +24,7 @@ impl ModuleRawFuture {
 }
 
 impl Future for ModuleRawFuture {
-    type Output = Vec<u8>;
+    type Output = Result<Vec<u8>, InvocationError>;
 
     fn poll(
         self: std::pin::Pin<&mut Self>,
@@ -0,0 +1,307 @@
+use super::{
+    errors::InvocationError,
+    handle::{transfer_callback, transfer_handle, Resource},
+    mem::{export_to_guest, import_from_guest},
+    runtime::{current_instance, RuntimeInstanceData},
+};
+use crate::common::{
+    callback::{CallbackOwner, CallbackRef},
+    mem::FatPtr,
+};
+use serde::{de, de::DeserializeOwned, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::{any::Any, fmt, sync::Arc};
+
+type Function<A, R> = Arc<dyn Fn(A) -> R + Send + Sync>;
+
+/// Invokes a host callback with arguments passed by the plugin.
+type Invoke =
+    Box<dyn Fn(&RuntimeInstanceData, FatPtr) -> Result<FatPtr, InvocationError> + Send + Sync>;
+
+/// Callback that takes an argument of type `A` and returns a value of type
+/// `R`, which can be passed between the host and a plugin.
+///
+/// Callbacks are either created by the host using [Callback::new()], in which
+/// case the plugin can invoke them, or they are received from the plugin, in
+/// which case invoking them calls into the plugin. Either way, the callback
+/// stays alive until both sides have dropped all references to it.
+pub struct Callback<A, R> {
+    inner: Inner<A, R>,
+}
+
+enum Inner<A, R> {
+    Host {
+        id: Option<u32>,
+        function: Function<A, R>,
+        resource: Resource,
+    },
+    Plugin(Arc<PluginCallback>),
+}
+
+/// The form in which host callbacks are stored in the handle table.
+struct HostCallback {
+    invoke: Invoke,
+    function: Box<dyn Any + Send + Sync>,
+}
+
+/// Reference to a callback owned by the plugin, which is released when the
+/// last copy on the host side is dropped.
+struct PluginCallback {
+    id: u32,
+    env: RuntimeInstanceData,
+}
+
+impl Drop for PluginCallback {
+    fn drop(&mut self) {
+        self.env.guest_release_callback(self.id);
+    }
+}
+
+impl<A, R> Callback<A, R>
+where
+    A: DeserializeOwned + Serialize + 'static,
+    R: DeserializeOwned + Serialize + 'static,
+{
+    pub fn new(function: impl Fn(A) -> R + Send + Sync + 'static) -> Self {
+        let function: Function<A, R> = Arc::new(function);
+        let invoke = {
+            let function = function.clone();
+            move |env: &RuntimeInstanceData, args: FatPtr| {
+                let arg = import_from_guest(env, args)?;
+                export_to_guest(env, &function(arg))
+            }
+        };
+        let resource = Arc::new(HostCallback {
+            invoke: Box::new(invoke),
+            function: Box::new(function.clone()),
+        });
+
+        Self {
+            inner: Inner::Host {
+                id: None,
+                function,
+                resource,
+            },
+        }
+    }
+
+    /// Invokes the callback. If the callback is owned by the plugin, this
+    /// calls into the plugin.
+    pub fn call(&self, arg: A) -> Result<R, InvocationError> {
+        match &self.inner {
+            Inner::Host { function, .. } => Ok(function(arg)),
+            Inner::Plugin(callback) => {
+                let env = &callback.env;
+                let args = export_to_guest(env, &arg)?;
+                let result = env.guest_invoke_callback(callback.id, args)?;
+                import_from_guest(env, result)
+            }
+        }
+    }
+}
+
+impl<A, R> Clone for Callback<A, R> {
+    fn clone(&self) -> Self {
+        let inner = match &self.inner {
+            Inner::Host {
+                id,
+                function,
+                resource,
+            } => Inner::Host {
+                id: *id,
+                function: function.clone(),
+                resource: resource.clone(),
+            },
+            Inner::Plugin(callback) => Inner::Plugin(callback.clone()),
+        };
+        Self { inner }
+    }
+}
+
+impl<A, R> fmt::Debug for Callback<A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.inner {
+            Inner::Host { id, .. } => f.debug_tuple("HostCallback").field(id).finish(),
+            Inner::Plugin(callback) => f.debug_tuple("PluginCallback").field(&callback.id).finish(),
+        }
+    }
+}
+
+impl<A, R> PartialEq for Callback<A, R> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.inner, &other.inner) {
+            (
+                Inner::Host { resource, .. },
+                Inner::Host {
+                    resource: other, ..
+                },
+            ) => Arc::ptr_eq(resource, other),
+            (Inner::Plugin(callback), Inner::Plugin(other)) => callback.id == other.id,
+            _ => false,
+        }
+    }
+}
+
+impl<A, R> Serialize for Callback<A, R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let env = current_instance()
+            .ok_or_else(|| ser::Error::custom("callbacks can only be passed to a plugin"))?;
+
+        // The value carries a reference to the callback, so the plugin can
+        // release it again once it has received the value.
+        let callback = match &self.inner {
+            Inner::Host { id, resource, .. } => {
+                let mut table = env.handles.lock().unwrap();
+                let id = match *id {
+                    Some(id) if table.refers_to(id, resource) => {
+                        if transfer_handle(id) {
+                            table.retain(id);
+                        }
+                        id
+                    }
+                    _ => {
+                        let id = table.insert(resource.clone());
+                        transfer_handle(id);
+                        id
+                    }
+                };
+                CallbackRef {
+                    owner: CallbackOwner::Host,
+                    id,
+                }
+            }
+            Inner::Plugin(callback) => {
+                if transfer_callback(callback.id) {
+                    env.guest_retain_callback(callback.id);
+                }
+                CallbackRef {
+                    owner: CallbackOwner::Plugin,
+                    id: callback.id,
+                }
+            }
+        };
+        callback.serialize(serializer)
+    }
+}
+
+impl<'de, A, R> Deserialize<'de> for Callback<A, R>
+where
+    A: 'static,
+    R: 'static,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let CallbackRef { owner, id } = CallbackRef::deserialize(deserializer)?;
+        let env = current_instance()
+            .ok_or_else(|| de::Error::custom("callbacks can only be received from a plugin"))?;
+
+        let inner = match owner {
+            CallbackOwner::Host => {
+                // The reference the plugin passed along is released once the
+                // whole value has been received, since we can refer to the
+                // function directly.
+                transfer_handle(id);
+                let resource = env.handles.lock().unwrap().get(id);
+
+                let resource = resource
+                    .ok_or_else(|| de::Error::custom(format!("callback {id} does not exist")))?;
+                let function = resource
+                    .downcast_ref::<HostCallback>()
+                    .and_then(|callback| callback.function.downcast_ref::<Function<A, R>>())
+                    .cloned()
+                    .ok_or_else(|| {
+                        de::Error::custom(format!("callback {id} has a different signature"))
+                    })?;
+                Inner::Host {
+                    id: Some(id),
+                    function,
+                    resource,
+                }
+            }
+            CallbackOwner::Plugin => {
+                // Every deserialized callback owns a reference, so temporary
+                // copies that Serde creates along the way can be dropped
+                // safely. The reference carried by the value is released once
+                // the whole value has been received.
+                transfer_callback(id);
+                env.guest_retain_callback(id);
+                Inner::Plugin(Arc::new(PluginCallback { id, env }))
+            }
+        };
+        Ok(Self { inner })
+    }
+}
+
+/// Called by the plugin to invoke a callback owned by the host.
+pub fn invoke_callback(
+    env: &RuntimeInstanceData,
+    id: u32,
+    args: FatPtr,
+) -> Result<FatPtr, InvocationError> {
+    let resource = env.handles.lock().unwrap().get(id);
+    let callback = resource
+        .as_ref()
+        .and_then(|resource| resource.downcast_ref::<HostCallback>())
+        .unwrap_or_else(|| panic!("Plugin invoked callback {id}, which does not exist"));
+    (callback.invoke)(env, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wasmer2_host::{
+        handle::release_handle,
+        mem::{deserialize_from_guest, serialize_for_guest},
+    };
+
+    #[test]
+    fn resolves_host_callbacks_passed_back_by_guest() {
+        let env = RuntimeInstanceData::default();
+
+        let callback = Callback::new(|value: u32| value + 1);
+        let first = serialize_for_guest(&env, &callback).unwrap();
+        let second = serialize_for_guest(&env, &callback).unwrap();
+        assert_eq!(first, [0xc7, 5, 1, 0, 0, 0, 0, 1]);
+        assert_eq!(second, [0xc7, 5, 1, 0, 0, 0, 0, 2]);
+
+        // References the guest passes back are released once received:
+        let received: Callback<u32, u32> = deserialize_from_guest(&env, &first).unwrap();
+        assert_eq!(received, callback);
+        assert_eq!(received.call(1).unwrap(), 2);
+        assert!(env.handles.lock().unwrap().get(1).is_none());
+        assert!(env.handles.lock().unwrap().get(2).is_some());
+
+        let received: Callback<u32, u32> = deserialize_from_guest(&env, &second).unwrap();
+        assert_eq!(received.call(2).unwrap(), 3);
+        assert!(env.handles.lock().unwrap().get(2).is_none());
+    }
+
+    #[test]
+    fn values_carry_one_reference_per_callback() {
+        let env = RuntimeInstanceData::default();
+
+        let callback = Callback::new(|value: u32| value + 1);
+        let serialized = serialize_for_guest(&env, &callback).unwrap();
+        env.handles.lock().unwrap().retain(1);
+
+        // The guest holds two references, and passes back a value that
+        // carries a single one:
+        let value = [&[0x92][..], &serialized, &serialized].concat();
+        let received: Vec<Callback<u32, u32>> = deserialize_from_guest(&env, &value).unwrap();
+        assert_eq!(received[1].call(1).unwrap(), 2);
+        assert!(env.handles.lock().unwrap().get(1).is_some());
+
+        let serialized = serialize_for_guest(&env, &received).unwrap();
+        assert_eq!(serialized, value);
+        release_handle(&env, 1);
+        let _: Vec<Callback<u32, u32>> = deserialize_from_guest(&env, &serialized).unwrap();
+        assert!(env.handles.lock().unwrap().get(1).is_none());
+    }
+
+    #[test]
+    fn rejects_callbacks_with_different_signature() {
+        let env = RuntimeInstanceData::default();
+
+        let serialized = serialize_for_guest(&env, &Callback::new(|value: u32| value + 1)).unwrap();
+        let result = deserialize_from_guest::<Callback<String, u32>>(&env, &serialized);
+        assert!(result.is_err());
+    }
+}
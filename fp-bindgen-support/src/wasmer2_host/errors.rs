@@ -1,9 +1,28 @@
+use crate::common::{
+    format::{WireFormat, WireFormatError},
+    protocol::ProtocolVersion,
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum RuntimeError {
     #[error(transparent)]
     CompileError(#[from] wasmer::CompileError),
+
+    #[error("plugin was built against protocol {found}, which is incompatible with {expected}")]
+    IncompatibleProtocol {
+        expected: Box<ProtocolVersion>,
+        found: Box<ProtocolVersion>,
+    },
+
+    #[error("could not determine the protocol version of the plugin: {0}")]
+    InvalidProtocolVersion(String),
+
+    #[error("could not negotiate the wire format with the plugin: {0}")]
+    WireFormatNegotiation(String),
+
+    #[error("could not set up the WASI environment: {0}")]
+    WasiSetup(String),
 }
 
 #[derive(Debug, Error)]
@@ -11,9 +30,36 @@ pub enum InvocationError {
     #[error("expected function was not exported: {0}")]
     FunctionNotExported(String),
 
+    #[error("linked plugin `{plugin}` failed while handling `{function}`: {source}")]
+    LinkedPlugin {
+        plugin: String,
+        function: String,
+        source: Box<InvocationError>,
+    },
+
     #[error("returned data did not match expected type")]
     UnexpectedReturnType,
 
+    /// The plugin passed data in another format than the one that was
+    /// negotiated. `found` is the ID of that format, which may not be the ID
+    /// of any known format.
+    #[error("plugin used the wire format with ID {found} instead of {expected}")]
+    UnexpectedWireFormat { expected: WireFormat, found: u8 },
+
+    #[error(transparent)]
+    WireFormat(#[from] WireFormatError),
+
     #[error(transparent)]
-    WasmerRuntimeError(#[from] wasmer::RuntimeError),
+    WasmerRuntimeError(wasmer::RuntimeError),
+}
+
+impl From<wasmer::RuntimeError> for InvocationError {
+    fn from(error: wasmer::RuntimeError) -> Self {
+        // Failures of linked plugins are passed through the plugin that called
+        // them as a trap, which we unwrap again here:
+        match error.downcast::<InvocationError>() {
+            Ok(error) => error,
+            Err(error) => Self::WasmerRuntimeError(error),
+        }
+    }
 }
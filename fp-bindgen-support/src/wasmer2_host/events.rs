@@ -0,0 +1,16 @@
+use super::{errors::InvocationError, mem::import_from_guest, runtime::RuntimeInstanceData};
+use crate::common::mem::FatPtr;
+
+/// Called by the plugin when it subscribes to an event.
+pub fn subscribe(env: &RuntimeInstanceData, event: FatPtr) -> Result<(), InvocationError> {
+    let event: String = import_from_guest(env, event)?;
+    env.subscriptions.lock().unwrap().insert(event);
+    Ok(())
+}
+
+/// Called by the plugin when it unsubscribes from an event.
+pub fn unsubscribe(env: &RuntimeInstanceData, event: FatPtr) -> Result<(), InvocationError> {
+    let event: String = import_from_guest(env, event)?;
+    env.subscriptions.lock().unwrap().remove(&event);
+    Ok(())
+}
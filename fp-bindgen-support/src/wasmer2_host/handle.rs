@@ -0,0 +1,406 @@
+use super::runtime::{current_instance, RuntimeInstanceData};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+use thiserror::Error;
+
+pub(crate) type Resource = Arc<dyn Any + Send + Sync>;
+
+thread_local! {
+    /// References carried by the value that is currently being passed to or
+    /// received from a plugin.
+    static TRANSFER: RefCell<Option<Transfer>> = const { RefCell::new(None) };
+}
+
+/// References carried by a value that is passed to or received from a plugin.
+#[derive(Default)]
+pub(crate) struct Transfer {
+    /// IDs of the entries in the handle table, which holds both the resources
+    /// behind handles and the callbacks owned by the host.
+    handles: HashSet<u32>,
+
+    /// IDs of the callbacks owned by the plugin.
+    callbacks: HashSet<u32>,
+}
+
+/// Error returned when accessing the resource behind a [Handle].
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum HandleError {
+    #[error("handle {0} does not refer to a live resource")]
+    Stale(u32),
+
+    #[error("handle {0} refers to a resource of another type")]
+    TypeMismatch(u32),
+
+    #[error("handles can only be exchanged with a plugin through the generated bindings")]
+    Unavailable,
+}
+
+/// Per-instance table of the resources a plugin holds handles to.
+///
+/// Every handle passed to the plugin is reference counted, so the resource
+/// stays in the table until the plugin has dropped all of its copies. Besides
+/// the resources behind handles, the table also holds the callbacks that were
+/// passed to the plugin.
+#[derive(Default)]
+pub struct HandleTable {
+    entries: HashMap<u32, HandleEntry>,
+    last_id: u32,
+}
+
+struct HandleEntry {
+    resource: Resource,
+    refs: u32,
+}
+
+impl HandleTable {
+    pub(crate) fn get(&self, id: u32) -> Option<Resource> {
+        self.entries.get(&id).map(|entry| entry.resource.clone())
+    }
+
+    pub(crate) fn insert(&mut self, resource: Resource) -> u32 {
+        loop {
+            self.last_id = self.last_id.wrapping_add(1);
+            if self.last_id != 0 && !self.entries.contains_key(&self.last_id) {
+                break;
+            }
+        }
+
+        self.entries
+            .insert(self.last_id, HandleEntry { resource, refs: 1 });
+        self.last_id
+    }
+
+    pub(crate) fn retain(&mut self, id: u32) {
+        if let Some(entry) = self.entries.get_mut(&id) {
+            entry.refs += 1;
+        }
+    }
+
+    /// Returns whether the given entry still refers to the given resource.
+    pub(crate) fn refers_to(&self, id: u32, resource: &Resource) -> bool {
+        matches!(self.entries.get(&id), Some(entry) if Arc::ptr_eq(&entry.resource, resource))
+    }
+
+    /// Removes a reference to the given entry. Returns the resource if this
+    /// was the last reference, so the caller can drop it after releasing the
+    /// lock on the table.
+    #[must_use]
+    pub(crate) fn release(&mut self, id: u32) -> Option<Resource> {
+        let entry = self.entries.get_mut(&id)?;
+        entry.refs -= 1;
+        if entry.refs == 0 {
+            self.entries.remove(&id).map(|entry| entry.resource)
+        } else {
+            None
+        }
+    }
+}
+
+/// Handle to a host resource of type `T` that can be passed to a plugin.
+///
+/// The plugin only ever sees an opaque ID, while the host keeps the resource
+/// itself in the handle table of the plugin instance. Handles received from
+/// the plugin are resolved against that table; if the plugin passed a handle
+/// that is no longer valid, accessing the resource returns a [HandleError].
+pub struct Handle<T> {
+    id: Option<u32>,
+    resource: Result<Arc<T>, HandleError>,
+}
+
+impl<T> Handle<T>
+where
+    T: Any + Send + Sync,
+{
+    pub fn new(resource: T) -> Self {
+        Self::from(Arc::new(resource))
+    }
+
+    /// Returns the ID by which the plugin refers to the resource, if the
+    /// handle was received from the plugin.
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+
+    /// Returns a reference to the resource behind the handle.
+    pub fn get(&self) -> Result<&T, HandleError> {
+        self.resource.as_deref().map_err(Clone::clone)
+    }
+
+    /// Returns a shared pointer to the resource behind the handle.
+    pub fn resource(&self) -> Result<Arc<T>, HandleError> {
+        self.resource.clone()
+    }
+}
+
+impl<T> From<Arc<T>> for Handle<T> {
+    fn from(resource: Arc<T>) -> Self {
+        Self {
+            id: None,
+            resource: Ok(resource),
+        }
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            resource: self.resource.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("id", &self.id)
+            .field("is_stale", &self.resource.is_err())
+            .finish()
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.resource, &other.resource) {
+            (Ok(resource), Ok(other_resource)) => Arc::ptr_eq(resource, other_resource),
+            (Err(error), Err(other_error)) => error == other_error,
+            _ => false,
+        }
+    }
+}
+
+impl<T> Serialize for Handle<T>
+where
+    T: Any + Send + Sync,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let resource: Resource = self.resource.clone().map_err(ser::Error::custom)?;
+        let env = current_instance().ok_or_else(|| ser::Error::custom(HandleError::Unavailable))?;
+        let mut table = env.handles.lock().unwrap();
+        let id = match self.id {
+            Some(id) if table.refers_to(id, &resource) => {
+                if transfer_handle(id) {
+                    table.retain(id);
+                }
+                id
+            }
+            _ => {
+                let id = table.insert(resource);
+                transfer_handle(id);
+                id
+            }
+        };
+        serializer.serialize_u32(id)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Handle<T>
+where
+    T: Any + Send + Sync,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = u32::deserialize(deserializer)?;
+        let env = current_instance().ok_or_else(|| de::Error::custom(HandleError::Unavailable))?;
+
+        // The reference the plugin passed along is released once the whole
+        // value has been received, since the handle holds on to the resource
+        // itself.
+        transfer_handle(id);
+        let resource = env.handles.lock().unwrap().get(id);
+
+        let resource = resource.ok_or(HandleError::Stale(id)).and_then(|resource| {
+            resource
+                .downcast::<T>()
+                .map_err(|_| HandleError::TypeMismatch(id))
+        });
+        Ok(Self {
+            id: Some(id),
+            resource,
+        })
+    }
+}
+
+/// Runs the given closure, which passes a value to or receives a value from a
+/// plugin, and returns the references carried by the value.
+///
+/// A value carries a single reference to each of the handles and callbacks in
+/// it, no matter how often they occur in the value, or how often Serde
+/// deserializes them while trying the variants of an untagged enum, for
+/// instance. The receiver releases these references once it has received the
+/// whole value.
+pub(crate) fn with_transferred_handles<R>(f: impl FnOnce() -> R) -> (R, Transfer) {
+    let previous = TRANSFER.with(|transfer| transfer.replace(Some(Transfer::default())));
+    let result = f();
+    let transfer = TRANSFER.with(|transfer| transfer.replace(previous));
+    (result, transfer.unwrap_or_default())
+}
+
+/// Adds the given entry of the handle table to the value that is currently
+/// being transferred. Returns whether the value did not carry a reference to
+/// the entry yet.
+pub(crate) fn transfer_handle(id: u32) -> bool {
+    TRANSFER.with(|transfer| {
+        transfer
+            .borrow_mut()
+            .as_mut()
+            .is_some_and(|transfer| transfer.handles.insert(id))
+    })
+}
+
+/// Adds the given plugin callback to the value that is currently being
+/// transferred. Returns whether the value did not carry a reference to the
+/// callback yet.
+pub(crate) fn transfer_callback(id: u32) -> bool {
+    TRANSFER.with(|transfer| {
+        transfer
+            .borrow_mut()
+            .as_mut()
+            .is_some_and(|transfer| transfer.callbacks.insert(id))
+    })
+}
+
+/// Releases the references carried by a value that was transferred.
+pub(crate) fn release_handles(env: &RuntimeInstanceData, transfer: Transfer) {
+    for id in transfer.handles {
+        release_handle(env, id);
+    }
+    for id in transfer.callbacks {
+        env.guest_release_callback(id);
+    }
+}
+
+/// Called by the plugin when it drops its copy of a handle.
+pub fn release_handle(env: &RuntimeInstanceData, id: u32) {
+    let resource = env.handles.lock().unwrap().release(id);
+    // Dropping the resource may call back into the plugin, so this needs to
+    // happen after the table is unlocked.
+    drop(resource);
+}
+
+/// Called by the plugin when it clones a handle.
+pub fn retain_handle(env: &RuntimeInstanceData, id: u32) {
+    env.handles.lock().unwrap().retain(id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wasmer2_host::mem::{deserialize_from_guest, serialize_for_guest};
+
+    struct Connection(&'static str);
+
+    #[test]
+    fn resolves_handles_passed_back_by_guest() {
+        let env = RuntimeInstanceData::default();
+
+        let handle = Handle::new(Connection("db"));
+        let serialized = serialize_for_guest(&env, &handle).unwrap();
+        assert_eq!(env.handles.lock().unwrap().entries.len(), 1);
+
+        // The guest retains the handle when serializing it, and the host
+        // releases that reference again after receiving it:
+        retain_handle(&env, 1);
+        let received: Handle<Connection> = deserialize_from_guest(&env, &serialized).unwrap();
+        assert_eq!(received.id(), Some(1));
+        assert_eq!(received.get().unwrap().0, "db");
+        assert_eq!(received, handle);
+        assert_eq!(env.handles.lock().unwrap().entries[&1].refs, 1);
+
+        // Passing a received handle back to the guest reuses its ID:
+        assert_eq!(serialize_for_guest(&env, &received).unwrap(), serialized);
+        assert_eq!(env.handles.lock().unwrap().entries.len(), 1);
+
+        release_handle(&env, 1);
+        assert_eq!(env.handles.lock().unwrap().entries.len(), 1);
+        release_handle(&env, 1);
+        assert!(env.handles.lock().unwrap().entries.is_empty());
+    }
+
+    #[test]
+    fn keeps_handles_returned_by_exports() {
+        let env = RuntimeInstanceData::default();
+
+        // The handle is passed to an export, which returns it as part of its
+        // result. The guest retains the handle when serializing the result,
+        // and drops its own copy before the host reads the result:
+        let serialized = serialize_for_guest(&env, &Handle::new(Connection("db"))).unwrap();
+        retain_handle(&env, 1);
+        release_handle(&env, 1);
+
+        let received: Handle<Connection> = deserialize_from_guest(&env, &serialized).unwrap();
+        assert_eq!(received.get().unwrap().0, "db");
+        assert!(env.handles.lock().unwrap().entries.is_empty());
+
+        // Passing the handle to the guest again assigns a new ID:
+        let serialized = serialize_for_guest(&env, &received).unwrap();
+        let id: u32 = rmp_serde::from_slice(&serialized).unwrap();
+        assert_eq!(id, 2);
+        assert_eq!(env.handles.lock().unwrap().entries[&2].refs, 1);
+    }
+
+    #[test]
+    fn values_carry_one_reference_per_handle() {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Message {
+            #[allow(dead_code)]
+            Request {
+                connection: Handle<Connection>,
+                id: u32,
+            },
+            Notification {
+                connection: Handle<Connection>,
+            },
+        }
+
+        #[derive(Serialize)]
+        struct Notification {
+            connection: u32,
+        }
+
+        let env = RuntimeInstanceData::default();
+        serialize_for_guest(&env, &Handle::new(Connection("db"))).unwrap();
+
+        // Serde tries to deserialize the handle as part of a request first,
+        // but the reference the guest passed along is only released once:
+        retain_handle(&env, 1);
+        let serialized = rmp_serde::to_vec_named(&Notification { connection: 1 }).unwrap();
+        let received: Message = deserialize_from_guest(&env, &serialized).unwrap();
+        let connection = match received {
+            Message::Notification { connection } => connection,
+            Message::Request { .. } => unreachable!(),
+        };
+        assert_eq!(connection.get().unwrap().0, "db");
+        assert_eq!(env.handles.lock().unwrap().entries[&1].refs, 1);
+
+        // Passing the same handle twice only adds a single reference:
+        serialize_for_guest(&env, &[connection.clone(), connection]).unwrap();
+        assert_eq!(env.handles.lock().unwrap().entries[&1].refs, 2);
+    }
+
+    #[test]
+    fn reports_stale_and_mismatched_handles() {
+        let env = RuntimeInstanceData::default();
+
+        let serialized = serialize_for_guest(&env, &Handle::new(Connection("db"))).unwrap();
+        retain_handle(&env, 1);
+        let mismatched: Handle<String> = deserialize_from_guest(&env, &serialized).unwrap();
+        assert_eq!(mismatched.get(), Err(HandleError::TypeMismatch(1)));
+
+        release_handle(&env, 1);
+        let stale: Handle<Connection> = deserialize_from_guest(&env, &serialized).unwrap();
+        assert_eq!(stale.get().err(), Some(HandleError::Stale(1)));
+    }
+
+    #[test]
+    fn requires_handle_table() {
+        let result = rmp_serde::to_vec(&Handle::new(Connection("db")));
+        assert!(result.is_err());
+    }
+}
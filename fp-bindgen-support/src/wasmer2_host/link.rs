@@ -0,0 +1,165 @@
+use super::{errors::InvocationError, runtime::RuntimeInstanceData};
+use std::{any::Any, collections::HashMap, sync::Arc};
+use thiserror::Error;
+
+/// Error returned when the imports of a plugin cannot be linked to the
+/// exports of another plugin.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum LinkError {
+    #[error("linking to plugin `{0}` would create a cycle, because it already calls this plugin")]
+    Cycle(String),
+
+    #[error("plugin `{0}` uses a different wire format, so calls cannot be forwarded to it")]
+    WireFormatMismatch(String),
+}
+
+/// Per-instance table of the imports that are linked to the exports of
+/// other plugins, keyed by the name of the import.
+pub(crate) type LinkTable = HashMap<&'static str, Link>;
+
+/// The plugin that calls to a linked import are forwarded to.
+#[derive(Clone)]
+pub struct Link {
+    name: Arc<str>,
+    provider: Arc<dyn Any + Send + Sync>,
+    provider_env: RuntimeInstanceData,
+}
+
+impl Link {
+    /// Returns the name the provider was linked under.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the runtime of the provider.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the provider is not of the given type. This cannot happen
+    /// when linking through the generated bindings, because plugins can only
+    /// be linked to plugins that use the same protocol.
+    pub fn provider<T: 'static>(&self) -> &T {
+        self.provider
+            .downcast_ref()
+            .expect("linked plugin has an unexpected runtime type")
+    }
+
+    /// Wraps an error that occurred while calling the given function in the
+    /// provider, so that it identifies the plugin that failed.
+    pub fn error(&self, function: &str, error: InvocationError) -> InvocationError {
+        InvocationError::LinkedPlugin {
+            plugin: self.name.to_string(),
+            function: function.to_owned(),
+            source: Box::new(error),
+        }
+    }
+}
+
+/// Links the given imports of the consumer to the exports of the provider.
+///
+/// Links to another provider that already exist for any of the imports are
+/// replaced. Fails if the provider, directly or through the plugins it is
+/// linked to itself, calls the consumer, or if the plugins negotiated
+/// different wire formats.
+pub fn link_plugin(
+    consumer: &RuntimeInstanceData,
+    name: &str,
+    provider: Arc<dyn Any + Send + Sync>,
+    provider_env: &RuntimeInstanceData,
+    imports: &[&'static str],
+) -> Result<(), LinkError> {
+    if depends_on(provider_env, consumer) {
+        return Err(LinkError::Cycle(name.to_owned()));
+    }
+
+    // Calls are forwarded without re-encoding their arguments and results:
+    if provider_env.wire_format() != consumer.wire_format() {
+        return Err(LinkError::WireFormatMismatch(name.to_owned()));
+    }
+
+    let link = Link {
+        name: name.into(),
+        provider,
+        provider_env: provider_env.clone(),
+    };
+    let mut links = consumer.links.lock().unwrap();
+    for import in imports {
+        links.insert(import, link.clone());
+    }
+    Ok(())
+}
+
+/// Removes all the links of the consumer, so that its imports are handled by
+/// the host again.
+pub fn unlink_plugins(consumer: &RuntimeInstanceData) {
+    // Drop the links outside of the lock, since they may hold the last
+    // reference to a provider:
+    let links = std::mem::take(&mut *consumer.links.lock().unwrap());
+    drop(links);
+}
+
+/// Returns whether the given instance is, or calls into, the target instance.
+fn depends_on(instance: &RuntimeInstanceData, target: &RuntimeInstanceData) -> bool {
+    if Arc::ptr_eq(&instance.links, &target.links) {
+        return true;
+    }
+
+    let providers = instance
+        .links
+        .lock()
+        .unwrap()
+        .values()
+        .map(|link| link.provider_env.clone())
+        .collect::<Vec<_>>();
+    providers
+        .iter()
+        .any(|provider_env| depends_on(provider_env, target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::format::WireFormat;
+
+    fn link(consumer: &RuntimeInstanceData, name: &str, provider: &RuntimeInstanceData) {
+        link_plugin(consumer, name, Arc::new(()), provider, &["produce"]).unwrap();
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let a = RuntimeInstanceData::default();
+        let b = RuntimeInstanceData::default();
+        let c = RuntimeInstanceData::default();
+
+        link(&a, "b", &b);
+        link(&b, "c", &c);
+        assert_eq!(
+            link_plugin(&c, "a", Arc::new(()), &a, &["produce"]),
+            Err(LinkError::Cycle("a".to_owned()))
+        );
+        assert_eq!(
+            link_plugin(&a, "a", Arc::new(()), &a, &["produce"]),
+            Err(LinkError::Cycle("a".to_owned()))
+        );
+
+        unlink_plugins(&a);
+        link(&c, "a", &a);
+        assert_eq!(c.linked_plugin("produce").unwrap().name(), "a");
+    }
+
+    #[test]
+    fn requires_matching_wire_formats() {
+        let a = RuntimeInstanceData::default();
+        let b = RuntimeInstanceData::default();
+        b.set_wire_format(WireFormat::Json);
+
+        assert_eq!(
+            link_plugin(&a, "b", Arc::new(()), &b, &["produce"]),
+            Err(LinkError::WireFormatMismatch("b".to_owned()))
+        );
+
+        a.set_wire_format(WireFormat::Json);
+        link(&a, "b", &b);
+        assert_eq!(a.linked_plugin("produce").unwrap().name(), "b");
+    }
+}
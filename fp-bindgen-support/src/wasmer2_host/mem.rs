@@ -1,7 +1,15 @@
-use super::{io::to_wasm_ptr, runtime::RuntimeInstanceData};
-use crate::common::mem::FatPtr;
-use rmp_serde::{decode::ReadReader, Deserializer, Serializer};
-use serde::{Deserialize, Serialize};
+use super::{
+    errors::InvocationError,
+    handle::{release_handles, with_transferred_handles},
+    io::to_wasm_ptr,
+    runtime::{with_instance, RuntimeInstanceData},
+};
+use crate::common::{
+    format::{format_id_of_fat_ptr, untag_fat_ptr, FAT_PTR_EXTENSION_BITS},
+    mem::FatPtr,
+};
+use rmp_serde::Serializer;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use wasmer::WasmCell;
 
 /// Serialize the given value to MessagePack
@@ -20,16 +28,45 @@ pub fn deserialize_from_slice<'a, T: Deserialize<'a>>(slice: &'a [u8]) -> T {
     T::deserialize(&mut deserializer).unwrap()
 }
 
+/// Serialize the given value using the wire format of the given instance, for
+/// passing it to that instance. Unlike `serialize_to_vec()`, this supports
+/// handles and callbacks.
+pub fn serialize_for_guest<T: Serialize>(
+    env: &RuntimeInstanceData,
+    value: &T,
+) -> Result<Vec<u8>, InvocationError> {
+    let (result, handles) = with_instance(env, || {
+        with_transferred_handles(|| env.wire_format().serialize(value))
+    });
+    if result.is_err() {
+        // The value never reaches the guest, so neither do the references it
+        // would carry:
+        release_handles(env, handles);
+    }
+    Ok(result?)
+}
+
+/// Deserialize the given slice, which was received from the given instance
+/// and is encoded using its wire format. Unlike `deserialize_from_slice()`,
+/// this supports handles and callbacks.
+pub fn deserialize_from_guest<'a, T: Deserialize<'a>>(
+    env: &RuntimeInstanceData,
+    slice: &'a [u8],
+) -> Result<T, InvocationError> {
+    let (result, handles) = with_instance(env, || {
+        with_transferred_handles(|| env.wire_format().deserialize(slice))
+    });
+    release_handles(env, handles);
+    Ok(result?)
+}
+
 /// Serialize an object from the linear memory and after that free up the memory
-pub fn import_from_guest<'de, T: Deserialize<'de>>(
+pub fn import_from_guest<T: DeserializeOwned>(
     env: &RuntimeInstanceData,
     fat_ptr: FatPtr,
-) -> T {
-    let value = import_from_guest_raw(env, fat_ptr);
-
-    let mut deserializer =
-        Deserializer::<ReadReader<&[u8]>>::new(value.as_ref()).with_human_readable();
-    T::deserialize(&mut deserializer).unwrap()
+) -> Result<T, InvocationError> {
+    let value = import_from_guest_raw(env, fat_ptr)?;
+    deserialize_from_guest(env, &value)
 }
 
 /// Retrieve a serialized object from the linear memory as a Vec<u8> and free up
@@ -37,18 +74,28 @@ pub fn import_from_guest<'de, T: Deserialize<'de>>(
 ///
 /// Useful when the consumer wants to pass the result, without having the
 /// deserialize and serialize it.
-pub fn import_from_guest_raw(env: &RuntimeInstanceData, fat_ptr: FatPtr) -> Vec<u8> {
+///
+/// Fails if the data is not encoded using the wire format that was negotiated
+/// with the instance. The memory is not freed in that case, since we cannot
+/// tell whether the fat pointer is valid at all.
+pub fn import_from_guest_raw(
+    env: &RuntimeInstanceData,
+    fat_ptr: FatPtr,
+) -> Result<Vec<u8>, InvocationError> {
     if fat_ptr == 0 {
         // This may happen with async calls that don't return a result:
-        return Vec::new();
+        return Ok(Vec::new());
+    }
+
+    let expected = env.wire_format();
+    let found = format_id_of_fat_ptr(fat_ptr);
+    if found != expected.id() {
+        return Err(InvocationError::UnexpectedWireFormat { expected, found });
     }
 
     let memory = unsafe { env.memory.get_unchecked() };
 
-    let (ptr, len) = to_wasm_ptr::<u8>(fat_ptr);
-    if len & 0xff000000 != 0 {
-        panic!("Unknown extension bits");
-    }
+    let (ptr, len) = to_wasm_ptr::<u8>(untag_fat_ptr(fat_ptr));
 
     let value: Vec<u8> = {
         let view = ptr.deref(memory, 0, len).unwrap();
@@ -57,22 +104,26 @@ pub fn import_from_guest_raw(env: &RuntimeInstanceData, fat_ptr: FatPtr) -> Vec<
 
     env.free(fat_ptr);
 
-    value
+    Ok(value)
 }
 
 /// Serialize a value and put it in linear memory.
-pub fn export_to_guest<T: Serialize>(env: &RuntimeInstanceData, value: &T) -> FatPtr {
-    export_to_guest_raw(env, rmp_serde::to_vec(value).unwrap())
+pub fn export_to_guest<T: Serialize>(
+    env: &RuntimeInstanceData,
+    value: &T,
+) -> Result<FatPtr, InvocationError> {
+    Ok(export_to_guest_raw(env, serialize_for_guest(env, value)?))
 }
 
-/// Copy the buffer into linear memory.
+/// Copy the buffer into linear memory. The buffer is expected to be encoded
+/// using the wire format of the instance.
 pub fn export_to_guest_raw(env: &RuntimeInstanceData, buffer: Vec<u8>) -> FatPtr {
     let memory = unsafe { env.memory.get_unchecked() };
 
     let len = buffer.len() as u32;
 
     // Make sure the length marker does not run into our extension bits:
-    if len & 0xff000000 != 0 {
+    if len & FAT_PTR_EXTENSION_BITS != 0 {
         panic!("Buffer too large ({} bytes)", len);
     }
 
@@ -85,5 +136,31 @@ pub fn export_to_guest_raw(env: &RuntimeInstanceData, buffer: Vec<u8>) -> FatPtr
         values[i].set(*val);
     }
 
-    fat_ptr
+    env.wire_format().tag_fat_ptr(fat_ptr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{format::WireFormat, mem::to_fat_ptr};
+
+    #[test]
+    fn rejects_data_in_other_wire_formats() {
+        let env = RuntimeInstanceData::default();
+
+        let fat_ptr = WireFormat::Json.tag_fat_ptr(to_fat_ptr(0x1000 as *const u8, 4));
+        assert!(matches!(
+            import_from_guest_raw(&env, fat_ptr),
+            Err(InvocationError::UnexpectedWireFormat {
+                expected: WireFormat::MessagePack,
+                found: 1
+            })
+        ));
+
+        let fat_ptr = to_fat_ptr(0x1000 as *const u8, 4) | 0x7f << 24;
+        assert!(matches!(
+            import_from_guest_raw(&env, fat_ptr),
+            Err(InvocationError::UnexpectedWireFormat { found: 0x7f, .. })
+        ));
+    }
 }
@@ -1,7 +1,12 @@
 #[cfg(feature = "async")]
 pub mod r#async;
 
+pub mod callback;
 pub mod errors;
+pub mod events;
+pub mod handle;
 pub mod io;
+pub mod link;
 pub mod mem;
+pub mod protocol;
 pub mod runtime;
@@ -0,0 +1,115 @@
+use super::{errors::RuntimeError, mem::import_from_guest_raw, runtime::RuntimeInstanceData};
+use crate::common::{
+    format::WireFormat,
+    mem::FatPtr,
+    protocol::{ProtocolCompatibility, ProtocolVersion},
+};
+use wasmer::Instance;
+
+/// Verifies that the plugin in the given instance was built against a version
+/// of the protocol that is compatible with the `expected` version.
+///
+/// Plugins built with older versions of fp-bindgen don't declare the version
+/// of their protocol, in which case only a warning is emitted. The same goes
+/// for protocols without a name or a valid semantic version.
+pub fn check_protocol_version(
+    instance: &Instance,
+    env: &RuntimeInstanceData,
+    expected: &ProtocolVersion,
+) -> Result<(), RuntimeError> {
+    let function = match instance
+        .exports
+        .get_native_function::<(), FatPtr>("__fp_protocol_version")
+    {
+        Ok(function) => function,
+        Err(_) => {
+            tracing::warn!(
+                "Plugin does not declare its protocol version, so it cannot be verified to be \
+                    compatible with {expected}"
+            );
+            return Ok(());
+        }
+    };
+
+    let fat_ptr = function
+        .call()
+        .map_err(|err| RuntimeError::InvalidProtocolVersion(err.to_string()))?;
+    let found: ProtocolVersion = import_from_guest_raw(env, fat_ptr)
+        .and_then(|data| Ok(env.wire_format().deserialize(&data)?))
+        .map_err(|err| RuntimeError::InvalidProtocolVersion(err.to_string()))?;
+
+    match expected.compatibility_with(&found) {
+        ProtocolCompatibility::Identical => Ok(()),
+        ProtocolCompatibility::Compatible => {
+            tracing::warn!(
+                "Plugin was built against protocol {found}, which differs from {expected}, \
+                    but should be compatible"
+            );
+            Ok(())
+        }
+        ProtocolCompatibility::Unknown => {
+            tracing::warn!(
+                "Plugin was built against protocol {found}, which differs from {expected}, \
+                    and cannot be verified to be compatible"
+            );
+            Ok(())
+        }
+        ProtocolCompatibility::Incompatible => Err(RuntimeError::IncompatibleProtocol {
+            expected: Box::new(expected.clone()),
+            found: Box::new(found),
+        }),
+    }
+}
+
+/// Negotiates the format in which values are passed between the host and the
+/// plugin in the given instance, and returns the format that was agreed on.
+///
+/// The `preferred` format is used if both sides support it. Otherwise, as
+/// well as for plugins built with older versions of fp-bindgen, MessagePack
+/// is used and a warning is emitted.
+pub fn negotiate_wire_format(
+    instance: &Instance,
+    env: &RuntimeInstanceData,
+    preferred: WireFormat,
+) -> Result<WireFormat, RuntimeError> {
+    if preferred == WireFormat::MessagePack {
+        return Ok(preferred);
+    }
+
+    if !preferred.is_supported() {
+        tracing::warn!(
+            "The {preferred} wire format is not supported by the runtime, falling back to \
+                MessagePack"
+        );
+        return Ok(WireFormat::MessagePack);
+    }
+
+    let function = match instance
+        .exports
+        .get_native_function::<u32, u32>("__fp_negotiate_wire_format")
+    {
+        Ok(function) => function,
+        Err(_) => {
+            tracing::warn!(
+                "Plugin does not support negotiating the wire format, falling back to MessagePack"
+            );
+            return Ok(WireFormat::MessagePack);
+        }
+    };
+
+    let id = function
+        .call(preferred.id() as u32)
+        .map_err(|err| RuntimeError::WireFormatNegotiation(err.to_string()))?;
+    let format = if id == preferred.id() as u32 {
+        preferred
+    } else {
+        tracing::warn!(
+            "The {preferred} wire format is not supported by the plugin, falling back to \
+                MessagePack"
+        );
+        WireFormat::MessagePack
+    };
+
+    env.set_wire_format(format);
+    Ok(format)
+}
@@ -1,9 +1,20 @@
-use crate::common::mem::FatPtr;
-use std::collections::HashMap;
+use super::{
+    errors::InvocationError,
+    handle::HandleTable,
+    link::{Link, LinkTable},
+};
+use crate::common::{format::WireFormat, mem::FatPtr};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::Waker;
 use wasmer::{LazyInit, Memory, NativeFunc, WasmerEnv};
 
+thread_local! {
+    static CURRENT_INSTANCE: RefCell<Option<RuntimeInstanceData>> = const { RefCell::new(None) };
+}
+
 #[derive(Clone, Default, WasmerEnv)]
 pub struct RuntimeInstanceData {
     #[wasmer(export)]
@@ -11,9 +22,29 @@ pub struct RuntimeInstanceData {
 
     pub(crate) wakers: Arc<Mutex<HashMap<FatPtr, Waker>>>,
 
+    pub(crate) handles: Arc<Mutex<HandleTable>>,
+
+    /// Names of the events the plugin has subscribed to.
+    pub(crate) subscriptions: Arc<Mutex<HashSet<String>>>,
+
+    /// Imports that are linked to the exports of other plugins.
+    pub(crate) links: Arc<Mutex<LinkTable>>,
+
+    /// ID of the wire format that was negotiated with the plugin.
+    pub(crate) wire_format: Arc<AtomicU8>,
+
     #[wasmer(export)]
     __fp_free: LazyInit<NativeFunc<FatPtr>>,
 
+    #[wasmer(export(optional = true))]
+    __fp_guest_invoke_callback: LazyInit<NativeFunc<(u32, FatPtr), FatPtr>>,
+
+    #[wasmer(export(optional = true))]
+    __fp_guest_release_callback: LazyInit<NativeFunc<u32>>,
+
+    #[wasmer(export(optional = true))]
+    __fp_guest_retain_callback: LazyInit<NativeFunc<u32>>,
+
     #[wasmer(export)]
     __fp_guest_resolve_async_value: LazyInit<NativeFunc<(FatPtr, FatPtr)>>,
 
@@ -22,6 +53,49 @@ pub struct RuntimeInstanceData {
 }
 
 impl RuntimeInstanceData {
+    /// Returns whether the plugin has subscribed to the given event.
+    pub fn is_subscribed(&self, event: &str) -> bool {
+        self.subscriptions.lock().unwrap().contains(event)
+    }
+
+    /// Returns the plugin that calls to the given import are forwarded to,
+    /// if the import is linked.
+    pub fn linked_plugin(&self, import: &str) -> Option<Link> {
+        self.links.lock().unwrap().get(import).cloned()
+    }
+
+    /// Returns the format in which values are passed to and from the plugin.
+    pub fn wire_format(&self) -> WireFormat {
+        WireFormat::from_id(self.wire_format.load(Ordering::Relaxed)).unwrap_or_default()
+    }
+
+    pub(crate) fn set_wire_format(&self, format: WireFormat) {
+        self.wire_format.store(format.id(), Ordering::Relaxed);
+    }
+
+    pub fn guest_invoke_callback(&self, id: u32, args: FatPtr) -> Result<FatPtr, InvocationError> {
+        let function = self.__fp_guest_invoke_callback.get_ref().ok_or_else(|| {
+            InvocationError::FunctionNotExported("__fp_guest_invoke_callback".to_owned())
+        })?;
+        Ok(function.call(id, args)?)
+    }
+
+    pub fn guest_release_callback(&self, id: u32) {
+        if let Some(function) = self.__fp_guest_release_callback.get_ref() {
+            if let Err(error) = function.call(id) {
+                tracing::warn!("Could not release callback {id}: {error}");
+            }
+        }
+    }
+
+    pub fn guest_retain_callback(&self, id: u32) {
+        if let Some(function) = self.__fp_guest_retain_callback.get_ref() {
+            function
+                .call(id)
+                .expect("Runtime error: Cannot retain callback");
+        }
+    }
+
     pub fn guest_resolve_async_value(&self, async_ptr: FatPtr, result_ptr: FatPtr) {
         unsafe {
             self.__fp_guest_resolve_async_value
@@ -49,3 +123,16 @@ impl RuntimeInstanceData {
         };
     }
 }
+
+/// Makes the given instance available to the (de)serialization of handles and
+/// callbacks for the duration of the closure.
+pub(crate) fn with_instance<R>(env: &RuntimeInstanceData, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT_INSTANCE.with(|current| current.replace(Some(env.clone())));
+    let result = f();
+    CURRENT_INSTANCE.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+pub(crate) fn current_instance() -> Option<RuntimeInstanceData> {
+    CURRENT_INSTANCE.with(|current| current.borrow().clone())
+}
@@ -0,0 +1,55 @@
+use crate::{
+    types::{TypeIdent, TypeMap},
+    Serializable, Type,
+};
+use std::marker::PhantomData;
+
+/// Callback that takes an argument of type `A` and returns a value of type
+/// `R`.
+///
+/// Callbacks may be passed in either direction, so the host can hand a plugin
+/// a progress reporter, or a plugin can register an event handler with the
+/// host:
+///
+/// ```ignore
+/// fp_import! {
+///     fn on_event(handler: Callback<Event, ()>);
+/// }
+///
+/// fp_export! {
+///     fn run(progress: Callback<u32, ()>);
+/// }
+/// ```
+///
+/// Callbacks that take multiple arguments can use a tuple for `A`.
+///
+/// This type only serves to describe protocols. The generated bindings use
+/// the `Callback` types from `fp-bindgen-support` instead.
+pub struct Callback<A, R>(PhantomData<(A, R)>);
+
+impl<A, R> Serializable for Callback<A, R>
+where
+    A: Serializable,
+    R: Serializable,
+{
+    fn ident() -> TypeIdent {
+        TypeIdent {
+            name: "Callback".to_owned(),
+            generic_args: vec![
+                (TypeIdent::from("A"), vec![]),
+                (TypeIdent::from("R"), vec![]),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn ty() -> Type {
+        Type::Callback(TypeIdent::from("A"), TypeIdent::from("R"))
+    }
+
+    fn collect_types(types: &mut TypeMap) {
+        types.entry(Self::ident()).or_insert_with(Self::ty);
+        A::collect_types(types);
+        R::collect_types(types);
+    }
+}
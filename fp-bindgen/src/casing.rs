@@ -1,8 +1,9 @@
 use inflector::Inflector;
 use std::convert::TryFrom;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub enum Casing {
+    #[default]
     Original,
     CamelCase,
     LowerCase,
@@ -35,12 +36,6 @@ impl Casing {
     }
 }
 
-impl Default for Casing {
-    fn default() -> Self {
-        Self::Original
-    }
-}
-
 impl TryFrom<&str> for Casing {
     type Error = String;
 
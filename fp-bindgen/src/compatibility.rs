@@ -0,0 +1,1664 @@
+//! Compatibility checks between two versions of a protocol.
+//!
+//! Plugins and runtimes are not always upgraded at the same time, so it is
+//! useful to know whether a protocol change can be deployed while builds
+//! against the previous version are still around. [check_compatibility()]
+//! compares two versions of a protocol and classifies every change it finds
+//! for both sides of the protocol:
+//!
+//! - **Host** compatibility tells whether a runtime built against the *new*
+//!   protocol can still run plugins that were built against the *old* one.
+//! - **Guest** compatibility tells whether plugins built against the *new*
+//!   protocol can still run inside runtimes built against the *old* one.
+//!
+//! Type changes are classified according to the direction in which values of
+//! the type travel. Adding a field to a struct that is only ever sent from the
+//! host to plugins, for instance, does not break old plugins, because they
+//! ignore the unknown field. But if old plugins send that struct to the host,
+//! the new host can only accept it if the added field is an `Option` or has a
+//! `default`.
+//!
+//! Compact structs are encoded as arrays, so their fields are compared by
+//! position instead of by name. Old readers reject values with more fields
+//! than they know about, and fields can only be omitted at the end if they
+//! have a `default`.
+
+use crate::{
+    casing::Casing,
+    functions::{Function, FunctionList},
+    primitives::Primitive,
+    types::{Enum, Field, Struct, Type, TypeIdent, TypeMap},
+    Protocol,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+/// Compares two versions of a protocol and reports all the changes between
+/// them, together with their impact on hosts and guests.
+pub fn check_compatibility(old: &Protocol, new: &Protocol) -> CompatibilityReport {
+    let mut checker = Checker {
+        old,
+        new,
+        comparing: BTreeSet::new(),
+    };
+
+    let mut changes = Vec::new();
+    checker.check_functions(
+        &old.import_functions,
+        &new.import_functions,
+        FunctionKind::Import,
+        &mut changes,
+    );
+    checker.check_functions(
+        &old.export_functions,
+        &new.export_functions,
+        FunctionKind::Export,
+        &mut changes,
+    );
+    checker.check_types(&mut changes);
+
+    CompatibilityReport { changes }
+}
+
+/// The result of comparing two versions of a protocol.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompatibilityReport {
+    pub changes: Vec<Change>,
+}
+
+impl CompatibilityReport {
+    /// Returns all the changes that are breaking for either the host or the
+    /// guest.
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.is_breaking())
+    }
+
+    /// Returns whether any of the changes is breaking for either side.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking_changes().next().is_some()
+    }
+
+    /// Returns whether a host built against the new protocol can no longer
+    /// run plugins built against the old protocol.
+    pub fn is_breaking_for_host(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.host == Compatibility::Breaking)
+    }
+
+    /// Returns whether plugins built against the new protocol can no longer
+    /// run inside a host built against the old protocol.
+    pub fn is_breaking_for_guest(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.guest == Compatibility::Breaking)
+    }
+}
+
+impl Display for CompatibilityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "No changes.");
+        }
+
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single change between two versions of a protocol.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Change {
+    /// The part of the protocol that was changed.
+    pub subject: ChangeSubject,
+
+    /// Human-readable description of the change.
+    pub description: String,
+
+    /// Whether a host built against the new protocol can still run plugins
+    /// built against the old protocol.
+    pub host: Compatibility,
+
+    /// Whether plugins built against the new protocol can still run inside
+    /// a host built against the old protocol.
+    pub guest: Compatibility,
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        self.host == Compatibility::Breaking || self.guest == Compatibility::Breaking
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} (host: {}, guest: {})",
+            self.subject, self.description, self.host, self.guest
+        )
+    }
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ChangeSubject {
+    /// An import function, identified by its name.
+    Import(String),
+
+    /// An export function, identified by its name.
+    Export(String),
+
+    /// A type, identified by its name.
+    Type(String),
+}
+
+impl Display for ChangeSubject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Import(name) => write!(f, "import `{name}`"),
+            Self::Export(name) => write!(f, "export `{name}`"),
+            Self::Type(name) => write!(f, "type `{name}`"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compatibility {
+    Compatible,
+    Breaking,
+}
+
+impl Compatibility {
+    fn from_bool(is_compatible: bool) -> Self {
+        if is_compatible {
+            Self::Compatible
+        } else {
+            Self::Breaking
+        }
+    }
+}
+
+impl Display for Compatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Compatible => "compatible",
+            Self::Breaking => "breaking",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum FunctionKind {
+    Import,
+    Export,
+}
+
+impl FunctionKind {
+    fn subject(&self, name: &str) -> ChangeSubject {
+        match self {
+            Self::Import => ChangeSubject::Import(name.to_owned()),
+            Self::Export => ChangeSubject::Export(name.to_owned()),
+        }
+    }
+
+    fn args_flow(&self) -> DataFlow {
+        match self {
+            Self::Import => DataFlow::GuestToHost,
+            Self::Export => DataFlow::HostToGuest,
+        }
+    }
+
+    fn return_flow(&self) -> DataFlow {
+        match self {
+            Self::Import => DataFlow::HostToGuest,
+            Self::Export => DataFlow::GuestToHost,
+        }
+    }
+}
+
+/// Direction in which values are sent across the protocol.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum DataFlow {
+    GuestToHost,
+    HostToGuest,
+}
+
+impl DataFlow {
+    fn reversed(self) -> Self {
+        match self {
+            Self::GuestToHost => Self::HostToGuest,
+            Self::HostToGuest => Self::GuestToHost,
+        }
+    }
+}
+
+/// Describes whether serialized values remain readable after a change.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct WireCompat {
+    /// Values serialized using the old definition can be deserialized using
+    /// the new one.
+    backward: bool,
+
+    /// Values serialized using the new definition can be deserialized using
+    /// the old one.
+    forward: bool,
+}
+
+impl WireCompat {
+    const COMPATIBLE: Self = Self {
+        backward: true,
+        forward: true,
+    };
+    const BREAKING: Self = Self {
+        backward: false,
+        forward: false,
+    };
+
+    fn and(self, other: Self) -> Self {
+        Self {
+            backward: self.backward && other.backward,
+            forward: self.forward && other.forward,
+        }
+    }
+
+    /// Swaps the directions, for values that are sent in the opposite
+    /// direction of the value that contains them, such as callback arguments.
+    fn reversed(self) -> Self {
+        Self {
+            backward: self.forward,
+            forward: self.backward,
+        }
+    }
+
+    /// Determines the compatibility for host and guest, given the directions
+    /// in which the affected values are sent.
+    fn for_flows<'a>(
+        self,
+        flows: impl IntoIterator<Item = &'a DataFlow>,
+    ) -> (Compatibility, Compatibility) {
+        let (mut host, mut guest) = (true, true);
+        for flow in flows {
+            match flow {
+                // New hosts receive values from old guests, while new guests
+                // send values to old hosts.
+                DataFlow::GuestToHost => {
+                    host &= self.backward;
+                    guest &= self.forward;
+                }
+                DataFlow::HostToGuest => {
+                    host &= self.forward;
+                    guest &= self.backward;
+                }
+            }
+        }
+        (
+            Compatibility::from_bool(host),
+            Compatibility::from_bool(guest),
+        )
+    }
+}
+
+struct Checker<'a> {
+    old: &'a Protocol,
+    new: &'a Protocol,
+
+    /// Pairs of differently named types that are currently being compared
+    /// structurally. Used to guard against infinite recursion.
+    comparing: BTreeSet<(String, String)>,
+}
+
+impl<'a> Checker<'a> {
+    fn check_functions(
+        &mut self,
+        old: &FunctionList,
+        new: &FunctionList,
+        kind: FunctionKind,
+        changes: &mut Vec<Change>,
+    ) {
+        let old_functions = index_functions(old);
+        let new_functions = index_functions(new);
+
+        let names: BTreeSet<_> = old_functions.keys().chain(new_functions.keys()).collect();
+        for name in names {
+            let subject = kind.subject(name);
+            match (old_functions.get(name), new_functions.get(name)) {
+                (Some(old_function), Some(new_function)) => {
+                    for (description, host, guest) in
+                        self.compare_functions(old_function, new_function, kind)
+                    {
+                        changes.push(Change {
+                            subject: subject.clone(),
+                            description,
+                            host,
+                            guest,
+                        });
+                    }
+                }
+                // Events are only delivered to plugins that subscribe to
+                // them, so they can be added and removed freely:
+                (None, Some(function)) if function.is_event => changes.push(Change {
+                    subject,
+                    description: "event was added; only plugins that subscribe to it receive it"
+                        .to_owned(),
+                    host: Compatibility::Compatible,
+                    guest: Compatibility::Compatible,
+                }),
+                (Some(function), None) if function.is_event => changes.push(Change {
+                    subject,
+                    description: "event was removed; plugins that subscribe to it no longer \
+                        receive it"
+                        .to_owned(),
+                    host: Compatibility::Compatible,
+                    guest: Compatibility::Compatible,
+                }),
+                // Exports are optional, and hosts can check whether a plugin
+                // implements them using `has_<name>()`:
+                (None, Some(_)) => changes.push(match kind {
+                    FunctionKind::Import => Change {
+                        subject,
+                        description: "function was added; old hosts don't provide it".to_owned(),
+                        host: Compatibility::Compatible,
+                        guest: Compatibility::Breaking,
+                    },
+                    FunctionKind::Export => Change {
+                        subject,
+                        description: format!(
+                            "function was added; old plugins don't export it, which hosts can \
+                                detect using `has_{name}()`"
+                        ),
+                        host: Compatibility::Compatible,
+                        guest: Compatibility::Compatible,
+                    },
+                }),
+                (Some(_), None) => changes.push(match kind {
+                    FunctionKind::Import => Change {
+                        subject,
+                        description: "function was removed; old plugins may still call it"
+                            .to_owned(),
+                        host: Compatibility::Breaking,
+                        guest: Compatibility::Compatible,
+                    },
+                    FunctionKind::Export => Change {
+                        subject,
+                        description: "function was removed; old hosts may still call it".to_owned(),
+                        host: Compatibility::Compatible,
+                        guest: Compatibility::Breaking,
+                    },
+                }),
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn compare_functions(
+        &mut self,
+        old: &Function,
+        new: &Function,
+        kind: FunctionKind,
+    ) -> Vec<(String, Compatibility, Compatibility)> {
+        let breaking = |description: String| {
+            vec![(
+                description,
+                Compatibility::Breaking,
+                Compatibility::Breaking,
+            )]
+        };
+
+        if old.is_event != new.is_event {
+            return breaking(if new.is_event {
+                "function was made an event".to_owned()
+            } else {
+                "function is no longer an event".to_owned()
+            });
+        }
+
+        if old.is_async != new.is_async {
+            return breaking(if new.is_async {
+                "function was made async".to_owned()
+            } else {
+                "function is no longer async".to_owned()
+            });
+        }
+
+        if old.args.len() != new.args.len() {
+            return breaking(format!(
+                "number of arguments changed from {} to {}",
+                old.args.len(),
+                new.args.len()
+            ));
+        }
+
+        let mut changes = Vec::new();
+        for (old_arg, new_arg) in old.args.iter().zip(new.args.iter()) {
+            if old_arg.ty != new_arg.ty {
+                let (host, guest) = self
+                    .signature_compat(&old_arg.ty, &new_arg.ty)
+                    .for_flows(&[kind.args_flow()]);
+                changes.push((
+                    format!(
+                        "type of argument `{}` changed from `{}` to `{}`",
+                        new_arg.name, old_arg.ty, new_arg.ty
+                    ),
+                    host,
+                    guest,
+                ));
+            }
+        }
+
+        match (&old.return_type, &new.return_type) {
+            (Some(old_ty), Some(new_ty)) if old_ty != new_ty => {
+                let (host, guest) = self
+                    .signature_compat(old_ty, new_ty)
+                    .for_flows(&[kind.return_flow()]);
+                changes.push((
+                    format!("return type changed from `{old_ty}` to `{new_ty}`"),
+                    host,
+                    guest,
+                ));
+            }
+            (None, Some(new_ty)) => changes.extend(breaking(format!(
+                "return type changed from `()` to `{new_ty}`"
+            ))),
+            (Some(old_ty), None) => changes.extend(breaking(format!(
+                "return type changed from `{old_ty}` to `()`"
+            ))),
+            _ => {}
+        }
+
+        changes
+    }
+
+    /// Primitives are passed to functions as-is, rather than being serialized,
+    /// so any change to them alters the function's signature.
+    fn signature_compat(&mut self, old: &TypeIdent, new: &TypeIdent) -> WireCompat {
+        if old.is_primitive() || new.is_primitive() {
+            WireCompat::BREAKING
+        } else {
+            self.ident_compat(old, new)
+        }
+    }
+
+    fn check_types(&mut self, changes: &mut Vec<Change>) {
+        let flows = collect_flows(self.old)
+            .into_iter()
+            .chain(collect_flows(self.new))
+            .fold(
+                BTreeMap::<String, BTreeSet<DataFlow>>::new(),
+                |mut flows, (name, type_flows)| {
+                    flows.entry(name).or_default().extend(type_flows);
+                    flows
+                },
+            );
+        let no_flows = BTreeSet::new();
+
+        let old_types = index_named_types(&self.old.types);
+        let new_types = index_named_types(&self.new.types);
+
+        let names: BTreeSet<_> = old_types.keys().chain(new_types.keys()).collect();
+        for name in names {
+            let subject = ChangeSubject::Type((*name).to_owned());
+            match (old_types.get(name), new_types.get(name)) {
+                (Some(old_ty), Some(new_ty)) => {
+                    let type_flows = flows.get(*name).unwrap_or(&no_flows);
+                    for (description, compat) in self.compare_types(old_ty, new_ty) {
+                        let (host, guest) = compat.for_flows(type_flows);
+                        changes.push(Change {
+                            subject: subject.clone(),
+                            description,
+                            host,
+                            guest,
+                        });
+                    }
+                }
+                // Adding or removing types is harmless by itself. If any
+                // function or type starts or stops using them, that change
+                // is reported there.
+                (None, Some(_)) => changes.push(Change {
+                    subject,
+                    description: "type was added".to_owned(),
+                    host: Compatibility::Compatible,
+                    guest: Compatibility::Compatible,
+                }),
+                (Some(_), None) => changes.push(Change {
+                    subject,
+                    description: "type was removed".to_owned(),
+                    host: Compatibility::Compatible,
+                    guest: Compatibility::Compatible,
+                }),
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn compare_types(&mut self, old: &Type, new: &Type) -> Vec<(String, WireCompat)> {
+        match (old, new) {
+            (old, new) if old == new => vec![],
+            (Type::Alias(_, old_ident), Type::Alias(_, new_ident)) => {
+                if old_ident == new_ident {
+                    vec![]
+                } else {
+                    vec![(
+                        format!("aliased type changed from `{old_ident}` to `{new_ident}`"),
+                        self.ident_compat(old_ident, new_ident),
+                    )]
+                }
+            }
+            (Type::Custom(old_custom), Type::Custom(new_custom)) => {
+                let mut changes = Vec::new();
+                if old_custom.rs_ty != new_custom.rs_ty {
+                    changes.push((
+                        format!(
+                            "Rust type changed from `{}` to `{}`",
+                            old_custom.rs_ty, new_custom.rs_ty
+                        ),
+                        WireCompat::BREAKING,
+                    ));
+                }
+                if old_custom.ts_ty != new_custom.ts_ty {
+                    changes.push((
+                        format!(
+                            "TypeScript type changed from `{}` to `{}`",
+                            old_custom.ts_ty, new_custom.ts_ty
+                        ),
+                        WireCompat::BREAKING,
+                    ));
+                }
+                if old_custom.serde_attrs != new_custom.serde_attrs {
+                    changes.push(("Serde attributes changed".to_owned(), WireCompat::BREAKING));
+                }
+                changes
+            }
+            (Type::Enum(old_enum), Type::Enum(new_enum)) => self.compare_enums(old_enum, new_enum),
+            (Type::Struct(old_struct), Type::Struct(new_struct)) => {
+                self.compare_structs(old_struct, new_struct)
+            }
+            (old, new) if kind_name(old) != kind_name(new) => vec![(
+                format!("changed from {} to {}", kind_name(old), kind_name(new)),
+                WireCompat::BREAKING,
+            )],
+            _ => vec![(
+                format!(
+                    "definition changed from `{}` to `{}`",
+                    old.name(),
+                    new.name()
+                ),
+                WireCompat::BREAKING,
+            )],
+        }
+    }
+
+    fn compare_structs(&mut self, old: &Struct, new: &Struct) -> Vec<(String, WireCompat)> {
+        if old.ident.generic_args.len() != new.ident.generic_args.len() {
+            return vec![(
+                "number of generic arguments changed".to_owned(),
+                WireCompat::BREAKING,
+            )];
+        }
+
+        match (old.is_compact(), new.is_compact()) {
+            (false, false) => self.compare_fields(
+                &old.fields,
+                old.options.field_casing,
+                &new.fields,
+                new.options.field_casing,
+            ),
+            (true, true) => self.compare_compact_fields(old, new),
+            _ => vec![(
+                "changed between compact and regular encoding".to_owned(),
+                WireCompat::BREAKING,
+            )],
+        }
+    }
+
+    /// Compares the fields of compact structs, which are identified by their
+    /// position in the array the struct is encoded as.
+    fn compare_compact_fields(&mut self, old: &Struct, new: &Struct) -> Vec<(String, WireCompat)> {
+        let mut changes = Vec::new();
+        for index in 0..old.fields.len().max(new.fields.len()) {
+            match (old.fields.get(index), new.fields.get(index)) {
+                (Some(old_field), Some(new_field)) => {
+                    let name = get_field_name(new_field, new.options.field_casing);
+                    let old_name = get_field_name(old_field, old.options.field_casing);
+                    if old_field.attrs.serialize_with != new_field.attrs.serialize_with
+                        || old_field.attrs.deserialize_with != new_field.attrs.deserialize_with
+                    {
+                        changes.push((
+                            format!("serialization of field `{name}` changed"),
+                            WireCompat::BREAKING,
+                        ));
+                        continue;
+                    }
+
+                    let mut compat = if old_field.ty == new_field.ty {
+                        WireCompat::COMPATIBLE
+                    } else {
+                        self.ident_compat(&old_field.ty, &new_field.ty)
+                    };
+
+                    // Writers may omit trailing fields that have a default.
+                    if old_field.attrs.default.is_some() && new_field.attrs.default.is_none() {
+                        compat.backward = false;
+                    }
+                    if new_field.attrs.default.is_some() && old_field.attrs.default.is_none() {
+                        compat.forward = false;
+                    }
+
+                    if old_field.ty != new_field.ty {
+                        changes.push((
+                            format!(
+                                "type of field `{name}` changed from `{}` to `{}`",
+                                old_field.ty, new_field.ty
+                            ),
+                            compat,
+                        ));
+                    } else if compat != WireCompat::COMPATIBLE {
+                        changes.push((
+                            format!("field `{name}` changed whether it has a default"),
+                            compat,
+                        ));
+                    } else if old_name != name {
+                        changes.push((
+                            format!("field `{old_name}` was renamed to `{name}`"),
+                            compat,
+                        ));
+                    }
+                }
+                // Old readers reject the additional field, while new readers
+                // can only do without it if it has a default.
+                (None, Some(new_field)) => changes.push((
+                    format!(
+                        "field `{}` was added",
+                        get_field_name(new_field, new.options.field_casing)
+                    ),
+                    WireCompat {
+                        backward: new_field.attrs.default.is_some(),
+                        forward: false,
+                    },
+                )),
+                (Some(old_field), None) => changes.push((
+                    format!(
+                        "field `{}` was removed",
+                        get_field_name(old_field, old.options.field_casing)
+                    ),
+                    WireCompat {
+                        backward: false,
+                        forward: old_field.attrs.default.is_some(),
+                    },
+                )),
+                (None, None) => unreachable!(),
+            }
+        }
+        changes
+    }
+
+    fn compare_enums(&mut self, old: &Enum, new: &Enum) -> Vec<(String, WireCompat)> {
+        if old.ident.generic_args.len() != new.ident.generic_args.len() {
+            return vec![(
+                "number of generic arguments changed".to_owned(),
+                WireCompat::BREAKING,
+            )];
+        }
+
+        if old.options.tag_prop_name != new.options.tag_prop_name
+            || old.options.content_prop_name != new.options.content_prop_name
+            || old.options.untagged != new.options.untagged
+        {
+            return vec![(
+                "enum representation changed".to_owned(),
+                WireCompat::BREAKING,
+            )];
+        }
+
+        let variant_name = |ty: &Enum, name: &str, rename: &Option<String>| {
+            rename
+                .clone()
+                .unwrap_or_else(|| ty.options.variant_casing.format_string(name))
+        };
+        let old_variants: BTreeMap<_, _> = old
+            .variants
+            .iter()
+            .map(|variant| {
+                (
+                    variant_name(old, &variant.name, &variant.attrs.rename),
+                    variant,
+                )
+            })
+            .collect();
+        let new_variants: BTreeMap<_, _> = new
+            .variants
+            .iter()
+            .map(|variant| {
+                (
+                    variant_name(new, &variant.name, &variant.attrs.rename),
+                    variant,
+                )
+            })
+            .collect();
+
+        let mut changes = Vec::new();
+        let names: BTreeSet<_> = old_variants.keys().chain(new_variants.keys()).collect();
+        for name in names {
+            match (old_variants.get(name), new_variants.get(name)) {
+                (Some(old_variant), Some(new_variant)) => {
+                    let variant_changes = match (&old_variant.ty, &new_variant.ty) {
+                        (Type::Unit, Type::Unit) => vec![],
+                        (Type::Struct(old_struct), Type::Struct(new_struct)) => self
+                            .compare_fields(
+                                &old_struct.fields,
+                                old_variant.attrs.field_casing,
+                                &new_struct.fields,
+                                new_variant.attrs.field_casing,
+                            ),
+                        (Type::Tuple(old_items), Type::Tuple(new_items)) => {
+                            self.compare_tuple_items(old_items, new_items)
+                        }
+                        _ => vec![("payload changed".to_owned(), WireCompat::BREAKING)],
+                    };
+                    changes.extend(variant_changes.into_iter().map(|(description, compat)| {
+                        (format!("variant `{name}`: {description}"), compat)
+                    }));
+                }
+                // Old values never contain the new variant, but old readers
+                // don't recognize it.
+                (None, Some(_)) => changes.push((
+                    format!("variant `{name}` was added"),
+                    WireCompat {
+                        backward: true,
+                        forward: false,
+                    },
+                )),
+                (Some(_), None) => changes.push((
+                    format!("variant `{name}` was removed"),
+                    WireCompat {
+                        backward: false,
+                        forward: true,
+                    },
+                )),
+                (None, None) => unreachable!(),
+            }
+        }
+        changes
+    }
+
+    fn compare_fields(
+        &mut self,
+        old_fields: &[Field],
+        old_casing: Casing,
+        new_fields: &[Field],
+        new_casing: Casing,
+    ) -> Vec<(String, WireCompat)> {
+        let is_tuple = |fields: &[Field]| fields.iter().any(|field| field.name.is_none());
+        match (is_tuple(old_fields), is_tuple(new_fields)) {
+            (true, true) => {
+                let old_items: Vec<_> = old_fields.iter().map(|field| field.ty.clone()).collect();
+                let new_items: Vec<_> = new_fields.iter().map(|field| field.ty.clone()).collect();
+                return self.compare_tuple_items(&old_items, &new_items);
+            }
+            (false, false) => {}
+            _ => {
+                return vec![(
+                    "changed between named and unnamed fields".to_owned(),
+                    WireCompat::BREAKING,
+                )]
+            }
+        }
+
+        let old_fields: BTreeMap<_, _> = old_fields
+            .iter()
+            .map(|field| (get_field_name(field, old_casing), field))
+            .collect();
+        let new_fields: BTreeMap<_, _> = new_fields
+            .iter()
+            .map(|field| (get_field_name(field, new_casing), field))
+            .collect();
+
+        let mut changes = Vec::new();
+        let names: BTreeSet<_> = old_fields.keys().chain(new_fields.keys()).collect();
+        for name in names {
+            match (old_fields.get(name), new_fields.get(name)) {
+                (Some(old_field), Some(new_field)) => {
+                    if old_field.attrs.flatten != new_field.attrs.flatten
+                        || old_field.attrs.serialize_with != new_field.attrs.serialize_with
+                        || old_field.attrs.deserialize_with != new_field.attrs.deserialize_with
+                    {
+                        changes.push((
+                            format!("serialization of field `{name}` changed"),
+                            WireCompat::BREAKING,
+                        ));
+                        continue;
+                    }
+
+                    let mut compat = if old_field.ty == new_field.ty {
+                        WireCompat::COMPATIBLE
+                    } else {
+                        self.ident_compat(&old_field.ty, &new_field.ty)
+                    };
+
+                    // Old versions may omit the field from the serialized
+                    // representation, in which case we need a fallback.
+                    if old_field.attrs.skip_serializing_if.is_some() && !is_optional(new_field) {
+                        compat.backward = false;
+                    }
+                    if new_field.attrs.skip_serializing_if.is_some() && !is_optional(old_field) {
+                        compat.forward = false;
+                    }
+
+                    if old_field.ty != new_field.ty {
+                        changes.push((
+                            format!(
+                                "type of field `{name}` changed from `{}` to `{}`",
+                                old_field.ty, new_field.ty
+                            ),
+                            compat,
+                        ));
+                    } else if compat != WireCompat::COMPATIBLE {
+                        changes.push((
+                            format!("field `{name}` may be omitted, but is not optional"),
+                            compat,
+                        ));
+                    }
+                }
+                // Unknown fields are ignored when deserializing, but missing
+                // fields are only accepted if they're optional.
+                (None, Some(new_field)) => changes.push((
+                    format!("field `{name}` was added"),
+                    WireCompat {
+                        backward: is_optional(new_field),
+                        forward: true,
+                    },
+                )),
+                (Some(old_field), None) => changes.push((
+                    format!("field `{name}` was removed"),
+                    WireCompat {
+                        backward: true,
+                        forward: is_optional(old_field),
+                    },
+                )),
+                (None, None) => unreachable!(),
+            }
+        }
+        changes
+    }
+
+    fn compare_tuple_items(
+        &mut self,
+        old_items: &[TypeIdent],
+        new_items: &[TypeIdent],
+    ) -> Vec<(String, WireCompat)> {
+        if old_items.len() != new_items.len() {
+            return vec![(
+                format!(
+                    "number of fields changed from {} to {}",
+                    old_items.len(),
+                    new_items.len()
+                ),
+                WireCompat::BREAKING,
+            )];
+        }
+
+        old_items
+            .iter()
+            .zip(new_items.iter())
+            .enumerate()
+            .filter(|(_, (old_item, new_item))| old_item != new_item)
+            .map(|(index, (old_item, new_item))| {
+                (
+                    format!("type of field {index} changed from `{old_item}` to `{new_item}`"),
+                    self.ident_compat(old_item, new_item),
+                )
+            })
+            .collect()
+    }
+
+    /// Determines whether values of type `old` in the old protocol and values
+    /// of type `new` in the new protocol can be read interchangeably.
+    ///
+    /// Changes made to the definition of a type that is referenced by both
+    /// identifiers are not taken into account here, since they are reported
+    /// for the type itself.
+    fn ident_compat(&mut self, old: &TypeIdent, new: &TypeIdent) -> WireCompat {
+        let old = resolve_transparent(old, &self.old.types);
+        let new = resolve_transparent(new, &self.new.types);
+        if old == new {
+            return WireCompat::COMPATIBLE;
+        }
+
+        if old.is_array() || new.is_array() {
+            return WireCompat::BREAKING;
+        }
+
+        match (old.name.as_str(), new.name.as_str()) {
+            ("Option", "Option") => {
+                return self.generic_args_compat(&old, &new);
+            }
+            // `None` values cannot be read by old versions, but any value can
+            // be upgraded to `Some`.
+            (_, "Option") => {
+                let compat = match new.generic_args.first() {
+                    Some((inner, _)) => self.ident_compat(&old, inner),
+                    None => WireCompat::BREAKING,
+                };
+                return WireCompat {
+                    backward: compat.backward,
+                    forward: false,
+                };
+            }
+            ("Option", _) => {
+                let compat = match old.generic_args.first() {
+                    Some((inner, _)) => self.ident_compat(inner, &new),
+                    None => WireCompat::BREAKING,
+                };
+                return WireCompat {
+                    backward: false,
+                    forward: compat.forward,
+                };
+            }
+            _ => {}
+        }
+
+        match (old.as_primitive(), new.as_primitive()) {
+            (Some(old_primitive), Some(new_primitive)) => {
+                return primitive_compat(old_primitive, new_primitive)
+            }
+            (None, None) => {}
+            _ => return WireCompat::BREAKING,
+        }
+
+        let old_ty = find_type(&self.old.types, &old.name);
+        let new_ty = find_type(&self.new.types, &new.name);
+        match (old_ty, new_ty) {
+            (Some(Type::List(_, _)), Some(Type::List(_, _)))
+            | (Some(Type::Map(_, _, _)), Some(Type::Map(_, _, _))) => {
+                self.generic_args_compat(&old, &new)
+            }
+            (Some(Type::Callback(_, _)), Some(Type::Callback(_, _))) => {
+                match (old.generic_args.as_slice(), new.generic_args.as_slice()) {
+                    ([(old_arg, _), (old_ret, _)], [(new_arg, _), (new_ret, _)]) => self
+                        .ident_compat(old_arg, new_arg)
+                        .reversed()
+                        .and(self.ident_compat(old_ret, new_ret)),
+                    _ => WireCompat::BREAKING,
+                }
+            }
+            (Some(old_ty), Some(new_ty)) if old.name == new.name => {
+                if kind_name(old_ty) == kind_name(new_ty) {
+                    self.generic_args_compat(&old, &new)
+                } else {
+                    WireCompat::BREAKING
+                }
+            }
+            // Type names are not part of the serialized representation, so
+            // renamed types are compatible as long as their structure is.
+            (Some(old_ty @ (Type::Enum(_) | Type::Struct(_))), Some(new_ty))
+                if old.generic_args.is_empty() && new.generic_args.is_empty() =>
+            {
+                let key = (old.name.clone(), new.name.clone());
+                if !self.comparing.insert(key.clone()) {
+                    // Assume compatibility for recursive types; any
+                    // incompatibilities will be found in the outer comparison.
+                    return WireCompat::COMPATIBLE;
+                }
+
+                let compat = self
+                    .compare_types(old_ty, new_ty)
+                    .into_iter()
+                    .fold(WireCompat::COMPATIBLE, |compat, (_, change)| {
+                        compat.and(change)
+                    });
+                self.comparing.remove(&key);
+                compat
+            }
+            _ => WireCompat::BREAKING,
+        }
+    }
+
+    fn generic_args_compat(&mut self, old: &TypeIdent, new: &TypeIdent) -> WireCompat {
+        if old.generic_args.len() != new.generic_args.len() {
+            return WireCompat::BREAKING;
+        }
+
+        old.generic_args.iter().zip(new.generic_args.iter()).fold(
+            WireCompat::COMPATIBLE,
+            |compat, ((old_arg, _), (new_arg, _))| compat.and(self.ident_compat(old_arg, new_arg)),
+        )
+    }
+}
+
+fn index_functions(functions: &FunctionList) -> BTreeMap<&str, &Function> {
+    functions
+        .iter()
+        .map(|function| (function.name.as_str(), function))
+        .collect()
+}
+
+/// Returns the user-defined types in the map, indexed by name.
+fn index_named_types(types: &TypeMap) -> BTreeMap<&str, &Type> {
+    types
+        .iter()
+        .filter(|(_, ty)| {
+            matches!(
+                ty,
+                Type::Alias(_, _) | Type::Custom(_) | Type::Enum(_) | Type::Struct(_)
+            )
+        })
+        .map(|(ident, ty)| (ident.name.as_str(), ty))
+        .collect()
+}
+
+fn find_type<'t>(types: &'t TypeMap, name: &str) -> Option<&'t Type> {
+    types
+        .iter()
+        .find(|(ident, _)| ident.name == name)
+        .map(|(_, ty)| ty)
+}
+
+/// Resolves aliases and containers such as `Box` that don't affect the
+/// serialized representation.
+fn resolve_transparent(ident: &TypeIdent, types: &TypeMap) -> TypeIdent {
+    let mut ident = ident.clone();
+
+    // Limit the number of steps, in case of circular aliases.
+    for _ in 0..32 {
+        if ident.is_array() {
+            break;
+        }
+
+        match find_type(types, &ident.name) {
+            Some(Type::Alias(_, target)) if ident.generic_args.is_empty() => {
+                ident = target.clone();
+            }
+            Some(Type::Container(name, _)) if name != "Option" && name != "Handle" => {
+                match ident.generic_args.first() {
+                    Some((inner, _)) => ident = inner.clone(),
+                    None => break,
+                }
+            }
+            _ => break,
+        }
+    }
+
+    ident
+}
+
+/// Returns the name of the field in the serialized representation.
+fn get_field_name(field: &Field, casing: Casing) -> String {
+    field
+        .attrs
+        .rename
+        .clone()
+        .unwrap_or_else(|| casing.format_string(field.name.as_deref().unwrap_or_default()))
+}
+
+/// Returns whether a field may be omitted from the serialized representation.
+fn is_optional(field: &Field) -> bool {
+    field.attrs.default.is_some()
+        || (field.ty.name == "Option" && field.attrs.deserialize_with.is_none())
+}
+
+fn primitive_compat(old: Primitive, new: Primitive) -> WireCompat {
+    if old == new {
+        return WireCompat::COMPATIBLE;
+    }
+
+    match (integer_range(old), integer_range(new)) {
+        (Some((old_min, old_max)), Some((new_min, new_max))) => WireCompat {
+            backward: new_min <= old_min && new_max >= old_max,
+            forward: old_min <= new_min && old_max >= new_max,
+        },
+        _ => match (old, new) {
+            (Primitive::F32, Primitive::F64) => WireCompat {
+                backward: true,
+                forward: false,
+            },
+            (Primitive::F64, Primitive::F32) => WireCompat {
+                backward: false,
+                forward: true,
+            },
+            _ => WireCompat::BREAKING,
+        },
+    }
+}
+
+fn integer_range(primitive: Primitive) -> Option<(i128, i128)> {
+    use Primitive::*;
+    match primitive {
+        I8 => Some((i8::MIN as i128, i8::MAX as i128)),
+        I16 => Some((i16::MIN as i128, i16::MAX as i128)),
+        I32 => Some((i32::MIN as i128, i32::MAX as i128)),
+        I64 => Some((i64::MIN as i128, i64::MAX as i128)),
+        U8 => Some((0, u8::MAX as i128)),
+        U16 => Some((0, u16::MAX as i128)),
+        U32 => Some((0, u32::MAX as i128)),
+        U64 => Some((0, u64::MAX as i128)),
+        // 128-bit integers are encoded differently from the other integers:
+        Bool | F32 | F64 | I128 | U128 | Char => None,
+    }
+}
+
+fn kind_name(ty: &Type) -> &'static str {
+    match ty {
+        Type::Alias(_, _) => "an alias",
+        Type::Array(_, _) => "an array",
+        Type::Callback(_, _) => "a callback",
+        Type::Container(_, _) => "a container",
+        Type::Custom(_) => "a custom type",
+        Type::Enum(_) => "an enum",
+        Type::List(_, _) => "a list",
+        Type::Map(_, _, _) => "a map",
+        Type::Primitive(_) => "a primitive",
+        Type::String => "a string",
+        Type::Struct(_) => "a struct",
+        Type::Tuple(_) => "a tuple",
+        Type::Unit => "unit",
+    }
+}
+
+/// Collects the directions in which values of every named type are sent.
+fn collect_flows(protocol: &Protocol) -> BTreeMap<String, BTreeSet<DataFlow>> {
+    let mut flows = BTreeMap::new();
+    for (functions, kind) in [
+        (&protocol.import_functions, FunctionKind::Import),
+        (&protocol.export_functions, FunctionKind::Export),
+    ] {
+        for function in functions {
+            for arg in &function.args {
+                collect_ident_flows(&arg.ty, kind.args_flow(), &protocol.types, &mut flows);
+            }
+            if let Some(return_type) = &function.return_type {
+                collect_ident_flows(return_type, kind.return_flow(), &protocol.types, &mut flows);
+            }
+        }
+    }
+    flows
+}
+
+fn collect_ident_flows(
+    ident: &TypeIdent,
+    flow: DataFlow,
+    types: &TypeMap,
+    flows: &mut BTreeMap<String, BTreeSet<DataFlow>>,
+) {
+    let is_callback = matches!(find_type(types, &ident.name), Some(Type::Callback(_, _)));
+    for (index, (arg, _)) in ident.generic_args.iter().enumerate() {
+        // Callback arguments are sent in the opposite direction:
+        let flow = if is_callback && index == 0 {
+            flow.reversed()
+        } else {
+            flow
+        };
+        collect_ident_flows(arg, flow, types, flows);
+    }
+
+    if !flows.entry(ident.name.clone()).or_default().insert(flow) {
+        return; // Already visited.
+    }
+
+    let referenced_idents: Vec<&TypeIdent> = match find_type(types, &ident.name) {
+        Some(Type::Alias(_, target)) => vec![target],
+        Some(Type::Enum(ty)) => ty
+            .variants
+            .iter()
+            .flat_map(|variant| match &variant.ty {
+                Type::Struct(variant) => variant.fields.iter().map(|field| &field.ty).collect(),
+                Type::Tuple(items) => items.iter().collect(),
+                _ => vec![],
+            })
+            .collect(),
+        Some(Type::Struct(ty)) => ty.fields.iter().map(|field| &field.ty).collect(),
+        _ => vec![],
+    };
+    for referenced_ident in referenced_idents {
+        collect_ident_flows(referenced_ident, flow, types, flows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Serializable;
+    use Compatibility::{Breaking, Compatible};
+
+    fn protocol(imports: &[&str], exports: &[&str], items: &[&str]) -> Protocol {
+        let mut protocol = Protocol::default();
+        for import in imports {
+            protocol.import_functions.add_function(import);
+        }
+        for export in exports {
+            protocol.export_functions.add_function(export);
+        }
+        for item in items {
+            let ty = Type::from_item(item);
+            let ident = match &ty {
+                Type::Enum(ty) => ty.ident.clone(),
+                Type::Struct(ty) => ty.ident.clone(),
+                _ => unreachable!(),
+            };
+            protocol.types.insert(ident, ty);
+        }
+        Option::<String>::collect_types(&mut protocol.types);
+        Vec::<String>::collect_types(&mut protocol.types);
+        crate::Callback::<String, ()>::collect_types(&mut protocol.types);
+        protocol
+    }
+
+    fn compatibility(report: &CompatibilityReport) -> Vec<(String, Compatibility, Compatibility)> {
+        report
+            .changes
+            .iter()
+            .map(|change| {
+                (
+                    format!("{}: {}", change.subject, change.description),
+                    change.host,
+                    change.guest,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_protocols_are_compatible() {
+        let imports = ["fn log(message: String);"];
+        let exports = ["fn handle(point: Point) -> Point;"];
+        let types = ["struct Point { x: i32, y: i32 }"];
+
+        let report = check_compatibility(
+            &protocol(&imports, &exports, &types),
+            &protocol(&imports, &exports, &types),
+        );
+        assert!(report.changes.is_empty());
+        assert!(!report.is_breaking());
+    }
+
+    #[test]
+    fn added_and_removed_functions() {
+        let old = protocol(&["fn old_import();"], &["fn old_export();"], &[]);
+        let new = protocol(&["fn new_import();"], &["fn new_export();"], &[]);
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![
+                (
+                    "import `new_import`: function was added; old hosts don't provide it"
+                        .to_owned(),
+                    Compatible,
+                    Breaking
+                ),
+                (
+                    "import `old_import`: function was removed; old plugins may still call it"
+                        .to_owned(),
+                    Breaking,
+                    Compatible
+                ),
+                (
+                    "export `new_export`: function was added; old plugins don't export it, \
+                        which hosts can detect using `has_new_export()`"
+                        .to_owned(),
+                    Compatible,
+                    Compatible
+                ),
+                (
+                    "export `old_export`: function was removed; old hosts may still call it"
+                        .to_owned(),
+                    Compatible,
+                    Breaking
+                ),
+            ]
+        );
+        assert!(report.is_breaking_for_host());
+        assert!(report.is_breaking_for_guest());
+    }
+
+    #[test]
+    fn added_exports_are_compatible() {
+        let old = protocol(&[], &["fn render();"], &[]);
+        let new = protocol(&[], &["fn render();", "fn render_preview();"], &[]);
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![(
+                "export `render_preview`: function was added; old plugins don't export it, \
+                    which hosts can detect using `has_render_preview()`"
+                    .to_owned(),
+                Compatible,
+                Compatible
+            )]
+        );
+        assert!(!report.is_breaking());
+    }
+
+    #[test]
+    fn changed_signatures() {
+        let old = protocol(
+            &["fn count(a: u32) -> u32;", "fn fetch(url: String);"],
+            &[],
+            &[],
+        );
+        let new = protocol(
+            &["fn count(a: u64) -> u32;", "async fn fetch(url: String);"],
+            &[],
+            &[],
+        );
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![
+                (
+                    "import `count`: type of argument `a` changed from `u32` to `u64`".to_owned(),
+                    Breaking,
+                    Breaking
+                ),
+                (
+                    "import `fetch`: function was made async".to_owned(),
+                    Breaking,
+                    Breaking
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn added_fields_depend_on_direction() {
+        let types_old = ["struct Request { url: String }"];
+        let types_new = [
+            "struct Request { url: String, body: String, #[serde(default)] method: String, timeout: Option<u32> }",
+        ];
+
+        // Requests are only sent from the host to plugins.
+        let old = protocol(&[], &["fn handle(request: Request);"], &types_old);
+        let new = protocol(&[], &["fn handle(request: Request);"], &types_new);
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![
+                (
+                    "type `Request`: field `body` was added".to_owned(),
+                    Compatible,
+                    Breaking
+                ),
+                (
+                    "type `Request`: field `method` was added".to_owned(),
+                    Compatible,
+                    Compatible
+                ),
+                (
+                    "type `Request`: field `timeout` was added".to_owned(),
+                    Compatible,
+                    Compatible
+                ),
+            ]
+        );
+
+        // Now plugins send requests to the host.
+        let old = protocol(&["fn handle(request: Request);"], &[], &types_old);
+        let new = protocol(&["fn handle(request: Request);"], &[], &types_new);
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(report.changes[0].host, Breaking);
+        assert_eq!(report.changes[0].guest, Compatible);
+        assert!(!report.changes[1].is_breaking());
+        assert!(!report.changes[2].is_breaking());
+    }
+
+    #[test]
+    fn compact_fields_are_compared_by_position() {
+        let old = protocol(
+            &[],
+            &["fn handle(point: Point);"],
+            &["#[fp(compact)] struct Point { x: i32, y: i32, z: i32 }"],
+        );
+        let new = protocol(
+            &[],
+            &["fn handle(point: Point);"],
+            &["#[fp(compact)] struct Point { x: i32, z: i32, #[serde(default)] label: String }"],
+        );
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![
+                (
+                    "type `Point`: field `y` was renamed to `z`".to_owned(),
+                    Compatible,
+                    Compatible
+                ),
+                (
+                    "type `Point`: type of field `label` changed from `i32` to `String`".to_owned(),
+                    Breaking,
+                    Breaking
+                ),
+            ]
+        );
+
+        let new = protocol(
+            &[],
+            &["fn handle(point: Point);"],
+            &["#[fp(compact)] struct Point { x: i32, y: i32, z: i32, #[serde(default)] w: i32 }"],
+        );
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![(
+                "type `Point`: field `w` was added".to_owned(),
+                Breaking,
+                Compatible
+            )]
+        );
+
+        let new = protocol(
+            &[],
+            &["fn handle(point: Point);"],
+            &["struct Point { x: i32, y: i32, z: i32 }"],
+        );
+        let report = check_compatibility(&old, &new);
+        assert!(report.changes[0].is_breaking());
+    }
+
+    #[test]
+    fn renamed_fields_use_serialized_names() {
+        let old = protocol(
+            &["fn get() -> Point;"],
+            &[],
+            &["#[serde(rename_all = \"camelCase\")] struct Point { x_pos: i32 }"],
+        );
+        let new = protocol(
+            &["fn get() -> Point;"],
+            &[],
+            &["struct Point { #[serde(rename = \"xPos\")] x: i64 }"],
+        );
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![(
+                "type `Point`: type of field `xPos` changed from `i32` to `i64`".to_owned(),
+                Breaking,
+                Compatible
+            )]
+        );
+    }
+
+    #[test]
+    fn enum_variants() {
+        let exports = ["fn handle(event: Event) -> Event;"];
+        let old = protocol(
+            &[],
+            &exports,
+            &["enum Event { Click { x: i32 }, Close, Legacy }"],
+        );
+        let new = protocol(
+            &[],
+            &exports,
+            &["enum Event { Click { x: i32, y: Option<i32> }, Close, Open(String) }"],
+        );
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![
+                (
+                    "type `Event`: variant `Click`: field `y` was added".to_owned(),
+                    Compatible,
+                    Compatible
+                ),
+                (
+                    "type `Event`: variant `Legacy` was removed".to_owned(),
+                    Breaking,
+                    Breaking
+                ),
+                (
+                    "type `Event`: variant `Open` was added".to_owned(),
+                    Breaking,
+                    Breaking
+                ),
+            ]
+        );
+
+        let tagged = protocol(
+            &[],
+            &exports,
+            &["#[serde(tag = \"type\")] enum Event { Click { x: i32 }, Close, Legacy }"],
+        );
+        let report = check_compatibility(&old, &tagged);
+        assert_eq!(
+            compatibility(&report),
+            vec![(
+                "type `Event`: enum representation changed".to_owned(),
+                Breaking,
+                Breaking
+            )]
+        );
+    }
+
+    #[test]
+    fn changed_types_of_arguments() {
+        let old = protocol(
+            &["fn store(items: Vec<Item>) -> Option<Item>;"],
+            &[],
+            &["struct Item { id: u32 }"],
+        );
+        let new = protocol(
+            &["fn store(items: Vec<Entry>) -> Option<Entry>;"],
+            &[],
+            &["struct Entry { id: u64 }"],
+        );
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![
+                (
+                    "import `store`: type of argument `items` changed from `Vec<Item>` to `Vec<Entry>`"
+                        .to_owned(),
+                    Compatible,
+                    Breaking
+                ),
+                (
+                    "import `store`: return type changed from `Option<Item>` to `Option<Entry>`"
+                        .to_owned(),
+                    Breaking,
+                    Compatible
+                ),
+                ("type `Entry`: type was added".to_owned(), Compatible, Compatible),
+                ("type `Item`: type was removed".to_owned(), Compatible, Compatible),
+            ]
+        );
+    }
+
+    #[test]
+    fn callback_arguments_flow_in_reverse() {
+        let old = protocol(
+            &["fn on_event(handler: Callback<u32, ()>);"],
+            &["fn run(progress: Callback<Progress, ()>);"],
+            &["struct Progress { done: u32 }"],
+        );
+        let new = protocol(
+            &["fn on_event(handler: Callback<u64, ()>);"],
+            &["fn run(progress: Callback<Progress, ()>);"],
+            &["struct Progress { done: u32, total: u32 }"],
+        );
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![
+                (
+                    "import `on_event`: type of argument `handler` changed from `Callback<u32, ()>` to `Callback<u64, ()>`"
+                        .to_owned(),
+                    Breaking,
+                    Compatible
+                ),
+                (
+                    "type `Progress`: field `total` was added".to_owned(),
+                    Breaking,
+                    Compatible
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn events() {
+        let old = protocol(
+            &[],
+            &[
+                "#[fp(event)] async fn removed(event: String);",
+                "async fn converted(event: String);",
+            ],
+            &[],
+        );
+        let new = protocol(
+            &[],
+            &[
+                "#[fp(event)] async fn added(event: String);",
+                "#[fp(event)] async fn converted(event: String);",
+            ],
+            &[],
+        );
+
+        let report = check_compatibility(&old, &new);
+        assert_eq!(
+            compatibility(&report),
+            vec![
+                (
+                    "export `added`: event was added; only plugins that subscribe to it receive it"
+                        .to_owned(),
+                    Compatible,
+                    Compatible
+                ),
+                (
+                    "export `converted`: function was made an event".to_owned(),
+                    Breaking,
+                    Breaking
+                ),
+                (
+                    "export `removed`: event was removed; plugins that subscribe to it no longer receive it"
+                        .to_owned(),
+                    Compatible,
+                    Compatible
+                ),
+            ]
+        );
+        assert!(report.is_breaking());
+    }
+
+    #[test]
+    fn report_display() {
+        let old = protocol(&[], &[], &[]);
+        let new = protocol(&["fn init();"], &[], &[]);
+
+        assert_eq!(check_compatibility(&old, &old).to_string(), "No changes.\n");
+        assert_eq!(
+            check_compatibility(&old, &new).to_string(),
+            "import `init`: function was added; old hosts don't provide it \
+                (host: compatible, guest: breaking)\n"
+        );
+    }
+}
@@ -4,8 +4,12 @@ use quote::ToTokens;
 use std::{collections::BTreeSet, convert::TryFrom};
 use syn::{FnArg, ForeignItemFn};
 
+/// Name of the Wasm module from which functions are imported, unless they are
+/// declared inside a named import module.
+pub const DEFAULT_IMPORT_MODULE: &str = "fp";
+
 /// Maps from function name to the stringified function declaration.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct FunctionList(BTreeSet<Function>);
 
 impl FunctionList {
@@ -13,6 +17,13 @@ impl FunctionList {
         self.0.insert(Function::new(function_decl));
     }
 
+    /// Adds a function that is imported from the named Wasm module, instead of
+    /// the default `fp` module.
+    pub fn add_function_to_module(&mut self, function_decl: &str, module: &str) {
+        self.0
+            .insert(Function::new(function_decl).with_import_module(module));
+    }
+
     pub fn iter(&self) -> std::collections::btree_set::Iter<'_, Function> {
         self.0.iter()
     }
@@ -22,6 +33,12 @@ impl FunctionList {
     }
 }
 
+impl FromIterator<Function> for FunctionList {
+    fn from_iter<T: IntoIterator<Item = Function>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 impl IntoIterator for FunctionList {
     type Item = Function;
     type IntoIter = std::collections::btree_set::IntoIter<Function>;
@@ -40,13 +57,21 @@ impl<'a> IntoIterator for &'a FunctionList {
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Function {
     pub name: String,
     pub doc_lines: Vec<String>,
     pub args: Vec<FunctionArg>,
     pub return_type: Option<TypeIdent>,
     pub is_async: bool,
+    /// Whether the function is an event, which is declared in `fp_export!`
+    /// using `#[fp(event)]`. Instead of implementing events as regular
+    /// exports, plugins subscribe to them, and the host only delivers them to
+    /// plugins that did.
+    pub is_event: bool,
+    /// Name of the Wasm module from which the function is imported. `None`
+    /// for functions in the default `fp` module, and for exported functions.
+    pub import_module: Option<String>,
 }
 
 impl Function {
@@ -65,12 +90,26 @@ impl Function {
                     "Methods are not supported. Found `self` in function declaration: {:?}",
                     item
                 ),
-                FnArg::Typed(arg) => FunctionArg {
-                    name: arg.pat.to_token_stream().to_string(),
-                    ty: TypeIdent::try_from(arg.ty.as_ref()).unwrap_or_else(|e| {
+                FnArg::Typed(arg) => {
+                    let ty = TypeIdent::try_from(arg.ty.as_ref()).unwrap_or_else(|e| {
                         panic!("Invalid argument type for function {}: {}", name, e)
-                    }),
-                },
+                    });
+                    let is_borrowed = matches!(arg.ty.as_ref(), syn::Type::Reference(_));
+                    if is_borrowed && ty.is_primitive() {
+                        panic!(
+                            "Invalid argument type for function {}: primitives should be passed \
+                                by value, found: {}",
+                            name,
+                            arg.ty.to_token_stream()
+                        );
+                    }
+
+                    FunctionArg {
+                        name: arg.pat.to_token_stream().to_string(),
+                        ty,
+                        is_borrowed,
+                    }
+                }
             })
             .collect();
         let return_type = normalize_return_type(&item.sig.output).map(|return_type| {
@@ -78,6 +117,7 @@ impl Function {
                 .unwrap_or_else(|_| panic!("Invalid return type for function {}", name))
         });
         let is_async = item.sig.asyncness.is_some();
+        let is_event = item.attrs.iter().any(is_event_attr);
 
         Self {
             name,
@@ -85,8 +125,30 @@ impl Function {
             args,
             return_type,
             is_async,
+            is_event,
+            import_module: None,
         }
     }
+
+    pub fn with_import_module(mut self, module: &str) -> Self {
+        self.import_module = Some(module.to_owned());
+        self
+    }
+
+    /// Returns the name of the Wasm module from which the function is imported.
+    pub fn import_module(&self) -> &str {
+        self.import_module
+            .as_deref()
+            .unwrap_or(DEFAULT_IMPORT_MODULE)
+    }
+}
+
+/// Returns whether the attribute is `#[fp(event)]`.
+fn is_event_attr(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("fp")
+        && attr
+            .parse_args::<syn::Ident>()
+            .is_ok_and(|ident| ident == "event")
 }
 
 impl Ord for Function {
@@ -101,8 +163,14 @@ impl PartialOrd for Function {
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FunctionArg {
     pub name: String,
+    /// The type of the argument. For borrowed arguments, this is the owned
+    /// type that is borrowed from, such as `String` for `&str`.
     pub ty: TypeIdent,
+    /// Whether the argument was declared as a reference. Callers pass borrowed
+    /// arguments by reference, while implementations still receive the owned
+    /// value.
+    pub is_borrowed: bool,
 }
@@ -1,21 +1,30 @@
 use crate::{
     functions::FunctionList,
-    types::{CargoDependency, Type, TypeIdent, TypeMap},
+    protocol::Protocol,
+    types::{CargoDependency, TsTypeConversion, Type, TypeIdent, TypeMap},
 };
 use std::{
     collections::{BTreeMap, BTreeSet},
+    env,
     fmt::Display,
-    fs,
 };
 
+mod output;
+pub mod protocol_schema;
 pub mod rust_plugin;
 pub mod rust_wasmer2_runtime;
 pub mod rust_wasmer2_wasi_runtime;
 pub mod ts_runtime;
+mod validation;
+
+pub use output::{BindingsError, GeneratedFiles};
+pub use validation::{validate_protocol, ValidationError, ValidationReport};
 
 #[non_exhaustive]
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum BindingsType {
+    ProtocolSchema,
     RustPlugin(RustPluginConfig),
     RustWasmer2Runtime,
     RustWasmer2WasiRuntime,
@@ -25,9 +34,10 @@ pub enum BindingsType {
 impl Display for BindingsType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
+            BindingsType::ProtocolSchema => "protocol-schema",
             BindingsType::RustPlugin { .. } => "rust-plugin",
-            BindingsType::RustWasmer2Runtime { .. } => "rust-wasmer2-runtime",
-            BindingsType::RustWasmer2WasiRuntime { .. } => "rust-wasmer2-wasi-runtime",
+            BindingsType::RustWasmer2Runtime => "rust-wasmer2-runtime",
+            BindingsType::RustWasmer2WasiRuntime => "rust-wasmer2-wasi-runtime",
             BindingsType::TsRuntime { .. } => "ts-runtime",
         })
     }
@@ -242,6 +252,171 @@ pub struct TsRuntimeConfig {
     /// environment that doesn't support streaming instantiation, such as
     /// Node.js.
     pub streaming_instantiation: bool,
+
+    /// Represent 64-bit integers inside MessagePack-encoded values as
+    /// `bigint`, instead of `number`.
+    ///
+    /// By default, 64-bit integers that are passed directly as function
+    /// arguments or return values are represented as `bigint`, but those that
+    /// are nested inside other types are represented as `number`, which
+    /// silently loses precision for values beyond `2^53 - 1`. Enabling this
+    /// setting makes the runtime (de)serialize such integers losslessly and
+    /// represents them as `bigint` everywhere.
+    ///
+    /// Note that this requires version 2.8.0 or later of `@msgpack/msgpack`.
+    /// Integers inside untagged enums are represented as `number | bigint`,
+    /// because their variant cannot be determined reliably, while integer map
+    /// keys remain represented as `number`.
+    pub use_bigint_for_64bit_integers: bool,
+
+    /// Whether or not to generate validators for the values decoded by the
+    /// runtime.
+    ///
+    /// Validators check that the MessagePack data received from the plugin
+    /// matches the protocol's types, and throw a `ValidationError` describing
+    /// the first mismatch otherwise. When enabled, `createRuntime()` accepts
+    /// an additional `options` argument, which can be used to turn validation
+    /// off at runtime, for instance in production builds.
+    pub generate_validators: bool,
+
+    /// The WASI implementation to provide to plugins compiled for the
+    /// `wasm32-wasi` target, if any.
+    ///
+    /// When set, `createRuntime()` accepts WASI options for configuring the
+    /// arguments, environment variables and preopened directories that are
+    /// available to the plugin.
+    pub wasi: Option<TsWasiShim>,
+
+    /// Whether or not to generate a runtime that runs the plugin inside a Web
+    /// Worker, so long-running calls don't block the main thread.
+    ///
+    /// This generates a `worker.ts` entry point for the worker, which hosts
+    /// the runtime created by `createRuntime()`, and a `workerRuntime.ts` with
+    /// a `createWorkerRuntime()` function for the main thread. It returns the
+    /// same functions as `createRuntime()`, except that they all return a
+    /// `Promise`, because calls are forwarded to the worker. Import functions
+    /// are called on the main thread. Synchronous import functions block the
+    /// worker while they're being called, which requires `SharedArrayBuffer`
+    /// and thus a cross-origin isolated page.
+    ///
+    /// Calls are forwarded using the raw export wrappers, so those are
+    /// generated regardless of the `generate_raw_export_wrappers` setting.
+    pub generate_web_worker: bool,
+
+    /// Whether to convert values of custom types to the richer representation
+    /// described by their [TsTypeConversion], if any.
+    ///
+    /// For instance, `time::OffsetDateTime` values are converted to `Date`,
+    /// `http::Uri` values to `URL`, and `bytes::Bytes` and
+    /// `serde_bytes::ByteBuf` values to `Uint8Array`. Values are converted
+    /// wherever they occur in arguments and return values, including nested
+    /// inside other types, except for those inside untagged enums, whose
+    /// variant cannot be determined reliably.
+    pub convert_custom_types: bool,
+
+    /// Whether to generate helpers for working with enums alongside their
+    /// types in `types.ts`.
+    ///
+    /// For every variant, this generates a constructor function named
+    /// `create<Enum><Variant>()` that takes the fields or the wrapped value of
+    /// the variant. Unless the enum is untagged, there is also a type guard
+    /// named `is<Enum><Variant>()` for every variant, and a `match<Enum>()`
+    /// function that calls the handler for the variant of the given value.
+    /// All of these respect the `tag`, `content`, `rename` and `rename_all`
+    /// options of the enum.
+    pub generate_enum_helpers: bool,
+
+    /// Conversions for custom types, keyed by the name of the custom type.
+    ///
+    /// These are always applied and take precedence over the conversions
+    /// defined by the custom types themselves. This can be used to convert
+    /// `OffsetDateTime` to `Temporal.Instant` instead of `Date`, for instance.
+    pub custom_type_conversions: BTreeMap<String, TsTypeConversion>,
+
+    /// The extension to use in relative import paths, such as `".ts"` for
+    /// Deno or `".js"` for ES modules in Node.js.
+    ///
+    /// If not set, `".js"` is used when generating an npm package, while
+    /// otherwise `".ts"` is used if the `msgpack_module` ends with `.ts` (as
+    /// it would for Deno users) and no extension is used in all other cases.
+    pub import_path_extension: Option<String>,
+
+    /// Generates a ready-to-publish npm package instead of plain TypeScript
+    /// files, if set.
+    ///
+    /// The package contains the TypeScript sources in its `src/` directory,
+    /// together with a `package.json` and TypeScript configs for compiling
+    /// them to both ES module and CommonJS entry points, with `.d.ts` type
+    /// declarations. The package is compiled by running `npm run build`,
+    /// which also happens automatically when it's installed from a git
+    /// repository or published.
+    pub npm_package: Option<TsNpmPackageConfig>,
+}
+
+/// Configuration for generating the TypeScript runtime as an npm package.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct TsNpmPackageConfig {
+    /// Name of the package.
+    pub name: String,
+
+    /// Version of the package.
+    pub version: String,
+
+    /// The human-readable description of the package.
+    pub description: Option<String>,
+
+    /// The license of the package, as an SPDX identifier.
+    pub license: Option<String>,
+
+    /// The version requirement for the `@msgpack/msgpack` dependency.
+    ///
+    /// Defaults to `"^2.7.2"`, or `"^2.8.0"` if
+    /// `use_bigint_for_64bit_integers` is enabled.
+    pub msgpack_version: Option<String>,
+}
+
+impl TsNpmPackageConfig {
+    /// Returns a new config for a package with the given name and version.
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            description: None,
+            license: None,
+            msgpack_version: None,
+        }
+    }
+
+    /// Sets the `description` setting.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the `license` setting.
+    pub fn with_license(mut self, license: impl Into<String>) -> Self {
+        self.license = Some(license.into());
+        self
+    }
+
+    /// Sets the `msgpack_version` setting.
+    pub fn with_msgpack_version(mut self, msgpack_version: impl Into<String>) -> Self {
+        self.msgpack_version = Some(msgpack_version.into());
+        self
+    }
+}
+
+/// WASI implementations that can be used by the TypeScript runtime.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TsWasiShim {
+    /// The built-in `node:wasi` module of Node.js.
+    Node,
+
+    /// The WASI module of the Deno standard library, imported from the given
+    /// URL. For example:
+    /// "https://deno.land/std@0.205.0/wasi/snapshot_preview1.ts".
+    Deno(String),
 }
 
 impl TsRuntimeConfig {
@@ -267,6 +442,66 @@ impl TsRuntimeConfig {
         self.streaming_instantiation = false;
         self
     }
+
+    /// Enables the `use_bigint_for_64bit_integers` setting.
+    pub fn with_bigint_for_64bit_integers(mut self) -> Self {
+        self.use_bigint_for_64bit_integers = true;
+        self
+    }
+
+    /// Enables the `generate_validators` setting.
+    pub fn with_validators(mut self) -> Self {
+        self.generate_validators = true;
+        self
+    }
+
+    /// Sets the `wasi` setting.
+    pub fn with_wasi(mut self, shim: TsWasiShim) -> Self {
+        self.wasi = Some(shim);
+        self
+    }
+
+    /// Enables the `generate_web_worker` setting.
+    pub fn with_web_worker(mut self) -> Self {
+        self.generate_web_worker = true;
+        self
+    }
+
+    /// Enables the `convert_custom_types` setting.
+    pub fn with_custom_type_conversions(mut self) -> Self {
+        self.convert_custom_types = true;
+        self
+    }
+
+    /// Enables the `generate_enum_helpers` setting.
+    pub fn with_enum_helpers(mut self) -> Self {
+        self.generate_enum_helpers = true;
+        self
+    }
+
+    /// Adds a conversion for the custom type with the given name to the
+    /// `custom_type_conversions` setting.
+    pub fn with_custom_type_conversion(
+        mut self,
+        type_name: &str,
+        conversion: TsTypeConversion,
+    ) -> Self {
+        self.custom_type_conversions
+            .insert(type_name.to_owned(), conversion);
+        self
+    }
+
+    /// Sets the `import_path_extension` setting.
+    pub fn with_import_path_extension(mut self, extension: &str) -> Self {
+        self.import_path_extension = Some(extension.to_owned());
+        self
+    }
+
+    /// Sets the `npm_package` setting.
+    pub fn with_npm_package(mut self, package: TsNpmPackageConfig) -> Self {
+        self.npm_package = Some(package);
+        self
+    }
 }
 
 impl Default for TsRuntimeConfig {
@@ -275,6 +510,15 @@ impl Default for TsRuntimeConfig {
             generate_raw_export_wrappers: false,
             msgpack_module: "@msgpack/msgpack".to_owned(),
             streaming_instantiation: true,
+            use_bigint_for_64bit_integers: false,
+            generate_validators: false,
+            wasi: None,
+            generate_web_worker: false,
+            convert_custom_types: false,
+            generate_enum_helpers: false,
+            custom_type_conversions: BTreeMap::new(),
+            import_path_extension: None,
+            npm_package: None,
         }
     }
 }
@@ -286,39 +530,137 @@ pub fn generate_bindings(
     export_functions: FunctionList,
     types: TypeMap,
     config: BindingConfig,
-) {
-    fs::create_dir_all(config.path).expect("Could not create output directory");
+) -> Result<(), BindingsError> {
+    generate_protocol_bindings(
+        Protocol::new(import_functions, export_functions, types),
+        config,
+    )
+}
+
+/// Name of the environment variable that, when set, causes
+/// [generate_protocol_bindings()] to write the protocol description to the
+/// directory it points to, instead of generating the configured bindings.
+///
+/// This allows tools such as the `fp-bindgen` CLI to extract the protocol from
+/// the crate that defines it, simply by running it.
+pub const PROTOCOL_DESCRIPTION_DIR_ENV_VAR: &str = "FP_BINDGEN_PROTOCOL_DESCRIPTION_DIR";
+
+/// Name of the environment variable that, when set, causes
+/// [generate_protocol_bindings()] to check the bindings on disk instead of
+/// writing them. See [check_protocol_bindings()].
+pub const CHECK_BINDINGS_ENV_VAR: &str = "FP_BINDGEN_CHECK";
+
+/// Generates the bindings for the given protocol and writes them to the
+/// configured path.
+///
+/// Existing files are overwritten, but files that are no longer generated are
+/// left alone.
+pub fn generate_protocol_bindings(
+    protocol: Protocol,
+    config: BindingConfig,
+) -> Result<(), BindingsError> {
+    if let Ok(path) = env::var(PROTOCOL_DESCRIPTION_DIR_ENV_VAR) {
+        let config = BindingConfig {
+            bindings_type: BindingsType::ProtocolSchema,
+            path: &path,
+        };
+        return render_protocol_bindings(protocol, config)?.write();
+    }
+
+    if env::var_os(CHECK_BINDINGS_ENV_VAR).is_some() {
+        check_protocol_bindings(protocol, config)
+    } else {
+        render_protocol_bindings(protocol, config)?.write()
+    }
+}
+
+/// Checks whether the bindings on disk are up-to-date with the given protocol,
+/// without modifying any files.
+///
+/// Returns [BindingsError::OutdatedFiles] if any of the files that would be
+/// generated is missing or has different contents.
+pub fn check_protocol_bindings(
+    protocol: Protocol,
+    config: BindingConfig,
+) -> Result<(), BindingsError> {
+    let outdated_files = render_protocol_bindings(protocol, config)?.outdated_files()?;
+    if outdated_files.is_empty() {
+        Ok(())
+    } else {
+        Err(BindingsError::OutdatedFiles(outdated_files))
+    }
+}
+
+/// Generates the bindings for the given protocol in memory, without touching
+/// the file system.
+pub fn render_protocol_bindings(
+    protocol: Protocol,
+    config: BindingConfig,
+) -> Result<GeneratedFiles, ValidationReport> {
+    validate_protocol(&protocol, &config.bindings_type)?;
+
+    let protocol_version = protocol.protocol_version();
+    let Protocol {
+        import_functions,
+        export_functions,
+        types,
+        wire_format,
+        ..
+    } = protocol;
 
     display_warnings(&import_functions, &export_functions, &types);
 
+    let mut files = GeneratedFiles::new();
     match config.bindings_type {
+        BindingsType::ProtocolSchema => protocol_schema::generate_bindings(
+            import_functions,
+            export_functions,
+            types,
+            &protocol_version,
+            wire_format,
+            config.path,
+            &mut files,
+        ),
         BindingsType::RustPlugin(plugin_config) => rust_plugin::generate_bindings(
             import_functions,
             export_functions,
             types,
             plugin_config,
+            &protocol_version,
+            wire_format,
             config.path,
+            &mut files,
         ),
         BindingsType::RustWasmer2Runtime => rust_wasmer2_runtime::generate_bindings(
             import_functions,
             export_functions,
             types,
+            &protocol_version,
+            wire_format,
             config.path,
+            &mut files,
         ),
         BindingsType::RustWasmer2WasiRuntime => rust_wasmer2_wasi_runtime::generate_bindings(
             import_functions,
             export_functions,
             types,
+            &protocol_version,
+            wire_format,
             config.path,
+            &mut files,
         ),
         BindingsType::TsRuntime(runtime_config) => ts_runtime::generate_bindings(
             import_functions,
             export_functions,
             types,
             runtime_config,
+            &protocol_version,
             config.path,
+            &mut files,
         ),
     };
+
+    Ok(files)
 }
 
 fn display_warnings(
@@ -0,0 +1,218 @@
+use super::ValidationReport;
+use std::{collections::BTreeMap, fmt::Display, fs, io, path::Path};
+
+/// The files that make up a set of generated bindings, mapped from their path
+/// to their contents.
+///
+/// Paths are formed by joining the `path` of the [BindingConfig](super::BindingConfig)
+/// with the location of the file inside the bindings.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GeneratedFiles(BTreeMap<String, String>);
+
+impl GeneratedFiles {
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// Returns the contents of the file at the given path, if it was generated.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.0.get(path).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, path: String, contents: impl Into<String>) {
+        self.0.insert(path, contents.into());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(path, contents)| (path.as_str(), contents.as_str()))
+    }
+
+    /// Returns the paths of all the files that are missing on disk, or whose
+    /// contents differ from the generated contents.
+    pub fn outdated_files(&self) -> Result<Vec<String>, BindingsError> {
+        let mut outdated_files = Vec::new();
+        for (path, contents) in &self.0 {
+            match fs::read_to_string(path) {
+                Ok(existing) if existing == *contents => {}
+                Ok(_) => outdated_files.push(path.clone()),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                    outdated_files.push(path.clone())
+                }
+                Err(error) => return Err(BindingsError::io(path, error)),
+            }
+        }
+        Ok(outdated_files)
+    }
+
+    /// Writes all the files to disk, creating directories as necessary.
+    pub fn write(&self) -> Result<(), BindingsError> {
+        for (path, contents) in &self.0 {
+            if let Some(dir) = Path::new(path).parent() {
+                fs::create_dir_all(dir).map_err(|error| BindingsError::io(path, error))?;
+            }
+            fs::write(path, contents).map_err(|error| BindingsError::io(path, error))?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for GeneratedFiles {
+    type Item = (String, String);
+    type IntoIter = std::collections::btree_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Error that may occur while generating bindings.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BindingsError {
+    /// The protocol cannot be used for the requested bindings type.
+    InvalidProtocol(ValidationReport),
+
+    /// A bindings file could not be read or written.
+    Io { path: String, error: io::Error },
+
+    /// Returned when checking bindings, if the files on disk are not the same
+    /// as the ones that would be generated. Contains the paths of the files
+    /// that are out of date.
+    OutdatedFiles(Vec<String>),
+}
+
+impl BindingsError {
+    fn io(path: &str, error: io::Error) -> Self {
+        Self::Io {
+            path: path.to_owned(),
+            error,
+        }
+    }
+}
+
+impl Display for BindingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidProtocol(report) => report.fmt(f),
+            Self::Io { path, error } => write!(f, "Could not access `{path}`: {error}"),
+            Self::OutdatedFiles(paths) => {
+                writeln!(f, "The following bindings files are out of date:")?;
+                for path in paths {
+                    writeln!(f, "  - {path}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BindingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidProtocol(report) => Some(report),
+            Self::Io { error, .. } => Some(error),
+            Self::OutdatedFiles(_) => None,
+        }
+    }
+}
+
+impl From<ValidationReport> for BindingsError {
+    fn from(report: ValidationReport) -> Self {
+        Self::InvalidProtocol(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        check_protocol_bindings,
+        functions::FunctionList,
+        primitives::Primitive,
+        protocol::Protocol,
+        render_protocol_bindings,
+        types::{Type, TypeIdent, TypeMap},
+        BindingConfig, BindingsType,
+    };
+
+    fn protocol() -> Protocol {
+        let mut import_functions = FunctionList::new();
+        import_functions.add_function("fn my_function() -> bool;");
+        let mut types = TypeMap::new();
+        types.insert(TypeIdent::from("bool"), Type::Primitive(Primitive::Bool));
+        Protocol::new(import_functions, FunctionList::new(), types)
+    }
+
+    fn temp_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("fp-bindgen-{name}-{}", std::process::id()));
+        dir.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn render_bindings_in_memory() {
+        let files = render_protocol_bindings(
+            protocol(),
+            BindingConfig {
+                bindings_type: BindingsType::ProtocolSchema,
+                path: "bindings",
+            },
+        )
+        .unwrap();
+
+        let paths: Vec<_> = files.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, ["bindings/protocol.json", "bindings/schema.json"]);
+        assert!(files
+            .get("bindings/protocol.json")
+            .unwrap()
+            .contains("\"my_function\""));
+    }
+
+    #[test]
+    fn write_and_check_files() {
+        let dir = temp_dir("output");
+
+        let mut files = GeneratedFiles::new();
+        files.insert(format!("{dir}/src/lib.rs"), "mod types;\n");
+        files.insert(format!("{dir}/src/types.rs"), "pub type Foo = u32;\n");
+        assert_eq!(
+            files.outdated_files().unwrap(),
+            vec![format!("{dir}/src/lib.rs"), format!("{dir}/src/types.rs")]
+        );
+
+        files.write().unwrap();
+        assert!(files.outdated_files().unwrap().is_empty());
+
+        fs::write(format!("{dir}/src/types.rs"), "pub type Foo = u64;\n").unwrap();
+        assert_eq!(
+            files.outdated_files().unwrap(),
+            vec![format!("{dir}/src/types.rs")]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn check_bindings() {
+        let dir = temp_dir("check");
+        let config = || BindingConfig {
+            bindings_type: BindingsType::ProtocolSchema,
+            path: &dir,
+        };
+
+        match check_protocol_bindings(protocol(), config()) {
+            Err(BindingsError::OutdatedFiles(paths)) => assert_eq!(paths.len(), 2),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(!Path::new(&dir).exists());
+
+        render_protocol_bindings(protocol(), config())
+            .unwrap()
+            .write()
+            .unwrap();
+        check_protocol_bindings(protocol(), config()).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,1022 @@
+use crate::{
+    casing::Casing,
+    functions::{Function, FunctionArg, FunctionList},
+    generators::GeneratedFiles,
+    primitives::Primitive,
+    protocol::{Protocol, ProtocolVersion, WireFormat},
+    types::{
+        CargoDependency, CustomType, Enum, EnumOptions, Field, FieldAttrs, Struct, StructOptions,
+        TsTypeConversion, Type, TypeIdent, TypeMap, Variant, VariantAttrs,
+    },
+};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    num::NonZeroUsize,
+};
+
+/// Version of the protocol description format.
+///
+/// This is bumped whenever the structure of `protocol.json` changes in a way
+/// that is not backward-compatible for consumers.
+pub const PROTOCOL_FORMAT_VERSION: u32 = 1;
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+pub(crate) fn generate_bindings(
+    import_functions: FunctionList,
+    export_functions: FunctionList,
+    types: TypeMap,
+    protocol_version: &ProtocolVersion,
+    wire_format: WireFormat,
+    path: &str,
+    files: &mut GeneratedFiles,
+) {
+    insert_json_file(
+        files,
+        format!("{path}/protocol.json"),
+        &format_protocol(
+            protocol_version,
+            wire_format,
+            &import_functions,
+            &export_functions,
+            &types,
+        ),
+    );
+    insert_json_file(
+        files,
+        format!("{path}/schema.json"),
+        &format_json_schema(&types),
+    );
+}
+
+/// Formats the full protocol as a JSON document.
+///
+/// The document contains all the information that is available to the
+/// generators, so that tooling can consume a protocol without having to link
+/// against the crate that defines it.
+pub fn format_protocol(
+    protocol_version: &ProtocolVersion,
+    wire_format: WireFormat,
+    import_functions: &FunctionList,
+    export_functions: &FunctionList,
+    types: &TypeMap,
+) -> Value {
+    json!({
+        "format_version": PROTOCOL_FORMAT_VERSION,
+        "name": protocol_version.name,
+        "version": protocol_version.version,
+        "hash": protocol_version.hash,
+        "wire_format": wire_format.name(),
+        "imports": import_functions.iter().map(format_function).collect::<Vec<_>>(),
+        "exports": export_functions.iter().map(format_function).collect::<Vec<_>>(),
+        "types": types
+            .iter()
+            .map(|(ident, ty)| json!({ "ident": format_type_ident(ident), "type": format_type(ty) }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn format_function(function: &Function) -> Value {
+    json!({
+        "name": function.name,
+        "doc_lines": function.doc_lines,
+        "args": function
+            .args
+            .iter()
+            .map(|arg| {
+                json!({
+                    "name": arg.name,
+                    "type": format_type_ident(&arg.ty),
+                    "is_borrowed": arg.is_borrowed,
+                })
+            })
+            .collect::<Vec<_>>(),
+        "return_type": function.return_type.as_ref().map(format_type_ident),
+        "is_async": function.is_async,
+        "is_event": function.is_event,
+        "import_module": function.import_module,
+    })
+}
+
+fn format_type_ident(ident: &TypeIdent) -> Value {
+    json!({
+        "name": ident.name,
+        "generic_args": ident
+            .generic_args
+            .iter()
+            .map(|(arg, bounds)| json!({ "ident": format_type_ident(arg), "bounds": bounds }))
+            .collect::<Vec<_>>(),
+        "array": ident.array,
+    })
+}
+
+fn format_type(ty: &Type) -> Value {
+    match ty {
+        Type::Alias(name, target) => json!({
+            "kind": "alias",
+            "name": name,
+            "target": format_type_ident(target),
+        }),
+        Type::Array(primitive, len) => json!({
+            "kind": "array",
+            "primitive": primitive.name(),
+            "len": len,
+        }),
+        Type::Callback(arg, ret) => json!({
+            "kind": "callback",
+            "arg": format_type_ident(arg),
+            "ret": format_type_ident(ret),
+        }),
+        Type::Container(name, item) => json!({
+            "kind": "container",
+            "name": name,
+            "item": format_type_ident(item),
+        }),
+        Type::Custom(custom) => format_custom_type(custom),
+        Type::Enum(ty) => format_enum(ty),
+        Type::List(name, item) => json!({
+            "kind": "list",
+            "name": name,
+            "item": format_type_ident(item),
+        }),
+        Type::Map(name, key, value) => json!({
+            "kind": "map",
+            "name": name,
+            "key": format_type_ident(key),
+            "value": format_type_ident(value),
+        }),
+        Type::Primitive(primitive) => json!({
+            "kind": "primitive",
+            "primitive": primitive.name(),
+        }),
+        Type::String => json!({ "kind": "string" }),
+        Type::Struct(ty) => format_struct(ty),
+        Type::Tuple(items) => json!({
+            "kind": "tuple",
+            "items": items.iter().map(format_type_ident).collect::<Vec<_>>(),
+        }),
+        Type::Unit => json!({ "kind": "unit" }),
+    }
+}
+
+fn format_custom_type(custom: &CustomType) -> Value {
+    json!({
+        "kind": "custom",
+        "ident": format_type_ident(&custom.ident),
+        "rs_ty": custom.rs_ty,
+        "rs_dependencies": custom
+            .rs_dependencies
+            .iter()
+            .map(|(name, dependency)| (name.to_string(), format_cargo_dependency(dependency)))
+            .collect::<Map<_, _>>(),
+        "serde_attrs": custom.serde_attrs,
+        "ts_ty": custom.ts_ty,
+        "ts_declaration": custom.ts_declaration,
+        "ts_conversion": custom.ts_conversion.as_ref().map(|conversion| json!({
+            "ty": conversion.ty,
+            "decode": conversion.decode,
+            "encode": conversion.encode,
+        })),
+    })
+}
+
+fn format_cargo_dependency(dependency: &CargoDependency) -> Value {
+    json!({
+        "branch": dependency.branch,
+        "default_features": dependency.default_features,
+        "features": dependency.features,
+        "git": dependency.git,
+        "path": dependency.path,
+        "registry": dependency.registry,
+        "version": dependency.version,
+        "workspace": dependency.workspace,
+    })
+}
+
+fn format_enum(ty: &Enum) -> Value {
+    json!({
+        "kind": "enum",
+        "ident": format_type_ident(&ty.ident),
+        "doc_lines": ty.doc_lines,
+        "options": format_enum_options(&ty.options),
+        "serde_attrs": ty.options.to_serde_attrs(),
+        "variants": ty.variants.iter().map(format_variant).collect::<Vec<_>>(),
+    })
+}
+
+fn format_enum_options(options: &EnumOptions) -> Value {
+    json!({
+        "variant_casing": format_casing(options.variant_casing),
+        "content_prop_name": options.content_prop_name,
+        "tag_prop_name": options.tag_prop_name,
+        "untagged": options.untagged,
+        "rust_module": options.rust_module,
+    })
+}
+
+fn format_variant(variant: &Variant) -> Value {
+    json!({
+        "name": variant.name,
+        "doc_lines": variant.doc_lines,
+        "attrs": format_variant_attrs(&variant.attrs),
+        "serde_attrs": variant.attrs.to_serde_attrs(),
+        "type": format_type(&variant.ty),
+    })
+}
+
+fn format_variant_attrs(attrs: &VariantAttrs) -> Value {
+    json!({
+        "field_casing": format_casing(attrs.field_casing),
+        "rename": attrs.rename,
+    })
+}
+
+fn format_struct(ty: &Struct) -> Value {
+    json!({
+        "kind": "struct",
+        "ident": format_type_ident(&ty.ident),
+        "doc_lines": ty.doc_lines,
+        "options": format_struct_options(&ty.options),
+        "serde_attrs": ty.options.to_serde_attrs(),
+        "fields": ty.fields.iter().map(format_field).collect::<Vec<_>>(),
+    })
+}
+
+fn format_struct_options(options: &StructOptions) -> Value {
+    json!({
+        "field_casing": format_casing(options.field_casing),
+        "rust_module": options.rust_module,
+        "is_resource": options.is_resource,
+        "is_compact": options.is_compact,
+    })
+}
+
+fn format_field(field: &Field) -> Value {
+    json!({
+        "name": field.name,
+        "type": format_type_ident(&field.ty),
+        "doc_lines": field.doc_lines,
+        "attrs": format_field_attrs(&field.attrs),
+        "serde_attrs": field.attrs.to_serde_attrs(),
+    })
+}
+
+fn format_field_attrs(attrs: &FieldAttrs) -> Value {
+    json!({
+        "default": attrs.default,
+        "deserialize_with": attrs.deserialize_with,
+        "flatten": attrs.flatten,
+        "rename": attrs.rename,
+        "serialize_with": attrs.serialize_with,
+        "skip_serializing_if": attrs.skip_serializing_if,
+    })
+}
+
+fn format_casing(casing: Casing) -> Value {
+    json!(casing.as_maybe_str())
+}
+
+/// Parses a protocol description, as formatted by [format_protocol()].
+///
+/// This is the inverse of [format_protocol()], allowing bindings to be
+/// generated for a protocol without linking against the crate that defines it.
+pub fn parse_protocol(json: &str) -> Result<Protocol, String> {
+    let protocol: Value = serde_json::from_str(json)
+        .map_err(|error| format!("Invalid protocol description: {error}"))?;
+
+    let format_version = get_property(&protocol, "format_version")?
+        .as_u64()
+        .ok_or("Expected a number for property: format_version")?;
+    if format_version != u64::from(PROTOCOL_FORMAT_VERSION) {
+        return Err(format!(
+            "Unsupported protocol format version: {format_version} (expected {PROTOCOL_FORMAT_VERSION})"
+        ));
+    }
+
+    let import_functions = get_array(&protocol, "imports")?
+        .iter()
+        .map(parse_function)
+        .collect::<Result<FunctionList, _>>()?;
+    let export_functions = get_array(&protocol, "exports")?
+        .iter()
+        .map(parse_function)
+        .collect::<Result<FunctionList, _>>()?;
+    let types = get_array(&protocol, "types")?
+        .iter()
+        .map(|entry| {
+            let ident = parse_type_ident(get_property(entry, "ident")?)?;
+            let ty = parse_type(get_property(entry, "type")?)?;
+            Ok((ident, ty))
+        })
+        .collect::<Result<TypeMap, String>>()?;
+
+    // Descriptions that predate wire formats use MessagePack:
+    let wire_format = match protocol.get("wire_format") {
+        Some(name) => name
+            .as_str()
+            .and_then(WireFormat::from_name)
+            .ok_or_else(|| format!("Unknown wire format: {name}"))?,
+        None => WireFormat::MessagePack,
+    };
+
+    Ok(Protocol::new(import_functions, export_functions, types)
+        .with_version(
+            get_string(&protocol, "name")?,
+            get_string(&protocol, "version")?,
+        )
+        .with_wire_format(wire_format))
+}
+
+fn parse_function(function: &Value) -> Result<Function, String> {
+    Ok(Function {
+        name: get_string(function, "name")?,
+        doc_lines: get_strings(function, "doc_lines")?,
+        args: get_array(function, "args")?
+            .iter()
+            .map(|arg| {
+                Ok(FunctionArg {
+                    name: get_string(arg, "name")?,
+                    ty: parse_type_ident(get_property(arg, "type")?)?,
+                    is_borrowed: get_optional_bool(arg, "is_borrowed")?.unwrap_or(false),
+                })
+            })
+            .collect::<Result<_, String>>()?,
+        return_type: match function.get("return_type") {
+            None | Some(Value::Null) => None,
+            Some(return_type) => Some(parse_type_ident(return_type)?),
+        },
+        is_async: get_bool(function, "is_async")?,
+        is_event: get_optional_bool(function, "is_event")?.unwrap_or(false),
+        import_module: get_optional_string(function, "import_module")?,
+    })
+}
+
+fn parse_type_ident(ident: &Value) -> Result<TypeIdent, String> {
+    Ok(TypeIdent {
+        name: get_string(ident, "name")?,
+        generic_args: get_array(ident, "generic_args")?
+            .iter()
+            .map(|arg| {
+                Ok((
+                    parse_type_ident(get_property(arg, "ident")?)?,
+                    get_strings(arg, "bounds")?,
+                ))
+            })
+            .collect::<Result<_, String>>()?,
+        array: match ident.get("array") {
+            None | Some(Value::Null) => None,
+            Some(len) => Some(
+                len.as_u64()
+                    .and_then(|len| NonZeroUsize::new(len as usize))
+                    .ok_or("Expected a positive number for property: array")?,
+            ),
+        },
+    })
+}
+
+fn parse_type(ty: &Value) -> Result<Type, String> {
+    let ty = match get_string(ty, "kind")?.as_str() {
+        "alias" => Type::Alias(
+            get_string(ty, "name")?,
+            parse_type_ident(get_property(ty, "target")?)?,
+        ),
+        "array" => Type::Array(
+            parse_primitive(ty)?,
+            get_property(ty, "len")?
+                .as_u64()
+                .ok_or("Expected a number for property: len")? as usize,
+        ),
+        "callback" => Type::Callback(
+            parse_type_ident(get_property(ty, "arg")?)?,
+            parse_type_ident(get_property(ty, "ret")?)?,
+        ),
+        "container" => Type::Container(
+            get_string(ty, "name")?,
+            parse_type_ident(get_property(ty, "item")?)?,
+        ),
+        "custom" => Type::Custom(parse_custom_type(ty)?),
+        "enum" => Type::Enum(parse_enum(ty)?),
+        "list" => Type::List(
+            get_string(ty, "name")?,
+            parse_type_ident(get_property(ty, "item")?)?,
+        ),
+        "map" => Type::Map(
+            get_string(ty, "name")?,
+            parse_type_ident(get_property(ty, "key")?)?,
+            parse_type_ident(get_property(ty, "value")?)?,
+        ),
+        "primitive" => Type::Primitive(parse_primitive(ty)?),
+        "string" => Type::String,
+        "struct" => Type::Struct(parse_struct(ty)?),
+        "tuple" => Type::Tuple(
+            get_array(ty, "items")?
+                .iter()
+                .map(parse_type_ident)
+                .collect::<Result<_, _>>()?,
+        ),
+        "unit" => Type::Unit,
+        other => return Err(format!("Unknown type kind: {other}")),
+    };
+    Ok(ty)
+}
+
+fn parse_primitive(ty: &Value) -> Result<Primitive, String> {
+    get_string(ty, "primitive")?.parse()
+}
+
+fn parse_custom_type(custom: &Value) -> Result<CustomType, String> {
+    let rs_dependencies = get_property(custom, "rs_dependencies")?
+        .as_object()
+        .ok_or("Expected an object for property: rs_dependencies")?
+        .iter()
+        .map(|(name, dependency)| {
+            Ok((
+                CargoDependency::leak_string(name.clone()),
+                parse_cargo_dependency(dependency)?,
+            ))
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(CustomType {
+        ident: parse_type_ident(get_property(custom, "ident")?)?,
+        rs_ty: get_string(custom, "rs_ty")?,
+        rs_dependencies,
+        serde_attrs: get_strings(custom, "serde_attrs")?,
+        ts_ty: get_string(custom, "ts_ty")?,
+        ts_declaration: get_optional_string(custom, "ts_declaration")?,
+        ts_conversion: match custom.get("ts_conversion") {
+            None | Some(Value::Null) => None,
+            Some(conversion) => Some(TsTypeConversion {
+                ty: get_string(conversion, "ty")?,
+                decode: get_string(conversion, "decode")?,
+                encode: get_optional_string(conversion, "encode")?,
+            }),
+        },
+    })
+}
+
+fn parse_cargo_dependency(dependency: &Value) -> Result<CargoDependency, String> {
+    let get_optional_str = |key| {
+        Ok::<_, String>(get_optional_string(dependency, key)?.map(CargoDependency::leak_string))
+    };
+
+    Ok(CargoDependency {
+        branch: get_optional_str("branch")?,
+        default_features: get_optional_bool(dependency, "default_features")?,
+        features: get_strings(dependency, "features")?
+            .into_iter()
+            .map(CargoDependency::leak_string)
+            .collect(),
+        git: get_optional_str("git")?,
+        path: get_optional_str("path")?,
+        registry: get_optional_str("registry")?,
+        version: get_optional_str("version")?,
+        workspace: get_optional_bool(dependency, "workspace")?,
+    })
+}
+
+fn parse_enum(ty: &Value) -> Result<Enum, String> {
+    let options = get_property(ty, "options")?;
+    Ok(Enum {
+        ident: parse_type_ident(get_property(ty, "ident")?)?,
+        variants: get_array(ty, "variants")?
+            .iter()
+            .map(parse_variant)
+            .collect::<Result<_, _>>()?,
+        doc_lines: get_strings(ty, "doc_lines")?,
+        options: EnumOptions {
+            variant_casing: get_casing(options, "variant_casing")?,
+            content_prop_name: get_optional_string(options, "content_prop_name")?,
+            tag_prop_name: get_optional_string(options, "tag_prop_name")?,
+            untagged: get_bool(options, "untagged")?,
+            rust_module: get_optional_string(options, "rust_module")?,
+        },
+    })
+}
+
+fn parse_variant(variant: &Value) -> Result<Variant, String> {
+    let attrs = get_property(variant, "attrs")?;
+    Ok(Variant {
+        name: get_string(variant, "name")?,
+        ty: parse_type(get_property(variant, "type")?)?,
+        doc_lines: get_strings(variant, "doc_lines")?,
+        attrs: VariantAttrs {
+            field_casing: get_casing(attrs, "field_casing")?,
+            rename: get_optional_string(attrs, "rename")?,
+        },
+    })
+}
+
+fn parse_struct(ty: &Value) -> Result<Struct, String> {
+    let options = get_property(ty, "options")?;
+    Ok(Struct {
+        ident: parse_type_ident(get_property(ty, "ident")?)?,
+        fields: get_array(ty, "fields")?
+            .iter()
+            .map(parse_field)
+            .collect::<Result<_, _>>()?,
+        doc_lines: get_strings(ty, "doc_lines")?,
+        options: StructOptions {
+            field_casing: get_casing(options, "field_casing")?,
+            rust_module: get_optional_string(options, "rust_module")?,
+            is_resource: get_optional_bool(options, "is_resource")?.unwrap_or(false),
+            is_compact: get_optional_bool(options, "is_compact")?.unwrap_or(false),
+        },
+    })
+}
+
+fn parse_field(field: &Value) -> Result<Field, String> {
+    let attrs = get_property(field, "attrs")?;
+    Ok(Field {
+        name: get_optional_string(field, "name")?,
+        ty: parse_type_ident(get_property(field, "type")?)?,
+        doc_lines: get_strings(field, "doc_lines")?,
+        attrs: FieldAttrs {
+            default: get_optional_string(attrs, "default")?,
+            deserialize_with: get_optional_string(attrs, "deserialize_with")?,
+            flatten: get_bool(attrs, "flatten")?,
+            rename: get_optional_string(attrs, "rename")?,
+            serialize_with: get_optional_string(attrs, "serialize_with")?,
+            skip_serializing_if: get_optional_string(attrs, "skip_serializing_if")?,
+        },
+    })
+}
+
+fn get_property<'a>(object: &'a Value, key: &str) -> Result<&'a Value, String> {
+    object
+        .get(key)
+        .ok_or_else(|| format!("Missing property: {key}"))
+}
+
+fn get_array<'a>(object: &'a Value, key: &str) -> Result<&'a Vec<Value>, String> {
+    get_property(object, key)?
+        .as_array()
+        .ok_or_else(|| format!("Expected an array for property: {key}"))
+}
+
+fn get_bool(object: &Value, key: &str) -> Result<bool, String> {
+    get_property(object, key)?
+        .as_bool()
+        .ok_or_else(|| format!("Expected a boolean for property: {key}"))
+}
+
+fn get_optional_bool(object: &Value, key: &str) -> Result<Option<bool>, String> {
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(value)) => Ok(Some(*value)),
+        Some(_) => Err(format!("Expected a boolean for property: {key}")),
+    }
+}
+
+fn get_casing(object: &Value, key: &str) -> Result<Casing, String> {
+    get_optional_string(object, key)?
+        .map(|casing| Casing::try_from(casing.as_str()))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+fn get_string(object: &Value, key: &str) -> Result<String, String> {
+    get_property(object, key)?
+        .as_str()
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| format!("Expected a string for property: {key}"))
+}
+
+fn get_optional_string(object: &Value, key: &str) -> Result<Option<String>, String> {
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(format!("Expected a string for property: {key}")),
+    }
+}
+
+fn get_strings(object: &Value, key: &str) -> Result<Vec<String>, String> {
+    get_array(object, key)?
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .map(ToOwned::to_owned)
+                .ok_or_else(|| format!("Expected an array of strings for property: {key}"))
+        })
+        .collect()
+}
+
+/// Formats a JSON Schema describing every named type in the protocol as it
+/// appears on the wire.
+///
+/// MessagePack values are described using the JSON data model: maps become
+/// objects, arrays and tuples become arrays and binary data is described as a
+/// base64-encoded string. Because JSON Schema has no notion of generics, the
+/// definitions of generic types leave their parameters unconstrained, while
+/// concrete usages of generic types are inlined with their arguments applied.
+pub fn format_json_schema(types: &TypeMap) -> Value {
+    let formatter = SchemaFormatter {
+        types,
+        generic_args: BTreeMap::new(),
+        inlined_types: BTreeSet::new(),
+    };
+
+    let definitions = types
+        .values()
+        .filter_map(|ty| {
+            let name = match ty {
+                Type::Alias(name, _) => name.clone(),
+                Type::Custom(CustomType { ident, .. })
+                | Type::Enum(Enum { ident, .. })
+                | Type::Struct(Struct { ident, .. }) => ident.name.clone(),
+                _ => return None,
+            };
+            Some((name, formatter.format_definition(ty)))
+        })
+        .collect::<Map<_, _>>();
+
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "$defs": definitions,
+    })
+}
+
+struct SchemaFormatter<'a> {
+    types: &'a TypeMap,
+
+    /// Concrete types to use for the generic parameters of the type that is
+    /// being formatted.
+    generic_args: BTreeMap<String, TypeIdent>,
+
+    /// Generic types (with their arguments) that are currently being inlined.
+    /// Recursive references to these are formatted as references to their
+    /// (unapplied) definitions instead.
+    inlined_types: BTreeSet<String>,
+}
+
+impl<'a> SchemaFormatter<'a> {
+    fn format_definition(&self, ty: &Type) -> Value {
+        match ty {
+            Type::Alias(_, target) => self.format_ident(target),
+            Type::Custom(custom) => format_custom_type_schema(custom),
+            Type::Enum(ty) => with_description(self.format_enum(ty), &ty.doc_lines),
+            Type::Struct(ty) => with_description(self.format_struct(ty), &ty.doc_lines),
+            _ => self.format_type(ty, &TypeIdent::default()),
+        }
+    }
+
+    /// Formats the schema for a reference to the given type.
+    fn format_ident(&self, ident: &TypeIdent) -> Value {
+        let ident = self.resolve_generic_args(ident);
+        match self.types.get(&ident) {
+            Some(Type::Alias(name, _)) => format_definition_ref(name),
+            Some(
+                ty @ (Type::Enum(Enum {
+                    ident: definition, ..
+                })
+                | Type::Struct(Struct {
+                    ident: definition, ..
+                })),
+            ) if !definition.generic_args.is_empty()
+                && !self.inlined_types.contains(&ident.to_string()) =>
+            {
+                // Apply the generic arguments by inlining the definition:
+                let mut inlined_types = self.inlined_types.clone();
+                inlined_types.insert(ident.to_string());
+                let formatter = SchemaFormatter {
+                    types: self.types,
+                    generic_args: definition
+                        .generic_args
+                        .iter()
+                        .zip(ident.generic_args.iter())
+                        .map(|((param, _), (arg, _))| (param.name.clone(), arg.clone()))
+                        .collect(),
+                    inlined_types,
+                };
+                formatter.format_definition(ty)
+            }
+            Some(Type::Custom(CustomType { ident, .. }))
+            | Some(Type::Enum(Enum { ident, .. }))
+            | Some(Type::Struct(Struct { ident, .. })) => format_definition_ref(&ident.name),
+            Some(ty) => self.format_type(ty, &ident),
+            None => json!({}), // Must be an unresolved generic.
+        }
+    }
+
+    /// Replaces references to generic parameters with their concrete types.
+    fn resolve_generic_args(&self, ident: &TypeIdent) -> TypeIdent {
+        if let Some(arg) = self.generic_args.get(&ident.name) {
+            return arg.clone();
+        }
+
+        let mut ident = ident.clone();
+        for (arg, _) in ident.generic_args.iter_mut() {
+            *arg = self.resolve_generic_args(arg);
+        }
+        ident
+    }
+
+    fn format_type(&self, ty: &Type, ident: &TypeIdent) -> Value {
+        let generic_arg = |index: usize| {
+            ident
+                .generic_args
+                .get(index)
+                .map(|(arg, _)| self.format_ident(arg))
+                .unwrap_or_else(|| json!({}))
+        };
+
+        match ty {
+            Type::Array(primitive, len) => json!({
+                "type": "array",
+                "items": format_primitive_schema(*primitive),
+                "minItems": len,
+                "maxItems": len,
+            }),
+            // Callbacks are encoded as MessagePack extension values, which
+            // have no JSON representation.
+            Type::Callback(_, _) => json!({
+                "description": "Reference to a callback",
+            }),
+            Type::Container(name, _) if name == "Option" => json!({
+                "anyOf": [generic_arg(0), { "type": "null" }],
+            }),
+            Type::Container(name, _) if name == "Handle" => json!({
+                "type": "integer",
+                "minimum": 0,
+            }),
+            Type::Container(_, _) => generic_arg(0),
+            Type::List(name, _) => {
+                let mut schema = json!({ "type": "array", "items": generic_arg(0) });
+                if name.ends_with("Set") {
+                    schema["uniqueItems"] = json!(true);
+                }
+                schema
+            }
+            Type::Map(_, _, _) => json!({
+                "type": "object",
+                "additionalProperties": generic_arg(1),
+            }),
+            Type::Primitive(primitive) => format_primitive_schema(*primitive),
+            Type::String => json!({ "type": "string" }),
+            Type::Tuple(items) => json!({
+                "type": "array",
+                "prefixItems": items
+                    .iter()
+                    .map(|item| self.format_ident(item))
+                    .collect::<Vec<_>>(),
+                "minItems": items.len(),
+                "maxItems": items.len(),
+            }),
+            Type::Unit => json!({ "type": "null" }),
+            Type::Alias(_, _) | Type::Custom(_) | Type::Enum(_) | Type::Struct(_) => {
+                self.format_ident(ident)
+            }
+        }
+    }
+
+    fn format_struct(&self, ty: &Struct) -> Value {
+        let is_tuple_struct = ty.fields.iter().any(|field| field.name.is_none());
+        if is_tuple_struct {
+            return if ty.fields.len() == 1 {
+                self.format_ident(&ty.fields[0].ty)
+            } else {
+                json!({
+                    "type": "array",
+                    "prefixItems": ty
+                        .fields
+                        .iter()
+                        .map(|field| self.format_ident(&field.ty))
+                        .collect::<Vec<_>>(),
+                    "minItems": ty.fields.len(),
+                    "maxItems": ty.fields.len(),
+                })
+            };
+        }
+
+        if ty.is_compact() {
+            return self.format_compact_struct(ty);
+        }
+
+        self.format_fields(&ty.fields, ty.options.field_casing, Map::new())
+    }
+
+    /// Formats the schema for a compact struct, which is encoded as an array
+    /// of its field values.
+    fn format_compact_struct(&self, ty: &Struct) -> Value {
+        let items = ty
+            .fields
+            .iter()
+            .map(|field| {
+                let mut schema = with_description(self.format_ident(&field.ty), &field.doc_lines);
+                schema["title"] = json!(get_field_name(field, ty.options.field_casing));
+                schema
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "type": "array",
+            "prefixItems": items,
+            "minItems": ty.min_compact_len(),
+            "maxItems": ty.fields.len(),
+        })
+    }
+
+    /// Formats the schema for an object with the given fields.
+    ///
+    /// `properties` may contain additional properties to include, such as the
+    /// tag of an internally tagged enum variant.
+    fn format_fields(
+        &self,
+        fields: &[Field],
+        casing: Casing,
+        mut properties: Map<String, Value>,
+    ) -> Value {
+        let mut required: Vec<Value> = properties.keys().cloned().map(Value::String).collect();
+        let mut flattened = Vec::new();
+        for field in fields {
+            if field.attrs.flatten {
+                flattened.push(self.format_ident(&field.ty));
+                continue;
+            }
+
+            let name = get_field_name(field, casing);
+            let is_optional = field.attrs.default.is_some()
+                || field.attrs.skip_serializing_if.is_some()
+                || matches!(
+                    self.types.get(&self.resolve_generic_args(&field.ty)),
+                    Some(Type::Container(name, _)) if name == "Option"
+                );
+            if !is_optional {
+                required.push(Value::String(name.clone()));
+            }
+
+            properties.insert(
+                name,
+                with_description(self.format_ident(&field.ty), &field.doc_lines),
+            );
+        }
+
+        let object = json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        });
+        if flattened.is_empty() {
+            object
+        } else {
+            flattened.insert(0, object);
+            json!({ "allOf": flattened })
+        }
+    }
+
+    fn format_enum(&self, ty: &Enum) -> Value {
+        let variants = ty
+            .variants
+            .iter()
+            .map(|variant| {
+                let name = get_variant_name(variant, &ty.options);
+                let schema = self.format_variant(variant, &name, &ty.options);
+                with_description(schema, &variant.doc_lines)
+            })
+            .collect::<Vec<_>>();
+
+        if ty.options.untagged {
+            json!({ "anyOf": variants })
+        } else {
+            json!({ "oneOf": variants })
+        }
+    }
+
+    fn format_variant(&self, variant: &Variant, name: &str, options: &EnumOptions) -> Value {
+        let content = match &variant.ty {
+            Type::Unit => None,
+            Type::Struct(ty) => {
+                Some(self.format_fields(&ty.fields, variant.attrs.field_casing, Map::new()))
+            }
+            Type::Tuple(items) if items.len() == 1 => Some(self.format_ident(&items[0])),
+            other => Some(self.format_type(other, &TypeIdent::default())),
+        };
+
+        if options.untagged {
+            return content.unwrap_or_else(|| json!({ "type": "null" }));
+        }
+
+        match (&options.tag_prop_name, &options.content_prop_name, content) {
+            (None, _, None) => json!({ "const": name }),
+            (None, _, Some(content)) => json!({
+                "type": "object",
+                "properties": { name: content },
+                "required": [name],
+                "additionalProperties": false,
+            }),
+            (Some(tag), Some(content_prop), Some(content)) => json!({
+                "type": "object",
+                "properties": { tag: { "const": name }, content_prop: content },
+                "required": [tag, content_prop],
+            }),
+            (Some(tag), _, None) => json!({
+                "type": "object",
+                "properties": { tag: { "const": name } },
+                "required": [tag],
+            }),
+            (Some(tag), None, Some(content)) => {
+                let tag_properties = Map::from_iter([(tag.clone(), json!({ "const": name }))]);
+                match &variant.ty {
+                    Type::Struct(ty) => {
+                        self.format_fields(&ty.fields, variant.attrs.field_casing, tag_properties)
+                    }
+                    _ => json!({
+                        "allOf": [
+                            { "type": "object", "properties": tag_properties, "required": [tag] },
+                            content,
+                        ],
+                    }),
+                }
+            }
+        }
+    }
+}
+
+fn format_definition_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{name}") })
+}
+
+fn format_primitive_schema(primitive: Primitive) -> Value {
+    let (min, max): (i128, i128) = match primitive {
+        Primitive::Bool => return json!({ "type": "boolean" }),
+        Primitive::F32 | Primitive::F64 => return json!({ "type": "number" }),
+        Primitive::I8 => (i8::MIN.into(), i8::MAX.into()),
+        Primitive::I16 => (i16::MIN.into(), i16::MAX.into()),
+        Primitive::I32 => (i32::MIN.into(), i32::MAX.into()),
+        Primitive::I64 => (i64::MIN.into(), i64::MAX.into()),
+        Primitive::U8 => (u8::MIN.into(), u8::MAX.into()),
+        Primitive::U16 => (u16::MIN.into(), u16::MAX.into()),
+        Primitive::U32 => (u32::MIN.into(), u32::MAX.into()),
+        Primitive::U64 => (u64::MIN.into(), u64::MAX.into()),
+        // 128-bit integers are encoded as 16 bytes, in big-endian order:
+        Primitive::I128 | Primitive::U128 => {
+            return json!({ "type": "string", "contentEncoding": "base64" })
+        }
+        Primitive::Char => return json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+    };
+    json!({
+        "type": "integer",
+        "minimum": format_integer_bound(min),
+        "maximum": format_integer_bound(max),
+    })
+}
+
+fn format_integer_bound(bound: i128) -> Value {
+    if let Ok(bound) = i64::try_from(bound) {
+        json!(bound)
+    } else {
+        json!(bound as u64)
+    }
+}
+
+fn format_custom_type_schema(custom: &CustomType) -> Value {
+    // Custom types are (de)serialized by external code, so the best we can do
+    // is to use the type they map to in TypeScript as a hint:
+    let mut schema = match custom.ts_ty.as_str() {
+        "boolean" => json!({ "type": "boolean" }),
+        "number" => json!({ "type": "number" }),
+        "string" => json!({ "type": "string" }),
+        "Uint8Array" => json!({ "type": "string", "contentEncoding": "base64" }),
+        _ => json!({}),
+    };
+    schema["description"] = json!(format!("Custom type `{}`.", custom.rs_ty));
+    schema
+}
+
+fn with_description(mut schema: Value, doc_lines: &[String]) -> Value {
+    if !doc_lines.is_empty() {
+        if let Value::Object(object) = &mut schema {
+            let description = doc_lines
+                .iter()
+                .map(|line| line.strip_prefix(' ').unwrap_or(line))
+                .collect::<Vec<_>>()
+                .join("\n");
+            object.insert("description".to_owned(), Value::String(description));
+        }
+    }
+    schema
+}
+
+fn get_field_name(field: &Field, casing: Casing) -> String {
+    if let Some(rename) = field.attrs.rename.as_ref() {
+        rename.to_owned()
+    } else {
+        let name = field.name.as_deref().unwrap_or_default();
+        casing.format_string(name.strip_prefix("r#").unwrap_or(name))
+    }
+}
+
+fn get_variant_name(variant: &Variant, options: &EnumOptions) -> String {
+    if let Some(rename) = variant.attrs.rename.as_ref() {
+        rename.to_owned()
+    } else {
+        options.variant_casing.format_string(&variant.name)
+    }
+}
+
+fn insert_json_file(files: &mut GeneratedFiles, file_path: String, value: &Value) {
+    let contents = serde_json::to_string_pretty(value).expect("Could not serialize JSON");
+    files.insert(file_path, format!("{contents}\n"));
+}
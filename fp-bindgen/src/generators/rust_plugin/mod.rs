@@ -1,36 +1,46 @@
-use crate::functions::Function;
-use crate::generators::RustPluginConfigValue;
+use crate::functions::{Function, FunctionArg};
+use crate::generators::{GeneratedFiles, RustPluginConfigValue};
+use crate::protocol::{ProtocolVersion, WireFormat};
 use crate::types::is_runtime_bound;
 use crate::{
     functions::FunctionList,
     types::{CargoDependency, Enum, Field, Struct, Type, TypeIdent, TypeMap},
     RustPluginConfig,
 };
+use std::collections::{BTreeMap, BTreeSet};
 use std::iter::FromIterator;
-use std::{
-    collections::{BTreeMap, BTreeSet},
-    fs,
-};
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn generate_bindings(
     import_functions: FunctionList,
     export_functions: FunctionList,
     types: TypeMap,
     config: RustPluginConfig,
+    protocol_version: &ProtocolVersion,
+    wire_format: WireFormat,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
     let src_path = format!("{path}/src");
-    fs::create_dir_all(&src_path).expect("Could not create output directory");
 
-    generate_cargo_file(config, &import_functions, &types, path);
+    generate_cargo_file(
+        config,
+        &import_functions,
+        &export_functions,
+        &types,
+        wire_format,
+        path,
+        files,
+    );
 
-    generate_type_bindings(&types, &src_path);
-    generate_imported_function_bindings(import_functions, &types, &src_path);
-    generate_exported_function_bindings(export_functions, &types, &src_path);
+    generate_type_bindings(&types, TypeBindingsTarget::Plugin, &src_path, files);
+    generate_imported_function_bindings(import_functions, &types, &src_path, files);
+    generate_exported_function_bindings(export_functions, &types, &src_path, files);
 
-    write_bindings_file(
+    files.insert(
         format!("{src_path}/lib.rs"),
-        "#![allow(unused_imports)]
+        format!(
+            "#![allow(unused_imports)]
 #[rustfmt::skip]
 mod export;
 #[rustfmt::skip]
@@ -43,22 +53,53 @@ pub use import::*;
 pub use types::*;
 
 pub use fp_bindgen_support::*;
+
+/// Returns the version of the protocol the plugin was built against, so that
+/// runtimes can verify their compatibility when loading the plugin.
+#[doc(hidden)]
+#[no_mangle]
+pub fn __fp_protocol_version() -> fp_bindgen_support::common::mem::FatPtr {{
+    fp_bindgen_support::guest::io::export_value_to_host(
+        &fp_bindgen_support::common::protocol::ProtocolVersion {{
+            name: {:?}.to_owned(),
+            version: {:?}.to_owned(),
+            hash: {:?}.to_owned(),
+        }},
+    )
+}}
 ",
+            protocol_version.name, protocol_version.version, protocol_version.hash
+        ),
     );
 }
 
 fn generate_cargo_file(
     config: RustPluginConfig,
     import_functions: &FunctionList,
+    export_functions: &FunctionList,
     types: &TypeMap,
+    wire_format: WireFormat,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
-    let requires_async = import_functions.iter().any(|function| function.is_async);
+    let requires_async = import_functions
+        .iter()
+        .chain(export_functions)
+        .any(|function| function.is_async);
 
     let mut support_features = BTreeSet::from(["guest"]);
     if requires_async {
         support_features.insert("async");
     }
+    match wire_format {
+        WireFormat::MessagePack => {}
+        WireFormat::Json => {
+            support_features.insert("json");
+        }
+        WireFormat::Postcard => {
+            support_features.insert("postcard");
+        }
+    }
 
     let mut dependencies = BTreeMap::from([
         (
@@ -97,7 +138,7 @@ fn generate_cargo_file(
         dependencies.insert(name, dependency);
     }
 
-    write_bindings_file(
+    files.insert(
         format!("{path}/Cargo.toml"),
         format!(
             "[package]
@@ -122,7 +163,22 @@ fn generate_cargo_file(
     );
 }
 
-pub fn generate_type_bindings(types: &TypeMap, path: &str) {
+/// The side of the bindings for which Rust type definitions are generated.
+///
+/// Both sides share the same definitions, except for resource types and the
+/// `Handle` type used to refer to them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TypeBindingsTarget {
+    Plugin,
+    Runtime,
+}
+
+pub fn generate_type_bindings(
+    types: &TypeMap,
+    target: TypeBindingsTarget,
+    path: &str,
+    files: &mut GeneratedFiles,
+) {
     let std_types: BTreeSet<_> = types.values().filter_map(collect_std_types).collect();
     let std_imports = if std_types.is_empty() {
         "".to_owned()
@@ -135,12 +191,17 @@ pub fn generate_type_bindings(types: &TypeMap, path: &str) {
         )
     };
 
-    let type_imports = types
+    let mut type_imports = types
         .values()
         .filter_map(|ty| {
             let (ident, rust_module) = match ty {
                 Type::Enum(Enum { ident, options, .. }) => (ident, &options.rust_module),
-                Type::Struct(Struct { ident, options, .. }) => (ident, &options.rust_module),
+                Type::Struct(Struct { ident, options, .. }) => match &options.rust_module {
+                    None if options.is_resource && target == TypeBindingsTarget::Runtime => {
+                        return Some(format!("pub use super::{};", ident.name));
+                    }
+                    rust_module => (ident, rust_module),
+                },
                 _ => return None,
             };
 
@@ -149,6 +210,25 @@ pub fn generate_type_bindings(types: &TypeMap, path: &str) {
                 .map(|module| format!("pub use {}::{};", module, ident.name))
         })
         .collect::<Vec<_>>();
+    let support_module = match target {
+        TypeBindingsTarget::Plugin => "guest",
+        TypeBindingsTarget::Runtime => "wasmer2_host",
+    };
+    if types
+        .values()
+        .any(|ty| matches!(ty, Type::Container(name, _) if name == "Handle"))
+    {
+        type_imports.insert(
+            0,
+            format!("pub use fp_bindgen_support::{support_module}::handle::Handle;"),
+        );
+    }
+    if types.values().any(|ty| matches!(ty, Type::Callback(_, _))) {
+        type_imports.insert(
+            0,
+            format!("pub use fp_bindgen_support::{support_module}::callback::Callback;"),
+        );
+    }
     let type_imports = if type_imports.is_empty() {
         "".to_owned()
     } else {
@@ -171,6 +251,11 @@ pub fn generate_type_bindings(types: &TypeMap, path: &str) {
             Type::Struct(ty) => {
                 if ty.options.rust_module.is_some() {
                     None
+                } else if ty.options.is_resource {
+                    match target {
+                        TypeBindingsTarget::Plugin => Some(create_resource_definition(ty)),
+                        TypeBindingsTarget::Runtime => None,
+                    }
                 } else {
                     Some(create_struct_definition(ty, types))
                 }
@@ -179,7 +264,7 @@ pub fn generate_type_bindings(types: &TypeMap, path: &str) {
         })
         .collect::<Vec<_>>();
 
-    write_bindings_file(
+    files.insert(
         format!("{path}/types.rs"),
         format!(
             "#![allow(dead_code, unused_imports)]\n\
@@ -203,7 +288,17 @@ pub fn format_modifiers(function: &Function) -> String {
     if function.is_async { "async " } else { "" }.to_owned()
 }
 
-fn format_functions(functions: FunctionList, types: &TypeMap, macro_path: &str) -> String {
+/// Formats the function declarations for the given macro.
+///
+/// If `borrow_args` is `true`, borrowed arguments are formatted as references,
+/// which is what callers of the functions get to pass. Otherwise they're
+/// formatted as the owned value implementations receive.
+fn format_functions(
+    functions: FunctionList,
+    types: &TypeMap,
+    macro_path: &str,
+    borrow_args: bool,
+) -> String {
     functions
         .iter()
         .map(|func| {
@@ -213,7 +308,14 @@ fn format_functions(functions: FunctionList, types: &TypeMap, macro_path: &str)
             let args_with_types = func
                 .args
                 .iter()
-                .map(|arg| format!("{}: {}", arg.name, format_ident(&arg.ty, types)))
+                .map(|arg| {
+                    let ty = if borrow_args {
+                        format_arg_ident(arg, types)
+                    } else {
+                        format_ident(&arg.ty, types)
+                    };
+                    format!("{}: {ty}", arg.name)
+                })
                 .collect::<Vec<_>>()
                 .join(", ");
             let return_type = match &func.return_type {
@@ -228,6 +330,20 @@ fn format_functions(functions: FunctionList, types: &TypeMap, macro_path: &str)
         .join("\n\n")
 }
 
+/// Formats the type of a function argument as it is passed by the caller,
+/// which is a reference for borrowed arguments.
+pub fn format_arg_ident(arg: &FunctionArg, types: &TypeMap) -> String {
+    if !arg.is_borrowed {
+        return format_ident(&arg.ty, types);
+    }
+
+    match (arg.ty.name.as_str(), arg.ty.generic_args.as_slice()) {
+        ("String", []) => "&str".to_owned(),
+        ("Vec", [(item, _)]) => format!("&[{}]", format_ident(item, types)),
+        _ => format!("&{}", format_ident(&arg.ty, types)),
+    }
+}
+
 pub fn format_ident(ident: &TypeIdent, types: &TypeMap) -> String {
     match types.get(ident) {
         Some(ty) => format_type_with_ident(ty, ident, types),
@@ -261,6 +377,7 @@ fn format_type_with_ident(ty: &Type, ident: &TypeIdent, types: &TypeMap) -> Stri
 
     match ty {
         Type::Alias(name, _) => name.clone(),
+        Type::Callback(_, _) => format_name_with_args("Callback", Some(2)),
         Type::Container(name, _) | Type::List(name, _) => format_name_with_args(name, Some(1)),
         Type::Custom(custom) => custom.rs_ty.clone(),
         Type::Enum(Enum { ident, .. }) => format_name_with_args(&ident.name, None),
@@ -292,15 +409,52 @@ fn generate_imported_function_bindings(
     import_functions: FunctionList,
     types: &TypeMap,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
-    write_bindings_file(
+    // Functions from named import modules are placed in a Rust module of the
+    // same name:
+    let mut modules = BTreeMap::<String, Vec<Function>>::new();
+    let mut default_functions = Vec::new();
+    for function in import_functions {
+        match &function.import_module {
+            Some(module) => modules.entry(module.clone()).or_default().push(function),
+            None => default_functions.push(function),
+        }
+    }
+
+    let module_decls = modules
+        .into_iter()
+        .map(|(module, functions)| {
+            let functions = format_functions(
+                FunctionList::from_iter(functions),
+                types,
+                &format!("fp_bindgen_support::fp_import_signature(module = \"{module}\")"),
+                true,
+            );
+            format!(
+                "\n\npub mod {module} {{\n    use crate::types::*;\n\n{}\n}}",
+                functions
+                    .lines()
+                    .map(|line| if line.is_empty() {
+                        String::new()
+                    } else {
+                        format!("    {line}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+        })
+        .collect::<String>();
+
+    files.insert(
         format!("{path}/import.rs"),
         format!(
-            "use crate::types::*;\n\n{}\n",
+            "use crate::types::*;\n\n{}{module_decls}\n",
             format_functions(
-                import_functions,
+                FunctionList::from_iter(default_functions),
                 types,
-                "fp_bindgen_support::fp_import_signature"
+                "fp_bindgen_support::fp_import_signature",
+                true
             )
         ),
     );
@@ -310,20 +464,71 @@ fn generate_exported_function_bindings(
     export_functions: FunctionList,
     types: &TypeMap,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
-    write_bindings_file(
+    let (events, functions): (Vec<_>, Vec<_>) = export_functions
+        .into_iter()
+        .partition(|function| function.is_event);
+
+    let event_decls = events
+        .iter()
+        .map(|event| format!("\n\n{}", format_event(event, types)))
+        .collect::<String>();
+
+    files.insert(
         format!("{path}/export.rs"),
         format!(
-            "use crate::types::*;\n\n{}\n",
+            "use crate::types::*;\n\n{}{event_decls}\n",
             format_functions(
-                export_functions,
+                FunctionList::from_iter(functions),
                 types,
-                "fp_bindgen_support::fp_export_signature"
+                "fp_bindgen_support::fp_export_signature",
+                false
             )
         ),
     );
 }
 
+/// Formats the functions for subscribing to an event, together with the
+/// export through which the host delivers it.
+fn format_event(event: &Function, types: &TypeMap) -> String {
+    let name = &event.name;
+    let ty = event
+        .args
+        .first()
+        .map(|arg| format_ident(&arg.ty, types))
+        .unwrap_or_else(|| "()".to_owned());
+    let doc = if event.doc_lines.is_empty() {
+        String::new()
+    } else {
+        format!("///\n{}", format_doc_lines(&event.doc_lines))
+    };
+
+    format!(
+        "/// Subscribes to the `{name}` event, replacing any previous handler.
+{doc}pub fn subscribe_{name}<F, FUT>(handler: F)
+where
+    F: Fn({ty}) -> FUT + 'static,
+    FUT: std::future::Future<Output = ()> + 'static,
+{{
+    fp_bindgen_support::guest::events::subscribe(\"{name}\", handler);
+}}
+
+/// Unsubscribes from the `{name}` event.
+pub fn unsubscribe_{name}() {{
+    fp_bindgen_support::guest::events::unsubscribe(\"{name}\");
+}}
+
+#[doc(hidden)]
+#[no_mangle]
+pub fn __fp_gen_{name}(
+    event: fp_bindgen_support::common::mem::FatPtr,
+) -> fp_bindgen_support::common::mem::FatPtr {{
+    fp_bindgen_support::guest::events::dispatch(\"{name}\", event)
+}}"
+    )
+}
+
 fn collect_std_types(ty: &Type) -> Option<String> {
     match ty {
         Type::Container(name, _) if name == "Rc" => Some("rc::Rc".to_owned()),
@@ -440,6 +645,16 @@ fn create_enum_definition(ty: &Enum, types: &TypeMap) -> String {
     )
 }
 
+/// Resources are owned by the host, so plugins get an uninhabited type that
+/// can only be referred to through a `Handle`.
+fn create_resource_definition(ty: &Struct) -> String {
+    format!(
+        "{}pub enum {} {{}}",
+        format_doc_lines(&ty.doc_lines),
+        ty.ident.name
+    )
+}
+
 fn create_struct_definition(ty: &Struct, types: &TypeMap) -> String {
     let is_tuple_struct = ty
         .fields
@@ -490,15 +705,26 @@ fn create_struct_definition(ty: &Struct, types: &TypeMap) -> String {
         }
     };
 
+    // Compact structs get a custom `Serialize` implementation that encodes
+    // them as arrays. Serde already accepts arrays when deserializing structs.
     let annotations = format!(
-        "{}#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]\n{}",
+        "{}#[derive(Clone, Debug, Deserialize, PartialEq{})]\n{}",
         format_docs(&ty.doc_lines),
+        if ty.is_compact() { "" } else { ", Serialize" },
         serde_annotation
     );
 
     // Format ident, include bounds and skip compile-time only bounds
     let ident = ty.ident.format(true);
-    if is_tuple_struct {
+    if ty.is_compact() {
+        format!(
+            "{}pub struct {} {{\n{}\n}}\n\n{}",
+            annotations,
+            ident,
+            fields.join("\n").trim_start_matches('\n'),
+            create_compact_serialize_impl(ty, types)
+        )
+    } else if is_tuple_struct {
         if fields.len() > 1 {
             format!(
                 "{}pub struct {}(\n{}\n);",
@@ -519,6 +745,94 @@ fn create_struct_definition(ty: &Struct, types: &TypeMap) -> String {
     }
 }
 
+/// Creates a `Serialize` implementation for a compact struct, which encodes
+/// the struct as an array of its field values, in declaration order.
+fn create_compact_serialize_impl(ty: &Struct, types: &TypeMap) -> String {
+    let format_params = |extra_bound: Option<&str>| {
+        ty.ident
+            .generic_args
+            .iter()
+            .map(|(arg, bounds)| {
+                let bounds = bounds
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|bound| is_runtime_bound(bound))
+                    .chain(extra_bound)
+                    .collect::<Vec<_>>();
+                if bounds.is_empty() {
+                    arg.to_string()
+                } else {
+                    format!("{}: {}", arg, bounds.join(" + "))
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let impl_params = format_params(Some("Serialize"));
+    let impl_params = if impl_params.is_empty() {
+        "".to_owned()
+    } else {
+        format!("<{}>", impl_params.join(", "))
+    };
+
+    let mut fields = ty
+        .fields
+        .iter()
+        .map(|field| {
+            // Only the attributes that affect serialization are relevant:
+            let serde_attrs = get_field_serde_attrs(field, types)
+                .into_iter()
+                .filter(|attr| attr.starts_with("serialize_with") || attr.starts_with("with"))
+                .collect::<Vec<_>>();
+            let annotation = if serde_attrs.is_empty() {
+                "".to_owned()
+            } else {
+                format!("#[serde({})] ", serde_attrs.join(", "))
+            };
+            format!(
+                "            {}&'a {},",
+                annotation,
+                format_ident(&field.ty, types)
+            )
+        })
+        .collect::<Vec<_>>();
+    // Serde serializes tuple structs with a single field as newtypes, so make
+    // sure there are at least two fields:
+    if fields.len() < 2 {
+        fields.push("            #[serde(skip)] std::marker::PhantomData<&'a ()>,".to_owned());
+    }
+
+    let mut values = ty
+        .fields
+        .iter()
+        .map(|field| format!("&self.{}", field.name.as_deref().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    if values.len() < 2 {
+        values.push("std::marker::PhantomData".to_owned());
+    }
+
+    format!(
+        "impl{} Serialize for {} {{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{
+        #[derive(Serialize)]
+        struct Compact<{}>(
+{}
+        );
+
+        Compact({}).serialize(serializer)
+    }}
+}}",
+        impl_params,
+        ty.ident.format(false),
+        std::iter::once("'a".to_owned())
+            .chain(format_params(None))
+            .collect::<Vec<_>>()
+            .join(", "),
+        fields.join("\n"),
+        values.join(", ")
+    )
+}
+
 fn format_docs(doc_lines: &[String]) -> String {
     doc_lines
         .iter()
@@ -531,13 +845,7 @@ fn format_struct_fields(fields: &[Field], types: &TypeMap) -> Vec<String> {
     fields
         .iter()
         .map(|field| {
-            let mut serde_attrs = field.attrs.to_serde_attrs();
-
-            if let Some(Type::Custom(custom_type)) = types.get(&field.ty) {
-                for attr in custom_type.serde_attrs.iter() {
-                    serde_attrs.push(attr.clone());
-                }
-            }
+            let mut serde_attrs = get_field_serde_attrs(field, types);
 
             let docs = if field.doc_lines.is_empty() {
                 "".to_owned()
@@ -575,11 +883,16 @@ fn format_struct_fields(fields: &[Field], types: &TypeMap) -> Vec<String> {
         .collect()
 }
 
-fn write_bindings_file<C>(file_path: String, contents: C)
-where
-    C: AsRef<[u8]>,
-{
-    fs::write(file_path, &contents).expect("Could not write bindings file");
+/// Returns the Serde attributes of the field, including those required by
+/// its type.
+fn get_field_serde_attrs(field: &Field, types: &TypeMap) -> Vec<String> {
+    let mut serde_attrs = field.attrs.to_serde_attrs();
+    if let Some(Type::Custom(custom_type)) = types.get(&field.ty) {
+        for attr in custom_type.serde_attrs.iter() {
+            serde_attrs.push(attr.clone());
+        }
+    }
+    serde_attrs
 }
 
 fn format_cargo_key(key: &str, value: Option<RustPluginConfigValue>) -> String {
@@ -602,3 +915,122 @@ fn format_cargo_key(key: &str, value: Option<RustPluginConfigValue>) -> String {
         "".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::{Callback, Handle},
+        Serializable,
+    };
+
+    fn render_types(target: TypeBindingsTarget) -> String {
+        let mut types = TypeMap::new();
+        Handle::<String>::collect_types(&mut types);
+        let connection = Type::from_item(
+            "/// A connection to the database.\n#[fp(resource)] pub struct Connection;",
+        );
+        types.insert(TypeIdent::from("Connection"), connection);
+
+        let mut files = GeneratedFiles::new();
+        generate_type_bindings(&types, target, "bindings", &mut files);
+        files.get("bindings/types.rs").unwrap().to_owned()
+    }
+
+    #[test]
+    fn generates_resources_for_plugins() {
+        let types = render_types(TypeBindingsTarget::Plugin);
+        assert!(types.contains("pub use fp_bindgen_support::guest::handle::Handle;\n"));
+        assert!(types.contains("/// A connection to the database.\npub enum Connection {}"));
+    }
+
+    #[test]
+    fn imports_resources_for_runtimes() {
+        let types = render_types(TypeBindingsTarget::Runtime);
+        assert!(types.contains(
+            "pub use fp_bindgen_support::wasmer2_host::handle::Handle;\npub use super::Connection;\n"
+        ));
+        assert!(!types.contains("enum Connection"));
+    }
+
+    #[test]
+    fn imports_callbacks() {
+        let mut types = TypeMap::new();
+        Callback::<Vec<String>, ()>::collect_types(&mut types);
+
+        let mut files = GeneratedFiles::new();
+        generate_type_bindings(&types, TypeBindingsTarget::Plugin, "bindings", &mut files);
+        let rendered = files.get("bindings/types.rs").unwrap();
+        assert!(rendered.contains("pub use fp_bindgen_support::guest::callback::Callback;\n"));
+
+        let ident = TypeIdent::from("Callback<Vec<String>, ()>");
+        assert_eq!(format_ident(&ident, &types), "Callback<Vec<String>, ()>");
+    }
+
+    #[test]
+    fn generates_event_subscriptions() {
+        let event = Function::new(
+            "/// Emitted for every log entry.\n#[fp(event)] async fn log_entry(entry: String);",
+        );
+
+        let rendered = format_event(&event, &TypeMap::new());
+        assert!(rendered.contains(
+            "/// Subscribes to the `log_entry` event, replacing any previous handler.
+///
+/// Emitted for every log entry.
+pub fn subscribe_log_entry<F, FUT>(handler: F)
+where
+    F: Fn(String) -> FUT + 'static,"
+        ));
+        assert!(rendered.contains("pub fn unsubscribe_log_entry() {"));
+        assert!(rendered.contains("pub fn __fp_gen_log_entry("));
+    }
+
+    #[test]
+    fn serializes_compact_structs_as_arrays() {
+        let point = Type::from_item(
+            "#[fp(compact)] pub struct Point<T> { pub x: T, #[fp(default)] pub y: Option<T> }",
+        );
+        let Type::Struct(point) = point else {
+            unreachable!()
+        };
+
+        let rendered = create_struct_definition(&point, &TypeMap::new());
+        assert!(rendered.starts_with("#[derive(Clone, Debug, Deserialize, PartialEq)]\n"));
+        assert!(rendered.contains(
+            "impl<T: Serialize> Serialize for Point<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Compact<'a, T>(
+            &'a T,
+            &'a Option<T>,
+        );
+
+        Compact(&self.x, &self.y).serialize(serializer)
+    }
+}"
+        ));
+    }
+
+    #[test]
+    fn enables_wire_format_support() {
+        let render_cargo_file = |wire_format| {
+            let mut files = GeneratedFiles::new();
+            generate_cargo_file(
+                RustPluginConfig::builder().name("plugin").build(),
+                &FunctionList::new(),
+                &FunctionList::new(),
+                &TypeMap::new(),
+                wire_format,
+                "bindings",
+                &mut files,
+            );
+            files.get("bindings/Cargo.toml").unwrap().to_owned()
+        };
+
+        assert!(render_cargo_file(WireFormat::MessagePack).contains("features = [\"guest\"]"));
+        assert!(render_cargo_file(WireFormat::Json).contains("features = [\"guest\", \"json\"]"));
+        assert!(render_cargo_file(WireFormat::Postcard)
+            .contains("features = [\"guest\", \"postcard\"]"));
+    }
+}
@@ -1,44 +1,81 @@
 use crate::{
-    functions::{Function, FunctionArg, FunctionList},
+    functions::{Function, FunctionArg, FunctionList, DEFAULT_IMPORT_MODULE},
     generators::rust_plugin::{
-        format_doc_lines, format_ident, format_modifiers, generate_type_bindings,
+        format_arg_ident, format_doc_lines, format_ident, format_modifiers, generate_type_bindings,
+        TypeBindingsTarget,
     },
-    types::{TypeIdent, TypeMap},
+    generators::GeneratedFiles,
+    protocol::{ProtocolVersion, WireFormat},
+    types::{Type, TypeIdent, TypeMap},
 };
-use std::fs;
+use std::collections::{BTreeMap, BTreeSet};
 
 pub(crate) fn generate_bindings(
     import_functions: FunctionList,
     export_functions: FunctionList,
     types: TypeMap,
+    protocol_version: &ProtocolVersion,
+    wire_format: WireFormat,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
-    fs::create_dir_all(path).expect("Could not create output directory");
-
-    generate_type_bindings(&types, path);
-
-    generate_function_bindings(import_functions, export_functions, &types, path);
+    generate_type_bindings(&types, TypeBindingsTarget::Runtime, path, files);
+
+    generate_function_bindings(
+        import_functions,
+        export_functions,
+        &types,
+        protocol_version,
+        wire_format,
+        path,
+        files,
+    );
 }
 
 fn generate_create_import_object_func(import_functions: &FunctionList) -> String {
-    let imports = import_functions
-        .iter()
-        .map(|function| {
-            let name = &function.name;
+    let mut namespaces = BTreeMap::<&str, Vec<String>>::new();
+    namespaces.insert(
+        DEFAULT_IMPORT_MODULE,
+        vec![
+            "\"__fp_host_invoke_callback\" => Function::new_native_with_env(store, env.clone(), invoke_callback),"
+                .to_owned(),
+            "\"__fp_host_release_handle\" => Function::new_native_with_env(store, env.clone(), release_handle),"
+                .to_owned(),
+            "\"__fp_host_resolve_async_value\" => Function::new_native_with_env(store, env.clone(), resolve_async_value),"
+                .to_owned(),
+            "\"__fp_host_retain_handle\" => Function::new_native_with_env(store, env.clone(), retain_handle),"
+                .to_owned(),
+            "\"__fp_host_subscribe\" => Function::new_native_with_env(store, env.clone(), subscribe),"
+                .to_owned(),
+            "\"__fp_host_unsubscribe\" => Function::new_native_with_env(store, env.clone(), unsubscribe),"
+                .to_owned(),
+        ],
+    );
+    for function in import_functions {
+        let name = &function.name;
+        namespaces
+            .entry(function.import_module())
+            .or_default()
+            .push(format!(
+            "\"__fp_gen_{name}\" => Function::new_native_with_env(store, env.clone(), _{name}),"
+        ));
+    }
+
+    let namespaces = namespaces
+        .into_iter()
+        .map(|(module, imports)| {
             format!(
-                "\"__fp_gen_{name}\" => Function::new_native_with_env(store, env.clone(), _{name}),"
+                "\"{module}\" => {{\n            {}\n        }}",
+                imports.join("\n            ")
             )
         })
         .collect::<Vec<_>>()
-        .join("\n            ");
+        .join(",\n        ");
 
     format!(
         r#"fn create_import_object(store: &Store, env: &RuntimeInstanceData) -> ImportObject {{
     imports! {{
-        "fp" => {{
-            "__fp_host_resolve_async_value" => Function::new_native_with_env(store, env.clone(), resolve_async_value),
-            {imports}
-        }}
+        {namespaces}
     }}
 }}"#
     )
@@ -90,13 +127,13 @@ pub(crate) fn generate_export_function_variables<'a>(
     let args = function
         .args
         .iter()
-        .map(|FunctionArg { name, ty }| format!(", {name}: {}", format_ident(ty, types)))
+        .map(|arg| format!(", {}: {}", arg.name, format_arg_ident(arg, types)))
         .collect::<Vec<_>>()
         .join("");
     let raw_args = function
         .args
         .iter()
-        .map(|FunctionArg { name, ty }| format!(", {name}: {}", format_raw_ident(ty, types)))
+        .map(|FunctionArg { name, ty, .. }| format!(", {name}: {}", format_raw_ident(ty, types)))
         .collect::<Vec<_>>()
         .join("");
     let wasm_args = function
@@ -130,7 +167,9 @@ pub(crate) fn generate_export_function_variables<'a>(
         .args
         .iter()
         .filter(|arg| !arg.ty.is_primitive())
-        .map(|FunctionArg { name, .. }| format!("let {name} = serialize_to_vec(&{name});"))
+        .map(|FunctionArg { name, .. }| {
+            format!("let {name} = serialize_for_guest(&self.env, &{name})?;")
+        })
         .collect::<Vec<_>>()
         .join("\n");
     let serialize_raw_args = function
@@ -158,8 +197,8 @@ pub(crate) fn generate_export_function_variables<'a>(
 
     let (raw_return_wrapper, return_wrapper) = if function.is_async {
         (
-            "let result = ModuleRawFuture::new(self.env.clone(), result).await;".to_string(),
-            "let result = result.await;\nlet result = result.map(|ref data| deserialize_from_slice(data));".to_string(),
+            "let result = ModuleRawFuture::new(self.env.clone(), result).await?;".to_string(),
+            "let result = result.await;\nlet result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));".to_string(),
         )
     } else if !function
         .return_type
@@ -168,8 +207,9 @@ pub(crate) fn generate_export_function_variables<'a>(
         .unwrap_or(true)
     {
         (
-            "let result = import_from_guest_raw(&self.env, result);".to_string(),
-            "let result = result.map(|ref data| deserialize_from_slice(data));".to_string(),
+            "let result = import_from_guest_raw(&self.env, result)?;".to_string(),
+            "let result = result.and_then(|ref data| deserialize_from_guest(&self.env, data));"
+                .to_string(),
         )
     } else {
         (
@@ -233,28 +273,284 @@ pub {modifiers}fn {name}_raw(&self{raw_args}) -> Result<{raw_return_type}, Invoc
     )
 }
 
+/// Generates the `emit_<name>()` functions for an event, which only invoke
+/// the plugin if it has subscribed to the event.
+pub(crate) fn format_emit_function(event: &Function, types: &TypeMap) -> String {
+    let doc = if event.doc_lines.is_empty() {
+        String::new()
+    } else {
+        format!("///\n{}", format_doc_lines(&event.doc_lines))
+    };
+    let name = &event.name;
+    let (arg_name, arg_type) = event
+        .args
+        .first()
+        .map(|arg| (arg.name.as_str(), format_arg_ident(arg, types)))
+        .unwrap_or(("event", "()".to_owned()));
+
+    format!(
+        r#"/// Emits the `{name}` event to the plugin, if it has subscribed to it.
+{doc}pub async fn emit_{name}(&self, {arg_name}: {arg_type}) -> Result<(), InvocationError> {{
+    if !self.env.is_subscribed("{name}") {{
+        return Ok(());
+    }}
+    let {arg_name} = serialize_for_guest(&self.env, &{arg_name})?;
+    self.emit_{name}_raw({arg_name}).await
+}}
+pub async fn emit_{name}_raw(&self, {arg_name}: Vec<u8>) -> Result<(), InvocationError> {{
+    if !self.env.is_subscribed("{name}") {{
+        return Ok(());
+    }}
+    let {arg_name} = export_to_guest_raw(&self.env, {arg_name});
+    let function = self.instance
+        .exports
+        .get_native_function::<FatPtr, FatPtr>("__fp_gen_{name}")
+        .map_err(|_| InvocationError::FunctionNotExported("__fp_gen_{name}".to_owned()))?;
+    let result = function.call({arg_name}.to_abi())?;
+    ModuleRawFuture::new(self.env.clone(), result).await?;
+    Ok(())
+}}"#
+    )
+}
+
+/// Generates `implemented_exports()` and a `has_<name>()` helper for every
+/// export, which allow hosts to detect which exports a plugin implements.
+pub(crate) fn format_export_detection_functions(export_functions: &FunctionList) -> String {
+    // Events are not implemented by plugins themselves, so they're left out.
+    let export_functions = export_functions
+        .iter()
+        .filter(|function| !function.is_event)
+        .collect::<Vec<_>>();
+    let export_names = export_functions
+        .iter()
+        .map(|function| format!("\"{}\",", function.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let has_functions = export_functions
+        .iter()
+        .map(|function| {
+            let name = &function.name;
+            format!(
+                r#"/// Returns whether the plugin implements the `{name}` export.
+pub fn has_{name}(&self) -> bool {{
+    self.has_export("{name}")
+}}"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        r#"/// Returns the names of all the exports implemented by the plugin.
+pub fn implemented_exports(&self) -> Vec<&'static str> {{
+    [{export_names}]
+        .iter()
+        .copied()
+        .filter(|name| self.has_export(name))
+        .collect()
+}}
+
+{has_functions}
+
+fn has_export(&self, name: &str) -> bool {{
+    self.instance
+        .exports
+        .get_function(&format!("__fp_gen_{{name}}"))
+        .is_ok()
+}}"#
+    )
+}
+
 pub(crate) fn format_import_arg(name: &str, ty: &TypeIdent, types: &TypeMap) -> String {
     if ty.is_primitive() {
         format!("let {name} = WasmAbi::from_abi({name});")
     } else {
         let ty = format_ident(ty, types);
-        format!("let {name} = import_from_guest::<{ty}>(env, {name});")
+        format!("let {name} = import_from_guest::<{ty}>(env, {name})?;")
+    }
+}
+
+/// Imports that have an export of the same name, split by whether they can be
+/// linked to that export.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct LinkableImports<'a> {
+    pub linkable: BTreeSet<&'a str>,
+
+    /// Imports that cannot be linked, with the reason why.
+    pub unlinkable: BTreeMap<&'a str, &'static str>,
+}
+
+/// Determines which imports can be linked to the export of the same name in
+/// another plugin.
+///
+/// Linked calls pass the raw MessagePack between the plugins, so only
+/// synchronous functions with identical signatures can be linked, and only if
+/// their arguments and return value don't contain handles or callbacks, which
+/// are specific to a single plugin instance.
+pub(crate) fn linkable_imports<'a>(
+    import_functions: &'a FunctionList,
+    export_functions: &FunctionList,
+    types: &TypeMap,
+) -> LinkableImports<'a> {
+    let mut imports = LinkableImports::default();
+    for import in import_functions {
+        let Some(export) = export_functions
+            .iter()
+            .find(|export| export.name == import.name)
+        else {
+            continue;
+        };
+
+        match link_obstacle(import, export, types) {
+            Some(reason) => {
+                imports.unlinkable.insert(&import.name, reason);
+            }
+            None => {
+                imports.linkable.insert(&import.name);
+            }
+        }
+    }
+    imports
+}
+
+/// Returns why the given import cannot be linked to the given export, if it
+/// cannot.
+fn link_obstacle(import: &Function, export: &Function, types: &TypeMap) -> Option<&'static str> {
+    let args = |function: &Function| {
+        function
+            .args
+            .iter()
+            .map(|arg| arg.ty.to_string())
+            .collect::<Vec<_>>()
+    };
+    let return_type = |function: &Function| function.return_type.as_ref().map(ToString::to_string);
+
+    if import.is_async || export.is_async {
+        Some("the import or the export is async")
+    } else if export.is_event {
+        Some("the export is an event")
+    } else if args(import) != args(export) || return_type(import) != return_type(export) {
+        Some("the signatures of the import and the export differ")
+    } else if import
+        .args
+        .iter()
+        .map(|arg| &arg.ty)
+        .chain(&import.return_type)
+        .any(|ty| contains_instance_references(ty, types, &mut BTreeSet::new()))
+    {
+        Some("its arguments or return value may contain handles or callbacks")
+    } else {
+        None
+    }
+}
+
+/// Returns whether values of the given type may contain handles or callbacks.
+fn contains_instance_references(
+    ty: &TypeIdent,
+    types: &TypeMap,
+    visited: &mut BTreeSet<String>,
+) -> bool {
+    if !visited.insert(ty.to_string()) {
+        return false;
+    }
+
+    let mut contains = |ty: &TypeIdent| contains_instance_references(ty, types, visited);
+    if ty.generic_args.iter().any(|(arg, _)| contains(arg)) {
+        return true;
+    }
+
+    match types.get(ty) {
+        Some(Type::Callback(_, _)) => true,
+        Some(Type::Container(name, _)) if name == "Handle" => true,
+        Some(Type::Alias(_, target)) => contains(target),
+        Some(Type::Struct(ty)) => ty.fields.iter().any(|field| contains(&field.ty)),
+        Some(Type::Enum(ty)) => ty.variants.iter().any(|variant| match &variant.ty {
+            Type::Struct(ty) => ty.fields.iter().any(|field| contains(&field.ty)),
+            Type::Tuple(items) => items.iter().any(&mut contains),
+            _ => false,
+        }),
+        Some(Type::Tuple(items)) => items.iter().any(contains),
+        _ => false,
     }
 }
 
-pub(crate) fn format_import_function(function: &Function, types: &TypeMap) -> String {
+/// Generates `link()` and `unlink()`, which allow hosts to forward calls to
+/// imports to another plugin that implements them as exports.
+pub(crate) fn format_link_functions(imports: &LinkableImports) -> String {
+    if imports.linkable.is_empty() {
+        return String::new();
+    }
+
+    let import_names = imports
+        .linkable
+        .iter()
+        .map(|name| format!("\"{name}\","))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let unlinkable_imports_doc = if imports.unlinkable.is_empty() {
+        String::new()
+    } else {
+        let list = imports
+            .unlinkable
+            .iter()
+            .map(|(name, reason)| format!("/// - `{name}`: {reason}.\n"))
+            .collect::<String>();
+        format!(
+            "///\n/// The following imports are exported by plugins as well, but cannot be\n\
+                /// linked, so they are always handled by the host:\n///\n{list}"
+        )
+    };
+
+    format!(
+        r#"/// Links the imports of this plugin to the exports of the `provider`
+/// plugin, so that calls to imports the provider implements as exports are
+/// forwarded to it, instead of being handled by the host. Errors that occur
+/// in the provider are reported as [InvocationError::LinkedPlugin], with the
+/// given `name` identifying the provider.
+///
+/// Returns the names of the imports that were linked. Fails if the provider
+/// calls this plugin itself, directly or through other linked plugins.
+{unlinkable_imports_doc}pub fn link(&self, name: &str, provider: &Runtime) -> Result<Vec<&'static str>, LinkError> {{
+    let imports: Vec<&'static str> = [{import_names}]
+        .iter()
+        .copied()
+        .filter(|import| provider.has_export(import))
+        .collect();
+    link_plugin(&self.env, name, Arc::new(provider.clone()), &provider.env, &imports)?;
+    Ok(imports)
+}}
+
+/// Removes all the links created using [Runtime::link()], so that all
+/// imports are handled by the host again.
+pub fn unlink(&self) {{
+    unlink_plugins(&self.env);
+}}"#
+    )
+}
+
+pub(crate) fn format_import_function(
+    function: &Function,
+    types: &TypeMap,
+    linkable: bool,
+) -> String {
     let name = &function.name;
+    // Implementations of functions in named import modules are expected in a
+    // Rust module of the same name:
+    let implementation = match &function.import_module {
+        Some(module) => format!("super::{module}::{name}"),
+        None => format!("super::{name}"),
+    };
     let wasm_args = function
         .args
         .iter()
-        .map(|FunctionArg { name, ty }| format!(", {name}: {}", format_wasm_ident(ty)))
+        .map(|FunctionArg { name, ty, .. }| format!(", {name}: {}", format_wasm_ident(ty)))
         .collect::<Vec<_>>()
         .join("");
 
     let wrapper_return_type = match (function.is_async, &function.return_type) {
-        (true, _) => " -> FatPtr".to_owned(),
-        (false, Some(ty)) => format!(" -> {}", format_wasm_ident(ty)),
-        (false, None) => "".to_owned(),
+        (true, _) => "FatPtr".to_owned(),
+        (false, Some(ty)) => format_wasm_ident(ty),
+        (false, None) => "()".to_owned(),
     };
 
     let import_args = function
@@ -264,6 +560,15 @@ pub(crate) fn format_import_function(function: &Function, types: &TypeMap) -> St
         .collect::<Vec<_>>()
         .join("\n");
 
+    if linkable {
+        return format_linkable_import_function(
+            function,
+            &implementation,
+            &wasm_args,
+            &import_args,
+        );
+    }
+
     let arg_names = function
         .args
         .iter()
@@ -277,22 +582,93 @@ pub(crate) fn format_import_function(function: &Function, types: &TypeMap) -> St
     let async_ptr = create_future_value(&env);
     let handle = tokio::runtime::Handle::current();
     handle.spawn(async move {{
-        let result = super::{name}({arg_names}).await;
-        let result_ptr = export_to_guest(&env, &result);
+        let result = {implementation}({arg_names}).await;
+        // The plugin is waiting for the result, so there is no way to report
+        // the failure to it:
+        let result_ptr = export_to_guest(&env, &result).unwrap_or_else(|error| {{
+            panic!("Could not pass the result of `{name}` to the plugin: {{error}}")
+        }});
         env.guest_resolve_async_value(async_ptr, result_ptr);
     }});
-    async_ptr"#
+    Ok(async_ptr)"#
         )
     } else {
         match &function.return_type {
-            None => format!("super::{name}({arg_names})"),
-            Some(ty) if ty.is_primitive() => format!("super::{name}({arg_names}).to_abi()"),
-            _ => format!("export_to_guest(env, &super::{name}({arg_names}))"),
+            None => format!("{implementation}({arg_names});\n    Ok(())"),
+            Some(ty) if ty.is_primitive() => format!("Ok({implementation}({arg_names}).to_abi())"),
+            _ => format!("export_to_guest(env, &{implementation}({arg_names}))"),
         }
     };
 
     format!(
-        r#"pub fn _{name}(env: &RuntimeInstanceData{wasm_args}){wrapper_return_type} {{
+        r#"pub fn _{name}(env: &RuntimeInstanceData{wasm_args}) -> Result<{wrapper_return_type}, InvocationError> {{
+    {import_args}
+    {return_wrapper}
+}}"#
+    )
+}
+
+/// Formats the wrapper of an import that may be linked to another plugin, in
+/// which case calls are forwarded to the other plugin without deserializing
+/// their arguments and return value.
+fn format_linkable_import_function(
+    function: &Function,
+    implementation: &str,
+    wasm_args: &str,
+    import_args: &str,
+) -> String {
+    let name = &function.name;
+    let primitive_args = function
+        .args
+        .iter()
+        .filter(|arg| arg.ty.is_primitive())
+        .map(|arg| format!("let {0} = WasmAbi::from_abi({0});", arg.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let raw_args = function
+        .args
+        .iter()
+        .filter(|arg| !arg.ty.is_primitive())
+        .map(|arg| format!("let {0} = import_from_guest_raw(env, {0})?;", arg.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let arg_names = function
+        .args
+        .iter()
+        .map(|arg| arg.name.as_ref())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let (wrapper_return_type, raw_return_wrapper, return_wrapper) = match &function.return_type {
+        None => (
+            "()".to_owned(),
+            "Ok(result)".to_owned(),
+            format!("{implementation}({arg_names});\n    Ok(())"),
+        ),
+        Some(ty) if ty.is_primitive() => (
+            format_wasm_ident(ty),
+            "Ok(result.to_abi())".to_owned(),
+            format!("Ok({implementation}({arg_names}).to_abi())"),
+        ),
+        Some(_) => (
+            "FatPtr".to_owned(),
+            "Ok(export_to_guest_raw(env, result))".to_owned(),
+            format!("export_to_guest(env, &{implementation}({arg_names}))"),
+        ),
+    };
+
+    format!(
+        r#"pub fn _{name}(env: &RuntimeInstanceData{wasm_args}) -> Result<{wrapper_return_type}, InvocationError> {{
+    if let Some(link) = env.linked_plugin("{name}") {{
+        {primitive_args}
+        {raw_args}
+        let result = link
+            .provider::<Runtime>()
+            .{name}_raw({arg_names})
+            .map_err(|error| link.error("{name}", error))?;
+        return {raw_return_wrapper};
+    }}
+
     {import_args}
     {return_wrapper}
 }}"#
@@ -303,18 +679,36 @@ fn generate_function_bindings(
     import_functions: FunctionList,
     export_functions: FunctionList,
     types: &TypeMap,
+    protocol_version: &ProtocolVersion,
+    wire_format: WireFormat,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
+    let linkable_imports = linkable_imports(&import_functions, &export_functions, types);
     let imports = import_functions
         .iter()
-        .map(|function| format_import_function(function, types))
+        .map(|function| {
+            format_import_function(
+                function,
+                types,
+                linkable_imports.linkable.contains(function.name.as_str()),
+            )
+        })
         .collect::<Vec<_>>()
         .join("\n\n");
     let exports = export_functions
         .iter()
-        .map(|function| format_export_function(function, types))
+        .map(|function| {
+            if function.is_event {
+                format_emit_function(function, types)
+            } else {
+                format_export_function(function, types)
+            }
+        })
         .collect::<Vec<_>>()
         .join("\n\n");
+    let export_detection_funcs = format_export_detection_functions(&export_functions);
+    let link_funcs = format_link_functions(&linkable_imports);
     let new_func = r#"pub fn new(wasm_module: impl AsRef<[u8]>) -> Result<Self, RuntimeError> {
         let store = Self::default_store();
         let module = Module::new(&store, wasm_module)?;
@@ -322,35 +716,67 @@ fn generate_function_bindings(
         let import_object = create_import_object(module.store(), &env);
         let instance = Instance::new(&module, &import_object).unwrap();
         env.init_with_instance(&instance).unwrap();
+        check_protocol_version(&instance, &env, &Self::protocol_version())?;
+        negotiate_wire_format(&instance, &env, Self::PREFERRED_WIRE_FORMAT)?;
         Ok(Self { instance, env })
     }"#
     .to_string();
     let create_import_object_func = generate_create_import_object_func(&import_functions);
 
-    write_bindings_file(
+    files.insert(
         format!("{path}/bindings.rs"),
-        format_function_bindings(imports, exports, new_func, create_import_object_func),
+        format_function_bindings(
+            imports,
+            exports,
+            export_detection_funcs,
+            link_funcs,
+            new_func,
+            create_import_object_func,
+            protocol_version,
+            wire_format,
+        ),
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn format_function_bindings(
     imports: String,
     exports: String,
+    export_detection_funcs: String,
+    link_funcs: String,
     new_func: String,
     create_import_object_func: String,
+    protocol_version: &ProtocolVersion,
+    wire_format: WireFormat,
 ) -> String {
+    let ProtocolVersion {
+        name,
+        version,
+        hash,
+    } = protocol_version;
+    let wire_format = match wire_format {
+        WireFormat::MessagePack => "MessagePack",
+        WireFormat::Json => "Json",
+        WireFormat::Postcard => "Postcard",
+    };
     rustfmt_wrapper::rustfmt(format!(r#"#![allow(clippy::let_and_return, unused)]
 use super::types::*;
 use fp_bindgen_support::{{
-    common::{{mem::FatPtr, abi::WasmAbi}},
+    common::{{mem::FatPtr, abi::WasmAbi, format::WireFormat, protocol::ProtocolVersion}},
     wasmer2_host::{{
         errors::{{InvocationError, RuntimeError}},
-        mem::{{export_to_guest, export_to_guest_raw, import_from_guest, import_from_guest_raw, deserialize_from_slice, serialize_to_vec}},
+        callback::invoke_callback,
+        events::{{subscribe, unsubscribe}},
+        handle::{{release_handle, retain_handle}},
+        link::{{link_plugin, unlink_plugins, LinkError}},
+        mem::{{export_to_guest, export_to_guest_raw, import_from_guest, import_from_guest_raw, deserialize_from_guest, serialize_for_guest}},
+        protocol::{{check_protocol_version, negotiate_wire_format}},
         r#async::{{create_future_value, future::ModuleRawFuture, resolve_async_value}},
         runtime::RuntimeInstanceData,
     }},
 }};
 use std::cell::RefCell;
+use std::sync::Arc;
 use wasmer::{{imports, Function, ImportObject, Instance, Module, Store, WasmerEnv}};
 
 #[derive(Clone)]
@@ -360,8 +786,26 @@ pub struct Runtime {{
 }}
 
 impl Runtime {{
+    /// Wire format the runtime asks the plugin to use.
+    const PREFERRED_WIRE_FORMAT: WireFormat = WireFormat::{wire_format};
+
     {new_func}
 
+    /// Returns the version of the protocol this runtime was generated for.
+    pub fn protocol_version() -> ProtocolVersion {{
+        ProtocolVersion {{
+            name: {name:?}.to_owned(),
+            version: {version:?}.to_owned(),
+            hash: {hash:?}.to_owned(),
+        }}
+    }}
+
+    /// Returns the format in which values are passed to and from the plugin,
+    /// as negotiated when the plugin was loaded.
+    pub fn wire_format(&self) -> WireFormat {{
+        self.env.wire_format()
+    }}
+
     #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
     fn default_store() -> wasmer::Store {{
         let compiler = wasmer::Cranelift::default();
@@ -376,6 +820,10 @@ impl Runtime {{
         Store::new(&engine)
     }}
 
+    {export_detection_funcs}
+
+    {link_funcs}
+
     {exports}
 }}
 
@@ -386,9 +834,57 @@ impl Runtime {{
     .unwrap()
 }
 
-pub(crate) fn write_bindings_file<C>(file_path: String, contents: C)
-where
-    C: AsRef<[u8]>,
-{
-    fs::write(file_path, &contents).expect("Could not write bindings file");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::Callback, Serializable};
+
+    #[test]
+    fn links_imports_with_matching_exports() {
+        let mut imports = FunctionList::new();
+        let mut exports = FunctionList::new();
+        for decl in [
+            "fn next_item(cursor: String) -> Option<String>;",
+            "fn count(items: Vec<String>) -> u32;",
+            "async fn fetch(url: String) -> String;",
+            "fn on_item(handler: Callback<String, ()>);",
+            "fn import_only();",
+        ] {
+            imports.add_function(decl);
+        }
+        for decl in [
+            "fn next_item(cursor: String) -> Option<String>;",
+            "fn count(items: Vec<u32>) -> u32;",
+            "async fn fetch(url: String) -> String;",
+            "fn on_item(handler: Callback<String, ()>);",
+            "fn export_only();",
+        ] {
+            exports.add_function(decl);
+        }
+
+        let mut types = TypeMap::new();
+        Callback::<String, ()>::collect_types(&mut types);
+
+        let imports = linkable_imports(&imports, &exports, &types);
+        assert_eq!(
+            imports,
+            LinkableImports {
+                linkable: BTreeSet::from(["next_item"]),
+                unlinkable: BTreeMap::from([
+                    (
+                        "count",
+                        "the signatures of the import and the export differ"
+                    ),
+                    ("fetch", "the import or the export is async"),
+                    (
+                        "on_item",
+                        "its arguments or return value may contain handles or callbacks"
+                    ),
+                ]),
+            }
+        );
+        assert!(format_link_functions(&imports)
+            .contains("/// - `fetch`: the import or the export is async.\n"));
+        assert!(format_link_functions(&LinkableImports::default()).is_empty());
+    }
 }
@@ -1,57 +1,101 @@
 use crate::{
-    functions::{Function, FunctionList},
+    functions::{Function, FunctionList, DEFAULT_IMPORT_MODULE},
     generators::{
-        rust_plugin::generate_type_bindings,
+        rust_plugin::{generate_type_bindings, TypeBindingsTarget},
         rust_wasmer2_runtime::{
-            format_function_bindings, format_import_function, generate_export_function_variables,
-            write_bindings_file, ExportFunctionVariables,
+            format_emit_function, format_export_detection_functions, format_function_bindings,
+            format_import_function, format_link_functions, generate_export_function_variables,
+            linkable_imports, ExportFunctionVariables,
         },
+        GeneratedFiles,
     },
+    protocol::{ProtocolVersion, WireFormat},
     types::TypeMap,
 };
-use std::fs;
+use std::collections::BTreeMap;
 
 pub(crate) fn generate_bindings(
     import_functions: FunctionList,
     export_functions: FunctionList,
     types: TypeMap,
+    protocol_version: &ProtocolVersion,
+    wire_format: WireFormat,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
-    fs::create_dir_all(path).expect("Could not create output directory");
+    generate_type_bindings(&types, TypeBindingsTarget::Runtime, path, files);
 
-    generate_type_bindings(&types, path);
-
-    generate_function_bindings(import_functions, export_functions, &types, path);
+    generate_function_bindings(
+        import_functions,
+        export_functions,
+        &types,
+        protocol_version,
+        wire_format,
+        path,
+        files,
+    );
 }
 
 fn generate_create_import_object_func(import_functions: &FunctionList) -> String {
-    let imports = import_functions
-        .iter()
-        .map(|function| {
-            let name = &function.name;
+    let mut namespaces = BTreeMap::<&str, Vec<String>>::new();
+    namespaces.insert(
+        DEFAULT_IMPORT_MODULE,
+        vec![
+            format_namespace_insert("__fp_host_invoke_callback", "invoke_callback"),
+            format_namespace_insert("__fp_host_release_handle", "release_handle"),
+            format_namespace_insert("__fp_host_resolve_async_value", "resolve_async_value"),
+            format_namespace_insert("__fp_host_retain_handle", "retain_handle"),
+            format_namespace_insert("__fp_host_subscribe", "subscribe"),
+            format_namespace_insert("__fp_host_unsubscribe", "unsubscribe"),
+        ],
+    );
+    for function in import_functions {
+        let name = &function.name;
+        namespaces
+            .entry(function.import_module())
+            .or_default()
+            .push(format_namespace_insert(
+                &format!("__fp_gen_{name}"),
+                &format!("_{name}"),
+            ));
+    }
+
+    let namespaces = namespaces
+        .into_iter()
+        .map(|(module, imports)| {
             format!(
-                r#"namespace.insert(
-            "__fp_gen_{name}",
-            Function::new_native_with_env(store, env.clone(), _{name})
-    );"#
+                r#"let mut namespace = wasmer::Exports::new();
+    {}
+    namespaces.push(("{module}", namespace));"#,
+                imports.join("\n    ")
             )
         })
         .collect::<Vec<_>>()
-        .join("\n    ");
+        .join("\n\n    ");
 
     format!(
-        r#"fn create_import_object(store: &Store, env: &RuntimeInstanceData) -> wasmer::Exports {{
-    let mut namespace = wasmer::Exports::new();
-    namespace.insert(
-            "__fp_host_resolve_async_value",
-            Function::new_native_with_env(store, env.clone(), resolve_async_value)
-    );
-    {imports}
-    namespace
+        r#"fn create_import_namespaces(
+    store: &Store,
+    env: &RuntimeInstanceData,
+) -> Vec<(&'static str, wasmer::Exports)> {{
+    let mut namespaces = Vec::new();
+
+    {namespaces}
+
+    namespaces
 }}"#
     )
 }
 
+fn format_namespace_insert(name: &str, function: &str) -> String {
+    format!(
+        r#"namespace.insert(
+            "{name}",
+            Function::new_native_with_env(store, env.clone(), {function})
+    );"#
+    )
+}
+
 fn format_export_function(function: &Function, types: &TypeMap) -> String {
     let ExportFunctionVariables {
         doc,
@@ -92,35 +136,344 @@ fn generate_function_bindings(
     import_functions: FunctionList,
     export_functions: FunctionList,
     types: &TypeMap,
+    protocol_version: &ProtocolVersion,
+    wire_format: WireFormat,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
+    let linkable_imports = linkable_imports(&import_functions, &export_functions, types);
     let imports = import_functions
         .iter()
-        .map(|function| format_import_function(function, types))
+        .map(|function| {
+            format_import_function(
+                function,
+                types,
+                linkable_imports.linkable.contains(function.name.as_str()),
+            )
+        })
         .collect::<Vec<_>>()
         .join("\n\n");
     let exports = export_functions
         .iter()
-        .map(|function| format_export_function(function, types))
+        .map(|function| {
+            if function.is_event {
+                format_emit_function(function, types)
+            } else {
+                format_export_function(function, types)
+            }
+        })
         .collect::<Vec<_>>()
         .join("\n\n");
+    let export_detection_funcs = format_export_detection_functions(&export_functions);
+    let link_funcs = format_link_functions(&linkable_imports);
     let new_func = r#"pub fn new(wasm_module: impl AsRef<[u8]>) -> Result<Self, RuntimeError> {
+        Self::with_wasi_config(wasm_module, WasiConfig::default())
+    }
+
+    /// Creates a runtime in which the plugin runs in the given WASI environment.
+    pub fn with_wasi_config(
+        wasm_module: impl AsRef<[u8]>,
+        wasi_config: WasiConfig,
+    ) -> Result<Self, RuntimeError> {
         let store = Self::default_store();
         let module = Module::new(&store, wasm_module)?;
         let mut env = RuntimeInstanceData::default();
-        let mut wasi_env = wasmer_wasi::WasiState::new("fp").finalize().unwrap();
+        let mut wasi_env = wasi_config
+            .finalize()
+            .map_err(|error| RuntimeError::WasiSetup(error.to_string()))?;
         let mut import_object = wasi_env.import_object(&module).unwrap();
-        let namespace = create_import_object(module.store(), &env);
-        import_object.register("fp", namespace);
+        for (name, namespace) in create_import_namespaces(module.store(), &env) {
+            import_object.register(name, namespace);
+        }
         let instance = Instance::new(&module, &import_object).unwrap();
         env.init_with_instance(&instance).unwrap();
+        check_protocol_version(&instance, &env, &Self::protocol_version())?;
+        negotiate_wire_format(&instance, &env, Self::PREFERRED_WIRE_FORMAT)?;
         Ok(Self { instance, env })
     }"#
     .to_string();
     let create_import_object_func = generate_create_import_object_func(&import_functions);
 
-    write_bindings_file(
+    files.insert(
         format!("{path}/bindings.rs"),
-        format_function_bindings(imports, exports, new_func, create_import_object_func),
+        format_function_bindings(
+            imports,
+            exports,
+            export_detection_funcs,
+            link_funcs,
+            new_func,
+            format!("{create_import_object_func}\n\n{WASI_CONFIG}"),
+            protocol_version,
+            wire_format,
+        ),
     );
 }
+
+/// Configuration of the WASI environment, which is included in the bindings
+/// so runtimes don't need to set up `wasmer-wasi` themselves.
+const WASI_CONFIG: &str = r#"/// Configures the WASI environment in which a plugin runs.
+///
+/// By default, plugins run without any arguments, environment variables or
+/// access to the file system, while their output to stdout and stderr is
+/// forwarded to that of the host process.
+#[derive(Debug, Default)]
+pub struct WasiConfig {
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    mapped_dirs: Vec<MappedDir>,
+    stdout: Option<OutputCallback>,
+    stderr: Option<OutputCallback>,
+}
+
+impl WasiConfig {
+    /// Returns a new config for an empty WASI environment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a command-line argument for the plugin.
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Adds an environment variable for the plugin.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Makes the directory at `host_path` available to the plugin as `alias`,
+    /// with read and write access.
+    pub fn map_dir(mut self, alias: impl Into<String>, host_path: impl Into<std::path::PathBuf>) -> Self {
+        self.mapped_dirs.push(MappedDir {
+            alias: alias.into(),
+            host_path: host_path.into(),
+            read_only: false,
+        });
+        self
+    }
+
+    /// Makes the directory at `host_path` available to the plugin as `alias`,
+    /// with read access only.
+    pub fn map_dir_read_only(mut self, alias: impl Into<String>, host_path: impl Into<std::path::PathBuf>) -> Self {
+        self.mapped_dirs.push(MappedDir {
+            alias: alias.into(),
+            host_path: host_path.into(),
+            read_only: true,
+        });
+        self
+    }
+
+    /// Passes everything the plugin writes to stdout on to the given callback.
+    pub fn stdout(mut self, callback: impl FnMut(&[u8]) + Send + Sync + 'static) -> Self {
+        self.stdout = Some(OutputCallback(Box::new(callback)));
+        self
+    }
+
+    /// Passes everything the plugin writes to stderr on to the given callback.
+    pub fn stderr(mut self, callback: impl FnMut(&[u8]) + Send + Sync + 'static) -> Self {
+        self.stderr = Some(OutputCallback(Box::new(callback)));
+        self
+    }
+
+    fn finalize(self) -> Result<wasmer_wasi::WasiEnv, wasmer_wasi::WasiStateCreationError> {
+        let mut state = wasmer_wasi::WasiState::new("fp");
+        state.args(&self.args).envs(self.envs);
+        let read_only_dirs = self
+            .mapped_dirs
+            .iter()
+            .filter(|dir| dir.read_only)
+            .map(|dir| dir.host_path.clone())
+            .collect::<Vec<_>>();
+        state.set_fs(Box::new(HostFileSystem {
+            read_only_dirs: std::sync::Arc::new(read_only_dirs),
+        }));
+        for dir in self.mapped_dirs {
+            state.preopen(|preopen| {
+                preopen
+                    .directory(&dir.host_path)
+                    .alias(&dir.alias)
+                    .read(true)
+                    .write(!dir.read_only)
+                    .create(!dir.read_only)
+            })?;
+        }
+        if let Some(stdout) = self.stdout {
+            state.stdout(Box::new(stdout));
+        }
+        if let Some(stderr) = self.stderr {
+            state.stderr(Box::new(stderr));
+        }
+        state.finalize()
+    }
+}
+
+/// Collects the output of a plugin in memory.
+///
+/// Use [OutputBuffer::writer()] to create a callback for
+/// [WasiConfig::stdout()] or [WasiConfig::stderr()].
+#[derive(Clone, Debug, Default)]
+pub struct OutputBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl OutputBuffer {
+    /// Returns a new, empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a callback that appends everything passed to it to the buffer.
+    pub fn writer(&self) -> impl FnMut(&[u8]) + Send + Sync + 'static {
+        let buffer = self.0.clone();
+        move |bytes| buffer.lock().unwrap().extend_from_slice(bytes)
+    }
+
+    /// Returns the output collected so far, and clears the buffer.
+    pub fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+#[derive(Debug)]
+struct MappedDir {
+    alias: String,
+    host_path: std::path::PathBuf,
+    read_only: bool,
+}
+
+/// Gives plugins access to the file system of the host, except that nothing
+/// can be created, changed or removed inside read-only directories.
+///
+/// The rights of the pre-opened directories are not enough to guarantee this,
+/// because `wasmer-wasi` doesn't check them when a plugin creates a file.
+#[derive(Debug)]
+struct HostFileSystem {
+    read_only_dirs: std::sync::Arc<Vec<std::path::PathBuf>>,
+}
+
+impl wasmer_vfs::FileSystem for HostFileSystem {
+    fn read_dir(&self, path: &std::path::Path) -> wasmer_vfs::Result<wasmer_vfs::ReadDir> {
+        wasmer_vfs::FileSystem::read_dir(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn create_dir(&self, path: &std::path::Path) -> wasmer_vfs::Result<()> {
+        check_writable(&self.read_only_dirs, path)?;
+        wasmer_vfs::FileSystem::create_dir(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn remove_dir(&self, path: &std::path::Path) -> wasmer_vfs::Result<()> {
+        check_writable(&self.read_only_dirs, path)?;
+        wasmer_vfs::FileSystem::remove_dir(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn rename(&self, from: &std::path::Path, to: &std::path::Path) -> wasmer_vfs::Result<()> {
+        check_writable(&self.read_only_dirs, from)?;
+        check_writable(&self.read_only_dirs, to)?;
+        wasmer_vfs::FileSystem::rename(&wasmer_vfs::host_fs::FileSystem, from, to)
+    }
+
+    fn metadata(&self, path: &std::path::Path) -> wasmer_vfs::Result<wasmer_vfs::Metadata> {
+        wasmer_vfs::FileSystem::metadata(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn remove_file(&self, path: &std::path::Path) -> wasmer_vfs::Result<()> {
+        check_writable(&self.read_only_dirs, path)?;
+        wasmer_vfs::FileSystem::remove_file(&wasmer_vfs::host_fs::FileSystem, path)
+    }
+
+    fn new_open_options(&self) -> wasmer_vfs::OpenOptions {
+        wasmer_vfs::OpenOptions::new(Box::new(HostFileOpener {
+            read_only_dirs: self.read_only_dirs.clone(),
+        }))
+    }
+}
+
+struct HostFileOpener {
+    read_only_dirs: std::sync::Arc<Vec<std::path::PathBuf>>,
+}
+
+impl wasmer_vfs::FileOpener for HostFileOpener {
+    fn open(
+        &mut self,
+        path: &std::path::Path,
+        conf: &wasmer_vfs::OpenOptionsConfig,
+    ) -> wasmer_vfs::Result<Box<dyn wasmer_vfs::VirtualFile>> {
+        if conf.write() || conf.append() || conf.truncate() || conf.create() || conf.create_new() {
+            check_writable(&self.read_only_dirs, path)?;
+        }
+        wasmer_vfs::FileOpener::open(&mut wasmer_vfs::host_fs::FileOpener, path, conf)
+    }
+}
+
+fn check_writable(read_only_dirs: &[std::path::PathBuf], path: &std::path::Path) -> wasmer_vfs::Result<()> {
+    if read_only_dirs.iter().any(|dir| path.starts_with(dir)) {
+        Err(wasmer_vfs::FsError::PermissionDenied)
+    } else {
+        Ok(())
+    }
+}
+
+/// Output stream that passes everything written to it on to a callback.
+struct OutputCallback(Box<dyn FnMut(&[u8]) + Send + Sync>);
+
+impl std::fmt::Debug for OutputCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OutputCallback")
+    }
+}
+
+impl std::io::Read for OutputCallback {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(0)
+    }
+}
+
+impl std::io::Write for OutputCallback {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        (self.0)(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::io::Seek for OutputCallback {
+    fn seek(&mut self, _pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "cannot seek in an output stream",
+        ))
+    }
+}
+
+impl wasmer_wasi::VirtualFile for OutputCallback {
+    fn last_accessed(&self) -> u64 {
+        0
+    }
+
+    fn last_modified(&self) -> u64 {
+        0
+    }
+
+    fn created_time(&self) -> u64 {
+        0
+    }
+
+    fn size(&self) -> u64 {
+        0
+    }
+
+    fn set_len(&mut self, _new_size: u64) -> Result<(), wasmer_wasi::FsError> {
+        Err(wasmer_wasi::FsError::PermissionDenied)
+    }
+
+    fn unlink(&mut self) -> Result<(), wasmer_wasi::FsError> {
+        Ok(())
+    }
+
+    fn bytes_available(&self) -> Result<usize, wasmer_wasi::FsError> {
+        Ok(0)
+    }
+}"#;
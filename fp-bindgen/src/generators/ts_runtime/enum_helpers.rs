@@ -0,0 +1,292 @@
+use super::{format_ident, format_struct_fields, get_variable_name, get_variant_name, Int64Repr};
+use crate::types::{Enum, Field, Type, TypeMap, Variant};
+
+/// Formats the helpers for working with values of the given enum: a
+/// constructor function for every variant, and for enums that are not
+/// untagged, a type guard for every variant as well as a `match` helper that
+/// requires a handler for every variant.
+///
+/// `int64_repr` should be the representation used in the definition of the
+/// enum itself.
+pub(super) fn format_enum_helpers(ty: &Enum, types: &TypeMap, int64_repr: Int64Repr) -> String {
+    let helpers = EnumHelpers {
+        ty,
+        types,
+        int64_repr,
+    };
+
+    let mut functions = ty
+        .variants
+        .iter()
+        .filter_map(|variant| helpers.format_constructor(variant))
+        .collect::<Vec<_>>();
+
+    // The variant of an untagged enum cannot be determined reliably, so we
+    // only provide constructors for them:
+    if !ty.options.untagged {
+        functions.extend(
+            ty.variants
+                .iter()
+                .map(|variant| helpers.format_type_guard(variant)),
+        );
+        functions.push(helpers.format_match());
+    }
+
+    functions.join("\n\n")
+}
+
+struct EnumHelpers<'a> {
+    ty: &'a Enum,
+    types: &'a TypeMap,
+    int64_repr: Int64Repr,
+}
+
+impl EnumHelpers<'_> {
+    fn format_constructor(&self, variant: &Variant) -> Option<String> {
+        let enum_name = &self.ty.ident.name;
+        let enum_type = self.ty.ident.format(false);
+        let type_params = self.format_type_params(&[]);
+        let variant_name = get_variant_name(variant, &self.ty.options);
+        let tag = self.ty.options.tag_prop_name.as_deref();
+        let content = self.ty.options.content_prop_name.as_deref();
+
+        let (param, value) = match &variant.ty {
+            // Untagged unit variants are not supported by `Serializable`:
+            Type::Unit if self.ty.options.untagged => return None,
+            Type::Unit => {
+                let value = match tag {
+                    Some(tag) => format_object(&[(tag, &format!("{variant_name:?}"))]),
+                    None => format!("{variant_name:?}"),
+                };
+                (String::new(), value)
+            }
+            Type::Tuple(items) if items.len() == 1 => {
+                let param = format!(
+                    "value: {}",
+                    format_ident(&items[0], self.types, "", self.int64_repr)
+                );
+                let value = self.format_tagged_value(&variant_name, tag, content);
+                (param, value)
+            }
+            Type::Struct(struct_variant) => {
+                let fields_type = match (tag, content) {
+                    _ if self.ty.options.untagged => {
+                        self.format_fields_type(&struct_variant.fields, variant)
+                    }
+                    (Some(_), Some(content)) => format!(
+                        "{}[{content:?}]",
+                        self.format_variant_type(variant, &variant_name)
+                    ),
+                    (Some(tag), None) => format!(
+                        "Omit<{}, {tag:?}>",
+                        self.format_variant_type(variant, &variant_name)
+                    ),
+                    (None, _) => format!(
+                        "{}[{variant_name:?}]",
+                        self.format_variant_type(variant, &variant_name)
+                    ),
+                };
+                let param = format!("value: {fields_type}");
+                let value = self.format_tagged_value(&variant_name, tag, content);
+                (param, value)
+            }
+            other => panic!("Unsupported type for enum variant: {other:?}"),
+        };
+
+        Some(format!(
+            "/**
+ * Creates the `{}` variant of `{enum_name}`.
+ */
+export function create{enum_name}{}{type_params}({param}): {enum_type} {{
+    return {value};
+}}",
+            get_variable_name(&variant.name),
+            get_variable_name(&variant.name)
+        ))
+    }
+
+    /// Formats the value for a variant that wraps the constructor's `value`.
+    fn format_tagged_value(
+        &self,
+        variant_name: &str,
+        tag: Option<&str>,
+        content: Option<&str>,
+    ) -> String {
+        if self.ty.options.untagged {
+            return "value".to_owned();
+        }
+
+        let tag_value = format!("{variant_name:?}");
+        match (tag, content) {
+            (Some(tag), Some(content)) => format_object(&[(tag, &tag_value), (content, "value")]),
+            (Some(tag), None) => format!(
+                "{{ {}: {tag_value}, ...value }} as {}",
+                format_property_name(tag),
+                self.ty.ident.format(false)
+            ),
+            (None, _) => format_object(&[(variant_name, "value")]),
+        }
+    }
+
+    fn format_type_guard(&self, variant: &Variant) -> String {
+        let enum_name = &self.ty.ident.name;
+        let enum_type = self.ty.ident.format(false);
+        let type_params = self.format_type_params(&[]);
+        let variant_name = get_variant_name(variant, &self.ty.options);
+        let variant_type = self.format_variant_type(variant, &variant_name);
+
+        let condition = match &self.ty.options.tag_prop_name {
+            Some(tag) => format!("value{} === {variant_name:?}", format_property_access(tag)),
+            None if variant.ty == Type::Unit => format!("value === {variant_name:?}"),
+            None => format!(
+                "typeof value === \"object\" && value !== null && {variant_name:?} in value"
+            ),
+        };
+
+        format!(
+            "/**
+ * Returns whether the given value is the `{}` variant of `{enum_name}`.
+ */
+export function is{enum_name}{}{type_params}(value: {enum_type}): value is {variant_type} {{
+    return {condition};
+}}",
+            get_variable_name(&variant.name),
+            get_variable_name(&variant.name)
+        )
+    }
+
+    fn format_match(&self) -> String {
+        let enum_name = &self.ty.ident.name;
+        let enum_type = self.ty.ident.format(false);
+        let type_params = self.format_type_params(&["TResult"]);
+
+        let handler_types = self
+            .ty
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_name = get_variant_name(variant, &self.ty.options);
+                format!(
+                    "        {}: (value: {}) => TResult;\n",
+                    get_variable_name(&variant.name),
+                    self.format_variant_type(variant, &variant_name)
+                )
+            })
+            .collect::<String>();
+        let branches = self
+            .ty
+            .variants
+            .iter()
+            .map(|variant| {
+                format!(
+                    "    if (is{enum_name}{}(value)) {{
+        return handlers.{}(value);
+    }}
+",
+                    get_variable_name(&variant.name),
+                    get_variable_name(&variant.name)
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            "/**
+ * Calls the handler for the variant of the given `{enum_name}` value, and
+ * returns its result.
+ */
+export function match{enum_name}{type_params}(
+    value: {enum_type},
+    handlers: {{
+{handler_types}    }}
+): TResult {{
+{branches}    throw new Error(\"Unknown variant of {enum_name}\");
+}}"
+        )
+    }
+
+    /// Formats the type of the given variant, as narrowed from the enum type.
+    fn format_variant_type(&self, variant: &Variant, variant_name: &str) -> String {
+        let enum_type = self.ty.ident.format(false);
+        match &self.ty.options.tag_prop_name {
+            Some(tag) => format!(
+                "Extract<{enum_type}, {}>",
+                format_object(&[(tag, &format!("{variant_name:?}"))])
+            ),
+            None if variant.ty == Type::Unit => format!("Extract<{enum_type}, {variant_name:?}>"),
+            None => format!(
+                "Extract<{enum_type}, {}>",
+                format_object(&[(variant_name, "unknown")])
+            ),
+        }
+    }
+
+    /// Formats an inline object type for the fields of a struct variant.
+    fn format_fields_type(&self, fields: &[Field], variant: &Variant) -> String {
+        let fields = fields
+            .iter()
+            .cloned()
+            .map(|mut field| {
+                field.doc_lines = Vec::new();
+                field
+            })
+            .collect::<Vec<_>>();
+        let fields = format_struct_fields(
+            &fields,
+            self.types,
+            variant.attrs.field_casing,
+            self.int64_repr,
+        );
+        format!("{{ {} }}", fields.join(" ").trim_end_matches(';'))
+    }
+
+    /// Formats the generic parameters of the enum, followed by the given extra
+    /// parameters, for use in a function signature.
+    fn format_type_params(&self, extra_params: &[&str]) -> String {
+        let params = self
+            .ty
+            .ident
+            .generic_args
+            .iter()
+            .map(|(arg, _)| arg.name.as_str())
+            .chain(extra_params.iter().copied())
+            .collect::<Vec<_>>();
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        }
+    }
+}
+
+fn format_object(properties: &[(&str, &str)]) -> String {
+    let properties = properties
+        .iter()
+        .map(|(name, value)| format!("{}: {value}", format_property_name(name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{{ {properties} }}")
+}
+
+fn format_property_name(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_owned()
+    } else {
+        format!("{name:?}")
+    }
+}
+
+fn format_property_access(name: &str) -> String {
+    if is_identifier(name) {
+        format!(".{name}")
+    } else {
+        format!("[{name:?}]")
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
@@ -1,27 +1,97 @@
+mod enum_helpers;
+mod normalizers;
+mod npm_package;
+mod validators;
+mod web_worker;
+
 use crate::{
     casing::Casing,
     functions::{Function, FunctionList},
+    generators::GeneratedFiles,
     prelude::Primitive,
+    protocol::ProtocolVersion,
     types::{CustomType, Enum, EnumOptions, Field, Struct, Type, TypeIdent, TypeMap, Variant},
-    TsRuntimeConfig,
+    TsRuntimeConfig, TsWasiShim,
 };
 use inflector::Inflector;
-use std::fs;
+use normalizers::Normalizers;
+use std::collections::BTreeMap;
+use validators::Validators;
+use web_worker::WebWorker;
 
 pub(crate) fn generate_bindings(
     import_functions: FunctionList,
     export_functions: FunctionList,
-    types: TypeMap,
+    mut types: TypeMap,
     config: TsRuntimeConfig,
+    protocol_version: &ProtocolVersion,
     path: &str,
+    files: &mut GeneratedFiles,
 ) {
-    generate_type_bindings(&types, path);
+    apply_custom_type_conversions(&mut types, &config);
 
-    let import_decls =
-        format_function_declarations(&import_functions, &types, FunctionType::Import);
+    let int64_repr = if config.use_bigint_for_64bit_integers {
+        Int64Repr::BigInt
+    } else {
+        Int64Repr::Number
+    };
+    // Normalizers are only needed to turn decoded 64-bit integers into
+    // `bigint` consistently, and to apply custom type conversions:
+    let normalizers = Normalizers::for_decoding(&types, int64_repr == Int64Repr::BigInt);
+    let denormalizers = Normalizers::for_encoding(&types);
+    let validators = config.generate_validators.then(|| Validators::new(&types));
+
+    // npm packages keep their sources in a subdirectory, next to the package
+    // files:
+    let package_path = path;
+    let path = &match &config.npm_package {
+        Some(package) => {
+            npm_package::generate_package_files(package, &config, package_path, files);
+            format!("{package_path}/src")
+        }
+        None => package_path.to_owned(),
+    };
+
+    generate_type_bindings(
+        &types,
+        path,
+        int64_repr,
+        config.generate_enum_helpers,
+        files,
+    );
+
+    // Functions from named import modules get their own `Imports` type, and
+    // are passed to the plugin in a namespace of their own:
+    let mut default_import_functions = Vec::new();
+    let mut import_modules = BTreeMap::<String, Vec<Function>>::new();
+    for function in &import_functions {
+        match &function.import_module {
+            Some(module) => import_modules
+                .entry(module.clone())
+                .or_default()
+                .push(function.clone()),
+            None => default_import_functions.push(function.clone()),
+        }
+    }
+    let default_import_functions = FunctionList::from_iter(default_import_functions);
+    let import_modules = import_modules
+        .into_iter()
+        .map(|(module, functions)| (module, FunctionList::from_iter(functions)))
+        .collect::<Vec<_>>();
+
+    let import_decls = format_function_declarations(
+        &default_import_functions,
+        &types,
+        FunctionType::Import,
+        int64_repr,
+    );
     let export_decls =
-        format_function_declarations(&export_functions, &types, FunctionType::Export);
-    let raw_export_decls = if config.generate_raw_export_wrappers {
+        format_function_declarations(&export_functions, &types, FunctionType::Export, int64_repr);
+    // The Web Worker runtime relies on the raw export wrappers for forwarding
+    // calls to the worker:
+    let generate_raw_export_wrappers =
+        config.generate_raw_export_wrappers || config.generate_web_worker;
+    let raw_export_decls = if generate_raw_export_wrappers {
         format_raw_function_declarations(&export_functions, FunctionType::Export)
     } else {
         Vec::new()
@@ -30,30 +100,103 @@ pub(crate) fn generate_bindings(
     let has_async_import_functions = import_functions.iter().any(|function| function.is_async);
     let has_async_export_functions = export_functions.iter().any(|function| function.is_async);
 
-    let mut import_wrappers = format_import_wrappers(&import_functions, &types);
+    let mut import_wrappers = format_import_wrappers(
+        &default_import_functions,
+        &types,
+        int64_repr,
+        normalizers.as_ref(),
+        denormalizers.as_ref(),
+        validators.as_ref(),
+    );
     if has_async_export_functions {
         import_wrappers.push("__fp_host_resolve_async_value: resolvePromise,".to_owned());
     }
+    let has_events = export_functions.iter().any(|function| function.is_event);
+    if has_events {
+        import_wrappers.extend(
+            [
+                "__fp_host_subscribe: (event_ptr: FatPtr) => {",
+                "    subscriptions.add(parseObject<string>(event_ptr));",
+                "},",
+                "__fp_host_unsubscribe: (event_ptr: FatPtr) => {",
+                "    subscriptions.delete(parseObject<string>(event_ptr));",
+                "},",
+            ]
+            .map(str::to_owned),
+        );
+    }
+    let has_callbacks = types.values().any(|ty| matches!(ty, Type::Callback(_, _)));
+    if has_callbacks {
+        import_wrappers.extend(
+            [
+                "__fp_host_invoke_callback: invokeHostCallback,",
+                "__fp_host_release_handle: releaseHostCallback,",
+                "__fp_host_retain_handle: retainHostCallback,",
+            ]
+            .map(str::to_owned),
+        );
+    }
 
-    let export_wrappers = format_export_wrappers(&export_functions, &types);
-    let raw_export_wrappers = if config.generate_raw_export_wrappers {
+    let export_wrappers = format_export_wrappers(
+        &export_functions,
+        &types,
+        int64_repr,
+        normalizers.as_ref(),
+        denormalizers.as_ref(),
+        validators.as_ref(),
+    );
+    let raw_export_wrappers = if generate_raw_export_wrappers {
         format_raw_export_wrappers(&export_functions)
     } else {
         Vec::new()
     };
 
-    let msgpack_module = config.msgpack_module;
-    // HACK: Import paths in TypeScript are a bit of a mess. Usually, you
-    // shouldn't need an extension, but with some configurations you do.
-    // For now, we just try to detect Deno users by looking at the
-    // `msgpack_module` and accomodate them here:
-    let import_path_extension = if msgpack_module.ends_with(".ts") {
-        ".ts"
-    } else {
-        ""
+    let msgpack_module = &config.msgpack_module;
+    // Import paths in TypeScript are a bit of a mess. Usually, you shouldn't
+    // need an extension, but with some configurations you do. Unless an
+    // extension is configured explicitly, we try to detect Deno users by
+    // looking at the `msgpack_module` and accomodate them here:
+    let import_path_extension = match (&config.import_path_extension, &config.npm_package) {
+        (Some(extension), _) => extension.as_str(),
+        (None, Some(_)) => ".js",
+        (None, None) if msgpack_module.ends_with(".ts") => ".ts",
+        (None, None) => "",
     };
 
     let import_lines = join_lines(&import_decls, |line| format!("    {line};"));
+    let module_imports_intersection = import_modules
+        .iter()
+        .map(|(module, _)| format!(" & {}Imports", module.to_pascal_case()))
+        .collect::<String>();
+    let module_import_types = import_modules
+        .iter()
+        .map(|(module, functions)| {
+            let decls =
+                format_function_declarations(functions, &types, FunctionType::Import, int64_repr);
+            format!(
+                "\nexport type {}Imports = {{\n{}}};\n",
+                module.to_pascal_case(),
+                join_lines(&decls, |line| format!("    {line};"))
+            )
+        })
+        .collect::<String>();
+    let module_imports = import_modules
+        .iter()
+        .map(|(module, functions)| {
+            let wrappers = format_import_wrappers(
+                functions,
+                &types,
+                int64_repr,
+                normalizers.as_ref(),
+                denormalizers.as_ref(),
+                validators.as_ref(),
+            );
+            format!(
+                "        {module}: {{\n{}        }},\n",
+                join_lines(&wrappers, |line| format!("            {line}"))
+            )
+        })
+        .collect::<String>();
     let export_lines = join_lines(&export_decls, |line| format!("    {line};"));
     let raw_export_lines = join_lines(&raw_export_decls, |line| format!("    {line};"));
 
@@ -67,6 +210,155 @@ pub(crate) fn generate_bindings(
         ("ArrayBuffer", "The raw WASM plugin", "")
     };
 
+    let msgpack_options = match (int64_repr == Int64Repr::BigInt, has_callbacks) {
+        (true, true) => ", { extensionCodec, useBigInt64: true }",
+        (true, false) => ", { useBigInt64: true }",
+        (false, true) => ", { extensionCodec }",
+        (false, false) => "",
+    };
+    // Parameters of `parseObject()`, following the pointer to the object:
+    let mut parse_object_extra_params = Vec::new();
+    if validators.is_some() {
+        parse_object_extra_params.extend(["validate: Validator = validateAny", "path = \"value\""]);
+    }
+    if normalizers.is_some() {
+        parse_object_extra_params.push("normalize: Normalizer = identity");
+    }
+    let parse_object_params = std::iter::once("fatPtr: FatPtr")
+        .chain(parse_object_extra_params.iter().copied())
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Values with callbacks carry references, which need to be tracked while
+    // encoding or decoding them:
+    let (serialize_object_params, encoded_object) = if denormalizers.is_some() {
+        (
+            "object: T, denormalize: Normalizer = identity",
+            "denormalize(object)",
+        )
+    } else {
+        ("object: T", "object")
+    };
+    let (decode_call, encode_call) = if has_callbacks {
+        (
+            format!("transferCallbacks(true, () => decode(copy{msgpack_options}))"),
+            format!("transferCallbacks(false, () => encode({encoded_object}{msgpack_options}))"),
+        )
+    } else {
+        (
+            format!("decode(copy{msgpack_options})"),
+            format!("encode({encoded_object}{msgpack_options})"),
+        )
+    };
+    let parse_object_result = match (&validators, &normalizers) {
+        (None, None) => {
+            format!("const object = {decode_call} as unknown as T;\n        return object;")
+        }
+        (None, Some(_)) => {
+            format!("const object = normalize({decode_call}) as T;\n        return object;")
+        }
+        (Some(_), normalizers) => format!(
+            "const object = {decode_call};
+        if (options.validate !== false) {{
+            validate(object, path);
+        }}
+        return {} as T;",
+            if normalizers.is_some() {
+                "normalize(object)"
+            } else {
+                "object"
+            }
+        ),
+    };
+
+    let mut runtime_option_types = String::new();
+    let mut runtime_options = Vec::new();
+    if validators.is_some() {
+        runtime_option_types.push_str(VALIDATION_ERROR_TYPE);
+        runtime_options.push(VALIDATE_RUNTIME_OPTION);
+    }
+    if config.wasi.is_some() {
+        runtime_option_types.push_str(WASI_OPTIONS_TYPE);
+        runtime_options.push(WASI_RUNTIME_OPTION);
+    }
+    let has_runtime_options = !runtime_options.is_empty();
+    let (options_doc, options_param) = if !has_runtime_options {
+        ("", "")
+    } else {
+        runtime_option_types.push_str(&format!(
+            "
+/**
+ * Options for creating a runtime.
+ */
+export type RuntimeOptions = {{
+{}}};
+",
+            runtime_options.join("\n")
+        ));
+        (
+            "\n * @param options Options for the runtime.",
+            ",\n    options: RuntimeOptions = {}",
+        )
+    };
+
+    let (wasi_import, wasi_setup, wasi_imports, wasi_initialization) = match &config.wasi {
+        Some(TsWasiShim::Node) => (
+            "import { WASI } from \"node:wasi\";\n".to_owned(),
+            "    const wasi = new WASI({
+        version: \"preview1\",
+        args: options.wasi?.args ?? [],
+        env: options.wasi?.env ?? {},
+        preopens: options.wasi?.preopens ?? {},
+    });
+
+",
+            "        wasi_snapshot_preview1: wasi.wasiImport,\n",
+            "\n    wasi.initialize(instance);\n",
+        ),
+        Some(TsWasiShim::Deno(module)) => (
+            format!("import WasiContext from \"{module}\";\n"),
+            "    const wasi = new WasiContext({
+        args: options.wasi?.args ?? [],
+        env: options.wasi?.env ?? {},
+        preopens: options.wasi?.preopens ?? {},
+        exitOnReturn: false,
+    });
+
+",
+            "        wasi_snapshot_preview1: wasi.exports,\n",
+            "\n    wasi.initialize(instance);\n",
+        ),
+        None => (String::new(), "", "", ""),
+    };
+    let helper_functions = [
+        (normalizers.is_some() || denormalizers.is_some())
+            .then(|| normalizers::NORMALIZER_HELPERS.to_owned()),
+        normalizers.as_ref().map(Normalizers::format_functions),
+        denormalizers.as_ref().map(Normalizers::format_functions),
+        validators.as_ref().map(Validators::format_functions),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<String>();
+
+    let (msgpack_imports, callback_helpers) = if has_callbacks {
+        ("encode, decode, ExtensionCodec", CALLBACK_HELPERS)
+    } else {
+        ("encode, decode", "")
+    };
+
+    // Names of the events the plugin has subscribed to:
+    let subscriptions = if has_events {
+        "    const subscriptions = new Set<string>();\n"
+    } else {
+        ""
+    };
+
+    let ProtocolVersion {
+        name: protocol_name,
+        version: protocol_version,
+        hash: protocol_hash,
+    } = protocol_version;
+
     let contents = format!(
         "// ============================================= //
 // WebAssembly runtime for TypeScript            //
@@ -75,15 +367,15 @@ pub(crate) fn generate_bindings(
 // ============================================= //
 // deno-lint-ignore-file no-explicit-any no-unused-vars
 
-import {{ encode, decode }} from \"{msgpack_module}\";
-
+import {{ {msgpack_imports} }} from \"{msgpack_module}\";
+{wasi_import}
 import type * as types from \"./types{import_path_extension}\";
 
 type FatPtr = bigint;
 
 export type Imports = {{
-{import_lines}}};
-
+{import_lines}}}{module_imports_intersection};
+{module_import_types}
 export type Exports = {{
 {export_lines}{raw_export_lines}}};
 
@@ -97,20 +389,38 @@ export class FPRuntimeError extends Error {{
         super(message);
     }}
 }}
+{runtime_option_types}
+/**
+ * Identifies a specific revision of a protocol.
+ */
+export type ProtocolVersion = {{
+    name: string;
+    version: string;
+    hash: string;
+}};
+
+/**
+ * The version of the protocol this runtime was generated for.
+ */
+export const PROTOCOL_VERSION: ProtocolVersion = {{
+    name: {protocol_name:?},
+    version: {protocol_version:?},
+    hash: {protocol_hash:?},
+}};
 
 /**
  * Creates a runtime for executing the given plugin.
  *
  * @param source {source_doc}.
- * @param importFunctions The host functions that may be imported by the plugin.
+ * @param importFunctions The host functions that may be imported by the plugin.{options_doc}
  * @returns The functions that may be exported by the plugin.
  */
 export async function createRuntime(
     source: {source_type},
-    importFunctions: Imports
+    importFunctions: Imports{options_param}
 ): Promise<Exports> {{
     const promises = new Map<FatPtr, ((result: FatPtr) => void) | FatPtr>();
-
+{subscriptions}{callback_helpers}
     function createAsyncValue(): FatPtr {{
         const len = 12; // std::mem::size_of::<AsyncValue>()
         const fatPtr = malloc(len);
@@ -136,7 +446,8 @@ export async function createRuntime(
         }}
     }}
 
-    function parseObject<T>(fatPtr: FatPtr): T {{
+    function parseObject<T>({parse_object_params}): T {{
+        checkWireFormat(fatPtr);
         const [ptr, len] = fromFatPtr(fatPtr);
         const buffer = new Uint8Array(memory.buffer, ptr, len);
         // Without creating a copy of the memory, we risk corruption of any
@@ -145,8 +456,7 @@ export async function createRuntime(
         const copy = new Uint8Array(len);
         copy.set(buffer);
         free(fatPtr);
-        const object = decode(copy) as unknown as T;
-        return object;
+        {parse_object_result}
     }}
 
     function promiseFromPtr(ptr: FatPtr): Promise<FatPtr> {{
@@ -179,8 +489,8 @@ export async function createRuntime(
         }}
     }}
 
-    function serializeObject<T>(object: T): FatPtr {{
-        return exportToMemory(encode(object));
+    function serializeObject<T>({serialize_object_params}): FatPtr {{
+        return exportToMemory({encode_call});
     }}
 
     function exportToMemory(serialized: Uint8Array): FatPtr {{
@@ -192,6 +502,7 @@ export async function createRuntime(
     }}
 
     function importFromMemory(fatPtr: FatPtr): Uint8Array {{
+        checkWireFormat(fatPtr);
         const [ptr, len] = fromFatPtr(fatPtr);
         const buffer = new Uint8Array(memory.buffer, ptr, len);
         const copy = new Uint8Array(len);
@@ -200,11 +511,11 @@ export async function createRuntime(
         return copy;
     }}
 
-    const {{ instance }} = await WebAssembly.instantiate{streaming}(source, {{
+{wasi_setup}    const {{ instance }} = await WebAssembly.instantiate{streaming}(source, {{
         fp: {{
 {}        }},
-    }});
-
+{module_imports}{wasi_imports}    }});
+{wasi_initialization}
     const getExport = <T>(name: string): T => {{
         const exp = instance.exports[name];
         if (!exp) {{
@@ -216,19 +527,112 @@ export async function createRuntime(
     const memory = getExport<WebAssembly.Memory>(\"memory\");
     const malloc = getExport<(len: number) => FatPtr>(\"__fp_malloc\");
     const free = getExport<(ptr: FatPtr) => void>(\"__fp_free\");
+
+    const getProtocolVersion = instance.exports.__fp_protocol_version as unknown as
+        | (() => FatPtr)
+        | undefined;
+    if (getProtocolVersion) {{
+        checkProtocolVersion(parseObject<ProtocolVersion>(getProtocolVersion()));
+    }} else {{
+        console.warn(
+            \"Plugin does not declare its protocol version, so it cannot be verified to be \" +
+                `compatible with ${{formatProtocolVersion(PROTOCOL_VERSION)}}`
+        );
+    }}
 {}
     return {{
 {}{}    }};
 }}
 
+/**
+ * Returns the names of all the exports that are implemented by the plugin.
+ *
+ * @param exports The functions returned by `createRuntime()`.
+ */
+export function implementedExports(exports: Exports): Array<keyof Exports> {{
+    return (Object.keys(exports) as Array<keyof Exports>).filter(
+        (name) => typeof exports[name] === \"function\"
+    );
+}}
+
 function fromFatPtr(fatPtr: FatPtr): [ptr: number, len: number] {{
-    return [Number(fatPtr >> 32n), Number(fatPtr & 0xffff_ffffn)];
+    return [Number(fatPtr >> 32n), Number(fatPtr & 0xff_ffffn)];
+}}
+
+/**
+ * Verifies the data the fat pointer refers to is encoded as MessagePack, which
+ * is the only wire format supported by the TypeScript runtime.
+ */
+function checkWireFormat(fatPtr: FatPtr) {{
+    const wireFormat = Number((fatPtr >> 24n) & 0xffn);
+    if (wireFormat !== 0) {{
+        throw new FPRuntimeError(`Plugin used an unsupported wire format: ${{wireFormat}}`);
+    }}
 }}
 
 function toFatPtr(ptr: number, len: number): FatPtr {{
     return (BigInt(ptr) << 32n) | BigInt(len);
 }}
-",
+
+function checkProtocolVersion(found: ProtocolVersion) {{
+    if (found.hash === PROTOCOL_VERSION.hash) {{
+        return;
+    }}
+
+    const foundVersion = formatProtocolVersion(found);
+    const expectedVersion = formatProtocolVersion(PROTOCOL_VERSION);
+    const compatible =
+        found.name && PROTOCOL_VERSION.name
+            ? found.name === PROTOCOL_VERSION.name &&
+              isSemverCompatible(found.version, PROTOCOL_VERSION.version)
+            : undefined;
+    if (compatible === undefined) {{
+        console.warn(
+            `Plugin was built against protocol ${{foundVersion}}, which differs from ${{expectedVersion}}, and cannot be verified to be compatible`
+        );
+    }} else if (compatible) {{
+        console.warn(
+            `Plugin was built against protocol ${{foundVersion}}, which differs from ${{expectedVersion}}, but should be compatible`
+        );
+    }} else {{
+        throw new FPRuntimeError(
+            `Plugin was built against protocol ${{foundVersion}}, which is incompatible with ${{expectedVersion}}`
+        );
+    }}
+}}
+
+function formatProtocolVersion({{ name, version, hash }}: ProtocolVersion): string {{
+    return `${{name}} ${{version}} (${{hash}})`;
+}}
+
+/**
+ * Versions are compatible if their left-most non-zero component is the same.
+ * Returns `undefined` if either is not a valid semantic version.
+ */
+function isSemverCompatible(a: string, b: string): boolean | undefined {{
+    const parse = (version: string) => {{
+        const match = /^(\\d+)\\.(\\d+)\\.(\\d+)([-+].*)?$/.exec(version);
+        return match ? [Number(match[1]), Number(match[2]), Number(match[3])] : undefined;
+    }};
+
+    const [aVersion, bVersion] = [parse(a), parse(b)];
+    if (!aVersion || !bVersion) {{
+        return undefined;
+    }}
+
+    const [aMajor, aMinor, aPatch] = aVersion;
+    const [bMajor, bMinor, bPatch] = bVersion;
+    if (aMajor !== bMajor) {{
+        return false;
+    }} else if (aMajor > 0) {{
+        return true;
+    }} else if (aMinor !== bMinor) {{
+        return false;
+    }} else {{
+        return aMinor > 0 || aPatch === bPatch;
+    }}
+}}
+{helper_functions}",
         join_lines(&import_wrappers, |line| format!("            {line}")),
         if has_async_import_functions {
             "    const resolveFuture = getExport<(asyncValuePtr: FatPtr, resultPtr: FatPtr) => void>(\"__fp_guest_resolve_async_value\");\n"
@@ -238,23 +642,267 @@ function toFatPtr(ptr: number, len: number): FatPtr {{
         join_lines(&export_wrappers, |line| format!("        {line}")),
         join_lines(&raw_export_wrappers, |line| format!("        {line}")),
     );
-    write_bindings_file(format!("{path}/index.ts"), contents);
+    files.insert(format!("{path}/index.ts"), contents);
+
+    if config.generate_web_worker {
+        WebWorker {
+            import_functions: &import_functions,
+            export_functions: &export_functions,
+            types: &types,
+            int64_repr,
+            normalizers: normalizers.as_ref(),
+            denormalizers: denormalizers.as_ref(),
+            validators: validators.as_ref(),
+            msgpack_module,
+            msgpack_options,
+            serialize_object_params,
+            import_path_extension,
+            streaming_instantiation: config.streaming_instantiation,
+            has_runtime_options,
+            parse_object_params: &parse_object_extra_params,
+            parse_object_result: &parse_object_result,
+            helper_functions: &helper_functions,
+        }
+        .generate(path, files);
+    }
 }
 
+/// Encodes callbacks as MessagePack extension values, as specified by
+/// `fp_bindgen_support::common::callback::CallbackRef`.
+///
+/// Callbacks created by the host are kept in a reference-counted table, while
+/// callbacks received from the plugin are wrapped in functions that release
+/// them once they are garbage collected. Every encoded value carries a single
+/// reference to each of the callbacks in it, which the receiver releases once
+/// it has decoded the value.
+const CALLBACK_HELPERS: &str = "
+    type Callback = (arg: any) => any;
+
+    const CALLBACK_EXT_TYPE = 1;
+    const CALLBACK_OWNER_HOST = 0;
+    const CALLBACK_OWNER_PLUGIN = 1;
+
+    const hostCallbacks = new Map<number, { callback: Callback; refs: number }>();
+    const hostCallbackIds = new WeakMap<Callback, number>();
+    const pluginCallbackIds = new WeakMap<Callback, number>();
+    let lastCallbackId = 0;
+
+    // IDs of the callbacks in the value that is being encoded or decoded:
+    let transfer: { host: Set<number>; plugin: Set<number> } | undefined;
+
+    const pluginCallbackRegistry = new FinalizationRegistry<number>(releasePluginCallback);
+
+    const extensionCodec = new ExtensionCodec();
+    extensionCodec.register({
+        type: CALLBACK_EXT_TYPE,
+        encode: (value: unknown): Uint8Array | null => {
+            if (typeof value !== \"function\") {
+                return null;
+            }
+
+            const callback = value as Callback;
+            const pluginCallbackId = pluginCallbackIds.get(callback);
+            if (pluginCallbackId !== undefined) {
+                if (transferCallback(transfer?.plugin, pluginCallbackId)) {
+                    retainPluginCallback(pluginCallbackId);
+                }
+                return encodeCallbackRef(CALLBACK_OWNER_PLUGIN, pluginCallbackId);
+            }
+
+            return encodeCallbackRef(CALLBACK_OWNER_HOST, transferHostCallback(callback));
+        },
+        decode: (data: Uint8Array): Callback => {
+            if (data.length !== 5) {
+                throw new FPRuntimeError(\"Invalid callback reference\");
+            }
+
+            const owner = data[0];
+            const id = new DataView(data.buffer, data.byteOffset, data.byteLength).getUint32(1);
+            if (owner === CALLBACK_OWNER_HOST) {
+                // The reference the plugin passed along is released once the
+                // whole value has been decoded, since we can refer to the
+                // function directly.
+                transferCallback(transfer?.host, id);
+                const callback = hostCallbacks.get(id)?.callback;
+                if (!callback) {
+                    throw new FPRuntimeError(`Callback ${id} does not exist`);
+                }
+                return callback;
+            }
+
+            // Every decoded callback owns a reference, which is released once
+            // it is garbage collected.
+            transferCallback(transfer?.plugin, id);
+            retainPluginCallback(id);
+            const invoke = getExport<(id: number, args: FatPtr) => FatPtr>(
+                \"__fp_guest_invoke_callback\"
+            );
+            const callback = (arg: any) => parseObject<any>(invoke(id, serializeObject(arg)));
+            pluginCallbackIds.set(callback, id);
+            pluginCallbackRegistry.register(callback, id);
+            return callback;
+        },
+    });
+
+    function encodeCallbackRef(owner: number, id: number): Uint8Array {
+        const data = new Uint8Array(5);
+        data[0] = owner;
+        new DataView(data.buffer).setUint32(1, id);
+        return data;
+    }
+
+    /**
+     * Encodes or decodes a value, and keeps track of the callbacks in it. The
+     * references carried by the value are released if it was decoded, or if
+     * it could not be encoded.
+     */
+    function transferCallbacks<T>(decoding: boolean, code: () => T): T {
+        const previous = transfer;
+        const current = { host: new Set<number>(), plugin: new Set<number>() };
+        transfer = current;
+        let keepReferences = false;
+        try {
+            const result = code();
+            keepReferences = !decoding;
+            return result;
+        } finally {
+            transfer = previous;
+            if (!keepReferences) {
+                current.host.forEach(releaseHostCallback);
+                current.plugin.forEach(releasePluginCallback);
+            }
+        }
+    }
+
+    /**
+     * Adds the given callback to the value that is being encoded or decoded.
+     * Returns whether the value did not carry a reference to it yet.
+     */
+    function transferCallback(ids: Set<number> | undefined, id: number): boolean {
+        if (!ids || ids.has(id)) {
+            return false;
+        }
+        ids.add(id);
+        return true;
+    }
+
+    function transferHostCallback(callback: Callback): number {
+        const id = hostCallbackIds.get(callback);
+        const entry = id !== undefined ? hostCallbacks.get(id) : undefined;
+        if (id !== undefined && entry && entry.callback === callback) {
+            if (transferCallback(transfer?.host, id)) {
+                entry.refs++;
+            }
+            return id;
+        }
+
+        do {
+            lastCallbackId = (lastCallbackId + 1) >>> 0;
+        } while (lastCallbackId === 0 || hostCallbacks.has(lastCallbackId));
+        hostCallbacks.set(lastCallbackId, { callback, refs: 1 });
+        hostCallbackIds.set(callback, lastCallbackId);
+        transferCallback(transfer?.host, lastCallbackId);
+        return lastCallbackId;
+    }
+
+    function retainPluginCallback(id: number) {
+        getExport<(id: number) => void>(\"__fp_guest_retain_callback\")(id);
+    }
+
+    function releasePluginCallback(id: number) {
+        getExport<(id: number) => void>(\"__fp_guest_release_callback\")(id);
+    }
+
+    function invokeHostCallback(id: number, args: FatPtr): FatPtr {
+        const entry = hostCallbacks.get(id);
+        if (!entry) {
+            throw new FPRuntimeError(`Plugin invoked callback ${id}, which does not exist`);
+        }
+        return serializeObject(entry.callback(parseObject<any>(args)));
+    }
+
+    function releaseHostCallback(id: number) {
+        const entry = hostCallbacks.get(id);
+        if (entry && --entry.refs === 0) {
+            hostCallbacks.delete(id);
+        }
+    }
+
+    function retainHostCallback(id: number) {
+        const entry = hostCallbacks.get(id);
+        if (entry) {
+            entry.refs++;
+        }
+    }
+";
+
+const VALIDATION_ERROR_TYPE: &str = "
+/**
+ * Thrown when a value decoded by the runtime doesn't match its expected type.
+ */
+export class ValidationError extends FPRuntimeError {
+    constructor(readonly path: string, readonly expected: string, readonly value: unknown) {
+        super(`Invalid value at ${path}: expected ${expected}, but got ${describeValue(value)}`);
+    }
+}
+";
+
+const WASI_OPTIONS_TYPE: &str = "
+/**
+ * Options for the WASI environment in which the plugin runs.
+ */
+export type WasiOptions = {
+    /**
+     * The command-line arguments that are visible to the plugin.
+     */
+    args?: Array<string>;
+
+    /**
+     * The environment variables that are visible to the plugin.
+     */
+    env?: Record<string, string>;
+
+    /**
+     * The directories the plugin has access to, mapping the paths seen by the
+     * plugin to those on the host.
+     */
+    preopens?: Record<string, string>;
+};
+";
+
+const VALIDATE_RUNTIME_OPTION: &str = "    /**
+     * Whether to validate the values decoded by the runtime. Enabled by default.
+     *
+     * Validation adds overhead to every call, so you may wish to disable it in
+     * production builds.
+     */
+    validate?: boolean;
+";
+
+const WASI_RUNTIME_OPTION: &str = "    /**
+     * Options for the WASI environment in which the plugin runs.
+     */
+    wasi?: WasiOptions;
+";
+
 enum FunctionType {
     Import,
     Export,
+    /// Exports as they are called through a Web Worker, which always return a
+    /// `Promise`.
+    WorkerExport,
 }
 
 fn format_function_declarations(
     functions: &FunctionList,
     types: &TypeMap,
     function_type: FunctionType,
+    int64_repr: Int64Repr,
 ) -> Vec<String> {
     // Plugins can always omit exports, while runtimes are always expected to provide all imports:
     let optional_marker = match function_type {
         FunctionType::Import => "",
-        FunctionType::Export => "?",
+        FunctionType::Export | FunctionType::WorkerExport => "?",
     };
 
     functions
@@ -267,7 +915,7 @@ fn format_function_declarations(
                     format!(
                         "{}: {}",
                         arg.name.to_camel_case(),
-                        format_plain_primitive_or_ident(&arg.ty, types)
+                        format_plain_primitive_or_ident(&arg.ty, types, int64_repr)
                     )
                 })
                 .collect::<Vec<_>>()
@@ -276,22 +924,23 @@ fn format_function_declarations(
                 format!(
                     " => Promise<{}>",
                     match &function.return_type {
-                        Some(ty) => format_ident(ty, types, "types."),
+                        Some(ty) => format_ident(ty, types, "types.", int64_repr),
                         None => "void".to_owned(),
                     }
                 )
             } else {
-                format!(
-                    " => {}",
-                    match &function.return_type {
-                        Some(ty) => format_plain_primitive_or_ident(ty, types),
-                        None => "void".to_owned(),
-                    }
-                )
+                let return_type = match &function.return_type {
+                    Some(ty) => format_plain_primitive_or_ident(ty, types, int64_repr),
+                    None => "void".to_owned(),
+                };
+                match function_type {
+                    FunctionType::WorkerExport => format!(" => Promise<{return_type}>"),
+                    _ => format!(" => {return_type}"),
+                }
             };
             format!(
                 "{}{}: ({}){}",
-                function.name.to_camel_case(),
+                get_export_name(function),
                 optional_marker,
                 args,
                 return_type
@@ -307,7 +956,7 @@ fn format_raw_function_declarations(
     // Plugins can always omit exports, while runtimes are always expected to provide all imports:
     let optional_marker = match function_type {
         FunctionType::Import => "",
-        FunctionType::Export => "?",
+        FunctionType::Export | FunctionType::WorkerExport => "?",
     };
 
     functions
@@ -334,7 +983,7 @@ fn format_raw_function_declarations(
             };
             format!(
                 "{}Raw{}: ({}){}",
-                function.name.to_camel_case(),
+                get_export_name(function),
                 optional_marker,
                 args,
                 return_type
@@ -343,7 +992,14 @@ fn format_raw_function_declarations(
         .collect()
 }
 
-fn format_import_wrappers(import_functions: &FunctionList, types: &TypeMap) -> Vec<String> {
+fn format_import_wrappers(
+    import_functions: &FunctionList,
+    types: &TypeMap,
+    int64_repr: Int64Repr,
+    normalizers: Option<&Normalizers>,
+    denormalizers: Option<&Normalizers>,
+    validators: Option<&Validators>,
+) -> Vec<String> {
     import_functions
         .into_iter()
         .flat_map(|function| {
@@ -381,10 +1037,17 @@ fn format_import_wrappers(import_functions: &FunctionList, types: &TypeMap) -> V
                         None
                     } else {
                         Some(format!(
-                            "const {} = parseObject<{}>({});",
+                            "const {} = {};",
                             arg.name.to_camel_case(),
-                            format_ident(&arg.ty, types, "types."),
-                            get_pointer_name(&arg.name)
+                            format_parse_object(
+                                Some(&arg.ty),
+                                &get_pointer_name(&arg.name),
+                                &format!("{}({})", name.to_camel_case(), arg.name.to_camel_case()),
+                                types,
+                                int64_repr,
+                                normalizers,
+                                validators
+                            )
                         ))
                     }
                 })
@@ -396,7 +1059,8 @@ fn format_import_wrappers(import_functions: &FunctionList, types: &TypeMap) -> V
                 .collect::<Vec<_>>()
                 .join(", ");
             if function.is_async {
-                let async_result = "serializeObject(result)";
+                let async_result =
+                    format_serialize_object(function.return_type.as_ref(), "result", denormalizers);
 
                 format!(
                     "__fp_gen_{}: ({}){} => {{
@@ -441,10 +1105,13 @@ fn format_import_wrappers(import_functions: &FunctionList, types: &TypeMap) -> V
                             )
                         )
                     }
-                    _ => format!(
-                        "return serializeObject(importFunctions.{}({}));",
-                        name.to_camel_case(),
-                        args
+                    Some(ty) => format!(
+                        "return {};",
+                        format_serialize_object(
+                            Some(ty),
+                            &format!("importFunctions.{}({})", name.to_camel_case(), args),
+                            denormalizers
+                        )
                     ),
                 };
 
@@ -468,18 +1135,25 @@ fn format_import_wrappers(import_functions: &FunctionList, types: &TypeMap) -> V
         .collect()
 }
 
-fn format_export_wrappers(export_functions: &FunctionList, types: &TypeMap) -> Vec<String> {
+fn format_export_wrappers(
+    export_functions: &FunctionList,
+    types: &TypeMap,
+    int64_repr: Int64Repr,
+    normalizers: Option<&Normalizers>,
+    denormalizers: Option<&Normalizers>,
+    validators: Option<&Validators>,
+) -> Vec<String> {
     export_functions
         .into_iter()
         .flat_map(|function| {
             let name = &function.name;
+            let export_name = get_export_name(function);
 
             // Trivial functions can simply be returned as is:
             if is_primitive_function(function) {
                 return vec![format!(
                     "{}: instance.exports.__fp_gen_{} as any,",
-                    name.to_camel_case(),
-                    name
+                    export_name, name
                 )];
             }
 
@@ -490,7 +1164,7 @@ fn format_export_wrappers(export_functions: &FunctionList, types: &TypeMap) -> V
                     format!(
                         "{}: {}",
                         arg.name.to_camel_case(),
-                        format_plain_primitive_or_ident(&arg.ty, types)
+                        format_plain_primitive_or_ident(&arg.ty, types, int64_repr)
                     )
                 })
                 .collect::<Vec<_>>()
@@ -512,12 +1186,13 @@ fn format_export_wrappers(export_functions: &FunctionList, types: &TypeMap) -> V
                     };
 
                     format!(
-                        "const {} = serializeObject({});",
+                        "const {} = {};",
                         get_pointer_name(&arg.name),
-                        wrapped_arg
+                        format_serialize_object(Some(&arg.ty), &wrapped_arg, denormalizers)
                     )
                 })
                 .collect::<Vec<_>>();
+            let export_args = with_subscription_check(function, export_args, "");
 
             let call_args = function
                 .args
@@ -533,13 +1208,17 @@ fn format_export_wrappers(export_functions: &FunctionList, types: &TypeMap) -> V
                 .join(", ");
             let fn_call = if function.is_async {
                 format!(
-                    "return promiseFromPtr(export_fn({})).then((ptr) => parseObject<{}>(ptr));",
+                    "return promiseFromPtr(export_fn({})).then((ptr) => {});",
                     call_args,
-                    function
-                        .return_type
-                        .as_ref()
-                        .map(|ty| format_ident(ty, types, "types."))
-                        .unwrap_or_else(|| "void".to_owned()),
+                    format_parse_object(
+                        function.return_type.as_ref(),
+                        "ptr",
+                        &format!("{}()", export_name),
+                        types,
+                        int64_repr,
+                        normalizers,
+                        validators
+                    ),
                 )
             } else {
                 match &function.return_type {
@@ -549,9 +1228,16 @@ fn format_export_wrappers(export_functions: &FunctionList, types: &TypeMap) -> V
                         import_primitive(ty, &format!("export_fn({call_args})"))
                     ),
                     Some(ty) => format!(
-                        "return parseObject<{}>(export_fn({}));",
-                        format_ident(ty, types, "types."),
-                        call_args
+                        "return {};",
+                        format_parse_object(
+                            Some(ty),
+                            &format!("export_fn({call_args})"),
+                            &format!("{}()", export_name),
+                            types,
+                            int64_repr,
+                            normalizers,
+                            validators
+                        )
                     ),
                 }
             };
@@ -572,9 +1258,7 @@ fn format_export_wrappers(export_functions: &FunctionList, types: &TypeMap) -> V
 
     {}
 }})(),",
-                name.to_camel_case(),
-                name,
-                return_fn
+                export_name, name, return_fn
             )
             .split('\n')
             .map(str::to_owned)
@@ -583,12 +1267,87 @@ fn format_export_wrappers(export_functions: &FunctionList, types: &TypeMap) -> V
         .collect()
 }
 
+/// Returns the name under which an export is exposed to the host. Events
+/// are exposed as `emit<Event>()`.
+fn get_export_name(function: &Function) -> String {
+    if function.is_event {
+        format!("emit_{}", function.name).to_camel_case()
+    } else {
+        function.name.to_camel_case()
+    }
+}
+
+/// Prepends a check to the statements of an export wrapper, which skips
+/// emitting an event if the plugin has not subscribed to it. In that case, the
+/// wrapper resolves to the given result instead.
+fn with_subscription_check(
+    function: &Function,
+    statements: Vec<String>,
+    skipped_result: &str,
+) -> Vec<String> {
+    if !function.is_event {
+        return statements;
+    }
+
+    std::iter::once(format!(
+        "if (!subscriptions.has(\"{}\")) return Promise.resolve({skipped_result});",
+        function.name
+    ))
+    .chain(statements)
+    .collect()
+}
+
+/// Formats a call to `parseObject()` for parsing an object of the given type,
+/// passing along its validator and normalizer if it needs them.
+///
+/// `path` describes where the object came from, for use in validation errors.
+fn format_parse_object(
+    ty: Option<&TypeIdent>,
+    fat_ptr: &str,
+    path: &str,
+    types: &TypeMap,
+    int64_repr: Int64Repr,
+    normalizers: Option<&Normalizers>,
+    validators: Option<&Validators>,
+) -> String {
+    let type_arg = ty
+        .map(|ty| format_ident(ty, types, "types.", int64_repr))
+        .unwrap_or_else(|| "void".to_owned());
+
+    let mut args = vec![fat_ptr.to_owned()];
+    if let Some(validators) = validators {
+        args.push(
+            ty.map(|ty| validators.format_validator(ty))
+                .unwrap_or_else(|| "validateUnit".to_owned()),
+        );
+        args.push(format!("{path:?}"));
+    }
+    if let Some(normalizer) = ty.and_then(|ty| normalizers?.format_normalizer(ty)) {
+        args.push(normalizer);
+    }
+    format!("parseObject<{type_arg}>({})", args.join(", "))
+}
+
+/// Formats a call to `serializeObject()` for serializing the given value,
+/// passing along its denormalizer if it needs one.
+fn format_serialize_object(
+    ty: Option<&TypeIdent>,
+    value: &str,
+    denormalizers: Option<&Normalizers>,
+) -> String {
+    match ty.and_then(|ty| denormalizers?.format_normalizer(ty)) {
+        Some(denormalizer) => format!("serializeObject({value}, {denormalizer})"),
+        None => format!("serializeObject({value})"),
+    }
+}
+
 fn format_raw_export_wrappers(export_functions: &FunctionList) -> Vec<String> {
     export_functions
         .into_iter()
         .filter(|function| !is_primitive_function(function))
         .flat_map(|function| {
             let name = &function.name;
+            let export_name = get_export_name(function);
             let args = function
                 .args
                 .iter()
@@ -607,6 +1366,7 @@ fn format_raw_export_wrappers(export_functions: &FunctionList) -> Vec<String> {
                     )
                 })
                 .collect::<Vec<_>>();
+            let export_args = with_subscription_check(function, export_args, "new Uint8Array()");
 
             let call_args = function
                 .args
@@ -652,9 +1412,7 @@ fn format_raw_export_wrappers(export_functions: &FunctionList) -> Vec<String> {
 
     {}
 }})(),",
-                name.to_camel_case(),
-                name,
-                return_fn
+                export_name, name, return_fn
             )
             .split('\n')
             .map(str::to_owned)
@@ -663,7 +1421,36 @@ fn format_raw_export_wrappers(export_functions: &FunctionList) -> Vec<String> {
         .collect()
 }
 
-fn generate_type_bindings(types: &TypeMap, path: &str) {
+/// Determines which conversion applies to every custom type, and updates the
+/// TypeScript type of the custom types accordingly.
+///
+/// Conversions configured through `custom_type_conversions` always apply,
+/// while those defined by the custom types themselves only apply if
+/// `convert_custom_types` is enabled.
+fn apply_custom_type_conversions(types: &mut TypeMap, config: &TsRuntimeConfig) {
+    for ty in types.values_mut() {
+        if let Type::Custom(custom) = ty {
+            let conversion = match config.custom_type_conversions.get(&custom.ident.name) {
+                Some(conversion) => Some(conversion.clone()),
+                None if config.convert_custom_types => custom.ts_conversion.take(),
+                None => None,
+            };
+            if let Some(conversion) = &conversion {
+                custom.ts_ty = conversion.ty.clone();
+                custom.ts_declaration = None;
+            }
+            custom.ts_conversion = conversion;
+        }
+    }
+}
+
+fn generate_type_bindings(
+    types: &TypeMap,
+    path: &str,
+    int64_repr: Int64Repr,
+    generate_enum_helpers: bool,
+    files: &mut GeneratedFiles,
+) {
     let type_defs = types
         .values()
         .filter_map(|ty| match ty {
@@ -672,11 +1459,11 @@ fn generate_type_bindings(types: &TypeMap, path: &str) {
                 name,
                 // Now we're in a real pickle: We don't know the context in
                 // which this alias will be used. It could be either a plain
-                // primitive or a MessagePack-encoded one, so we account for
-                // both cases:
-                match ty.name.as_str() {
-                    "i64" | "u64" => "number | bigint".to_owned(),
-                    _ => format_ident(ty, types, ""),
+                // primitive or a MessagePack-encoded one, so unless both are
+                // represented as BigInt, we account for both cases:
+                match (ty.name.as_str(), int64_repr) {
+                    ("i64" | "u64", Int64Repr::Number) => "number | bigint".to_owned(),
+                    _ => format_ident(ty, types, "", int64_repr),
                 }
             )),
             Type::Custom(CustomType {
@@ -684,13 +1471,18 @@ fn generate_type_bindings(types: &TypeMap, path: &str) {
                 ts_declaration: Some(ts_declaration),
                 ..
             }) => Some(format!("export type {ts_ty} = {ts_declaration};")),
-            Type::Enum(ty) => Some(create_enum_definition(ty, types)),
-            Type::Struct(ty) => Some(create_struct_definition(ty, types)),
+            Type::Enum(ty) if generate_enum_helpers => Some(format!(
+                "{}\n\n{}",
+                create_enum_definition(ty, types, int64_repr),
+                enum_helpers::format_enum_helpers(ty, types, get_enum_int64_repr(ty, int64_repr))
+            )),
+            Type::Enum(ty) => Some(create_enum_definition(ty, types, int64_repr)),
+            Type::Struct(ty) => Some(create_struct_definition(ty, types, int64_repr)),
             _ => None,
         })
         .collect::<Vec<_>>();
 
-    write_bindings_file(
+    files.insert(
         format!("{path}/types.ts"),
         format!(
             "// ============================================= //
@@ -718,7 +1510,9 @@ fn is_primitive_function(function: &Function) -> bool {
             .unwrap_or(true)
 }
 
-fn create_enum_definition(ty: &Enum, types: &TypeMap) -> String {
+fn create_enum_definition(ty: &Enum, types: &TypeMap, int64_repr: Int64Repr) -> String {
+    let int64_repr = get_enum_int64_repr(ty, int64_repr);
+
     let variants = ty
         .variants
         .iter()
@@ -739,7 +1533,7 @@ fn create_enum_definition(ty: &Enum, types: &TypeMap) -> String {
                             format_struct_fields(
                                 &struct_variant.fields,
                                 types,
-                                variant.attrs.field_casing
+                                variant.attrs.field_casing, int64_repr
                             )
                             .join(" ")
                         )
@@ -747,7 +1541,7 @@ fn create_enum_definition(ty: &Enum, types: &TypeMap) -> String {
                         let field_lines = format_struct_fields(
                             &struct_variant.fields,
                             types,
-                            variant.attrs.field_casing,
+                            variant.attrs.field_casing, int64_repr,
                         );
                         let formatted_fields = if field_lines.len() > struct_variant.fields.len() {
                             format!(
@@ -783,7 +1577,7 @@ fn create_enum_definition(ty: &Enum, types: &TypeMap) -> String {
                 Type::Tuple(items) if items.len() == 1 => {
                     let item = items.first().unwrap();
                     if ty.options.untagged {
-                        format!("| {}", format_ident(item, types, ""))
+                        format!("| {}", format_ident(item, types, "", int64_repr))
                     } else {
                         match (&ty.options.tag_prop_name, &ty.options.content_prop_name) {
                             (Some(tag), Some(content)) => {
@@ -792,7 +1586,7 @@ fn create_enum_definition(ty: &Enum, types: &TypeMap) -> String {
                                     tag,
                                     variant_name,
                                     content,
-                                    format_ident(item, types, "")
+                                    format_ident(item, types, "", int64_repr)
                                 )
                             }
                             (Some(tag), None) => {
@@ -800,14 +1594,14 @@ fn create_enum_definition(ty: &Enum, types: &TypeMap) -> String {
                                     "| {{ {}: \"{}\" }} & {}",
                                     tag,
                                     variant_name,
-                                    format_ident(item, types, "")
+                                    format_ident(item, types, "", int64_repr)
                                 )
                             }
                             (None, _) => {
                                 format!(
                                     "| {{ {}: {} }}",
                                     variant_name,
-                                    format_ident(item, types, "")
+                                    format_ident(item, types, "", int64_repr)
                                 )
                             }
                         }
@@ -845,7 +1639,17 @@ fn create_enum_definition(ty: &Enum, types: &TypeMap) -> String {
     )
 }
 
-fn create_struct_definition(ty: &Struct, types: &TypeMap) -> String {
+/// Returns the representation of 64-bit integers inside the given enum.
+fn get_enum_int64_repr(ty: &Enum, int64_repr: Int64Repr) -> Int64Repr {
+    // The variant of an untagged enum cannot be determined reliably, so we
+    // don't know which of its integers to normalize:
+    match int64_repr {
+        Int64Repr::BigInt if ty.options.untagged => Int64Repr::NumberOrBigInt,
+        int64_repr => int64_repr,
+    }
+}
+
+fn create_struct_definition(ty: &Struct, types: &TypeMap, int64_repr: Int64Repr) -> String {
     let is_newtype = ty.fields.len() == 1 && ty.fields.iter().any(|field| field.name.is_none());
     if is_newtype {
         format!(
@@ -854,7 +1658,7 @@ fn create_struct_definition(ty: &Struct, types: &TypeMap) -> String {
             ty.ident,
             ty.fields
                 .first()
-                .map(|field| format_ident(&field.ty, types, ""))
+                .map(|field| format_ident(&field.ty, types, "", int64_repr))
                 .unwrap()
         )
     } else {
@@ -869,7 +1673,8 @@ fn create_struct_definition(ty: &Struct, types: &TypeMap) -> String {
                 &format_struct_fields(
                     &fields.into_iter().cloned().collect::<Vec<_>>(),
                     types,
-                    ty.options.field_casing
+                    ty.options.field_casing,
+                    int64_repr
                 ),
                 |line| format!("    {line}")
             )
@@ -899,7 +1704,12 @@ fn format_docs(doc_lines: &[String]) -> Vec<String> {
     }
 }
 
-fn format_struct_fields(fields: &[Field], types: &TypeMap, casing: Casing) -> Vec<String> {
+fn format_struct_fields(
+    fields: &[Field],
+    types: &TypeMap,
+    casing: Casing,
+    int64_repr: Int64Repr,
+) -> Vec<String> {
     fields
         .iter()
         .flat_map(|field| {
@@ -920,7 +1730,7 @@ fn format_struct_fields(fields: &[Field], types: &TypeMap, casing: Casing) -> Ve
                         } else {
                             ""
                         },
-                        format_ident(arg, types, ""),
+                        format_ident(arg, types, "", int64_repr),
                         if is_option_type && !has_skip_serializing_attribute {
                             " | null"
                         } else {
@@ -936,7 +1746,7 @@ fn format_struct_fields(fields: &[Field], types: &TypeMap, casing: Casing) -> Ve
                     } else {
                         ""
                     },
-                    format_ident(&field.ty, types, ""),
+                    format_ident(&field.ty, types, "", int64_repr),
                 ),
             };
             if field.doc_lines.is_empty() {
@@ -960,15 +1770,21 @@ fn format_raw_type(ty: &TypeIdent) -> &str {
 }
 
 /// Formats a type so it's valid TypeScript.
-fn format_ident(ident: &TypeIdent, types: &TypeMap, scope: &str) -> String {
+fn format_ident(ident: &TypeIdent, types: &TypeMap, scope: &str, int64_repr: Int64Repr) -> String {
     match types.get(ident) {
-        Some(ty) => format_type_with_ident(ty, ident, types, scope),
+        Some(ty) => format_type_with_ident(ty, ident, types, scope, int64_repr),
         None => ident.to_string(), // Must be a generic.
     }
 }
 
 /// Formats a type so it's valid TypeScript.
-fn format_type_with_ident(ty: &Type, ident: &TypeIdent, types: &TypeMap, scope: &str) -> String {
+fn format_type_with_ident(
+    ty: &Type,
+    ident: &TypeIdent,
+    types: &TypeMap,
+    scope: &str,
+    int64_repr: Int64Repr,
+) -> String {
     match ty {
         Type::Alias(name, _) => format!("{scope}{name}"),
         Type::Array(primitive, _) => primitive.js_array_name().unwrap_or_else(|| {
@@ -977,6 +1793,21 @@ fn format_type_with_ident(ty: &Type, ident: &TypeIdent, types: &TypeMap, scope:
                 primitive
             )
         }),
+        Type::Callback(_, _) => {
+            let (arg, _) = ident
+                .generic_args
+                .first()
+                .expect("Identifier was expected to contain a generic argument");
+            let (ret, _) = ident
+                .generic_args
+                .get(1)
+                .expect("Identifier was expected to contain two arguments");
+            format!(
+                "(arg: {}) => {}",
+                format_ident(arg, types, scope, int64_repr),
+                format_ident(ret, types, scope, int64_repr)
+            )
+        }
         Type::Container(name, _) => {
             let (arg, _) = ident
                 .generic_args
@@ -984,9 +1815,9 @@ fn format_type_with_ident(ty: &Type, ident: &TypeIdent, types: &TypeMap, scope:
                 .expect("Identifier was expected to contain a generic argument");
 
             if name == "Option" {
-                format!("{} | null", format_ident(arg, types, scope))
+                format!("{} | null", format_ident(arg, types, scope, int64_repr))
             } else {
-                format_ident(arg, types, scope)
+                format_ident(arg, types, scope, int64_repr)
             }
         }
         Type::Custom(custom) => custom.ts_ty.clone(),
@@ -994,7 +1825,7 @@ fn format_type_with_ident(ty: &Type, ident: &TypeIdent, types: &TypeMap, scope:
             let args: Vec<_> = ident
                 .generic_args
                 .iter()
-                .map(|(arg, _)| format_ident(arg, types, scope))
+                .map(|(arg, _)| format_ident(arg, types, scope, int64_repr))
                 .collect();
             if args.is_empty() {
                 format!("{}{}", scope, ident.name)
@@ -1007,7 +1838,7 @@ fn format_type_with_ident(ty: &Type, ident: &TypeIdent, types: &TypeMap, scope:
                 .generic_args
                 .first()
                 .expect("Identifier was expected to contain a generic argument");
-            format!("Array<{}>", format_ident(arg, types, scope))
+            format!("Array<{}>", format_ident(arg, types, scope, int64_repr))
         }
         Type::Map(_, _, _) => {
             let (arg1, _) = ident
@@ -1020,17 +1851,18 @@ fn format_type_with_ident(ty: &Type, ident: &TypeIdent, types: &TypeMap, scope:
                 .expect("Identifier was expected to contain two arguments");
             format!(
                 "Record<{}, {}>",
-                format_ident(arg1, types, scope),
-                format_ident(arg2, types, scope)
+                // Object keys cannot be `bigint`:
+                format_ident(arg1, types, scope, Int64Repr::Number),
+                format_ident(arg2, types, scope, int64_repr)
             )
         }
-        Type::Primitive(primitive) => format_encoded_primitive(*primitive).to_owned(),
+        Type::Primitive(primitive) => format_encoded_primitive(*primitive, int64_repr).to_owned(),
         Type::String => "string".to_owned(),
         Type::Tuple(items) => format!(
             "[{}]",
             items
                 .iter()
-                .map(|item| format_ident(item, types, scope))
+                .map(|item| format_ident(item, types, scope, int64_repr))
                 .collect::<Vec<_>>()
                 .join(", ")
         ),
@@ -1051,29 +1883,69 @@ fn format_plain_primitive(primitive: Primitive) -> &'static str {
         Primitive::U16 => "number",
         Primitive::U32 => "number",
         Primitive::U64 => "bigint",
+        Primitive::I128 => "bigint",
+        Primitive::U128 => "bigint",
+        Primitive::Char => "string",
     }
 }
 
-fn format_plain_primitive_or_ident(ident: &TypeIdent, types: &TypeMap) -> String {
+fn format_plain_primitive_or_ident(
+    ident: &TypeIdent,
+    types: &TypeMap,
+    int64_repr: Int64Repr,
+) -> String {
     if let Some(primitive) = ident.as_primitive() {
         format_plain_primitive(primitive).to_owned()
     } else {
-        format_ident(ident, types, "types.")
+        format_ident(ident, types, "types.", int64_repr)
     }
 }
 
-// When encoded as part of a MessagePack type, 64-bit numbers are decoded into
-// regular numbers rather than BigInt. This effectively limits them to a maximum
-// value of `2^53 - 1`.
-// See: https://github.com/msgpack/msgpack-javascript/issues/115
-fn format_encoded_primitive(primitive: Primitive) -> &'static str {
-    match primitive {
-        Primitive::I64 => "number",
-        Primitive::U64 => "number",
-        primitive => format_plain_primitive(primitive),
+/// Determines how 64-bit integers are represented when encoded as part of a
+/// MessagePack type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Int64Repr {
+    /// 64-bit numbers are decoded into regular numbers rather than BigInt.
+    /// This effectively limits them to a maximum value of `2^53 - 1`.
+    /// See: https://github.com/msgpack/msgpack-javascript/issues/115
+    Number,
+
+    /// 64-bit numbers are decoded losslessly and normalized into BigInt. See
+    /// `TsRuntimeConfig::use_bigint_for_64bit_integers`.
+    BigInt,
+
+    /// 64-bit numbers are decoded losslessly, but cannot be normalized, so
+    /// small values remain regular numbers.
+    NumberOrBigInt,
+}
+
+fn format_encoded_primitive(primitive: Primitive, int64_repr: Int64Repr) -> &'static str {
+    match (primitive, int64_repr) {
+        (Primitive::I64 | Primitive::U64, Int64Repr::Number) => "number",
+        (Primitive::I64 | Primitive::U64, Int64Repr::NumberOrBigInt) => "number | bigint",
+        (primitive, _) => format_plain_primitive(primitive),
     }
 }
 
+/// Formats an object literal with the given properties.
+fn format_object(properties: impl IntoIterator<Item = (String, String)>) -> String {
+    let properties = properties
+        .into_iter()
+        .map(|(key, value)| {
+            let is_identifier = key.chars().enumerate().all(|(i, c)| {
+                c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())
+            });
+            if is_identifier {
+                format!("{key}: {value}")
+            } else {
+                format!("{key:?}: {value}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{{ {properties} }}")
+}
+
 fn get_field_name(field: &Field, casing: Casing) -> String {
     if let Some(rename) = field.attrs.rename.as_ref() {
         rename.to_owned()
@@ -1139,10 +2011,3 @@ where
         format!("{lines}\n")
     }
 }
-
-fn write_bindings_file<C>(file_path: String, contents: C)
-where
-    C: AsRef<[u8]>,
-{
-    fs::write(file_path, &contents).expect("Could not write bindings file");
-}
@@ -0,0 +1,594 @@
+use super::{format_object, get_field_name, get_variant_name};
+use crate::{
+    casing::Casing,
+    primitives::Primitive,
+    types::{CustomType, Enum, Field, Struct, TsTypeConversion, Type, TypeIdent, TypeMap},
+};
+use std::collections::BTreeSet;
+
+/// Generates normalizers: functions that convert decoded MessagePack values
+/// into the representation used by the runtime, or back again before encoding.
+///
+/// When decoding, normalizers convert 64-bit integers to `bigint`: MessagePack
+/// encoders use the most compact representation available for every integer,
+/// so the decoder cannot know whether a small integer was supposed to be a
+/// 64-bit one. Normalizers use the types from the protocol to find out
+/// instead.
+///
+/// 128-bit integers don't fit in any MessagePack integer, so they are encoded
+/// as 16 bytes instead. Normalizers convert these to and from `bigint`.
+///
+/// In both directions, normalizers also apply the conversions of custom types
+/// that have a [TsTypeConversion], and convert compact structs between the
+/// arrays they are encoded as and the objects used by the runtime.
+pub(super) struct Normalizers<'a> {
+    types: &'a TypeMap,
+
+    direction: Direction,
+
+    /// Whether 64-bit integers need to be converted to `bigint`.
+    normalize_int64: bool,
+
+    /// Names of the types that need to be normalized, regardless of their
+    /// generic arguments.
+    always_normalized: BTreeSet<String>,
+
+    /// Names of the generic types that need to be normalized if (one of) their
+    /// generic arguments need to be normalized.
+    normalized_with_args: BTreeSet<String>,
+}
+
+/// The direction in which normalizers convert values.
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    /// From decoded values to the values used by the runtime.
+    Decode,
+    /// From the values used by the runtime to the values to encode.
+    Encode,
+}
+
+impl<'a> Normalizers<'a> {
+    /// Creates the normalizers for values that are decoded, or `None` if no
+    /// decoded value needs to be normalized.
+    pub fn for_decoding(types: &'a TypeMap, normalize_int64: bool) -> Option<Self> {
+        let has_conversions = types.values().any(|ty| get_conversion(ty).is_some());
+        (normalize_int64 || has_conversions || has_int128(types) || has_compact_structs(types))
+            .then(|| Self::new(types, Direction::Decode, normalize_int64))
+    }
+
+    /// Creates the normalizers for values that are about to be encoded, or
+    /// `None` if no value needs to be normalized before encoding.
+    ///
+    /// These are called "denormalizers" in the generated code.
+    pub fn for_encoding(types: &'a TypeMap) -> Option<Self> {
+        types
+            .values()
+            .any(|ty| {
+                get_conversion(ty).is_some_and(|conversion| conversion.encode.is_some())
+                    || is_int128(ty)
+                    || is_compact_struct(ty)
+            })
+            .then(|| Self::new(types, Direction::Encode, false))
+    }
+
+    fn new(types: &'a TypeMap, direction: Direction, normalize_int64: bool) -> Self {
+        let mut normalizers = Self {
+            types,
+            direction,
+            normalize_int64,
+            always_normalized: BTreeSet::new(),
+            normalized_with_args: BTreeSet::new(),
+        };
+
+        // Types may reference one another, even recursively, so we keep
+        // iterating until we stop discovering types that need normalizing:
+        loop {
+            let mut changed = false;
+            for ty in types.values() {
+                let ident = match ty {
+                    Type::Enum(Enum { ident, .. }) | Type::Struct(Struct { ident, .. }) => ident,
+                    _ => continue,
+                };
+
+                if !normalizers.always_normalized.contains(&ident.name)
+                    && normalizers.format_definition(ty, &[]).is_some()
+                {
+                    normalizers.always_normalized.insert(ident.name.clone());
+                    changed = true;
+                }
+
+                let params = get_generic_params(ident);
+                if !params.is_empty()
+                    && !normalizers.normalized_with_args.contains(&ident.name)
+                    && normalizers.format_definition(ty, &params).is_some()
+                {
+                    normalizers.normalized_with_args.insert(ident.name.clone());
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break normalizers;
+            }
+        }
+    }
+
+    /// Returns an expression that evaluates to the normalizer for the given
+    /// type, or `None` if values of the type don't need to be normalized.
+    pub fn format_normalizer(&self, ident: &TypeIdent) -> Option<String> {
+        self.format_ident(ident, &[])
+    }
+
+    /// Formats the functions for all the types that need to be normalized.
+    ///
+    /// The helpers these functions rely on are not included, because they are
+    /// shared between both directions. See [NORMALIZER_HELPERS].
+    pub fn format_functions(&self) -> String {
+        let int128_functions = if has_int128(self.types) {
+            match self.direction {
+                Direction::Decode => INT128_NORMALIZERS,
+                Direction::Encode => INT128_DENORMALIZERS,
+            }
+        } else {
+            ""
+        };
+
+        let compact_functions = if has_compact_structs(self.types) {
+            match self.direction {
+                Direction::Decode => COMPACT_NORMALIZERS,
+                Direction::Encode => COMPACT_DENORMALIZERS,
+            }
+        } else {
+            ""
+        };
+
+        let functions = self
+            .types
+            .values()
+            .filter_map(|ty| match ty {
+                Type::Custom(CustomType { ident, .. }) => {
+                    let conversion = self.get_conversion_function(ty)?;
+                    Some(format!(
+                        "\nconst {}: Normalizer = {conversion};\n",
+                        self.format_normalizer_name(&ident.name)
+                    ))
+                }
+                Type::Enum(Enum { ident, .. }) | Type::Struct(Struct { ident, .. })
+                    if self.always_normalized.contains(&ident.name)
+                        || self.normalized_with_args.contains(&ident.name) =>
+                {
+                    Some(self.format_function(ty, ident))
+                }
+                _ => None,
+            })
+            .collect::<String>();
+
+        format!("{int128_functions}{compact_functions}{functions}")
+    }
+
+    fn format_function(&self, ty: &Type, ident: &TypeIdent) -> String {
+        let name = self.format_normalizer_name(&ident.name);
+        let params = get_generic_params(ident);
+        let normalizer = self
+            .format_definition(ty, &params)
+            .unwrap_or_else(|| "identity".to_owned());
+
+        if params.is_empty() {
+            format!(
+                "
+function {name}(value: any): any {{
+    return {normalizer}(value);
+}}
+"
+            )
+        } else {
+            let params = params
+                .iter()
+                .map(|param| format!("{}: Normalizer", self.format_normalizer_name(param)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "
+function {name}({params}): Normalizer {{
+    return (value) => {normalizer}(value);
+}}
+"
+            )
+        }
+    }
+
+    /// Formats the normalizer for the definition of a struct or enum.
+    ///
+    /// `params` are the generic parameters of the definition, for which the
+    /// normalizers are passed as arguments to the normalizer function.
+    fn format_definition(&self, ty: &Type, params: &[String]) -> Option<String> {
+        match ty {
+            Type::Struct(ty) if ty.is_compact() => Some(self.format_compact_struct(ty, params)),
+            Type::Struct(ty) => {
+                if ty.fields.iter().any(|field| field.name.is_none()) {
+                    let items = ty.fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+                    match items.as_slice() {
+                        [item] => self.format_ident(item, params),
+                        items => self.format_tuple(items, params),
+                    }
+                } else {
+                    self.format_fields(&ty.fields, ty.options.field_casing, params)
+                }
+            }
+            Type::Enum(ty) => self.format_enum(ty, params),
+            _ => None,
+        }
+    }
+
+    /// Formats the normalizer for a compact struct, which converts between
+    /// the array the struct is encoded as and the object used by the runtime.
+    fn format_compact_struct(&self, ty: &Struct, params: &[String]) -> String {
+        let field_names = ty
+            .fields
+            .iter()
+            .map(|field| format!("{:?}", get_field_name(field, ty.options.field_casing)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let fields = self.format_fields(&ty.fields, ty.options.field_casing, params);
+        match (self.direction, fields) {
+            (Direction::Decode, Some(fields)) => {
+                format!("normalizeAll(normalizeCompact([{field_names}]), {fields})")
+            }
+            (Direction::Decode, None) => format!("normalizeCompact([{field_names}])"),
+            (Direction::Encode, Some(fields)) => format!(
+                "normalizeAll({fields}, denormalizeCompact([{field_names}], {}))",
+                ty.min_compact_len()
+            ),
+            (Direction::Encode, None) => format!(
+                "denormalizeCompact([{field_names}], {})",
+                ty.min_compact_len()
+            ),
+        }
+    }
+
+    fn format_enum(&self, ty: &Enum, params: &[String]) -> Option<String> {
+        // The variant of an untagged enum cannot be determined reliably, so we
+        // leave them alone:
+        if ty.options.untagged {
+            return None;
+        }
+
+        let variants = ty
+            .variants
+            .iter()
+            .filter_map(|variant| {
+                let normalizer = match &variant.ty {
+                    Type::Struct(ty) => {
+                        self.format_fields(&ty.fields, variant.attrs.field_casing, params)
+                    }
+                    Type::Tuple(items) if items.len() == 1 => self.format_ident(&items[0], params),
+                    _ => None,
+                }?;
+                Some((get_variant_name(variant, &ty.options), normalizer))
+            })
+            .collect::<Vec<_>>();
+        if variants.is_empty() {
+            return None;
+        }
+
+        let normalizer = match (&ty.options.tag_prop_name, &ty.options.content_prop_name) {
+            (Some(tag), Some(content)) => format!(
+                "normalizeTagged({tag:?}, {})",
+                format_object(variants.into_iter().map(|(name, normalizer)| {
+                    let normalizer = format!(
+                        "normalizeFields({})",
+                        format_object([(content.clone(), normalizer)])
+                    );
+                    (name, normalizer)
+                }))
+            ),
+            (Some(tag), None) => format!("normalizeTagged({tag:?}, {})", format_object(variants)),
+            (None, _) => format!("normalizeFields({})", format_object(variants)),
+        };
+        Some(normalizer)
+    }
+
+    fn format_fields(&self, fields: &[Field], casing: Casing, params: &[String]) -> Option<String> {
+        let (flattened, fields): (Vec<_>, Vec<_>) =
+            fields.iter().partition(|field| field.attrs.flatten);
+
+        let mut normalizers = flattened
+            .into_iter()
+            .filter_map(|field| self.format_ident(&field.ty, params))
+            .collect::<Vec<_>>();
+
+        let fields = fields
+            .into_iter()
+            .filter_map(|field| {
+                let normalizer = self.format_ident(&field.ty, params)?;
+                Some((get_field_name(field, casing), normalizer))
+            })
+            .collect::<Vec<_>>();
+        if !fields.is_empty() {
+            normalizers.push(format!("normalizeFields({})", format_object(fields)));
+        }
+
+        match normalizers.len() {
+            0 => None,
+            1 => normalizers.pop(),
+            _ => Some(format!("normalizeAll({})", normalizers.join(", "))),
+        }
+    }
+
+    fn format_ident(&self, ident: &TypeIdent, params: &[String]) -> Option<String> {
+        if ident.generic_args.is_empty() && params.contains(&ident.name) {
+            return Some(self.format_normalizer_name(&ident.name));
+        }
+
+        let generic_arg = |index: usize| {
+            ident
+                .generic_args
+                .get(index)
+                .and_then(|(arg, _)| self.format_ident(arg, params))
+        };
+
+        match self.types.get(ident)? {
+            Type::Alias(_, target) => self.format_ident(target, params),
+            // Values passed to callbacks are encoded separately, without
+            // knowledge of their types:
+            Type::Callback(_, _) => None,
+            Type::Container(name, _) if name == "Option" => {
+                Some(format!("normalizeOption({})", generic_arg(0)?))
+            }
+            Type::Container(_, _) => generic_arg(0),
+            Type::List(_, _) => Some(format!("normalizeList({})", generic_arg(0)?)),
+            Type::Map(_, _, _) => Some(format!("normalizeMap({})", generic_arg(1)?)),
+            Type::Primitive(Primitive::I64 | Primitive::U64) if self.normalize_int64 => {
+                Some("normalizeInt64".to_owned())
+            }
+            Type::Primitive(primitive @ (Primitive::I128 | Primitive::U128)) => {
+                Some(match (self.direction, primitive) {
+                    (Direction::Decode, Primitive::I128) => "normalizeI128".to_owned(),
+                    (Direction::Decode, _) => "normalizeU128".to_owned(),
+                    (Direction::Encode, _) => "denormalizeInt128".to_owned(),
+                })
+            }
+            Type::Tuple(items) => self.format_tuple(&items.iter().collect::<Vec<_>>(), params),
+            Type::Enum(Enum {
+                ident: definition, ..
+            })
+            | Type::Struct(Struct {
+                ident: definition, ..
+            }) => {
+                let name = self.format_normalizer_name(&definition.name);
+                if definition.generic_args.is_empty() {
+                    return self
+                        .always_normalized
+                        .contains(&definition.name)
+                        .then_some(name);
+                }
+
+                let args = ident
+                    .generic_args
+                    .iter()
+                    .map(|(arg, _)| self.format_ident(arg, params))
+                    .collect::<Vec<_>>();
+                let is_normalized = self.always_normalized.contains(&definition.name)
+                    || (self.normalized_with_args.contains(&definition.name)
+                        && args.iter().any(Option::is_some));
+                is_normalized.then(|| {
+                    let args = args
+                        .into_iter()
+                        .map(|arg| arg.unwrap_or_else(|| "identity".to_owned()))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("{name}({args})")
+                })
+            }
+            ty @ Type::Custom(CustomType {
+                ident: definition, ..
+            }) => self
+                .get_conversion_function(ty)
+                .map(|_| self.format_normalizer_name(&definition.name)),
+            Type::Array(_, _) | Type::Primitive(_) | Type::String | Type::Unit => None,
+        }
+    }
+
+    fn format_tuple(&self, items: &[&TypeIdent], params: &[String]) -> Option<String> {
+        let normalizers = items
+            .iter()
+            .map(|item| self.format_ident(item, params))
+            .collect::<Vec<_>>();
+        if normalizers.iter().all(Option::is_none) {
+            return None;
+        }
+
+        let normalizers = normalizers
+            .into_iter()
+            .map(|normalizer| normalizer.unwrap_or_else(|| "identity".to_owned()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!("normalizeTuple([{normalizers}])"))
+    }
+}
+
+impl Normalizers<'_> {
+    /// Returns the function that converts values of the given custom type in
+    /// the direction of these normalizers, if any.
+    fn get_conversion_function<'t>(&self, ty: &'t Type) -> Option<&'t str> {
+        let conversion = get_conversion(ty)?;
+        match self.direction {
+            Direction::Decode => Some(&conversion.decode),
+            Direction::Encode => conversion.encode.as_deref(),
+        }
+    }
+
+    fn format_normalizer_name(&self, type_name: &str) -> String {
+        // Custom types may be referred to by their path:
+        let type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+        match self.direction {
+            Direction::Decode => format!("normalize{type_name}"),
+            Direction::Encode => format!("denormalize{type_name}"),
+        }
+    }
+}
+
+fn get_conversion(ty: &Type) -> Option<&TsTypeConversion> {
+    match ty {
+        Type::Custom(custom) => custom.ts_conversion.as_ref(),
+        _ => None,
+    }
+}
+
+fn has_compact_structs(types: &TypeMap) -> bool {
+    types.values().any(is_compact_struct)
+}
+
+fn is_compact_struct(ty: &Type) -> bool {
+    matches!(ty, Type::Struct(ty) if ty.is_compact())
+}
+
+fn has_int128(types: &TypeMap) -> bool {
+    types.values().any(is_int128)
+}
+
+fn is_int128(ty: &Type) -> bool {
+    matches!(ty, Type::Primitive(Primitive::I128 | Primitive::U128))
+}
+
+fn get_generic_params(ident: &TypeIdent) -> Vec<String> {
+    ident
+        .generic_args
+        .iter()
+        .map(|(arg, _)| arg.name.clone())
+        .collect()
+}
+
+/// Helpers used by the normalizers in both directions.
+pub(super) const NORMALIZER_HELPERS: &str = "
+/**
+ * Converts a decoded value into the representation used by the runtime, or
+ * back again before encoding.
+ *
+ * Normalizers are needed because integers that don't require 64 bits are
+ * encoded more compactly, and are therefore decoded as `number`, and because
+ * some types are represented differently at runtime than they are encoded.
+ */
+type Normalizer = (value: any) => any;
+
+const identity: Normalizer = (value) => value;
+
+function normalizeInt64(value: any): any {
+    return typeof value === \"number\" ? BigInt(value) : value;
+}
+
+function normalizeOption(normalize: Normalizer): Normalizer {
+    return (value) => (value === null || value === undefined ? value : normalize(value));
+}
+
+function normalizeList(normalize: Normalizer): Normalizer {
+    return (value) => value.map((item: any) => normalize(item));
+}
+
+function normalizeMap(normalize: Normalizer): Normalizer {
+    return (value) =>
+        Object.fromEntries(Object.entries(value).map(([key, item]) => [key, normalize(item)]));
+}
+
+function normalizeTuple(normalizers: Array<Normalizer>): Normalizer {
+    return (value) => value.map((item: any, index: number) => normalizers[index](item));
+}
+
+function normalizeFields(normalizers: Record<string, Normalizer>): Normalizer {
+    return (value) => {
+        if (typeof value !== \"object\" || value === null) {
+            return value;
+        }
+
+        const result = { ...value };
+        for (const [key, normalize] of Object.entries(normalizers)) {
+            if (result[key] !== null && result[key] !== undefined) {
+                result[key] = normalize(result[key]);
+            }
+        }
+        return result;
+    };
+}
+
+function normalizeTagged(tagProp: string, normalizers: Record<string, Normalizer>): Normalizer {
+    return (value) => {
+        const normalize = normalizers[value?.[tagProp]];
+        return normalize ? normalize(value) : value;
+    };
+}
+
+function normalizeAll(...normalizers: Array<Normalizer>): Normalizer {
+    return (value) => normalizers.reduce((result, normalize) => normalize(result), value);
+}
+";
+
+const INT128_NORMALIZERS: &str = "
+function normalizeU128(value: any): any {
+    if (!(value instanceof Uint8Array)) {
+        return BigInt(value);
+    }
+
+    return value.reduce((result, byte) => (result << 8n) | BigInt(byte), 0n);
+}
+
+function normalizeI128(value: any): any {
+    return BigInt.asIntN(128, normalizeU128(value));
+}
+";
+
+const INT128_DENORMALIZERS: &str = "
+function denormalizeInt128(value: any): any {
+    // 128-bit integers are encoded as 16 bytes in big-endian order, using
+    // two's complement for negative numbers:
+    let remaining = BigInt.asUintN(128, BigInt(value));
+    const bytes = new Uint8Array(16);
+    for (let index = 15; index >= 0; index--) {
+        bytes[index] = Number(remaining & 0xffn);
+        remaining >>= 8n;
+    }
+    return bytes;
+}
+";
+
+const COMPACT_NORMALIZERS: &str = "
+/**
+ * Converts a compact struct, which is encoded as an array of its field values,
+ * into an object. Trailing fields that were omitted are left out.
+ */
+function normalizeCompact(fields: Array<string>): Normalizer {
+    return (value) => {
+        if (!Array.isArray(value)) {
+            return value;
+        }
+
+        const result: Record<string, any> = {};
+        fields.forEach((field, index) => {
+            if (index < value.length) {
+                result[field] = value[index];
+            }
+        });
+        return result;
+    };
+}
+";
+
+const COMPACT_DENORMALIZERS: &str = "
+/**
+ * Converts an object into the array a compact struct is encoded as. Trailing
+ * fields beyond `minLength` that are `undefined` are omitted, so the plugin
+ * uses their default values.
+ */
+function denormalizeCompact(fields: Array<string>, minLength: number): Normalizer {
+    return (value) => {
+        if (typeof value !== \"object\" || value === null || Array.isArray(value)) {
+            return value;
+        }
+
+        let length = fields.length;
+        while (length > minLength && value[fields[length - 1]] === undefined) {
+            length--;
+        }
+        return fields.slice(0, length).map((field) => value[field] ?? null);
+    };
+}
+";
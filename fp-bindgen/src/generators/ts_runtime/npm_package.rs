@@ -0,0 +1,132 @@
+use crate::{
+    generators::{GeneratedFiles, TsWasiShim},
+    TsNpmPackageConfig, TsRuntimeConfig,
+};
+
+/// Generates the files that turn the TypeScript runtime into an npm package:
+/// the `package.json` and the TypeScript configs for compiling the sources in
+/// `src/` to ES module and CommonJS entry points.
+pub(super) fn generate_package_files(
+    package: &TsNpmPackageConfig,
+    config: &TsRuntimeConfig,
+    path: &str,
+    files: &mut GeneratedFiles,
+) {
+    files.insert(
+        format!("{path}/package.json"),
+        format_package_json(package, config),
+    );
+    files.insert(format!("{path}/tsconfig.json"), TSCONFIG_JSON);
+    files.insert(format!("{path}/tsconfig.cjs.json"), TSCONFIG_CJS_JSON);
+}
+
+fn format_package_json(package: &TsNpmPackageConfig, config: &TsRuntimeConfig) -> String {
+    let mut metadata = vec![("name", &package.name), ("version", &package.version)];
+    if let Some(description) = &package.description {
+        metadata.push(("description", description));
+    }
+    if let Some(license) = &package.license {
+        metadata.push(("license", license));
+    }
+    let metadata = metadata
+        .into_iter()
+        .map(|(key, value)| format!("  \"{key}\": {},\n", format_string(value)))
+        .collect::<String>();
+
+    let msgpack_version = match &package.msgpack_version {
+        Some(version) => version.as_str(),
+        None if config.use_bigint_for_64bit_integers => "^2.8.0",
+        None => "^2.7.2",
+    };
+
+    let mut dev_dependencies = vec![("typescript", "^5.0.0")];
+    if config.wasi == Some(TsWasiShim::Node) {
+        dev_dependencies.insert(0, ("@types/node", "^18.0.0"));
+    }
+    let dev_dependencies = dev_dependencies
+        .into_iter()
+        .map(|(name, version)| format!("    \"{name}\": \"{version}\""))
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    let mut modules = vec![("./types", "types")];
+    if config.generate_web_worker {
+        modules.extend([("./worker", "worker"), ("./workerRuntime", "workerRuntime")]);
+    }
+    let module_exports = modules
+        .into_iter()
+        .map(|(path, module)| {
+            format!(
+                r#",
+    "{path}": {{
+      "types": "./dist/types/{module}.d.ts",
+      "import": "./dist/esm/{module}.js",
+      "require": "./dist/cjs/{module}.js"
+    }}"#
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        r#"{{
+{metadata}  "type": "module",
+  "main": "./dist/cjs/index.js",
+  "module": "./dist/esm/index.js",
+  "types": "./dist/types/index.d.ts",
+  "exports": {{
+    ".": {{
+      "types": "./dist/types/index.d.ts",
+      "import": "./dist/esm/index.js",
+      "require": "./dist/cjs/index.js"
+    }}{module_exports}
+  }},
+  "files": [
+    "dist"
+  ],
+  "scripts": {{
+    "build": "npm run build:esm && npm run build:cjs",
+    "build:esm": "tsc -p tsconfig.json",
+    "build:cjs": "tsc -p tsconfig.cjs.json && node -e \"require('fs').writeFileSync('dist/cjs/package.json', '{{\\\"type\\\": \\\"commonjs\\\"}}')\"",
+    "prepare": "npm run build"
+  }},
+  "dependencies": {{
+    "@msgpack/msgpack": {}
+  }},
+  "devDependencies": {{
+{dev_dependencies}
+  }}
+}}
+"#,
+        format_string(msgpack_version)
+    )
+}
+
+fn format_string(value: &str) -> String {
+    serde_json::to_string(value).expect("Could not serialize string")
+}
+
+const TSCONFIG_JSON: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2020",
+    "module": "ES2020",
+    "moduleResolution": "node",
+    "lib": ["ES2020", "DOM"],
+    "strict": true,
+    "skipLibCheck": true,
+    "declaration": true,
+    "declarationDir": "dist/types",
+    "outDir": "dist/esm"
+  },
+  "include": ["src"]
+}
+"#;
+
+const TSCONFIG_CJS_JSON: &str = r#"{
+  "extends": "./tsconfig.json",
+  "compilerOptions": {
+    "module": "CommonJS",
+    "declaration": false,
+    "outDir": "dist/cjs"
+  }
+}
+"#;
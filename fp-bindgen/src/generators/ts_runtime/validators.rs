@@ -0,0 +1,540 @@
+use super::{format_object, get_field_name, get_variant_name};
+use crate::{
+    casing::Casing,
+    primitives::Primitive,
+    types::{Enum, Field, Struct, Type, TypeIdent, TypeMap},
+};
+
+/// Generates validators: functions that verify decoded MessagePack values
+/// match the types from the protocol.
+///
+/// Validators throw a `ValidationError` for the first mismatch they encounter,
+/// which includes the path to the offending value.
+pub(super) struct Validators<'a> {
+    types: &'a TypeMap,
+}
+
+impl<'a> Validators<'a> {
+    pub fn new(types: &'a TypeMap) -> Self {
+        Self { types }
+    }
+
+    /// Returns an expression that evaluates to the validator for the given
+    /// type.
+    pub fn format_validator(&self, ident: &TypeIdent) -> String {
+        self.format_ident(ident, &[])
+    }
+
+    /// Formats the functions for validating all the structs and enums,
+    /// including the helpers they rely on.
+    pub fn format_functions(&self) -> String {
+        let functions = self
+            .types
+            .values()
+            .filter_map(|ty| match ty {
+                Type::Enum(Enum { ident, .. }) | Type::Struct(Struct { ident, .. }) => {
+                    Some(self.format_function(ty, ident))
+                }
+                _ => None,
+            })
+            .collect::<String>();
+
+        format!("{VALIDATOR_HELPERS}{functions}")
+    }
+
+    fn format_function(&self, ty: &Type, ident: &TypeIdent) -> String {
+        let name = format_validator_name(&ident.name);
+        let params = ident
+            .generic_args
+            .iter()
+            .map(|(arg, _)| arg.name.clone())
+            .collect::<Vec<_>>();
+        let validator = self.format_definition(ty, &params);
+
+        if params.is_empty() {
+            format!(
+                "
+function {name}(value: any, path: string) {{
+    {validator}(value, path);
+}}
+"
+            )
+        } else {
+            let params = params
+                .iter()
+                .map(|param| format!("{}: Validator", format_validator_name(param)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "
+function {name}({params}): Validator {{
+    return (value, path) => {validator}(value, path);
+}}
+"
+            )
+        }
+    }
+
+    /// Formats the validator for the definition of a struct or enum.
+    ///
+    /// `params` are the generic parameters of the definition, for which the
+    /// validators are passed as arguments to the validator function.
+    fn format_definition(&self, ty: &Type, params: &[String]) -> String {
+        match ty {
+            Type::Struct(ty) if ty.is_compact() => {
+                let fields = ty.fields.iter().map(|field| {
+                    (
+                        get_field_name(field, ty.options.field_casing),
+                        self.format_field(field, params),
+                    )
+                });
+                format!(
+                    "validateCompact({}, {})",
+                    format_object(fields),
+                    ty.min_compact_len()
+                )
+            }
+            Type::Struct(ty) => {
+                if ty.fields.iter().any(|field| field.name.is_none()) {
+                    let items = ty.fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+                    match items.as_slice() {
+                        [item] => self.format_ident(item, params),
+                        items => self.format_tuple(items, params),
+                    }
+                } else {
+                    self.format_fields(&ty.fields, ty.options.field_casing, params)
+                }
+            }
+            Type::Enum(ty) => self.format_enum(ty, params),
+            _ => "validateAny".to_owned(),
+        }
+    }
+
+    fn format_enum(&self, ty: &Enum, params: &[String]) -> String {
+        let format_variant = |ty: &Type, casing: Casing| match ty {
+            Type::Struct(ty) => self.format_fields(&ty.fields, casing, params),
+            Type::Tuple(items) if items.len() == 1 => self.format_ident(&items[0], params),
+            Type::Tuple(items) => self.format_tuple(&items.iter().collect::<Vec<_>>(), params),
+            _ => "validateUnit".to_owned(),
+        };
+
+        if ty.options.untagged {
+            let validators = ty
+                .variants
+                .iter()
+                .map(|variant| format_variant(&variant.ty, variant.attrs.field_casing))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return format!("validateUntagged({:?}, [{validators}])", ty.ident.name);
+        }
+
+        match (&ty.options.tag_prop_name, &ty.options.content_prop_name) {
+            (Some(tag), content) => {
+                let variants = ty.variants.iter().map(|variant| {
+                    let validator = match (&variant.ty, content) {
+                        (Type::Unit, _) => "validateAny".to_owned(),
+                        (ty, Some(content)) => format!(
+                            "validateFields({})",
+                            format_object([(
+                                content.clone(),
+                                format_variant(ty, variant.attrs.field_casing)
+                            )])
+                        ),
+                        (ty, None) => format_variant(ty, variant.attrs.field_casing),
+                    };
+                    (get_variant_name(variant, &ty.options), validator)
+                });
+                format!("validateTagged({tag:?}, {})", format_object(variants))
+            }
+            (None, _) => {
+                let (unit_variants, variants): (Vec<_>, Vec<_>) = ty
+                    .variants
+                    .iter()
+                    .partition(|variant| matches!(variant.ty, Type::Unit));
+                let unit_variants = unit_variants
+                    .into_iter()
+                    .map(|variant| format!("{:?}", get_variant_name(variant, &ty.options)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let variants = variants.into_iter().map(|variant| {
+                    (
+                        get_variant_name(variant, &ty.options),
+                        format_variant(&variant.ty, variant.attrs.field_casing),
+                    )
+                });
+                format!(
+                    "validateExternallyTagged([{unit_variants}], {})",
+                    format_object(variants)
+                )
+            }
+        }
+    }
+
+    fn format_fields(&self, fields: &[Field], casing: Casing, params: &[String]) -> String {
+        let (flattened, fields): (Vec<_>, Vec<_>) =
+            fields.iter().partition(|field| field.attrs.flatten);
+
+        // Flattened fields are validated against the object as a whole, which
+        // makes validating the remaining fields redundant if there are none:
+        let mut validators = Vec::new();
+        if !fields.is_empty() || flattened.is_empty() {
+            let fields = fields.into_iter().map(|field| {
+                (
+                    get_field_name(field, casing),
+                    self.format_field(field, params),
+                )
+            });
+            validators.push(format!("validateFields({})", format_object(fields)));
+        }
+        validators.extend(
+            flattened
+                .into_iter()
+                .map(|field| self.format_ident(&field.ty, params)),
+        );
+
+        match validators.len() {
+            1 => validators.remove(0),
+            _ => format!("validateAll({})", validators.join(", ")),
+        }
+    }
+
+    fn format_field(&self, field: &Field, params: &[String]) -> String {
+        // Custom (de)serializers may use any representation:
+        if field.attrs.serialize_with.is_some() || field.attrs.deserialize_with.is_some() {
+            "validateAny".to_owned()
+        } else if field.attrs.skip_serializing_if.is_some() {
+            format!("validateOptional({})", self.format_ident(&field.ty, params))
+        } else {
+            self.format_ident(&field.ty, params)
+        }
+    }
+
+    fn format_ident(&self, ident: &TypeIdent, params: &[String]) -> String {
+        if ident.generic_args.is_empty() && params.contains(&ident.name) {
+            return format_validator_name(&ident.name);
+        }
+
+        let generic_arg = |index: usize| {
+            ident
+                .generic_args
+                .get(index)
+                .map(|(arg, _)| self.format_ident(arg, params))
+                .unwrap_or_else(|| "validateAny".to_owned())
+        };
+
+        let Some(ty) = self.types.get(ident) else {
+            return "validateAny".to_owned();
+        };
+        match ty {
+            Type::Alias(_, target) => self.format_ident(target, params),
+            Type::Array(_, size) => format!("validateArray({size})"),
+            Type::Callback(_, _) => "validateCallback".to_owned(),
+            Type::Container(name, _) if name == "Option" => {
+                format!("validateOption({})", generic_arg(0))
+            }
+            Type::Container(_, _) => generic_arg(0),
+            Type::Custom(_) => "validateAny".to_owned(),
+            Type::List(_, _) => format!("validateList({})", generic_arg(0)),
+            Type::Map(_, _, _) => format!("validateMap({})", generic_arg(1)),
+            Type::Primitive(primitive) => format_primitive_validator(*primitive).to_owned(),
+            Type::String => "validateString".to_owned(),
+            Type::Tuple(items) => self.format_tuple(&items.iter().collect::<Vec<_>>(), params),
+            Type::Unit => "validateUnit".to_owned(),
+            Type::Enum(Enum {
+                ident: definition, ..
+            })
+            | Type::Struct(Struct {
+                ident: definition, ..
+            }) => {
+                let name = format_validator_name(&definition.name);
+                if definition.generic_args.is_empty() {
+                    name
+                } else {
+                    let args = ident
+                        .generic_args
+                        .iter()
+                        .map(|(arg, _)| self.format_ident(arg, params))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("{name}({args})")
+                }
+            }
+        }
+    }
+
+    fn format_tuple(&self, items: &[&TypeIdent], params: &[String]) -> String {
+        let validators = items
+            .iter()
+            .map(|item| self.format_ident(item, params))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("validateTuple([{validators}])")
+    }
+}
+
+fn format_primitive_validator(primitive: Primitive) -> &'static str {
+    match primitive {
+        Primitive::Bool => "validateBoolean",
+        Primitive::F32 | Primitive::F64 => "validateNumber",
+        Primitive::I8 => "validateI8",
+        Primitive::I16 => "validateI16",
+        Primitive::I32 => "validateI32",
+        Primitive::I64 => "validateI64",
+        Primitive::U8 => "validateU8",
+        Primitive::U16 => "validateU16",
+        Primitive::U32 => "validateU32",
+        Primitive::U64 => "validateU64",
+        Primitive::I128 => "validateI128",
+        Primitive::U128 => "validateU128",
+        Primitive::Char => "validateChar",
+    }
+}
+
+fn format_validator_name(type_name: &str) -> String {
+    format!("validate{type_name}")
+}
+
+const VALIDATOR_HELPERS: &str = "
+/**
+ * Verifies the value at the given path matches the expected type, and throws a
+ * `ValidationError` otherwise.
+ */
+type Validator = (value: any, path: string) => void;
+
+function describeValue(value: unknown): string {
+    if (value === null || value === undefined) {
+        return String(value);
+    } else if (typeof value === \"string\") {
+        return JSON.stringify(value);
+    } else if (typeof value === \"number\" || typeof value === \"boolean\") {
+        return String(value);
+    } else if (typeof value === \"bigint\") {
+        return `${value}n`;
+    } else if (Array.isArray(value)) {
+        return `array of length ${value.length}`;
+    } else {
+        return typeof value;
+    }
+}
+
+const validateAny: Validator = () => {};
+
+function validateBoolean(value: any, path: string) {
+    if (typeof value !== \"boolean\") {
+        throw new ValidationError(path, \"boolean\", value);
+    }
+}
+
+function validateNumber(value: any, path: string) {
+    if (typeof value !== \"number\") {
+        throw new ValidationError(path, \"number\", value);
+    }
+}
+
+function validateInteger(name: string, min: bigint, max: bigint): Validator {
+    return (value, path) => {
+        const isInteger = typeof value === \"bigint\" || Number.isInteger(value);
+        if (!isInteger || BigInt(value) < min || BigInt(value) > max) {
+            throw new ValidationError(path, name, value);
+        }
+    };
+}
+
+const validateI8 = validateInteger(\"i8\", -(2n ** 7n), 2n ** 7n - 1n);
+const validateI16 = validateInteger(\"i16\", -(2n ** 15n), 2n ** 15n - 1n);
+const validateI32 = validateInteger(\"i32\", -(2n ** 31n), 2n ** 31n - 1n);
+const validateI64 = validateInteger(\"i64\", -(2n ** 63n), 2n ** 63n - 1n);
+const validateU8 = validateInteger(\"u8\", 0n, 2n ** 8n - 1n);
+const validateU16 = validateInteger(\"u16\", 0n, 2n ** 16n - 1n);
+const validateU32 = validateInteger(\"u32\", 0n, 2n ** 32n - 1n);
+const validateU64 = validateInteger(\"u64\", 0n, 2n ** 64n - 1n);
+
+function validateInteger128(name: string, min: bigint, max: bigint): Validator {
+    const validate = validateInteger(name, min, max);
+    return (value, path) => {
+        // 128-bit integers are encoded as 16 bytes, although they may also be
+        // encoded as regular integers if they fit:
+        if (!(value instanceof Uint8Array)) {
+            validate(value, path);
+        } else if (value.length !== 16) {
+            throw new ValidationError(path, name, value);
+        }
+    };
+}
+
+const validateI128 = validateInteger128(\"i128\", -(2n ** 127n), 2n ** 127n - 1n);
+const validateU128 = validateInteger128(\"u128\", 0n, 2n ** 128n - 1n);
+
+function validateChar(value: any, path: string) {
+    if (typeof value !== \"string\" || [...value].length !== 1) {
+        throw new ValidationError(path, \"char\", value);
+    }
+}
+
+function validateString(value: any, path: string) {
+    if (typeof value !== \"string\") {
+        throw new ValidationError(path, \"string\", value);
+    }
+}
+
+function validateUnit(value: any, path: string) {
+    if (value !== null && value !== undefined) {
+        throw new ValidationError(path, \"null\", value);
+    }
+}
+
+function validateCallback(value: any, path: string) {
+    if (typeof value !== \"function\") {
+        throw new ValidationError(path, \"callback\", value);
+    }
+}
+
+function validateOption(validate: Validator): Validator {
+    return (value, path) => {
+        if (value !== null && value !== undefined) {
+            validate(value, path);
+        }
+    };
+}
+
+function validateOptional(validate: Validator): Validator {
+    return (value, path) => {
+        if (value !== undefined) {
+            validate(value, path);
+        }
+    };
+}
+
+function validateArray(size: number): Validator {
+    return (value, path) => {
+        const isArray = Array.isArray(value) || ArrayBuffer.isView(value);
+        if (!isArray || (value as ArrayLike<unknown>).length !== size) {
+            throw new ValidationError(path, `array of length ${size}`, value);
+        }
+    };
+}
+
+function validateList(validate: Validator): Validator {
+    return (value, path) => {
+        if (!Array.isArray(value)) {
+            throw new ValidationError(path, \"array\", value);
+        }
+
+        value.forEach((item, index) => validate(item, `${path}[${index}]`));
+    };
+}
+
+function validateMap(validate: Validator): Validator {
+    return (value, path) => {
+        validateObject(value, path);
+        for (const [key, item] of Object.entries(value)) {
+            validate(item, `${path}[${JSON.stringify(key)}]`);
+        }
+    };
+}
+
+function validateTuple(validators: Array<Validator>): Validator {
+    return (value, path) => {
+        if (!Array.isArray(value) || value.length !== validators.length) {
+            throw new ValidationError(path, `tuple of length ${validators.length}`, value);
+        }
+
+        validators.forEach((validate, index) => validate(value[index], `${path}[${index}]`));
+    };
+}
+
+function validateObject(value: any, path: string) {
+    if (typeof value !== \"object\" || value === null || Array.isArray(value)) {
+        throw new ValidationError(path, \"object\", value);
+    }
+}
+
+function validateFields(validators: Record<string, Validator>): Validator {
+    return (value, path) => {
+        validateObject(value, path);
+        for (const [key, validate] of Object.entries(validators)) {
+            validate(value[key], `${path}.${key}`);
+        }
+    };
+}
+
+function validateCompact(validators: Record<string, Validator>, minLength: number): Validator {
+    const fields = Object.entries(validators);
+    return (value, path) => {
+        if (!Array.isArray(value) || value.length < minLength || value.length > fields.length) {
+            throw new ValidationError(
+                path,
+                `array of ${minLength} to ${fields.length} field values`,
+                value
+            );
+        }
+
+        fields.forEach(([key, validate], index) => {
+            if (index < value.length) {
+                validate(value[index], `${path}.${key}`);
+            }
+        });
+    };
+}
+
+function validateExternallyTagged(
+    unitVariants: Array<string>,
+    validators: Record<string, Validator>
+): Validator {
+    const variants = [...unitVariants, ...Object.keys(validators)];
+    const expected = `one of ${variants.map((variant) => JSON.stringify(variant)).join(\", \")}`;
+    return (value, path) => {
+        if (typeof value === \"string\") {
+            if (!unitVariants.includes(value)) {
+                throw new ValidationError(path, expected, value);
+            }
+            return;
+        }
+
+        validateObject(value, path);
+        const keys = Object.keys(value);
+        if (keys.length !== 1 || !Object.prototype.hasOwnProperty.call(validators, keys[0])) {
+            throw new ValidationError(path, `object with ${expected} as its only key`, value);
+        }
+
+        validators[keys[0]](value[keys[0]], `${path}.${keys[0]}`);
+    };
+}
+
+function validateTagged(tagProp: string, validators: Record<string, Validator>): Validator {
+    const variants = Object.keys(validators);
+    const expected = `one of ${variants.map((variant) => JSON.stringify(variant)).join(\", \")}`;
+    return (value, path) => {
+        validateObject(value, path);
+        const tag = value[tagProp];
+        if (typeof tag !== \"string\" || !Object.prototype.hasOwnProperty.call(validators, tag)) {
+            throw new ValidationError(`${path}.${tagProp}`, expected, tag);
+        }
+
+        validators[tag](value, path);
+    };
+}
+
+function validateUntagged(name: string, validators: Array<Validator>): Validator {
+    return (value, path) => {
+        const matches = validators.some((validate) => {
+            try {
+                validate(value, path);
+                return true;
+            } catch (error) {
+                if (error instanceof ValidationError) {
+                    return false;
+                }
+                throw error;
+            }
+        });
+        if (!matches) {
+            throw new ValidationError(path, `any variant of ${name}`, value);
+        }
+    };
+}
+
+function validateAll(...validators: Array<Validator>): Validator {
+    return (value, path) => validators.forEach((validate) => validate(value, path));
+}
+";
@@ -0,0 +1,702 @@
+use super::{
+    format_function_declarations, format_parse_object, format_plain_primitive,
+    format_plain_primitive_or_ident, format_serialize_object, is_primitive_function, join_lines,
+    normalizers::Normalizers, validators::Validators, FunctionType, Int64Repr,
+};
+use crate::{
+    functions::{Function, FunctionList},
+    generators::GeneratedFiles,
+    types::TypeMap,
+};
+use inflector::Inflector;
+
+/// Generates a runtime that runs the plugin inside a Web Worker.
+///
+/// This consists of two files: `worker.ts`, which is the entry point for the
+/// worker and hosts the regular runtime, and `workerRuntime.ts`, which creates
+/// a proxy for it on the main thread. Calls to exports are forwarded to the
+/// worker as raw MessagePack data, while calls to imports are forwarded back
+/// to the main thread.
+pub(super) struct WebWorker<'a> {
+    pub import_functions: &'a FunctionList,
+    pub export_functions: &'a FunctionList,
+    pub types: &'a TypeMap,
+    pub int64_repr: Int64Repr,
+    pub normalizers: Option<&'a Normalizers<'a>>,
+    pub denormalizers: Option<&'a Normalizers<'a>>,
+    pub validators: Option<&'a Validators<'a>>,
+    pub msgpack_module: &'a str,
+    pub msgpack_options: &'a str,
+
+    /// Parameters of `serializeObject()`.
+    pub serialize_object_params: &'a str,
+
+    pub import_path_extension: &'a str,
+    pub streaming_instantiation: bool,
+    pub has_runtime_options: bool,
+
+    /// Parameters for `parseObject()` that follow the data to be parsed.
+    pub parse_object_params: &'a [&'a str],
+
+    /// Body of `parseObject()`, which decodes the data in `copy`.
+    pub parse_object_result: &'a str,
+
+    /// Module-level helpers used by `parseObject()`.
+    pub helper_functions: &'a str,
+}
+
+impl<'a> WebWorker<'a> {
+    pub fn generate(&self, path: &str, files: &mut GeneratedFiles) {
+        files.insert(format!("{path}/worker.ts"), self.format_worker());
+        files.insert(
+            format!("{path}/workerRuntime.ts"),
+            self.format_worker_runtime(),
+        );
+    }
+
+    fn format_worker(&self) -> String {
+        let ext = self.import_path_extension;
+        let (sync_imports, async_imports): (Vec<_>, Vec<_>) = self
+            .import_functions
+            .iter()
+            .partition(|function| !function.is_async);
+        let format_names = |functions: Vec<&Function>| {
+            functions
+                .iter()
+                .map(|function| format!("    {:?},\n", function.name.to_camel_case()))
+                .collect::<String>()
+        };
+        let sync_imports = format_names(sync_imports);
+        let async_imports = format_names(async_imports);
+
+        let (options_type_import, options_arg, options_param, runtime_options_arg) =
+            if self.has_runtime_options {
+                (
+                    ", RuntimeOptions",
+                    ", message.options",
+                    ", options?: RuntimeOptions",
+                    ", options",
+                )
+            } else {
+                ("", "", "", "")
+            };
+        let runtime_source = if self.streaming_instantiation {
+            "new Response(source, { headers: { \"Content-Type\": \"application/wasm\" } })"
+        } else {
+            "source"
+        };
+
+        format!(
+            "// ============================================= //
+// Web Worker entry point for TypeScript         //
+//                                               //
+// This file is generated. PLEASE DO NOT MODIFY. //
+// ============================================= //
+// deno-lint-ignore-file no-explicit-any no-unused-vars
+
+import {{ createRuntime, implementedExports, FPRuntimeError }} from \"./index{ext}\";
+import type {{ Exports, Imports{options_type_import} }} from \"./index{ext}\";
+import {{ callSyncImport, formatError }} from \"./workerRuntime{ext}\";
+import type {{ WorkerRequest, WorkerResponse }} from \"./workerRuntime{ext}\";
+
+type WorkerScope = {{
+    onmessage: ((event: MessageEvent<WorkerRequest>) => void) | null;
+    postMessage(message: WorkerResponse, options?: {{ transfer?: Array<Transferable> }}): void;
+}};
+
+const scope = self as unknown as WorkerScope;
+
+const syncImports: Array<keyof Imports> = [
+{sync_imports}];
+
+const asyncImports: Array<keyof Imports> = [
+{async_imports}];
+
+let exports: Exports | undefined;
+let syncImportBuffer: SharedArrayBuffer | undefined;
+
+let nextImportId = 0;
+const pendingImports = new Map<
+    number,
+    {{ resolve: (value: any) => void; reject: (error: Error) => void }}
+>();
+
+scope.onmessage = (event) => {{
+    const message = event.data;
+    switch (message.type) {{
+        case \"init\":
+            syncImportBuffer = message.syncImportBuffer;
+            respond(message.id, () => initialize(message.source{options_arg}));
+            break;
+        case \"call\":
+            respond(message.id, () => callExport(message.name, message.args));
+            break;
+        case \"importResult\": {{
+            const pendingImport = pendingImports.get(message.id);
+            if (pendingImport) {{
+                pendingImports.delete(message.id);
+                if (message.error !== undefined) {{
+                    pendingImport.reject(new FPRuntimeError(message.error));
+                }} else {{
+                    pendingImport.resolve(message.value);
+                }}
+            }}
+            break;
+        }}
+    }}
+}};
+
+async function initialize(source: ArrayBuffer{options_param}): Promise<Array<string>> {{
+    exports = await createRuntime(
+        {runtime_source},
+        createImportFunctions(){runtime_options_arg}
+    );
+    return implementedExports(exports);
+}}
+
+function callExport(name: string, args: Array<unknown>): unknown {{
+    const exportFunction = exports?.[name as keyof Exports] as
+        | ((...args: Array<unknown>) => unknown)
+        | undefined;
+    if (!exportFunction) {{
+        throw new FPRuntimeError(`Plugin does not implement export: \"${{name}}\"`);
+    }}
+    return exportFunction(...args);
+}}
+
+/**
+ * Sends the result of the given handler back to the main thread. Raw
+ * MessagePack data is transferred rather than copied.
+ */
+function respond(id: number, handler: () => unknown) {{
+    Promise.resolve()
+        .then(handler)
+        .then(
+            (value) =>
+                scope.postMessage(
+                    {{ type: \"result\", id, value }},
+                    {{ transfer: value instanceof Uint8Array ? [value.buffer] : [] }}
+                ),
+            (error) => scope.postMessage({{ type: \"result\", id, error: formatError(error) }})
+        );
+}}
+
+/**
+ * Creates import functions that forward their calls to the main thread.
+ */
+function createImportFunctions(): Imports {{
+    const importFunctions: Record<string, (...args: Array<unknown>) => unknown> = {{}};
+    for (const name of syncImports) {{
+        importFunctions[name] = (...args) => {{
+            if (!syncImportBuffer) {{
+                throw new FPRuntimeError(
+                    `Cannot call synchronous import function \"${{name}}\" without SharedArrayBuffer`
+                );
+            }}
+            return callSyncImport(syncImportBuffer, () =>
+                scope.postMessage({{ type: \"syncImport\", name, args }})
+            );
+        }};
+    }}
+    for (const name of asyncImports) {{
+        importFunctions[name] = (...args) =>
+            new Promise((resolve, reject) => {{
+                const id = nextImportId++;
+                pendingImports.set(id, {{ resolve, reject }});
+                scope.postMessage({{ type: \"asyncImport\", id, name, args }});
+            }});
+    }}
+    return importFunctions as unknown as Imports;
+}}
+"
+        )
+    }
+
+    fn format_worker_runtime(&self) -> String {
+        let ext = self.import_path_extension;
+        let msgpack_module = self.msgpack_module;
+        let msgpack_options = self.msgpack_options;
+        let serialize_object_params = self.serialize_object_params;
+        let encoded_object = if self.denormalizers.is_some() {
+            "denormalize(object)"
+        } else {
+            "object"
+        };
+        let has_sync_imports = self
+            .import_functions
+            .iter()
+            .any(|function| !function.is_async);
+
+        let export_decls = format_function_declarations(
+            self.export_functions,
+            self.types,
+            FunctionType::WorkerExport,
+            self.int64_repr,
+        );
+        let export_lines = join_lines(&export_decls, |line| format!("    {line};"));
+        let export_wrappers = self
+            .export_functions
+            .iter()
+            .flat_map(|function| {
+                self.format_export_wrapper(function)
+                    .split('\n')
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let export_wrappers = join_lines(&export_wrappers, |line| format!("        {line}"));
+
+        let validation_error_import = if self.validators.is_some() {
+            ", ValidationError"
+        } else {
+            ""
+        };
+        let runtime_options_import = if self.has_runtime_options {
+            ", RuntimeOptions"
+        } else {
+            ""
+        };
+        let init_options_member = if self.has_runtime_options {
+            "\n          options: RuntimeOptions;"
+        } else {
+            ""
+        };
+        let init_options = if self.has_runtime_options {
+            "\n            options,"
+        } else {
+            ""
+        };
+
+        let options_type = match (self.has_runtime_options, has_sync_imports) {
+            (false, false) => None,
+            (true, false) => Some("RuntimeOptions;\n".to_owned()),
+            (has_runtime_options, true) => Some(format!(
+                "{}{{
+    /**
+     * Size of the buffer through which the results of synchronous import
+     * functions are passed to the worker, in bytes. Defaults to 1 MiB, and
+     * cannot be less than 1 KiB.
+     */
+    syncImportBufferSize?: number;
+}};
+",
+                if has_runtime_options {
+                    "RuntimeOptions & "
+                } else {
+                    ""
+                }
+            )),
+        };
+        let (options_type, options_doc, options_param) = match options_type {
+            Some(options_type) => (
+                format!(
+                    "
+/**
+ * Options for creating a worker runtime.
+ */
+export type WorkerRuntimeOptions = {options_type}"
+                ),
+                "\n * @param options Options for the runtime.",
+                ",\n    options: WorkerRuntimeOptions = {}",
+            ),
+            None => (String::new(), "", ""),
+        };
+
+        let (sync_import_setup, sync_import_init, sync_import_handler) = if has_sync_imports {
+            (
+                "
+    // Synchronous import functions block the worker until their result has
+    // been written to this buffer, which requires `SharedArrayBuffer`:
+    const syncImportBuffer =
+        typeof SharedArrayBuffer !== \"undefined\"
+            ? new SharedArrayBuffer(
+                  SYNC_DATA_OFFSET + Math.max(options.syncImportBufferSize ?? 1024 * 1024, 1024)
+              )
+            : undefined;
+",
+                "\n            syncImportBuffer,",
+                "
+            case \"syncImport\": {
+                let result: SyncImportResult;
+                try {
+                    result = { value: callImport(message.name, message.args) };
+                } catch (error) {
+                    result = { error: formatError(error) };
+                }
+                writeSyncImportResult(syncImportBuffer!, result);
+                break;
+            }",
+            )
+        } else {
+            ("", "", "")
+        };
+
+        let (source_type, source_doc, source_buffer) = if self.streaming_instantiation {
+            (
+                "Response | Promise<Response>",
+                "The response for fetching the WASM plugin",
+                "await (await source).arrayBuffer()",
+            )
+        } else {
+            ("ArrayBuffer", "The raw WASM plugin", "source.slice(0)")
+        };
+
+        let parse_object_params = std::iter::once("copy: Uint8Array")
+            .chain(self.parse_object_params.iter().copied())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let parse_object_result = self.parse_object_result;
+        let helper_functions = self.helper_functions;
+
+        format!(
+            "// ============================================= //
+// Web Worker runtime for TypeScript             //
+//                                               //
+// This file is generated. PLEASE DO NOT MODIFY. //
+// ============================================= //
+// deno-lint-ignore-file no-explicit-any no-unused-vars
+
+import {{ encode, decode }} from \"{msgpack_module}\";
+
+import {{ FPRuntimeError{validation_error_import} }} from \"./index{ext}\";
+import type {{ Imports{runtime_options_import} }} from \"./index{ext}\";
+import type * as types from \"./types{ext}\";
+
+/**
+ * The functions exported by a plugin that runs inside a worker.
+ *
+ * These are the same as those returned by `createRuntime()`, except that they
+ * always return a `Promise`.
+ */
+export type WorkerExports = {{
+{export_lines}}};
+{options_type}
+/**
+ * Messages sent from the main thread to the worker.
+ */
+export type WorkerRequest =
+    | {{
+          type: \"init\";
+          id: number;
+          source: ArrayBuffer;{init_options_member}
+          syncImportBuffer?: SharedArrayBuffer;
+      }}
+    | {{ type: \"call\"; id: number; name: string; args: Array<unknown> }}
+    | {{ type: \"importResult\"; id: number; value?: unknown; error?: string }};
+
+/**
+ * Messages sent from the worker to the main thread.
+ */
+export type WorkerResponse =
+    | {{ type: \"result\"; id: number; value?: unknown; error?: string }}
+    | {{ type: \"syncImport\"; name: string; args: Array<unknown> }}
+    | {{ type: \"asyncImport\"; id: number; name: string; args: Array<unknown> }};
+
+/**
+ * The result of a synchronous import function, as passed to the worker.
+ */
+export type SyncImportResult = {{ value: unknown }} | {{ error: string }};
+
+type PendingRequest = {{ resolve: (value: any) => void; reject: (error: Error) => void }};
+
+/**
+ * Creates a runtime that executes the given plugin inside a Web Worker.
+ *
+ * The worker must be created from the `worker.ts` module that is generated
+ * alongside this one, for instance:
+ *
+ * ```ts
+ * const worker = new Worker(new URL(\"./worker.ts\", import.meta.url), {{ type: \"module\" }});
+ * ```
+ *
+ * @param worker The worker in which to run the plugin.
+ * @param source {source_doc}.
+ * @param importFunctions The host functions that may be imported by the plugin.{options_doc}
+ * @returns The functions that may be exported by the plugin.
+ */
+export async function createWorkerRuntime(
+    worker: Worker,
+    source: {source_type},
+    importFunctions: Imports{options_param}
+): Promise<WorkerExports> {{
+    let nextRequestId = 0;
+    const pendingRequests = new Map<number, PendingRequest>();
+{sync_import_setup}
+    function request<T>(message: WorkerRequest, transfer: Array<Transferable> = []): Promise<T> {{
+        return new Promise((resolve, reject) => {{
+            pendingRequests.set(message.id, {{ resolve, reject }});
+            worker.postMessage(message, {{ transfer }});
+        }});
+    }}
+
+    function call<T>(name: string, args: Array<unknown>): Promise<T> {{
+        const transfer = args.flatMap((arg) => (arg instanceof Uint8Array ? [arg.buffer] : []));
+        return request({{ type: \"call\", id: nextRequestId++, name, args }}, transfer);
+    }}
+
+    function callImport(name: string, args: Array<unknown>): unknown {{
+        const importFunction = importFunctions[name as keyof Imports] as unknown as
+            | ((...args: Array<unknown>) => unknown)
+            | undefined;
+        if (!importFunction) {{
+            throw new FPRuntimeError(`Plugin called unknown import function: \"${{name}}\"`);
+        }}
+        return importFunction(...args);
+    }}
+
+    function parseObject<T>({parse_object_params}): T {{
+        {parse_object_result}
+    }}
+
+    function serializeObject<T>({serialize_object_params}): Uint8Array {{
+        // The encoded data is copied, so we can be sure the buffer we
+        // transfer to the worker isn't shared with anything else:
+        return encode({encoded_object}{msgpack_options}).slice();
+    }}
+
+    worker.onmessage = (event: MessageEvent<WorkerResponse>) => {{
+        const message = event.data;
+        switch (message.type) {{
+            case \"result\": {{
+                const pendingRequest = pendingRequests.get(message.id);
+                if (pendingRequest) {{
+                    pendingRequests.delete(message.id);
+                    if (message.error !== undefined) {{
+                        pendingRequest.reject(new FPRuntimeError(message.error));
+                    }} else {{
+                        pendingRequest.resolve(message.value);
+                    }}
+                }}
+                break;
+            }}{sync_import_handler}
+            case \"asyncImport\": {{
+                const {{ id }} = message;
+                Promise.resolve()
+                    .then(() => callImport(message.name, message.args))
+                    .then(
+                        (value) => worker.postMessage({{ type: \"importResult\", id, value }}),
+                        (error) =>
+                            worker.postMessage({{
+                                type: \"importResult\",
+                                id,
+                                error: formatError(error),
+                            }})
+                    );
+                break;
+            }}
+        }}
+    }};
+
+    worker.onerror = (event: ErrorEvent) => {{
+        const error = new FPRuntimeError(`Error in plugin worker: ${{event.message}}`);
+        for (const pendingRequest of pendingRequests.values()) {{
+            pendingRequest.reject(error);
+        }}
+        pendingRequests.clear();
+    }};
+
+    const buffer = {source_buffer};
+    const implemented = await request<Array<string>>(
+        {{
+            type: \"init\",
+            id: nextRequestId++,
+            source: buffer,{init_options}{sync_import_init}
+        }},
+        [buffer]
+    );
+
+    const exports: WorkerExports = {{
+{export_wrappers}    }};
+
+    return Object.fromEntries(
+        Object.entries(exports).filter(([name]) => implemented.includes(name))
+    ) as WorkerExports;
+}}
+
+/**
+ * Formats an error for passing it between threads.
+ */
+export function formatError(error: unknown): string {{
+    return error instanceof Error ? error.message : String(error);
+}}
+
+// The buffer for the results of synchronous import functions starts with a
+// header of 32-bit integers, followed by a slot for `bigint` values and the
+// MessagePack encoding of all other values.
+const SYNC_HEADER_LENGTH = 4;
+const SYNC_STATE_INDEX = 0;
+const SYNC_KIND_INDEX = 1;
+const SYNC_LENGTH_INDEX = 2;
+const SYNC_BIGINT_OFFSET = 16;
+const SYNC_DATA_OFFSET = 24;
+
+const SYNC_STATE_PENDING = 0;
+const SYNC_STATE_DONE = 1;
+
+const SYNC_KIND_UNDEFINED = 0;
+const SYNC_KIND_BIGINT = 1;
+const SYNC_KIND_ENCODED = 2;
+const SYNC_KIND_ERROR = 3;
+
+/**
+ * Calls a synchronous import function from the worker, by sending a request
+ * to the main thread and blocking until it has written the result.
+ *
+ * @param buffer The buffer shared with the main thread.
+ * @param sendRequest Sends the request for calling the import function.
+ */
+export function callSyncImport(buffer: SharedArrayBuffer, sendRequest: () => void): unknown {{
+    const header = new Int32Array(buffer, 0, SYNC_HEADER_LENGTH);
+    Atomics.store(header, SYNC_STATE_INDEX, SYNC_STATE_PENDING);
+    sendRequest();
+    Atomics.wait(header, SYNC_STATE_INDEX, SYNC_STATE_PENDING);
+
+    const readData = () =>
+        new Uint8Array(buffer, SYNC_DATA_OFFSET, header[SYNC_LENGTH_INDEX]).slice();
+    switch (header[SYNC_KIND_INDEX]) {{
+        case SYNC_KIND_BIGINT:
+            return new BigInt64Array(buffer, SYNC_BIGINT_OFFSET, 1)[0];
+        case SYNC_KIND_ENCODED:
+            return decode(readData(){msgpack_options});
+        case SYNC_KIND_ERROR:
+            throw new FPRuntimeError(decode(readData()) as string);
+        default:
+            return undefined;
+    }}
+}}
+
+/**
+ * Writes the result of a synchronous import function for the worker, and
+ * wakes it up.
+ *
+ * @param buffer The buffer shared with the worker.
+ * @param result The result of the import function.
+ */
+export function writeSyncImportResult(buffer: SharedArrayBuffer, result: SyncImportResult) {{
+    const header = new Int32Array(buffer, 0, SYNC_HEADER_LENGTH);
+    const writeData = (kind: number, data: Uint8Array) => {{
+        const capacity = buffer.byteLength - SYNC_DATA_OFFSET;
+        if (data.length > capacity) {{
+            kind = SYNC_KIND_ERROR;
+            data = encode(`Result of synchronous import function exceeds ${{capacity}} bytes`);
+        }}
+        new Uint8Array(buffer, SYNC_DATA_OFFSET, data.length).set(data);
+        header[SYNC_KIND_INDEX] = kind;
+        header[SYNC_LENGTH_INDEX] = data.length;
+    }};
+
+    if (\"error\" in result) {{
+        writeData(SYNC_KIND_ERROR, encode(result.error));
+    }} else if (result.value === undefined) {{
+        header[SYNC_KIND_INDEX] = SYNC_KIND_UNDEFINED;
+    }} else if (typeof result.value === \"bigint\") {{
+        // Primitive 64-bit integers are always represented as `bigint`, even
+        // if MessagePack values are not:
+        new BigInt64Array(buffer, SYNC_BIGINT_OFFSET, 1)[0] = BigInt.asIntN(64, result.value);
+        header[SYNC_KIND_INDEX] = SYNC_KIND_BIGINT;
+    }} else {{
+        writeData(SYNC_KIND_ENCODED, encode(result.value{msgpack_options}));
+    }}
+
+    Atomics.store(header, SYNC_STATE_INDEX, SYNC_STATE_DONE);
+    Atomics.notify(header, SYNC_STATE_INDEX);
+}}
+{helper_functions}"
+        )
+    }
+
+    /// Formats the wrapper on the main thread for the given export, which
+    /// forwards calls to the worker.
+    ///
+    /// Functions that only take and return primitives are called directly,
+    /// while others are called through their raw wrapper, so their arguments
+    /// and results are transferred as MessagePack data.
+    fn format_export_wrapper(&self, function: &Function) -> String {
+        let name = function.name.to_camel_case();
+        let args = function
+            .args
+            .iter()
+            .map(|arg| {
+                format!(
+                    "{}: {}",
+                    arg.name.to_camel_case(),
+                    format_plain_primitive_or_ident(&arg.ty, self.types, self.int64_repr)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if is_primitive_function(function) {
+            let call_args = function
+                .args
+                .iter()
+                .map(|arg| arg.name.to_camel_case())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let return_type = function
+                .return_type
+                .as_ref()
+                .and_then(|ty| ty.as_primitive())
+                .map(format_plain_primitive)
+                .unwrap_or("void");
+            return format!("{name}: ({args}) => call<{return_type}>({name:?}, [{call_args}]),");
+        }
+
+        let call_args = function
+            .args
+            .iter()
+            .map(|arg| {
+                let name = arg.name.to_camel_case();
+                if arg.ty.is_primitive() {
+                    name
+                } else if arg.ty.is_array() {
+                    // See `format_export_wrappers()` for why arrays are converted:
+                    format_serialize_object(
+                        Some(&arg.ty),
+                        &format!("Array.from({name})"),
+                        self.denormalizers,
+                    )
+                } else {
+                    format_serialize_object(Some(&arg.ty), &name, self.denormalizers)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let raw_name = format!("{name}Raw");
+
+        let parse_result = if function.is_async {
+            Some(function.return_type.as_ref())
+        } else {
+            match &function.return_type {
+                Some(ty) if !ty.is_primitive() => Some(Some(ty)),
+                _ => None,
+            }
+        };
+        match parse_result {
+            Some(ty) => format!(
+                "{name}: async ({args}) => {{
+    const result = await call<Uint8Array>({raw_name:?}, [{call_args}]);
+    return {};
+}},",
+                format_parse_object(
+                    ty,
+                    "result",
+                    &format!("{name}()"),
+                    self.types,
+                    self.int64_repr,
+                    self.normalizers,
+                    self.validators,
+                )
+            ),
+            None => {
+                let return_type = function
+                    .return_type
+                    .as_ref()
+                    .and_then(|ty| ty.as_primitive())
+                    .map(format_plain_primitive)
+                    .unwrap_or("void");
+                format!("{name}: ({args}) => call<{return_type}>({raw_name:?}, [{call_args}]),")
+            }
+        }
+    }
+}
@@ -0,0 +1,1026 @@
+use super::BindingsType;
+use crate::{
+    casing::Casing,
+    functions::{Function, DEFAULT_IMPORT_MODULE},
+    protocol::{Protocol, WireFormat},
+    types::{CustomType, Enum, Field, Struct, Type, TypeIdent, TypeMap, Variant},
+};
+use inflector::Inflector;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+/// Words that may not be used as identifiers in generated TypeScript code.
+const TS_RESERVED_WORDS: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Methods that are always generated on the `Runtime` of the Rust runtimes,
+/// and which may therefore not be used as export names.
+const RUST_RUNTIME_METHODS: &[&str] = &[
+    "default_store",
+    "has_export",
+    "implemented_exports",
+    "link",
+    "new",
+    "protocol_version",
+    "unlink",
+    "wire_format",
+    "with_wasi_config",
+];
+
+/// Module from which WASI functions are imported.
+const WASI_IMPORT_MODULE: &str = "wasi_snapshot_preview1";
+
+/// Custom types that can only be deserialized from a self-describing format.
+const SELF_DESCRIBING_TYPES: &[&str] = &["rmpv::Value", "serde_json::Value"];
+
+/// A single problem that prevents bindings from being generated.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationError {
+    /// Describes where in the protocol the problem was found, such as
+    /// "export `my_function`" or "type `MyStruct`".
+    pub location: String,
+    pub message: String,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// All the problems found while validating a protocol.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ValidationReport {
+    pub bindings_type: String,
+    pub errors: Vec<ValidationError>,
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Cannot generate {} bindings, because the protocol has {} {}:",
+            self.bindings_type,
+            self.errors.len(),
+            if self.errors.len() == 1 {
+                "problem"
+            } else {
+                "problems"
+            }
+        )?;
+        for error in &self.errors {
+            writeln!(f, "  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+/// Validates the protocol can be used to generate bindings of the given type.
+///
+/// Instead of stopping at the first problem, all the problems that are found
+/// are collected into the returned report.
+pub fn validate_protocol(
+    protocol: &Protocol,
+    bindings_type: &BindingsType,
+) -> Result<(), ValidationReport> {
+    let mut validator = Validator {
+        types: &protocol.types,
+        bindings_type,
+        wire_format: protocol.wire_format,
+        errors: Vec::new(),
+    };
+
+    if protocol.wire_format != WireFormat::MessagePack
+        && matches!(bindings_type, BindingsType::TsRuntime(_))
+    {
+        validator.error(
+            "protocol",
+            format!(
+                "the `{}` wire format is not supported by the TypeScript runtime, which only \
+                    supports MessagePack",
+                protocol.wire_format.name()
+            ),
+        );
+    }
+
+    for (kind, functions) in [
+        ("import", &protocol.import_functions),
+        ("export", &protocol.export_functions),
+    ] {
+        for function in functions {
+            validator.validate_function(kind, function);
+        }
+        validator.validate_function_names(kind, functions.iter());
+    }
+
+    for ty in protocol.types.values() {
+        match ty {
+            Type::Alias(name, ident) => {
+                validator.validate_ident(&format!("alias `{name}`"), ident, &[])
+            }
+            Type::Custom(ty) => validator.validate_custom_type(ty),
+            Type::Enum(ty) => validator.validate_enum(ty),
+            Type::Struct(ty) => validator.validate_struct(ty),
+            Type::Tuple(items) => {
+                for item in items {
+                    validator.validate_ident(&format!("type `{}`", ty.name()), item, &[]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if validator.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationReport {
+            bindings_type: bindings_type.to_string(),
+            errors: validator.errors,
+        })
+    }
+}
+
+struct Validator<'a> {
+    types: &'a TypeMap,
+    bindings_type: &'a BindingsType,
+    wire_format: WireFormat,
+    errors: Vec<ValidationError>,
+}
+
+impl Validator<'_> {
+    fn error(&mut self, location: &str, message: impl Into<String>) {
+        self.errors.push(ValidationError {
+            location: location.to_owned(),
+            message: message.into(),
+        });
+    }
+
+    fn validate_function(&mut self, kind: &str, function: &Function) {
+        let location = format!("{kind} `{}`", function.name);
+
+        if let Some(module) = &function.import_module {
+            self.validate_import_module(kind, &location, module);
+        }
+
+        let mut ts_arg_names = BTreeMap::new();
+        for arg in &function.args {
+            let arg_location = format!("{location}, argument `{}`", arg.name);
+            self.validate_ident(&arg_location, &arg.ty, &[]);
+
+            match self.bindings_type {
+                BindingsType::TsRuntime(_) => {
+                    let ts_name = arg.name.to_camel_case();
+                    if TS_RESERVED_WORDS.contains(&ts_name.as_str()) {
+                        self.error(
+                            &arg_location,
+                            format!("`{ts_name}` is a reserved word in TypeScript"),
+                        );
+                    }
+                    if let Some(other) = ts_arg_names.insert(ts_name.clone(), &arg.name) {
+                        self.error(
+                            &arg_location,
+                            format!(
+                                "conflicts with argument `{other}`, as both become `{ts_name}` \
+                                    in TypeScript"
+                            ),
+                        );
+                    }
+                }
+                BindingsType::RustPlugin(_) if kind == "export" && arg.name == "fptr" => {
+                    self.error(
+                        &arg_location,
+                        "`fptr` is reserved for use by the Rust plugin bindings",
+                    );
+                }
+                BindingsType::RustWasmer2Runtime | BindingsType::RustWasmer2WasiRuntime => {
+                    let reserved = if kind == "import" { "env" } else { "function" };
+                    if arg.name == reserved {
+                        self.error(
+                            &arg_location,
+                            format!(
+                                "`{reserved}` is reserved for use by the Rust runtime bindings"
+                            ),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(return_type) = &function.return_type {
+            let return_location = format!("{location}, return type");
+            self.validate_ident(&return_location, return_type, &[]);
+        }
+
+        if function.is_event {
+            self.validate_event(kind, &location, function);
+        }
+    }
+
+    fn validate_event(&mut self, kind: &str, location: &str, function: &Function) {
+        if kind != "export" {
+            self.error(location, "events can only be declared as exports");
+        }
+        if !function.is_async {
+            self.error(location, "events must be async");
+        }
+        match function.args.as_slice() {
+            [arg] if arg.ty.is_primitive() => self.error(
+                location,
+                "the argument of an event must be serialized, so it cannot be a primitive",
+            ),
+            [_] => {}
+            _ => self.error(location, "events must have exactly one argument"),
+        }
+        if function.return_type.is_some() {
+            self.error(location, "events cannot return a value");
+        }
+        if matches!(self.bindings_type, BindingsType::TsRuntime(config) if config.generate_web_worker)
+        {
+            self.error(
+                location,
+                "events are not supported by the Web Worker runtime",
+            );
+        }
+    }
+
+    fn validate_import_module(&mut self, kind: &str, location: &str, module: &str) {
+        let is_snake_case_ident = matches!(module.chars().next(), Some('a'..='z'))
+            && module
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        let uses_wasi = match self.bindings_type {
+            BindingsType::RustWasmer2WasiRuntime => true,
+            BindingsType::TsRuntime(config) => config.wasi.is_some(),
+            _ => false,
+        };
+
+        if kind == "export" {
+            self.error(
+                location,
+                "only imported functions can be part of an import module",
+            );
+        } else if !is_snake_case_ident {
+            self.error(
+                location,
+                format!("import module `{module}` is not a snake_case identifier"),
+            );
+        } else if module == DEFAULT_IMPORT_MODULE {
+            self.error(
+                location,
+                format!(
+                    "import module `{module}` is reserved for functions outside of a named module"
+                ),
+            );
+        } else if module == WASI_IMPORT_MODULE && uses_wasi {
+            self.error(
+                location,
+                format!("import module `{module}` is reserved for WASI"),
+            );
+        }
+    }
+
+    /// Checks that the names of the functions of the given kind don't conflict
+    /// with each other, or with other functions that are generated for them.
+    fn validate_function_names<'f>(
+        &mut self,
+        kind: &str,
+        functions: impl Iterator<Item = &'f Function>,
+    ) {
+        let mut generated_names = BTreeMap::new();
+        let mut reserved_names = BTreeSet::new();
+        for function in functions {
+            let names = match self.bindings_type {
+                BindingsType::TsRuntime(config) => {
+                    let name = if function.is_event {
+                        format!("emit_{}", function.name).to_camel_case()
+                    } else {
+                        function.name.to_camel_case()
+                    };
+                    let raw_wrappers =
+                        config.generate_raw_export_wrappers || config.generate_web_worker;
+                    if kind == "export" && raw_wrappers {
+                        vec![format!("{name}Raw"), name]
+                    } else {
+                        vec![name]
+                    }
+                }
+                BindingsType::RustWasmer2Runtime | BindingsType::RustWasmer2WasiRuntime
+                    if kind == "export" =>
+                {
+                    reserved_names.extend(RUST_RUNTIME_METHODS.iter().copied());
+                    if function.is_event {
+                        vec![
+                            format!("emit_{}", function.name),
+                            format!("emit_{}_raw", function.name),
+                        ]
+                    } else {
+                        vec![
+                            function.name.clone(),
+                            format!("{}_raw", function.name),
+                            format!("has_{}", function.name),
+                        ]
+                    }
+                }
+                BindingsType::RustPlugin(_) if kind == "export" => {
+                    if function.is_event {
+                        vec![
+                            format!("subscribe_{}", function.name),
+                            format!("unsubscribe_{}", function.name),
+                        ]
+                    } else {
+                        vec![function.name.clone()]
+                    }
+                }
+                _ => continue,
+            };
+
+            for name in names {
+                let location = format!("{kind} `{}`", function.name);
+                if reserved_names.contains(name.as_str()) {
+                    self.error(
+                        &location,
+                        format!(
+                            "`{name}` is reserved for use by the {} bindings",
+                            self.bindings_type
+                        ),
+                    );
+                } else if let Some(other) = generated_names.insert(name.clone(), &function.name) {
+                    self.error(
+                        &location,
+                        format!("conflicts with {kind} `{other}`, as both generate `{name}`"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_struct(&mut self, ty: &Struct) {
+        let location = format!("type `{}`", ty.ident.format(false));
+        let generic_params = get_generic_params(&ty.ident);
+
+        if ty.options.is_resource && (!ty.fields.is_empty() || !generic_params.is_empty()) {
+            self.error(
+                &location,
+                "resource types must be unit structs, because their contents are never \
+                    exposed to plugins",
+            );
+        }
+
+        if ty.is_compact() {
+            self.validate_compact_struct(&location, ty);
+        } else if ty.options.is_compact {
+            self.error(
+                &location,
+                "`#[fp(compact)]` only applies to structs with named fields, because other \
+                    structs are not encoded as maps",
+            );
+        }
+
+        self.validate_fields(
+            &location,
+            &ty.fields,
+            ty.options.field_casing,
+            &generic_params,
+        );
+    }
+
+    /// Checks that a compact struct can be encoded as an array, in which
+    /// every field has a fixed position.
+    fn validate_compact_struct(&mut self, location: &str, ty: &Struct) {
+        if ty.options.rust_module.is_some() {
+            self.error(
+                location,
+                "compact structs cannot use `rust_module`, because their serialization is generated",
+            );
+        }
+
+        for field in &ty.fields {
+            let field_location = format!(
+                "{location}, field `{}`",
+                field.name.as_deref().unwrap_or_default()
+            );
+            if field.attrs.flatten {
+                self.error(
+                    &field_location,
+                    "fields of compact structs cannot be flattened",
+                );
+            }
+            if field.attrs.skip_serializing_if.is_some() {
+                self.error(
+                    &field_location,
+                    "fields of compact structs cannot be skipped, because that would shift the \
+                        fields after them",
+                );
+            }
+        }
+    }
+
+    fn validate_custom_type(&mut self, ty: &CustomType) {
+        if self.wire_format == WireFormat::Postcard
+            && SELF_DESCRIBING_TYPES.contains(&ty.rs_ty.as_str())
+        {
+            self.error(
+                &format!("type `{}`", ty.ident),
+                "cannot be deserialized from the `postcard` wire format, because it is not \
+                    self-describing",
+            );
+        }
+    }
+
+    fn validate_enum(&mut self, ty: &Enum) {
+        let location = format!("type `{}`", ty.ident.format(false));
+        let generic_params = get_generic_params(&ty.ident);
+        let is_ts = matches!(self.bindings_type, BindingsType::TsRuntime(_));
+
+        if self.wire_format == WireFormat::Postcard {
+            let tagging = if ty.options.untagged {
+                Some("untagged")
+            } else if ty.options.content_prop_name.is_some() {
+                Some("adjacently tagged")
+            } else if ty.options.tag_prop_name.is_some() {
+                Some("internally tagged")
+            } else {
+                None
+            };
+            if let Some(tagging) = tagging {
+                self.error(
+                    &location,
+                    format!(
+                        "{tagging} enums cannot be used with the `postcard` wire format, because \
+                            it is not self-describing"
+                    ),
+                );
+            }
+        }
+
+        let mut variant_names = BTreeMap::new();
+        for variant in &ty.variants {
+            let variant_location = format!("{location}, variant `{}`", variant.name);
+            let serialized_name = get_variant_name(variant, ty.options.variant_casing);
+            if let Some(other) = variant_names.insert(serialized_name.clone(), &variant.name) {
+                self.error(
+                    &variant_location,
+                    format!(
+                        "conflicts with variant `{other}`, as both are serialized as `{serialized_name}`"
+                    ),
+                );
+            }
+
+            match &variant.ty {
+                Type::Struct(variant_struct) => self.validate_fields(
+                    &variant_location,
+                    &variant_struct.fields,
+                    variant.attrs.field_casing,
+                    &generic_params,
+                ),
+                Type::Tuple(items) => {
+                    if is_ts && items.len() > 1 {
+                        self.error(
+                            &variant_location,
+                            "variants with multiple unnamed fields are not supported in TypeScript",
+                        );
+                    }
+                    for item in items {
+                        self.validate_ident(&variant_location, item, &generic_params);
+                    }
+                }
+                Type::Unit if is_ts && ty.options.untagged => self.error(
+                    &variant_location,
+                    "unit variants in untagged enums are not supported in TypeScript",
+                ),
+                _ => {}
+            }
+        }
+
+        if ty.options.untagged {
+            self.validate_untagged_variants(&location, &ty.variants);
+        }
+    }
+
+    /// Checks that every variant of an untagged enum can be told apart from
+    /// the variants before it. Otherwise, the variant can never be
+    /// deserialized, because the first matching variant wins.
+    fn validate_untagged_variants(&mut self, location: &str, variants: &[Variant]) {
+        let mut shapes = Vec::<(String, &Variant)>::new();
+        for variant in variants {
+            let shape = match &variant.ty {
+                Type::Struct(variant_struct) => {
+                    let fields = variant_struct
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            format!(
+                                "{}: {}",
+                                get_field_name(field, variant.attrs.field_casing),
+                                field.ty
+                            )
+                        })
+                        .collect::<BTreeSet<_>>();
+                    format!("{fields:?}")
+                }
+                other => format!("{other:?}"),
+            };
+
+            if let Some((_, other)) = shapes.iter().find(|(other_shape, _)| *other_shape == shape) {
+                self.error(
+                    &format!("{location}, variant `{}`", variant.name),
+                    format!(
+                        "is indistinguishable from variant `{}` in an untagged enum, so it can \
+                            never be deserialized",
+                        other.name
+                    ),
+                );
+            } else {
+                shapes.push((shape, variant));
+            }
+        }
+    }
+
+    fn validate_fields(
+        &mut self,
+        location: &str,
+        fields: &[Field],
+        casing: Casing,
+        generic_params: &[&str],
+    ) {
+        let mut field_names = BTreeMap::new();
+        for field in fields {
+            let field_location = match &field.name {
+                Some(name) => format!("{location}, field `{name}`"),
+                None => location.to_owned(),
+            };
+            self.validate_ident(&field_location, &field.ty, generic_params);
+
+            if self.wire_format == WireFormat::Postcard {
+                if field.attrs.flatten {
+                    self.error(
+                        &field_location,
+                        "fields cannot be flattened with the `postcard` wire format, because it \
+                            is not self-describing",
+                    );
+                }
+                if field.attrs.skip_serializing_if.is_some() {
+                    self.error(
+                        &field_location,
+                        "fields cannot be skipped with the `postcard` wire format, because it \
+                            identifies fields by their position",
+                    );
+                }
+            }
+
+            if field.attrs.flatten {
+                if matches!(self.types.get(&field.ty), Some(Type::Struct(ty)) if ty.is_compact()) {
+                    self.error(
+                        &field_location,
+                        "compact structs cannot be flattened, because they are encoded as arrays",
+                    );
+                }
+                if matches!(self.bindings_type, BindingsType::TsRuntime(_))
+                    && !generic_params.contains(&field.ty.name.as_str())
+                    && !matches!(
+                        self.types.get(&field.ty),
+                        None | Some(Type::Struct(_) | Type::Enum(_))
+                    )
+                {
+                    self.error(
+                        &field_location,
+                        "only structs and enums can be flattened in TypeScript",
+                    );
+                }
+                continue;
+            }
+
+            if let Some(name) = &field.name {
+                let serialized_name = get_field_name(field, casing);
+                if let Some(other) = field_names.insert(serialized_name.clone(), name) {
+                    self.error(
+                        &field_location,
+                        format!(
+                            "conflicts with field `{other}`, as both are serialized as `{serialized_name}`"
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks that the type referred to by the identifier, and the types of
+    /// all its generic arguments, are known.
+    fn validate_ident(&mut self, location: &str, ident: &TypeIdent, generic_params: &[&str]) {
+        let is_generic_param = ident.generic_args.is_empty()
+            && !ident.is_array()
+            && generic_params.contains(&ident.name.as_str());
+        if !is_generic_param && !self.types.contains_key(ident) {
+            self.error(
+                location,
+                format!(
+                    "type `{ident}` is not part of the protocol; does it implement `Serializable`?"
+                ),
+            );
+        }
+
+        if matches!(self.types.get(ident), Some(Type::Container(name, _)) if name == "Handle") {
+            if matches!(self.bindings_type, BindingsType::TsRuntime(_)) {
+                self.error(location, "handles are not supported in TypeScript");
+            }
+
+            let is_resource = ident.generic_args.first().is_some_and(|(arg, _)| {
+                generic_params.contains(&arg.name.as_str())
+                    || matches!(self.types.get(arg), Some(Type::Struct(ty)) if ty.options.is_resource)
+            });
+            if !is_resource {
+                self.error(
+                    location,
+                    format!("`{ident}` does not refer to a type declared with `#[fp(resource)]`"),
+                );
+            }
+        }
+
+        if matches!(self.types.get(ident), Some(Type::Callback(_, _))) {
+            if let BindingsType::TsRuntime(config) = self.bindings_type {
+                if config.generate_web_worker {
+                    self.error(
+                        location,
+                        "callbacks are not supported by the Web Worker runtime",
+                    );
+                }
+            }
+        }
+
+        for (arg, _) in &ident.generic_args {
+            self.validate_ident(location, arg, generic_params);
+        }
+    }
+}
+
+fn get_generic_params(ident: &TypeIdent) -> Vec<&str> {
+    ident
+        .generic_args
+        .iter()
+        .map(|(arg, _)| arg.name.as_str())
+        .collect()
+}
+
+fn get_field_name(field: &Field, casing: Casing) -> String {
+    match (&field.attrs.rename, &field.name) {
+        (Some(rename), _) => rename.clone(),
+        (None, Some(name)) => casing.format_string(name.trim_start_matches("r#")),
+        (None, None) => String::new(),
+    }
+}
+
+fn get_variant_name(variant: &Variant, casing: Casing) -> String {
+    match &variant.attrs.rename {
+        Some(rename) => rename.clone(),
+        None => casing.format_string(variant.name.trim_start_matches("r#")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        functions::FunctionList,
+        prelude::{Callback, Handle},
+        RustPluginConfig, Serializable, TsRuntimeConfig,
+    };
+
+    fn protocol(import_decls: &[&str], items: &[&str]) -> Protocol {
+        let mut import_functions = FunctionList::new();
+        for decl in import_decls {
+            import_functions.add_function(decl);
+        }
+
+        let mut types = TypeMap::new();
+        for primitive in ["String", "u32", "i64"] {
+            types.insert(TypeIdent::from(primitive), Type::String);
+        }
+        for item in items {
+            let ty = Type::from_item(item);
+            let ident = match &ty {
+                Type::Enum(ty) => ty.ident.clone(),
+                Type::Struct(ty) => ty.ident.clone(),
+                _ => unreachable!(),
+            };
+            types.insert(ident, ty);
+        }
+
+        Protocol::new(import_functions, FunctionList::new(), types)
+    }
+
+    #[test]
+    fn valid_protocol() {
+        let protocol = protocol(
+            &["fn my_function(arg: Point<u32>) -> String;"],
+            &["struct Point<T> { x: T, y: T }"],
+        );
+
+        assert_eq!(
+            validate_protocol(&protocol, &BindingsType::TsRuntime(TsRuntimeConfig::new())),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn collects_all_problems() {
+        let protocol = protocol(
+            &[
+                "fn my_function(new: Missing, other_arg: u32, otherArg: u32);",
+                "fn myFunction();",
+            ],
+            &[
+                r#"#[serde(rename_all = "camelCase")] struct Renamed { my_field: u32, myField: u32 }"#,
+                "#[serde(untagged)] enum Untagged { A(u32), B(u32), C }",
+                "struct Flattened { #[serde(flatten)] extra: u32 }",
+            ],
+        );
+
+        let report = validate_protocol(&protocol, &BindingsType::TsRuntime(TsRuntimeConfig::new()))
+            .unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "Cannot generate ts-runtime bindings, because the protocol has 8 problems:
+  - import `my_function`, argument `new`: type `Missing` is not part of the protocol; does it implement `Serializable`?
+  - import `my_function`, argument `new`: `new` is a reserved word in TypeScript
+  - import `my_function`, argument `otherArg`: conflicts with argument `other_arg`, as both become `otherArg` in TypeScript
+  - import `my_function`: conflicts with import `myFunction`, as both generate `myFunction`
+  - type `Flattened`, field `extra`: only structs and enums can be flattened in TypeScript
+  - type `Renamed`, field `myField`: conflicts with field `my_field`, as both are serialized as `myField`
+  - type `Untagged`, variant `C`: unit variants in untagged enums are not supported in TypeScript
+  - type `Untagged`, variant `B`: is indistinguishable from variant `A` in an untagged enum, so it can never be deserialized
+"
+        );
+    }
+
+    #[test]
+    fn validates_compact_structs() {
+        let protocol = protocol(
+            &[],
+            &[
+                "#[fp(compact)] struct Point { x: u32, #[serde(default)] y: u32 }",
+                r#"#[fp(compact)] struct Sparse { #[serde(skip_serializing_if = "Option::is_none")] x: u32 }"#,
+                "struct Wrapper { #[serde(flatten)] point: Point }",
+                "#[fp(compact)] struct Pair(u32, u32);",
+                "#[fp(compact)] struct Empty;",
+            ],
+        );
+
+        let report = validate_protocol(
+            &protocol,
+            &BindingsType::RustPlugin(RustPluginConfig::builder().name("plugin").build()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "Cannot generate rust-plugin bindings, because the protocol has 4 problems:
+  - type `Empty`: `#[fp(compact)]` only applies to structs with named fields, because other structs are not encoded as maps
+  - type `Pair`: `#[fp(compact)]` only applies to structs with named fields, because other structs are not encoded as maps
+  - type `Sparse`, field `x`: fields of compact structs cannot be skipped, because that would shift the fields after them
+  - type `Wrapper`, field `point`: compact structs cannot be flattened, because they are encoded as arrays
+"
+        );
+    }
+
+    #[test]
+    fn validates_postcard_protocols() {
+        let mut protocol = protocol(
+            &["fn my_function(arg: Wrapper) -> Event;"],
+            &[
+                r#"struct Wrapper { #[serde(flatten)] point: Point, #[serde(skip_serializing_if = "is_zero")] count: u32 }"#,
+                "struct Point { x: u32, y: u32 }",
+                "#[serde(untagged)] enum Value { Int(u32), Text(String) }",
+                r#"#[serde(tag = "type")] enum Event { Started, Stopped }"#,
+                r#"#[serde(tag = "type", content = "payload")] enum Message { Text(String) }"#,
+            ],
+        )
+        .with_wire_format(WireFormat::Postcard);
+        protocol.types.insert(
+            TypeIdent::from("Json"),
+            Type::Custom(CustomType {
+                ident: TypeIdent::from("Json"),
+                rs_ty: "serde_json::Value".to_owned(),
+                rs_dependencies: BTreeMap::new(),
+                serde_attrs: vec![],
+                ts_ty: "any".to_owned(),
+                ts_declaration: None,
+                ts_conversion: None,
+            }),
+        );
+
+        let report = validate_protocol(
+            &protocol,
+            &BindingsType::RustPlugin(RustPluginConfig::builder().name("plugin").build()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "Cannot generate rust-plugin bindings, because the protocol has 6 problems:
+  - type `Event`: internally tagged enums cannot be used with the `postcard` wire format, because it is not self-describing
+  - type `Json`: cannot be deserialized from the `postcard` wire format, because it is not self-describing
+  - type `Message`: adjacently tagged enums cannot be used with the `postcard` wire format, because it is not self-describing
+  - type `Value`: untagged enums cannot be used with the `postcard` wire format, because it is not self-describing
+  - type `Wrapper`, field `point`: fields cannot be flattened with the `postcard` wire format, because it is not self-describing
+  - type `Wrapper`, field `count`: fields cannot be skipped with the `postcard` wire format, because it identifies fields by their position
+"
+        );
+    }
+
+    #[test]
+    fn rejects_other_wire_formats_for_ts_runtime() {
+        let protocol = protocol(&[], &[]).with_wire_format(WireFormat::Json);
+
+        let report = validate_protocol(&protocol, &BindingsType::TsRuntime(TsRuntimeConfig::new()))
+            .unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "Cannot generate ts-runtime bindings, because the protocol has 1 problem:
+  - protocol: the `json` wire format is not supported by the TypeScript runtime, which only supports MessagePack
+"
+        );
+    }
+
+    #[test]
+    fn validates_import_modules() {
+        let mut protocol = protocol(&[], &[]);
+        for (decl, module) in [
+            ("fn log(message: String);", "logging"),
+            ("fn fp_log(message: String);", "fp"),
+            ("fn bad_log(message: String);", "Bad-Module"),
+        ] {
+            protocol
+                .import_functions
+                .add_function_to_module(decl, module);
+        }
+        protocol
+            .export_functions
+            .add_function_to_module("fn exported();", "logging");
+
+        let report = validate_protocol(&protocol, &BindingsType::RustWasmer2Runtime).unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "Cannot generate rust-wasmer2-runtime bindings, because the protocol has 3 problems:
+  - import `bad_log`: import module `Bad-Module` is not a snake_case identifier
+  - import `fp_log`: import module `fp` is reserved for functions outside of a named module
+  - export `exported`: only imported functions can be part of an import module
+"
+        );
+    }
+
+    #[test]
+    fn validates_handles() {
+        let mut protocol = protocol(
+            &[
+                "fn open_connection(url: String) -> Handle<Connection>;",
+                "fn open_file(path: String) -> Handle<String>;",
+            ],
+            &[
+                "#[fp(resource)] struct Connection;",
+                "#[fp(resource)] struct File { path: String }",
+                "struct Wrapper { connection: Handle<Connection> }",
+            ],
+        );
+        Handle::<String>::collect_types(&mut protocol.types);
+        protocol
+            .export_functions
+            .add_function("fn export_connection(connection: Handle<Connection>) -> Wrapper;");
+
+        let report = validate_protocol(&protocol, &BindingsType::RustWasmer2Runtime).unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "Cannot generate rust-wasmer2-runtime bindings, because the protocol has 2 problems:
+  - import `open_file`, return type: `Handle<String>` does not refer to a type declared with `#[fp(resource)]`
+  - type `File`: resource types must be unit structs, because their contents are never exposed to plugins
+"
+        );
+
+        let report = validate_protocol(&protocol, &BindingsType::TsRuntime(TsRuntimeConfig::new()))
+            .unwrap_err();
+        assert!(report.to_string().contains(
+            "import `open_connection`, return type: handles are not supported in TypeScript"
+        ));
+    }
+
+    #[test]
+    fn validates_callbacks() {
+        let mut protocol = protocol(&["fn on_event(handler: Callback<String, ()>);"], &[]);
+        Callback::<String, ()>::collect_types(&mut protocol.types);
+
+        let config = TsRuntimeConfig::new();
+        assert_eq!(
+            validate_protocol(&protocol, &BindingsType::TsRuntime(config.clone())),
+            Ok(())
+        );
+
+        let report = validate_protocol(
+            &protocol,
+            &BindingsType::TsRuntime(config.with_web_worker()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            report.errors[0].to_string(),
+            "import `on_event`, argument `handler`: callbacks are not supported by the Web Worker runtime"
+        );
+    }
+
+    #[test]
+    fn validates_events() {
+        let mut invalid = protocol(&["#[fp(event)] async fn imported(event: String);"], &[]);
+        for decl in [
+            "#[fp(event)] async fn log_entry(entry: String);",
+            "#[fp(event)] fn tick(count: u32) -> u32;",
+            "#[fp(event)] async fn nothing();",
+            "fn subscribe_log_entry();",
+        ] {
+            invalid.export_functions.add_function(decl);
+        }
+
+        let report = validate_protocol(
+            &invalid,
+            &BindingsType::RustPlugin(RustPluginConfig::builder().name("plugin").build()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "Cannot generate rust-plugin bindings, because the protocol has 6 problems:
+  - import `imported`: events can only be declared as exports
+  - export `nothing`: events must have exactly one argument
+  - export `tick`: events must be async
+  - export `tick`: the argument of an event must be serialized, so it cannot be a primitive
+  - export `tick`: events cannot return a value
+  - export `subscribe_log_entry`: conflicts with export `log_entry`, as both generate `subscribe_log_entry`
+"
+        );
+
+        let mut protocol = protocol(&[], &[]);
+        protocol
+            .export_functions
+            .add_function("#[fp(event)] async fn log_entry(entry: String);");
+        let config = TsRuntimeConfig::new();
+        assert_eq!(
+            validate_protocol(&protocol, &BindingsType::TsRuntime(config.clone())),
+            Ok(())
+        );
+        let report = validate_protocol(
+            &protocol,
+            &BindingsType::TsRuntime(config.with_web_worker()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            report.errors[0].to_string(),
+            "export `log_entry`: events are not supported by the Web Worker runtime"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    types::{TypeIdent, TypeMap},
+    Serializable, Type,
+};
+use std::marker::PhantomData;
+
+/// Handle to a resource of type `T` that is owned by the host.
+///
+/// Handles may be used in the signatures of functions to pass host objects to
+/// plugins without exposing their contents. `T` must be a resource type,
+/// which is declared using `#[fp(resource)]`:
+///
+/// ```ignore
+/// #[derive(Serializable)]
+/// #[fp(resource)]
+/// pub struct Connection;
+///
+/// fp_import! {
+///     fn open_connection(url: String) -> Handle<Connection>;
+///     fn query(connection: &Handle<Connection>, query: String) -> Vec<String>;
+/// }
+/// ```
+///
+/// This type only serves to describe protocols. The generated bindings use
+/// the `Handle` types from `fp-bindgen-support` instead.
+pub struct Handle<T>(PhantomData<T>);
+
+impl<T> Serializable for Handle<T>
+where
+    T: Serializable,
+{
+    fn ident() -> TypeIdent {
+        TypeIdent {
+            name: "Handle".to_owned(),
+            generic_args: vec![(TypeIdent::from("T"), vec![])],
+            ..Default::default()
+        }
+    }
+
+    fn ty() -> Type {
+        Type::Container("Handle".to_owned(), TypeIdent::from("T"))
+    }
+
+    fn collect_types(types: &mut TypeMap) {
+        types.entry(Self::ident()).or_insert_with(Self::ty);
+        T::collect_types(types);
+    }
+}
@@ -75,13 +75,13 @@ fp_bindgen::prelude::fp_export! {
 
 **Important caveat:** There must be exactly one `fp_import!` block and one `fp_export!` block in the
 same module as where you invoke `fp_bindgen!()`. If you only have imports, or only have exports, you
-should create an empty block for the other.
+should create an empty block for the other. Imports can be grouped further using
+[import modules](#import-modules).
 
 ### Data structures
 
 Besides primitives, functions can pass Rust `struct`s and `enum`s as their arguments and return
-value, but only by value (passing a reference across the Wasm bridge is currently not supported) and
-only for types that implement `Serializable`.
+value, as long as they implement `Serializable`. Return values are always passed by value.
 
 **Example:**
 
@@ -100,6 +100,27 @@ fp_bindgen::prelude::fp_import! {
 Note that `Serializable` is implemented by default for some common standard types, such as
 `Option`, `Vec`, and other container types.
 
+### Borrowed arguments
+
+Arguments may also be declared as shared references, such as `&str`, `&[u8]` or `&MyStruct`. This
+allows callers to pass data without having to clone it first. Whoever implements the function still
+receives an owned value: a `&str` argument is received as a `String`, a `&[T]` argument as a
+`Vec<T>`, and a `&T` argument as a `T`. Borrowed arguments are encoded the same way as their owned
+counterparts, so they don't affect compatibility with existing plugins and runtimes.
+
+**Example:**
+
+```ignore
+fp_bindgen::prelude::fp_export! {
+    fn process_document(title: &str, contents: &[u8]) -> MyStruct;
+}
+```
+
+The Rust Wasmer runtime can invoke this export as `runtime.process_document(&title, &contents)`,
+while the plugin implements it as `fn process_document(title: String, contents: Vec<u8>)`.
+Primitives that are passed directly across the Wasm bridge, such as `u32` or `bool`, cannot be
+borrowed.
+
 ### Async functions
 
 Functions can also be `async`, which works as you would expect:
@@ -112,6 +133,119 @@ fp_bindgen::prelude::fp_import! {
 }
 ```
 
+### Import modules
+
+By default, plugins import all functions from the `fp` Wasm module. Inside the `fp_import!` block,
+functions can also be grouped in named modules, which are imported from a Wasm module of the same
+name. This makes it possible to compose a protocol from multiple parts, such as a shared logging
+protocol and a domain-specific one:
+
+**Example:**
+
+```ignore
+fp_bindgen::prelude::fp_import! {
+    fn my_imported_function(a: u32, b: u32) -> u32;
+
+    mod logging {
+        fn log(level: LogLevel, message: String);
+    }
+}
+```
+
+Every module gets its own part in the generated bindings:
+
+- The Rust plugin bindings place the functions in a Rust module of the same name, so plugins call
+  `logging::log()`.
+- The Rust Wasmer runtimes expect their implementations in a `logging` module next to the bindings,
+  such as `spec::logging::log()`.
+- The TypeScript runtime declares a `LoggingImports` type with the functions of the module, which
+  is included in the `Imports` type.
+
+Function names must be unique across all modules, and modules cannot be nested. Exports are not
+namespaced, so `fp_export!` does not support modules.
+
+### Resource handles
+
+Host objects such as database connections or file descriptors cannot be serialized, but plugins may
+still need to refer to them. Instead of passing around string IDs that need to be cleaned up
+manually, functions can use `Handle<T>`, where `T` is a resource type declared using
+`#[fp(resource)]`:
+
+**Example:**
+
+```ignore
+#[derive(Serializable)]
+#[fp(resource)]
+pub struct Connection;
+
+fp_bindgen::prelude::fp_import! {
+    fn open_connection(url: String) -> Handle<Connection>;
+    fn query(connection: &Handle<Connection>, query: String) -> Vec<String>;
+}
+```
+
+Plugins only see an opaque ID, while the Rust Wasmer runtimes keep the resource in a table that
+belongs to the plugin instance:
+
+- The Rust plugin bindings declare `Connection` as an uninhabited type. Handles are reference
+  counted: cloning a handle adds a reference, and the runtime is notified when a handle is dropped.
+  Passing a handle by value gives up the reference once the call is done, so use a borrowed argument
+  such as `&Handle<Connection>` for handles the plugin holds on to.
+- The Rust Wasmer runtimes expect the host type in the module that includes the bindings, such as
+  `spec::Connection`. Import functions return handles created with `Handle::new(connection)`, and
+  receive handles on which `get()` returns the resource, or a `HandleError` if the plugin passed a
+  handle it no longer owns, or one that refers to a resource of another type.
+
+Handles are not supported by the TypeScript runtime.
+
+### Callbacks
+
+Functions can be passed across the bridge using `Callback<A, R>`, where `A` is the type of the
+argument and `R` the type of the result. Callbacks that take multiple arguments can use a tuple for
+`A`. This allows the host to hand a plugin a progress reporter, or a plugin to register an event
+handler with the host:
+
+**Example:**
+
+```ignore
+fp_bindgen::prelude::fp_import! {
+    fn on_event(handler: Callback<Event, ()>);
+}
+
+fp_bindgen::prelude::fp_export! {
+    fn run(progress: Callback<u32, ()>);
+}
+```
+
+Callbacks are created using `Callback::new(|event| ...)`, and invoked using `call()`. Invoking a
+callback that was received from the other side calls across the bridge; in the Rust Wasmer runtimes,
+this returns a `Result`, since the plugin may not support callbacks. In TypeScript, callbacks are
+plain functions. Callbacks are reference counted, so the side that created a callback keeps it
+alive until the other side has dropped all of its copies. See the
+[specification](docs/SPEC.md#callbacks) for how callbacks are passed between host and plugin.
+
+### Events
+
+Events allow hosts to notify plugins of things that happen, without calling plugins that are not
+interested in them. Events are declared in `fp_export!` using the `#[fp(event)]` attribute, and
+must be async functions with a single, serialized argument and no return value:
+
+**Example:**
+
+```ignore
+fp_bindgen::prelude::fp_export! {
+    /// Emitted whenever a document is saved.
+    #[fp(event)]
+    async fn document_saved(event: DocumentSaved);
+}
+```
+
+Instead of implementing the export, plugins call the generated `subscribe_document_saved()` with
+an async handler, and `unsubscribe_document_saved()` once they are no longer interested. Hosts
+call `emit_document_saved()` (or `emitDocumentSaved()` in TypeScript), which resolves once the
+handler has finished, and returns right away if the plugin has not subscribed. See the
+[specification](docs/SPEC.md#events) for how subscriptions are communicated to the host.
+
 ### Using existing Rust types
 
 Sometimes you may wish to use Rust types for your protocol that you also want to use directly in the
@@ -180,9 +314,146 @@ Currently, we support the following binding types:
 - `BindingsType::RustPlugin`: Generates bindings for a Rust plugin.
 - `BindingsType::RustWasmerRuntime`: Generates runtime bindings for use with Wasmer.
 - `BindingsType::TsRuntimeWithExtendedConfig`: Generates bindings for a TypeScript runtime.
+- `BindingsType::ProtocolSchema`: Generates a machine-readable description of the protocol.
 
 Note that some binding types take an additional config argument.
 
+Before any bindings are written, the protocol is validated for the chosen binding type. All the
+problems that are found, such as types that are missing from the protocol, names that conflict
+after casing is applied, reserved identifiers, or features the target language doesn't support,
+are reported together. You can also run the validation yourself using
+`fp_bindgen::validate_protocol()`, which returns a `ValidationReport` listing every problem.
+
+Generating bindings may fail, so `fp_bindgen::generate_protocol_bindings()` returns a `Result`
+with a `BindingsError`. The `fp_bindgen!` macro panics with the error instead. If you'd rather
+inspect the bindings than write them, `fp_bindgen::render_protocol_bindings()` returns the
+generated files as a map from their path to their contents:
+
+```ignore
+let files = fp_bindgen::render_protocol_bindings(protocol, config)?;
+for (path, contents) in files.iter() {
+    println!("{path}: {} bytes", contents.len());
+}
+```
+
+To verify that committed bindings are up-to-date, for instance in CI, use
+`fp_bindgen::check_protocol_bindings()`. It generates the bindings in memory and returns
+`BindingsError::OutdatedFiles` with the paths of the files that are missing or would change,
+without touching any files. Setting the `FP_BINDGEN_CHECK` environment variable makes
+`generate_protocol_bindings()`, and thus `fp_bindgen!`, perform this check instead of writing the
+bindings.
+
+### Choosing a wire format
+
+By default, values are serialized as MessagePack when they are passed between host and plugin. A
+protocol can select another wire format by passing it to the `fp_bindgen!` macro:
+
+```ignore
+fp_bindgen::prelude::fp_bindgen!(config, wire_format = fp_bindgen::prelude::WireFormat::Json);
+```
+
+The following alternatives are available:
+
+- `WireFormat::Json`, which is mainly useful for debugging, since it allows inspecting the values
+  that are passed. It requires the `json` feature of `fp-bindgen-support`.
+- `WireFormat::Postcard`, a binary format that is faster than MessagePack, because it doesn't
+  describe the values it encodes. In exchange, protocols that use it cannot contain untagged,
+  internally tagged or adjacently tagged enums, flattened fields, or fields that are skipped when
+  serializing, which is checked when generating bindings. It requires the `postcard` feature of
+  `fp-bindgen-support`.
+
+Generated plugins enable the required feature of `fp-bindgen-support` automatically, but Rust
+runtimes need to enable it themselves. The format is
+negotiated when a runtime loads a plugin: if either side doesn't support the format, MessagePack is
+used instead, so plugins and runtimes that were generated with different formats remain compatible.
+`Runtime::wire_format()` returns the format that was agreed on. Plugins can only be linked to
+plugins that use the same format. The TypeScript runtime only supports MessagePack, so it cannot be
+generated for protocols that select another format.
+
+### Compact structs
+
+Structs are encoded as maps by default, which repeats the name of every field in every value. Structs
+with named fields can opt into a compact encoding with `#[fp(compact)]`, which encodes them as arrays
+of their field values instead:
+
+```ignore
+#[derive(fp_bindgen::prelude::Serializable)]
+#[fp(compact)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    #[fp(default)]
+    pub label: Option<String>,
+}
+```
+
+This is applied consistently by the Rust plugin, the Rust runtimes and the TypeScript runtime, which
+still exposes compact structs as regular objects. Because fields are identified by their position,
+they cannot be flattened or skipped, and only fields that are appended at the end and have a
+`default` can be added without breaking older builds. See the
+[specification](https://github.com/fiberplane/fp-bindgen/blob/main/docs/SPEC.md#compact-structs) for
+the full compatibility rules.
+
+### Using the CLI
+
+Alternatively, you can generate bindings using the `fp-bindgen` command-line tool, which you can
+install with `cargo install fp-bindgen-cli`:
+
+```sh
+fp-bindgen generate --protocol path/to/my-protocol --target ts-runtime --out bindings
+```
+
+The `--protocol` option accepts either the crate that defines your protocol, or a protocol
+description that was generated using `BindingsType::ProtocolSchema`. In the former case, the CLI
+runs the crate with `cargo run`, which is expected to invoke `fp_bindgen!` at least once. The
+`--target` option can be specified multiple times, and every bindings type is written to a
+subdirectory of the `--out` directory named after it.
+
+Pass `--check` to verify that the bindings on disk are up-to-date instead of writing them. The
+command then lists the files that would change and exits with an error if there are any.
+
+Generator options can be specified in an `fp-bindgen.toml` file, which is read from the current
+directory, or from the path given with `--config`. Every bindings type has its own section, and
+the bindings types that have a section are generated when no `--target` is given:
+
+```toml
+protocol = "../my-protocol"
+out = "bindings"
+
+[rust-plugin]
+name = "my-bindings"
+authors = ["Me <me@example.com>"]
+version = "1.0.0"
+path = "../my-bindings" # Overrides the output directory for this bindings type.
+
+[rust-plugin.dependencies]
+time = { version = "0.3", features = ["serde-well-known"] }
+
+[ts-runtime]
+msgpack-module = "https://unpkg.com/@msgpack/msgpack@2.7.2/mod.ts"
+raw-export-wrappers = true
+streaming-instantiation = false
+bigint-for-64bit-integers = false
+validators = true
+wasi = "node" # Or: wasi = { deno = "https://deno.land/std@0.205.0/wasi/snapshot_preview1.ts" }
+web-worker = true
+custom-type-conversions = true
+enum-helpers = true
+import-path-extension = ".ts"
+
+[ts-runtime.custom-types.OffsetDateTime] # Overrides the conversion for `OffsetDateTime`.
+ty = "Temporal.Instant"
+decode = "(value: string) => Temporal.Instant.from(value)"
+encode = "(value: Temporal.Instant) => value.toString()"
+
+[ts-runtime.npm-package]
+name = "my-runtime" # Defaults to the protocol name, followed by "-runtime".
+version = "1.0.0" # Defaults to the protocol version.
+license = "MIT"
+
+[rust-wasmer2-runtime]
+```
+
 ## Using the bindings
 
 How to use the generated bindings differs between the various types.
@@ -231,6 +502,41 @@ provided on the `Runtime` instance as methods. Please be aware that implementati
 `fp_export!` functions is always at the discretion of the plugin, and an attempt to invoke a missing
 implementation can fail with an `InvocationError::FunctionNotExported` error.
 
+To find out in advance which functions a plugin implements, the `Runtime` also provides a
+`has_<function_name>()` method for every `fp_export!` function, as well as an
+`implemented_exports()` method that returns the names of all the implemented functions.
+
+The WASI variant of the runtime (`BindingsType::RustWasmer2WasiRuntime`) additionally provides a
+`Runtime::with_wasi_config()` constructor, which takes a `WasiConfig` describing the WASI environment
+of the plugin: its arguments, environment variables and mapped directories (optionally read-only),
+as well as callbacks that receive its output to stdout and stderr. An `OutputBuffer` can be used to
+collect such output in memory. Plugins cannot create, change or remove anything inside read-only
+directories. Note that the generated bindings require dependencies on `wasmer-wasi` and
+`wasmer-vfs`.
+
+#### Linking plugins
+
+When a protocol declares a function both in `fp_import!` and in `fp_export!`, with the same
+signature, the Rust Wasmer runtimes can forward calls to the import of one plugin to the export of
+another. This allows plugins to be composed into pipelines, where one plugin produces the data that
+another one consumes:
+
+```ignore
+let producer = Runtime::new(producer_wasm)?;
+let consumer = Runtime::new(consumer_wasm)?;
+let linked_imports = consumer.link("producer", &producer)?;
+```
+
+Calls to linked imports pass the serialized arguments and return values along using the `_raw`
+export wrappers, so they don't need to be deserialized and serialized again by the host. Imports
+that the provider doesn't implement are still handled by the host, and `Runtime::unlink()` removes
+all links again. Linking fails with a `LinkError` if it would create a cycle, while failures in a
+linked plugin are reported as `InvocationError::LinkedPlugin`, which contains the name the plugin
+was linked under. Only synchronous functions can be linked, and only if their arguments and return
+value don't contain handles or callbacks. Other imports are always handled by the host, even if the
+provider exports them; the documentation of the generated `link()` method lists these imports,
+along with the reason they cannot be linked.
+
 ### Using the TypeScript runtime bindings
 
 The TypeScript runtime generator can work with browsers, Node.js and Deno.
@@ -242,6 +548,112 @@ are expected to provide implementations for all the `fp_import!` functions, whil
 `Promise` will give you an object with all the `fp_export!` functions the provided plugin has
 implemented.
 
+The `implementedExports()` helper returns the names of the functions on that object, which is
+useful if you want to check which optional exports a plugin provides.
+
+If you enable `TsRuntimeConfig::with_validators()`, the runtime checks all the data it decodes
+against the protocol's types, and throws a `ValidationError` with the path of the first mismatch.
+Validation can be disabled at runtime by passing `{ validate: false }` as the third argument to
+`createRuntime()`, which you may want to do for production builds.
+
+Plugins compiled for the `wasm32-wasi` target can be run by enabling `TsRuntimeConfig::with_wasi()`,
+using either `TsWasiShim::Node` for Node's built-in `node:wasi` module, or `TsWasiShim::Deno` with
+the URL of the WASI module from the Deno standard library. The arguments, environment variables and
+preopened directories that are available to the plugin can then be passed through the `wasi` option
+of `createRuntime()`.
+
+Constructing enum values by hand can be error-prone, because their representation depends on
+options such as `tag`, `content` and `rename_all`. `TsRuntimeConfig::with_enum_helpers()` adds
+helpers for every enum to `types.ts`, which take these options into account: a
+`create<Enum><Variant>()` constructor for every variant, and unless the enum is untagged, an
+`is<Enum><Variant>()` type guard for every variant and a `match<Enum>()` function that requires a
+handler for every variant:
+
+```ts
+const action = createReduxActionUpdateTitle({ title: "Hello" });
+const description = matchReduxAction(action, {
+    ClearTitle: () => "Clears the title",
+    UpdateTitle: ({ payload }) => `Sets the title to ${payload.title}`,
+});
+```
+
+By default, custom types such as `time::OffsetDateTime` and `http::Uri` appear in TypeScript as
+they are encoded, usually as strings. Enable `TsRuntimeConfig::with_custom_type_conversions()` to
+convert them to richer types instead: `OffsetDateTime` becomes a `Date`, `http::Uri` becomes a
+`URL`, and `bytes::Bytes` and `serde_bytes::ByteBuf` become a `Uint8Array`. Values are converted
+wherever they occur in arguments and results, including in nested struct fields, but not inside
+untagged enums. Custom types define their conversion through `CustomType::ts_conversion`, which you
+can override per type with `TsRuntimeConfig::with_custom_type_conversion()`:
+
+```ignore
+let config = TsRuntimeConfig::new().with_custom_type_conversion(
+    "OffsetDateTime",
+    TsTypeConversion::new("Temporal.Instant", "(value: string) => Temporal.Instant.from(value)")
+        .with_encode("(value: Temporal.Instant) => value.toString()"),
+);
+```
+
+To keep long-running plugin calls from blocking the main thread of a browser, enable
+`TsRuntimeConfig::with_web_worker()`. This generates a `worker.ts` entry point that hosts the
+runtime inside a Web Worker, and a `workerRuntime.ts` module with a `createWorkerRuntime()`
+function for the main thread:
+
+```ts
+const worker = new Worker(new URL("./worker.ts", import.meta.url), { type: "module" });
+const plugin = await createWorkerRuntime(worker, fetch("plugin.wasm"), importFunctions);
+```
+
+The returned functions are the same as those of `createRuntime()`, except that they always return
+a `Promise`. Arguments and results are transferred to and from the worker as raw MessagePack data,
+while import functions are still called on the main thread. Synchronous import functions block the
+worker until they return, which relies on `SharedArrayBuffer` and therefore requires the page to be
+[cross-origin isolated](https://developer.mozilla.org/en-US/docs/Web/API/crossOriginIsolated).
+Values that are passed to import functions are copied between threads, so when combined with custom
+type conversions, the converted types must support structured cloning (`URL` does not).
+
+To publish the runtime to npm, pass a `TsNpmPackageConfig` to `TsRuntimeConfig::with_npm_package()`.
+The sources are then written to a `src/` directory, next to a `package.json` and TypeScript configs
+that compile them into an ES module and a CommonJS module, each with `.d.ts` type declarations.
+Run `npm install && npm run build` in the output directory to build the package. Relative imports
+in the generated sources use a `.js` extension in this case, as required for ES modules, but you can
+choose another extension with `TsRuntimeConfig::with_import_path_extension()`.
+
+### Using the protocol description
+
+The `ProtocolSchema` bindings type doesn't generate code, but a `protocol.json` and a
+`schema.json` file that allow tooling to consume a protocol without linking against the crate that
+defines it.
+
+`protocol.json` describes all the `fp_import!` and `fp_export!` functions (including their doc
+comments, arguments, return types and whether they are `async`), as well as all the types they
+reference, including their options and Serde attributes. The document contains a `format_version`
+field that is incremented whenever its structure changes in an incompatible way.
+
+`schema.json` contains a [JSON Schema](https://json-schema.org/) definition for every named type in
+the protocol, describing the shape in which values appear on the wire.
+
+### Checking compatibility between protocol versions
+
+Hosts and plugins are not always upgraded at the same time. To find out whether a new version of
+your protocol can be deployed while builds against the old version are still around, you can
+compare both versions using `fp_bindgen::compatibility::check_compatibility()`:
+
+```ignore
+use fp_bindgen::{compatibility::check_compatibility, prelude::*};
+
+let report = check_compatibility(&old_protocol, &new_protocol);
+if report.is_breaking() {
+    eprintln!("{report}");
+    std::process::exit(1);
+}
+```
+
+Every change in the report is classified separately for the host (can a host built against the
+new protocol still run old plugins?) and the guest (can new plugins still run inside an old host?).
+For example, adding an import is breaking for the guest, but not for the host, while adding a
+field to a struct is only breaking if old versions would need to deserialize a value without it.
+Adding an export is breaking for neither, since exports are optional.
+
 ## Examples
 
 Please have a look at [`examples/README.md`](examples/README.md) for various examples on how to use
@@ -257,14 +669,23 @@ If that is you, please have a look at [`docs/SPEC.md`](docs/SPEC.md).
 
 ## Known Limitations
 
-- Data types may only contain value types. References are currently unsupported.
+- Data types may only contain value types. References are only supported for function arguments.
 - Referencing types using their full module path is prone to cause mismatches during type
   discovery. Please import types using a `use` statement and refer to them by their name only.
-- TypeScript bindings handle 64-bit integers somewhat inconsistently. When passed as primitives (as
-  plain function arguments or return values) they will be encoded using the `BigInt` type. But when
-  they're part of a MessagePack-encoded data type, they will be encoded using `number`, which
-  effectively limits them to a maximum size of `2^53 - 1`. For more information, see:
-  <https://github.com/msgpack/msgpack-javascript/issues/115>
+- TypeScript bindings handle 64-bit integers somewhat inconsistently by default. When passed as
+  primitives (as plain function arguments or return values) they will be encoded using the `BigInt`
+  type. But when they're part of a MessagePack-encoded data type, they will be encoded using
+  `number`, which effectively limits them to a maximum size of `2^53 - 1`. For more information,
+  see: <https://github.com/msgpack/msgpack-javascript/issues/115>. Use
+  `TsRuntimeConfig::with_bigint_for_64bit_integers()` to represent them as `bigint` everywhere
+  instead. This requires `@msgpack/msgpack` 2.8.0 or later.
+- 128-bit integers are represented as `bigint` in TypeScript and are always serialized, even when
+  passed as primitives. Values nested inside untagged enums are not converted, and will be left as
+  the `Uint8Array` used on the wire.
+- Resource handles are only supported by the Rust plugin and Rust Wasmer runtime bindings.
+- Callbacks are synchronous. In TypeScript, they cannot be used with the Web Worker runtime, and
+  the values passed to and from them are not validated or normalized.
+- Events are not supported by the TypeScript Web Worker runtime.
 
 ## FAQ
 
@@ -302,12 +723,26 @@ fp_bindgen::prelude::fp_import! {
 
 ### What about versioning?
 
-Generally, versioning is considered out-of-scope for this project. This means it is your own
-responsibility to verify a plugin you execute was compiled against a compatible version of the
-protocol your runtime provides.
+The `fp_bindgen!` macro identifies your protocol using the name and version of the crate in which
+it is invoked, together with a hash of all the function signatures and type definitions. Generated
+plugins export this identity through a `__fp_protocol_version` function, and the generated runtimes
+verify it when a plugin is loaded:
 
-If your protocol ever needs to introduce breaking changes, we advise to include a `version() -> u32`
-export function in the protocol itself that you can call before invoking any other functions.
+- If the hashes match, the plugin was built against the exact same protocol.
+- If the hashes differ, but the protocol names match and the versions are compatible according to
+  the semantic versioning rules used by Cargo, the plugin is loaded, but a warning is emitted.
+- Otherwise, the plugin is refused. The Rust runtimes return a `RuntimeError::IncompatibleProtocol`
+  error from `Runtime::new()`, while the TypeScript runtime throws an `FPRuntimeError` from
+  `createRuntime()`.
+
+Plugins built using older versions of `fp-bindgen` don't export their protocol version. These are
+loaded with a warning. The same goes for protocols that lack a name or a valid semantic version,
+such as those whose bindings are generated using `generate_bindings()` instead of `fp_bindgen!`.
+
+This means it is still your responsibility to bump the version of your protocol crate whenever you
+make a breaking change to it. You can use `check_compatibility()` (see
+[Checking compatibility between protocol versions](#checking-compatibility-between-protocol-versions))
+to find out whether a change is breaking.
 
 As for what constitutes a breaking change, we offer the following guidelines:
 
@@ -320,10 +755,6 @@ As for what constitutes a breaking change, we offer the following guidelines:
 - Adding new types is always safe.
 - **Anything else should be considered a breaking change.**
 
-Note that, because of the above guidelines, you should never need to define a versioning function in
-your first iteration. Because plugin exports are optional, the absense of a versioning function can
-simply be interpreted as meaning the plugin is at version 1.
-
 ## Getting Help
 
 Please see
@@ -349,15 +780,19 @@ See [LICENSE-APACHE](LICENSE-APACHE) and [LICENSE-MIT](LICENSE-MIT).
 
 */
 
+mod callback;
 mod casing;
+pub mod compatibility;
 mod docs;
 mod functions;
 #[cfg(feature = "generators")]
 mod generators;
+mod handle;
 mod serializable;
 
 pub mod prelude;
 pub mod primitives;
+mod protocol;
 pub mod types;
 mod utils;
 
@@ -368,6 +803,9 @@ primitive_impls!();
 
 #[cfg(feature = "generators")]
 pub use generators::{
-    generate_bindings, BindingConfig, BindingsType, RustPluginConfig, RustPluginConfigValue,
-    TsRuntimeConfig,
+    check_protocol_bindings, generate_bindings, generate_protocol_bindings,
+    render_protocol_bindings, validate_protocol, BindingConfig, BindingsError, BindingsType,
+    GeneratedFiles, RustPluginConfig, RustPluginConfigValue, TsNpmPackageConfig, TsRuntimeConfig,
+    TsWasiShim, ValidationError, ValidationReport, CHECK_BINDINGS_ENV_VAR,
+    PROTOCOL_DESCRIPTION_DIR_ENV_VAR,
 };
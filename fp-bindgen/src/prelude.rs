@@ -1,9 +1,13 @@
-pub use crate::functions::{Function, FunctionList};
+pub use crate::callback::Callback;
+pub use crate::functions::{Function, FunctionList, DEFAULT_IMPORT_MODULE};
+pub use crate::handle::Handle;
 pub use crate::primitives::Primitive;
+pub use crate::protocol::{Protocol, ProtocolVersion, WireFormat};
 pub use crate::serializable::Serializable;
-pub use crate::types::{CustomType, Type, TypeIdent, TypeMap};
+pub use crate::types::{CustomType, TsTypeConversion, Type, TypeIdent, TypeMap};
 #[cfg(feature = "generators")]
 pub use crate::{
-    BindingConfig, BindingsType, RustPluginConfig, RustPluginConfigValue, TsRuntimeConfig,
+    BindingConfig, BindingsType, RustPluginConfig, RustPluginConfigValue, TsNpmPackageConfig,
+    TsRuntimeConfig, TsWasiShim,
 };
 pub use fp_bindgen_macros::*;
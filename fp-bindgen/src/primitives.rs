@@ -11,10 +11,13 @@ pub enum Primitive {
     I16,
     I32,
     I64,
+    I128,
     U8,
     U16,
     U32,
     U64,
+    U128,
+    Char,
 }
 
 impl Primitive {
@@ -28,14 +31,24 @@ impl Primitive {
             I16 => "i16",
             I32 => "i32",
             I64 => "i64",
+            I128 => "i128",
             U8 => "u8",
             U16 => "u16",
             U32 => "u32",
             U64 => "u64",
+            U128 => "u128",
+            Char => "char",
         };
         string.to_owned()
     }
 
+    /// Returns whether values of this primitive need to be serialized, because
+    /// they don't fit into the numerical types that are natively supported by
+    /// WebAssembly.
+    pub fn requires_serialization(&self) -> bool {
+        matches!(self, Primitive::I128 | Primitive::U128 | Primitive::Char)
+    }
+
     pub fn js_array_name(&self) -> Option<String> {
         use Primitive::*;
         match self {
@@ -65,10 +78,13 @@ impl FromStr for Primitive {
             "i16" => Primitive::I16,
             "i32" => Primitive::I32,
             "i64" => Primitive::I64,
+            "i128" => Primitive::I128,
             "u8" => Primitive::U8,
             "u16" => Primitive::U16,
             "u32" => Primitive::U32,
             "u64" => Primitive::U64,
+            "u128" => Primitive::U128,
+            "char" => Primitive::Char,
             string => return Err(format!("Unknown primitive type: \"{string}\"")),
         };
         Ok(primitive)
@@ -85,10 +101,13 @@ impl ToTokens for Primitive {
             Primitive::I16 => quote! {i16},
             Primitive::I32 => quote! {i32},
             Primitive::I64 => quote! {i64},
+            Primitive::I128 => quote! {i128},
             Primitive::U8 => quote! {u8},
             Primitive::U16 => quote! {u16},
             Primitive::U32 => quote! {u32},
             Primitive::U64 => quote! {u64},
+            Primitive::U128 => quote! {u128},
+            Primitive::Char => quote! {char},
         })
         .to_tokens(tokens)
     }
@@ -0,0 +1,343 @@
+use crate::{
+    functions::{Function, FunctionList},
+    types::{CustomType, Enum, Field, Struct, Type, TypeMap, Variant},
+};
+use std::fmt::Write;
+
+/// A complete description of a protocol: the functions it imports and exports,
+/// and all the types they reference.
+#[derive(Clone, Debug, Default)]
+pub struct Protocol {
+    /// Name of the protocol, usually the name of the crate that defines it.
+    pub name: String,
+
+    /// Semantic version of the protocol, usually the version of the crate that
+    /// defines it.
+    pub version: String,
+
+    /// Functions provided by the host that may be called by plugins.
+    pub import_functions: FunctionList,
+
+    /// Functions provided by plugins that may be called by the host.
+    pub export_functions: FunctionList,
+
+    /// All the types used by the import and export functions.
+    pub types: TypeMap,
+
+    /// Format that runtimes ask plugins to use for passing values.
+    pub wire_format: WireFormat,
+}
+
+impl Protocol {
+    pub fn new(
+        import_functions: FunctionList,
+        export_functions: FunctionList,
+        types: TypeMap,
+    ) -> Self {
+        Self {
+            import_functions,
+            export_functions,
+            types,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_version(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.name = name.into();
+        self.version = version.into();
+        self
+    }
+
+    pub fn with_wire_format(mut self, wire_format: WireFormat) -> Self {
+        self.wire_format = wire_format;
+        self
+    }
+
+    /// Parses a protocol description, as generated using
+    /// `BindingsType::ProtocolSchema`.
+    #[cfg(feature = "generators")]
+    pub fn from_description(json: &str) -> Result<Self, String> {
+        crate::generators::protocol_schema::parse_protocol(json)
+    }
+
+    /// Returns the identity of the protocol that gets embedded in generated
+    /// plugins and runtimes.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        ProtocolVersion {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            hash: self.signature_hash(),
+        }
+    }
+
+    /// Returns a hash of all the function signatures and type definitions in
+    /// the protocol.
+    ///
+    /// Only those parts that affect how plugins and runtimes interact are
+    /// included in the hash, so changing documentation for instance, does not
+    /// affect the result. Types are hashed in a Rust-like notation in which
+    /// attributes only appear when they are set, so that adding new options
+    /// to fp-bindgen does not change the hash of existing protocols.
+    pub fn signature_hash(&self) -> String {
+        let mut signatures = String::new();
+        for (kind, functions) in [
+            ("import", &self.import_functions),
+            ("export", &self.export_functions),
+        ] {
+            for function in functions {
+                writeln!(signatures, "{kind} {}", format_signature(function)).unwrap();
+            }
+        }
+        for (ident, ty) in &self.types {
+            writeln!(signatures, "type {ident} = {}", format_type(ty)).unwrap();
+        }
+
+        format!("{:016x}", fnv1a_hash(signatures.as_bytes()))
+    }
+}
+
+/// Format in which values are serialized when they are passed between host
+/// and plugin.
+///
+/// The format is negotiated when a runtime loads a plugin. If either side
+/// doesn't support the format, MessagePack is used instead, which is why the
+/// format is not part of the signature hash of the protocol.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum WireFormat {
+    /// MessagePack, with structs encoded as maps, unless they are compact.
+    #[default]
+    MessagePack,
+
+    /// JSON, which is mainly useful for debugging, since it allows inspecting
+    /// the values that are passed. It requires the `json` feature of
+    /// `fp-bindgen-support` to be enabled on both sides.
+    Json,
+
+    /// Postcard, a compact binary format that is faster than MessagePack, but
+    /// which is not self-describing. Protocols that use it cannot contain
+    /// untagged, internally tagged or adjacently tagged enums, flattened
+    /// fields or fields that are skipped when serializing, and cannot be used
+    /// with the TypeScript runtime. It requires the `postcard` feature of
+    /// `fp-bindgen-support` to be enabled on both sides.
+    Postcard,
+}
+
+impl WireFormat {
+    /// Returns the name by which the format is identified in protocol
+    /// descriptions.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MessagePack => "message_pack",
+            Self::Json => "json",
+            Self::Postcard => "postcard",
+        }
+    }
+
+    /// Returns the format with the given name, as returned by [Self::name()].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "message_pack" => Some(Self::MessagePack),
+            "json" => Some(Self::Json),
+            "postcard" => Some(Self::Postcard),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies a specific revision of a protocol.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProtocolVersion {
+    pub name: String,
+    pub version: String,
+    pub hash: String,
+}
+
+fn format_signature(function: &Function) -> String {
+    let args = function
+        .args
+        .iter()
+        .map(|arg| arg.ty.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let return_type = function
+        .return_type
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| "()".to_owned());
+    let modifiers = match (function.is_event, function.is_async) {
+        (true, true) => "event async ",
+        (true, false) => "event ",
+        (false, true) => "async ",
+        (false, false) => "",
+    };
+    let module = function
+        .import_module
+        .as_ref()
+        .map(|module| format!("{module}::"))
+        .unwrap_or_default();
+    format!(
+        "{modifiers}fn {module}{}({args}) -> {return_type}",
+        function.name
+    )
+}
+
+/// Formats the parts of a type definition that affect its representation at
+/// runtime.
+fn format_type(ty: &Type) -> String {
+    match ty {
+        Type::Alias(_, target) => format!("alias {target}"),
+        Type::Array(primitive, len) => format!("[{}; {len}]", primitive.name()),
+        Type::Callback(arg, ret) => format!("callback({arg}) -> {ret}"),
+        Type::Container(name, item) | Type::List(name, item) => format!("{name}<{item}>"),
+        Type::Custom(CustomType {
+            rs_ty,
+            serde_attrs,
+            ts_ty,
+            ..
+        }) => format!(
+            "{}custom {rs_ty} as {ts_ty}",
+            format_attrs("serde", serde_attrs)
+        ),
+        Type::Enum(Enum {
+            variants, options, ..
+        }) => format!(
+            "{}enum {{ {} }}",
+            format_attrs("serde", &options.to_serde_attrs()),
+            variants
+                .iter()
+                .map(format_variant)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Map(name, key, value) => format!("{name}<{key}, {value}>"),
+        Type::Primitive(primitive) => primitive.name(),
+        Type::String => "String".to_owned(),
+        Type::Struct(Struct {
+            fields, options, ..
+        }) => {
+            let mut fp_attrs = Vec::new();
+            if options.is_resource {
+                fp_attrs.push("resource".to_owned());
+            }
+            if options.is_compact {
+                fp_attrs.push("compact".to_owned());
+            }
+            format!(
+                "{}{}struct{}",
+                format_attrs("serde", &options.to_serde_attrs()),
+                format_attrs("fp", &fp_attrs),
+                format_fields(fields)
+            )
+        }
+        Type::Tuple(items) => format!(
+            "({})",
+            items
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Unit => "()".to_owned(),
+    }
+}
+
+fn format_variant(variant: &Variant) -> String {
+    let attrs = format_attrs("serde", &variant.attrs.to_serde_attrs());
+    let name = &variant.name;
+    match &variant.ty {
+        Type::Struct(ty) => format!("{attrs}{name}{}", format_fields(&ty.fields)),
+        ty @ Type::Tuple(_) => format!("{attrs}{name}{}", format_type(ty)),
+        Type::Unit => format!("{attrs}{name}"),
+        ty => format!("{attrs}{name}({})", format_type(ty)),
+    }
+}
+
+fn format_fields(fields: &[Field]) -> String {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let attrs = format_attrs("serde", &field.attrs.to_serde_attrs());
+            match &field.name {
+                Some(name) => format!("{attrs}{name}: {}", field.ty),
+                None => format!("{attrs}{}", field.ty),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(" {{ {fields} }}")
+}
+
+fn format_attrs(name: &str, attrs: &[String]) -> String {
+    if attrs.is_empty() {
+        String::new()
+    } else {
+        format!("#[{name}({})] ", attrs.join(", "))
+    }
+}
+
+/// 64-bit FNV-1a hash. Used instead of the hashers from the standard library,
+/// because those don't guarantee the same output across Rust versions.
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protocol(export: &str, item: &str) -> Protocol {
+        let mut protocol = Protocol::default();
+        protocol.export_functions.add_function(export);
+        if let Type::Struct(ty) = Type::from_item(item) {
+            protocol.types.insert(ty.ident.clone(), Type::Struct(ty));
+        }
+        protocol
+    }
+
+    #[test]
+    fn signature_hash_ignores_docs() {
+        let hash = protocol("fn get() -> Point;", "struct Point { x: i32 }").signature_hash();
+        assert_eq!(hash.len(), 16);
+
+        let documented = protocol(
+            "/// Returns a point.\nfn get() -> Point;",
+            "/// A point.\nstruct Point { /// X.\nx: i32 }",
+        );
+        assert_eq!(documented.signature_hash(), hash);
+
+        let changed = protocol("fn get() -> Point;", "struct Point { x: i64 }");
+        assert_ne!(changed.signature_hash(), hash);
+    }
+
+    #[test]
+    fn signature_hash_only_includes_options_that_are_set() {
+        let point = protocol("fn get() -> Point;", "struct Point { x: i32 }");
+        assert_eq!(
+            format_type(point.types.values().next().unwrap()),
+            "struct { x: i32 }"
+        );
+        assert_eq!(point.signature_hash(), "319b9c56f8098c19");
+
+        let moved = protocol(
+            "fn get() -> Point;",
+            "#[fp(rust_module = \"points\")]\nstruct Point { x: i32 }",
+        );
+        assert_eq!(moved.signature_hash(), point.signature_hash());
+
+        let compact = protocol(
+            "fn get() -> Point;",
+            "#[fp(compact)]\nstruct Point { #[serde(default)] x: i32 }",
+        );
+        assert_eq!(
+            format_type(compact.types.values().next().unwrap()),
+            "#[fp(compact)] struct { #[serde(default)] x: i32 }"
+        );
+        assert_ne!(compact.signature_hash(), point.signature_hash());
+    }
+}
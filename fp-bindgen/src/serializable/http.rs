@@ -1,5 +1,5 @@
 use super::Serializable;
-use crate::types::{CargoDependency, CustomType, Type, TypeIdent};
+use crate::types::{CargoDependency, CustomType, TsTypeConversion, Type, TypeIdent};
 use std::collections::{BTreeMap, BTreeSet};
 
 impl Serializable for http::Method {
@@ -31,6 +31,7 @@ impl Serializable for http::Method {
     | "TRACE""#
                     .to_owned(),
             ),
+            ts_conversion: None,
         })
     }
 }
@@ -52,6 +53,7 @@ impl Serializable for http::uri::Scheme {
             ],
             ts_ty: "Scheme".to_owned(),
             ts_declaration: Some(r#""http" | "https""#.to_owned()),
+            ts_conversion: None,
         })
     }
 }
@@ -72,6 +74,10 @@ impl Serializable for http::uri::Uri {
             ],
             ts_ty: "string".to_owned(),
             ts_declaration: None,
+            ts_conversion: Some(
+                TsTypeConversion::new("URL", "(value: string) => new URL(value)")
+                    .with_encode("(value: URL) => value.toString()"),
+            ),
         })
     }
 }
@@ -93,6 +99,7 @@ impl Serializable for http::HeaderMap {
             ],
             ts_ty: "HeaderMap".to_owned(),
             ts_declaration: Some(r#"{ [key: string]: Uint8Array }"#.into()),
+            ts_conversion: None,
         })
     }
 }
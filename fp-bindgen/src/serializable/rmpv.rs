@@ -22,6 +22,7 @@ impl Serializable for rmpv::Value {
             serde_attrs: Vec::new(),
             ts_ty: "any".to_owned(),
             ts_declaration: None,
+            ts_conversion: None,
         })
     }
 }
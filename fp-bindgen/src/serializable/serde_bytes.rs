@@ -1,5 +1,5 @@
 use super::Serializable;
-use crate::types::{CargoDependency, CustomType, Type, TypeIdent};
+use crate::types::{CargoDependency, CustomType, TsTypeConversion, Type, TypeIdent};
 use std::collections::BTreeMap;
 
 impl Serializable for serde_bytes::ByteBuf {
@@ -18,6 +18,11 @@ impl Serializable for serde_bytes::ByteBuf {
             serde_attrs: vec![],
             ts_ty: "ArrayBuffer".to_owned(),
             ts_declaration: None,
+            ts_conversion: Some(TsTypeConversion::new(
+                "Uint8Array",
+                "(value: ArrayLike<number>) => \
+                    (value instanceof Uint8Array ? value : Uint8Array.from(value))",
+            )),
         })
     }
 }
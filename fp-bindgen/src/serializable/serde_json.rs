@@ -16,6 +16,7 @@ impl Serializable for serde_json::Value {
             serde_attrs: Vec::new(),
             ts_ty: "any".to_owned(),
             ts_declaration: None,
+            ts_conversion: None,
         })
     }
 }
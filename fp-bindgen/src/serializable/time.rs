@@ -1,5 +1,5 @@
 use super::Serializable;
-use crate::types::{CargoDependency, CustomType, Type, TypeIdent};
+use crate::types::{CargoDependency, CustomType, TsTypeConversion, Type, TypeIdent};
 use std::collections::{BTreeMap, BTreeSet};
 
 impl Serializable for time::OffsetDateTime {
@@ -22,6 +22,10 @@ impl Serializable for time::OffsetDateTime {
             serde_attrs: vec![r#"with = "time::serde::rfc3339""#.to_owned()],
             ts_ty: "string".to_owned(),
             ts_declaration: None,
+            ts_conversion: Some(
+                TsTypeConversion::new("Date", "(value: string) => new Date(value)")
+                    .with_encode("(value: Date) => value.toISOString()"),
+            ),
         })
     }
 }
@@ -46,6 +50,7 @@ impl Serializable for time::PrimitiveDateTime {
             serde_attrs: vec![r#"with = "time::serde::rfc3339""#.to_owned()],
             ts_ty: "string".to_owned(),
             ts_declaration: None,
+            ts_conversion: None,
         })
     }
 }
@@ -15,6 +15,15 @@ pub struct CargoDependency {
 }
 
 impl CargoDependency {
+    /// Dependencies use static strings, because they are normally declared in
+    /// code. This converts a string that is only known at runtime, such as one
+    /// read from a protocol description, by leaking it. This is fine as long
+    /// as it only happens once per dependency during the lifetime of the
+    /// process.
+    pub(crate) fn leak_string(string: String) -> &'static str {
+        Box::leak(string.into_boxed_str())
+    }
+
     pub fn from_workspace() -> Self {
         Self::from_workspace_with_features(BTreeSet::new())
     }
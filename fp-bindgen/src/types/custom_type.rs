@@ -27,4 +27,47 @@ pub struct CustomType {
     /// Optional declaration, for when `ts_ty` does not refer to a built-in
     /// type.
     pub ts_declaration: Option<String>,
+
+    /// Optional conversion to a richer representation in the TypeScript
+    /// runtime, such as a `Date` instead of a string.
+    ///
+    /// Conversions are only applied if enabled through
+    /// `TsRuntimeConfig::with_custom_type_conversions()`.
+    pub ts_conversion: Option<TsTypeConversion>,
+}
+
+/// Describes how the TypeScript runtime converts values of a custom type
+/// between the representation used by MessagePack (as described by `ts_ty`)
+/// and a richer one.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TsTypeConversion {
+    /// The type that values are converted to, such as `Date`.
+    pub ty: String,
+
+    /// Expression that evaluates to a function for converting a decoded value
+    /// to `ty`, such as `(value: string) => new Date(value)`.
+    pub decode: String,
+
+    /// Expression that evaluates to a function for converting a value of `ty`
+    /// back before it is encoded, such as `(value: Date) => value.toISOString()`.
+    ///
+    /// May be omitted if values of `ty` are encoded correctly as they are.
+    pub encode: Option<String>,
+}
+
+impl TsTypeConversion {
+    /// Returns a conversion to `ty`, using the given `decode` function.
+    pub fn new(ty: impl Into<String>, decode: impl Into<String>) -> Self {
+        Self {
+            ty: ty.into(),
+            decode: decode.into(),
+            encode: None,
+        }
+    }
+
+    /// Sets the `encode` function.
+    pub fn with_encode(mut self, encode: impl Into<String>) -> Self {
+        self.encode = Some(encode.into());
+        self
+    }
 }
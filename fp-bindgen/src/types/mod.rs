@@ -9,7 +9,7 @@ mod structs;
 mod type_ident;
 
 pub use cargo_dependency::CargoDependency;
-pub use custom_type::CustomType;
+pub use custom_type::{CustomType, TsTypeConversion};
 pub use enums::{Enum, EnumOptions, Variant, VariantAttrs};
 pub use structs::{Field, FieldAttrs, Struct, StructOptions};
 pub use type_ident::TypeIdent;
@@ -20,6 +20,7 @@ pub type TypeMap = BTreeMap<TypeIdent, Type>;
 pub enum Type {
     Alias(String, TypeIdent),
     Array(Primitive, usize),
+    Callback(TypeIdent, TypeIdent),
     Container(String, TypeIdent),
     Custom(CustomType),
     Enum(Enum),
@@ -49,6 +50,7 @@ impl Type {
         match self {
             Self::Alias(name, _) => name.clone(),
             Self::Array(primitive, size) => format!("[{}; {}]", primitive.name(), size),
+            Self::Callback(arg, ret) => format!("Callback<{arg}, {ret}>"),
             Self::Container(name, ident) => format!("{name}<{ident}>"),
             Self::Custom(custom) => custom.ident.to_string(),
             Self::Enum(Enum { ident, .. }) => ident.to_string(),
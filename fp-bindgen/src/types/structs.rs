@@ -16,6 +16,28 @@ pub struct Struct {
     pub options: StructOptions,
 }
 
+impl Struct {
+    /// Returns whether the struct is encoded as an array of its field values.
+    ///
+    /// This is the case for compact structs with named fields. Tuple structs
+    /// are always encoded as arrays, unless they have a single field, and
+    /// unit structs are left alone.
+    pub fn is_compact(&self) -> bool {
+        self.options.is_compact
+            && !self.fields.is_empty()
+            && self.fields.iter().all(|field| field.name.is_some())
+    }
+
+    /// Returns the minimum number of values in the encoding of a compact
+    /// struct: only fields with a default value at the end may be omitted.
+    pub fn min_compact_len(&self) -> usize {
+        self.fields
+            .iter()
+            .rposition(|field| field.attrs.default.is_none())
+            .map_or(0, |index| index + 1)
+    }
+}
+
 pub(crate) fn parse_struct_item(item: ItemStruct) -> Struct {
     let ident = TypeIdent {
         name: item.ident.to_string(),
@@ -76,6 +98,37 @@ pub struct StructOptions {
     ///
     /// Instead of generating the struct definition itself.
     pub rust_module: Option<String>,
+
+    /// Whether the struct represents a host resource, which plugins can only
+    /// refer to through a `Handle`.
+    ///
+    /// ## Example:
+    ///
+    /// ```rs
+    /// #[fp(resource)]
+    /// struct Connection;
+    /// ```
+    ///
+    /// The Rust plugin generator emits an uninhabited type for resources,
+    /// while the Rust runtime generators import the type from the module in
+    /// which the bindings are included, where the host should define it.
+    pub is_resource: bool,
+
+    /// Whether the struct is encoded as an array of its field values, rather
+    /// than as a map from field names to values.
+    ///
+    /// ## Example:
+    ///
+    /// ```rs
+    /// #[fp(compact)]
+    /// struct Point { x: f64, y: f64 }
+    /// ```
+    ///
+    /// Compact structs omit the field names from every encoded value, which
+    /// makes them smaller, but it means fields are identified by their
+    /// position. See the specification for the compatibility rules this
+    /// implies.
+    pub is_compact: bool,
 }
 
 impl StructOptions {
@@ -98,6 +151,12 @@ impl StructOptions {
         if let Some(other_rust_module) = &other.rust_module {
             self.rust_module = Some(other_rust_module.clone());
         }
+        if other.is_resource {
+            self.is_resource = true;
+        }
+        if other.is_compact {
+            self.is_compact = true;
+        }
     }
 
     pub fn to_serde_attrs(&self) -> Vec<String> {
@@ -135,6 +194,12 @@ impl Parse for StructOptions {
                 "rust_module" => {
                     result.rust_module = Some(parse_value()?);
                 }
+                "resource" => {
+                    result.is_resource = true;
+                }
+                "compact" => {
+                    result.is_compact = true;
+                }
                 other => {
                     return Err(Error::new(
                         content.span(),
@@ -2,7 +2,7 @@ use super::is_runtime_bound;
 use crate::primitives::Primitive;
 use std::num::NonZeroUsize;
 use std::{convert::TryFrom, fmt::Display, str::FromStr};
-use syn::{PathArguments, TypeParamBound, TypePath, TypeTuple};
+use syn::{PathArguments, TypeParamBound, TypePath, TypeReference, TypeSlice, TypeTuple};
 
 #[derive(Clone, Default, Debug, Eq, Hash, PartialEq)]
 #[non_exhaustive]
@@ -30,9 +30,15 @@ impl TypeIdent {
         self.as_primitive().is_some()
     }
 
+    /// Returns the primitive this identifier refers to, if it is one that is
+    /// passed directly across the WebAssembly boundary.
+    ///
+    /// Primitives that require serialization are treated like any other type.
     pub fn as_primitive(&self) -> Option<Primitive> {
         if self.array.is_none() {
-            Primitive::from_str(&self.name).ok()
+            Primitive::from_str(&self.name)
+                .ok()
+                .filter(|primitive| !primitive.requires_serialization())
         } else {
             None
         }
@@ -245,6 +251,26 @@ impl TryFrom<&syn::Type> for TypeIdent {
                     ..Default::default()
                 })
             }
+            // References resolve to the owned type they borrow from, since
+            // that is what ends up being passed across the Wasm boundary:
+            syn::Type::Reference(TypeReference {
+                mutability, elem, ..
+            }) => {
+                if mutability.is_some() {
+                    return Err(format!("Mutable references are not supported: {ty:?}"));
+                }
+
+                match elem.as_ref() {
+                    syn::Type::Path(TypePath { path, qself: None }) if path.is_ident("str") => {
+                        Ok(TypeIdent::from("String"))
+                    }
+                    syn::Type::Slice(TypeSlice { elem, .. }) => Ok(TypeIdent::new(
+                        "Vec",
+                        vec![(TypeIdent::try_from(elem.as_ref())?, vec![])],
+                    )),
+                    elem => TypeIdent::try_from(elem),
+                }
+            }
             syn::Type::Tuple(TypeTuple {
                 elems,
                 paren_token: _,
@@ -304,6 +330,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn type_ident_from_syn_reference() {
+        let ty = syn::parse_str::<syn::Type>("&str").unwrap();
+        assert_eq!(TypeIdent::try_from(&ty).unwrap(), TypeIdent::from("String"));
+
+        let ty = syn::parse_str::<syn::Type>("&[u8]").unwrap();
+        assert_eq!(
+            TypeIdent::try_from(&ty).unwrap(),
+            TypeIdent::from("Vec<u8>")
+        );
+
+        let ty = syn::parse_str::<syn::Type>("&BTreeMap<String, Point<f64>>").unwrap();
+        assert_eq!(
+            TypeIdent::try_from(&ty).unwrap(),
+            TypeIdent::from("BTreeMap<String, Point<f64>>")
+        );
+
+        let ty = syn::parse_str::<syn::Type>("&[u8; 4]").unwrap();
+        assert_eq!(
+            TypeIdent::try_from(&ty).unwrap(),
+            TypeIdent::from("[u8; 4]")
+        );
+
+        let ty = syn::parse_str::<syn::Type>("&mut String").unwrap();
+        assert!(TypeIdent::try_from(&ty).is_err());
+    }
+
     #[test]
     fn type_ident_from_str() {
         let t = TypeIdent::from_str("u32").unwrap();
@@ -0,0 +1,108 @@
+use proc_macro::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{parse_quote, Attribute, Fields, Item, Meta, NestedMeta};
+
+pub(crate) fn impl_derive_serialize_compact(item: TokenStream) -> TokenStream {
+    let item = match syn::parse::<Item>(item) {
+        Ok(Item::Struct(item)) => item,
+        Ok(item) => abort!(item, "`SerializeCompact` can only be derived for structs"),
+        Err(error) => abort!(error.span(), error),
+    };
+
+    if !has_compact_option(&item.attrs) {
+        abort!(
+            item.ident,
+            "`SerializeCompact` can only be derived for structs marked with `#[fp(compact)]`"
+        );
+    }
+
+    let fields = match &item.fields {
+        Fields::Named(fields) if !fields.named.is_empty() => &fields.named,
+        _ => abort!(
+            item.ident,
+            "`SerializeCompact` can only be derived for structs with named fields"
+        ),
+    };
+
+    let names = fields.iter().map(|field| &field.ident);
+    let mut compact_fields = fields
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            let serde_attrs = get_serialize_attrs(&field.attrs);
+            quote! { #(#[serde(#serde_attrs)])* &'a #ty }
+        })
+        .collect::<Vec<_>>();
+    // Serde serializes tuple structs with a single field as newtypes, so make
+    // sure there are at least two fields:
+    if compact_fields.len() < 2 {
+        compact_fields.push(quote! { #[serde(skip)] std::marker::PhantomData<&'a ()> });
+    }
+    let phantom_value = if fields.len() < 2 {
+        quote! { , std::marker::PhantomData }
+    } else {
+        quote! {}
+    };
+
+    let item_name = &item.ident;
+    let type_params = item.generics.type_params().map(|param| &param.ident);
+    let mut generics = item.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(serde::Serialize));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let implementation = quote! {
+        impl #impl_generics serde::Serialize for #item_name #ty_generics #where_clause {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                #[derive(serde::Serialize)]
+                struct Compact<'a, #(#type_params),*>(
+                    #(#compact_fields),*
+                );
+
+                serde::Serialize::serialize(
+                    &Compact(#(&self.#names),* #phantom_value),
+                    serializer,
+                )
+            }
+        }
+    };
+
+    implementation.into()
+}
+
+fn has_compact_option(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("fp"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .any(|meta| match meta {
+            Meta::List(list) => list.nested.iter().any(
+                |nested| matches!(nested, NestedMeta::Meta(Meta::Path(path)) if path.is_ident("compact")),
+            ),
+            _ => false,
+        })
+}
+
+/// Returns the field attributes that affect serialization, in the form in
+/// which they can be passed to Serde.
+fn get_serialize_attrs(attrs: &[Attribute]) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("fp") || attr.path.is_ident("serde"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .flat_map(|meta| match meta {
+            Meta::List(list) => list.nested.into_iter().collect(),
+            _ => Vec::new(),
+        })
+        .filter_map(|nested| match nested {
+            NestedMeta::Meta(meta @ Meta::NameValue(_))
+                if meta.path().is_ident("serialize_with") || meta.path().is_ident("with") =>
+            {
+                Some(meta)
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -1,17 +1,28 @@
 use crate::{primitives::Primitive, utils::extract_path_from_type};
-use proc_macro::{TokenStream, TokenTree};
-use proc_macro_error::{abort, proc_macro_error, ResultExt};
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+use proc_macro_error::{abort, emit_error, proc_macro_error, ResultExt};
 use quote::{format_ident, quote, ToTokens};
 use std::{
     collections::{HashMap, HashSet},
     iter::once,
 };
 use syn::{
-    AttributeArgs, FnArg, ForeignItemFn, GenericParam, ItemFn, ItemType, ItemUse, Pat, PatPath,
-    Path, PathArguments, PathSegment, ReturnType,
+    parse::{Parse, ParseStream},
+    AttributeArgs, Expr, ExprLit, FnArg, ForeignItemFn, GenericArgument, GenericParam, Ident,
+    ItemFn, ItemType, ItemUse, Lit, Pat, PatPath, Path, PathArguments, PathSegment, ReturnType,
+    Token, Type, TypeArray,
 };
 use utils::{flatten_using_statement, normalize_return_type};
 
+/// Name of the Wasm module from which functions are imported, unless they are
+/// declared inside a named module. Mirrors `fp_bindgen::DEFAULT_IMPORT_MODULE`.
+const DEFAULT_IMPORT_MODULE: &str = "fp";
+
+/// Types of which arrays can be used in protocols: the primitives for which
+/// JavaScript has a typed array. Mirrors `Primitive::js_array_name()`.
+const ARRAY_ELEMENT_TYPES: &[&str] = &["u8", "u16", "u32", "i8", "i16", "i32", "f32", "f64"];
+
+mod compact;
 mod primitives;
 mod serializable;
 mod typing;
@@ -19,32 +30,50 @@ mod utils;
 
 /// Used to annotate types (`enum`s and `struct`s) that can be passed across the Wasm bridge.
 #[proc_macro_derive(Serializable, attributes(fp))]
+#[proc_macro_error]
 pub fn derive_serializable(item: TokenStream) -> TokenStream {
     crate::serializable::impl_derive_serializable(item)
 }
 
+/// Implements Serde's `Serialize` trait for structs marked with
+/// `#[fp(compact)]`, so that they are encoded as an array of their field
+/// values, like in the generated bindings.
+///
+/// Use this instead of deriving `Serialize` for compact structs that are
+/// shared with a host.
+#[proc_macro_derive(SerializeCompact, attributes(fp, serde))]
+#[proc_macro_error]
+pub fn derive_serialize_compact(item: TokenStream) -> TokenStream {
+    crate::compact::impl_derive_serialize_compact(item)
+}
+
 /// Declares functions the plugin can import from the host runtime.
 #[proc_macro]
+#[proc_macro_error]
 pub fn fp_import(token_stream: TokenStream) -> TokenStream {
     let ParsedStatements {
         functions,
         collectable_types,
         aliases,
-    } = parse_statements(token_stream);
+    } = parse_statements(token_stream, true, false);
     let collectable_types = collectable_types.iter();
     let alias_keys = aliases.keys();
     let alias_paths = aliases
         .values()
-        .map(|path| path.to_token_stream().to_string());
+        .map(CollectableTypeDefinition::to_type_ident);
+    let add_functions = functions.iter().map(|(module, function)| match module {
+        Some(module) => quote! { list.add_function_to_module(#function, #module); },
+        None => quote! { list.add_function(#function); },
+    });
 
     let replacement = quote! {
         fn __fp_declare_import_fns() -> (fp_bindgen::prelude::FunctionList, fp_bindgen::prelude::TypeMap) {
             let mut import_types = fp_bindgen::prelude::TypeMap::new();
             #( #collectable_types::collect_types(&mut import_types); )*
-            #( import_types.insert(TypeIdent::from(#alias_keys), Type::Alias(#alias_keys.to_owned(), std::str::FromStr::from_str(#alias_paths).unwrap())); )*
+            #( import_types.insert(TypeIdent::from(#alias_keys), Type::Alias(#alias_keys.to_owned(), TypeIdent::from(#alias_paths))); )*
 
             let mut list = fp_bindgen::prelude::FunctionList::new();
-            #( list.add_function(#functions); )*
+            #( #add_functions )*
 
             (list, import_types)
         }
@@ -54,23 +83,25 @@ pub fn fp_import(token_stream: TokenStream) -> TokenStream {
 
 /// Declares functions the plugin may export to the host runtime.
 #[proc_macro]
+#[proc_macro_error]
 pub fn fp_export(token_stream: TokenStream) -> TokenStream {
     let ParsedStatements {
         functions,
         collectable_types,
         aliases,
-    } = parse_statements(token_stream);
+    } = parse_statements(token_stream, false, true);
     let collectable_types = collectable_types.iter();
     let alias_keys = aliases.keys();
     let alias_paths = aliases
         .values()
-        .map(|path| path.to_token_stream().to_string());
+        .map(CollectableTypeDefinition::to_type_ident);
+    let functions = functions.iter().map(|(_, function)| function);
 
     let replacement = quote! {
         fn __fp_declare_export_fns() -> (fp_bindgen::prelude::FunctionList, fp_bindgen::prelude::TypeMap) {
             let mut export_types = fp_bindgen::prelude::TypeMap::new();
             #( #collectable_types::collect_types(&mut export_types); )*
-            #( export_types.insert(TypeIdent::from(#alias_keys), Type::Alias(#alias_keys.to_owned(), std::str::FromStr::from_str(#alias_paths).unwrap())); )*
+            #( export_types.insert(TypeIdent::from(#alias_keys), Type::Alias(#alias_keys.to_owned(), TypeIdent::from(#alias_paths))); )*
 
             let mut list = fp_bindgen::prelude::FunctionList::new();
             #( list.add_function(#functions); )*
@@ -84,7 +115,9 @@ pub fn fp_export(token_stream: TokenStream) -> TokenStream {
 /// Contains all the relevant information extracted from inside the `fp_import!` and `fp_export!`
 /// macros.
 struct ParsedStatements {
-    pub functions: Vec<String>,
+    /// Stringified function declarations, along with the name of the import
+    /// module they were declared in, if any.
+    pub functions: Vec<(Option<String>, String)>,
     pub collectable_types: HashSet<CollectableTypeDefinition>,
     pub aliases: HashMap<String, CollectableTypeDefinition>,
 }
@@ -96,6 +129,18 @@ struct CollectableTypeDefinition {
     pub array_len: usize,
 }
 
+impl CollectableTypeDefinition {
+    /// Returns the string from which `fp_bindgen` parses the `TypeIdent` of
+    /// the type.
+    fn to_type_ident(&self) -> String {
+        if self.array_len > 0 {
+            format!("[{}; {}]", self.path.to_token_stream(), self.array_len)
+        } else {
+            self.path.to_token_stream().to_string()
+        }
+    }
+}
+
 impl ToTokens for CollectableTypeDefinition {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let path = &self.path;
@@ -113,74 +158,124 @@ impl ToTokens for CollectableTypeDefinition {
 /// Finally, it returns two sets: one with all the paths for types that may need serialization
 /// to call the functions, and one with all the paths for types that may need deserialization to
 /// call the functions.
-fn parse_statements(token_stream: TokenStream) -> ParsedStatements {
+///
+/// Functions may be grouped in named modules, using `mod name { ... }`, if
+/// `allow_modules` is `true`. Their declarations are returned along with the
+/// name of the module.
+///
+/// Functions may be declared as events, using `#[fp(event)]`, if
+/// `allow_events` is `true`.
+///
+/// Invalid statements are reported as errors pointing at the offending tokens.
+fn parse_statements(
+    token_stream: TokenStream,
+    allow_modules: bool,
+    allow_events: bool,
+) -> ParsedStatements {
     let mut functions = Vec::new();
+    let mut function_names = HashSet::new();
     let mut collectable_types = HashSet::new();
     let mut aliases = HashMap::new();
 
     let mut current_item_tokens = Vec::<TokenTree>::new();
     for token in token_stream.into_iter() {
         match token {
+            TokenTree::Group(group)
+                if group.delimiter() == Delimiter::Brace
+                    && get_statement_keyword(current_item_tokens.iter().cloned().collect())
+                        .as_deref()
+                        == Some("mod") =>
+            {
+                let module = std::mem::take(&mut current_item_tokens);
+                let Some(module) = parse_module_name(module, allow_modules) else {
+                    continue;
+                };
+
+                let parsed = parse_statements(group.stream(), false, false);
+                for (_, function) in parsed.functions {
+                    functions.push((Some(module.clone()), function));
+                }
+                collectable_types.extend(parsed.collectable_types);
+                aliases.extend(parsed.aliases);
+            }
             TokenTree::Punct(punct) if punct.as_char() == ';' => {
                 current_item_tokens.push(TokenTree::Punct(punct));
 
-                let stream = current_item_tokens.into_iter().collect::<TokenStream>();
-
-                if let Ok(function) = syn::parse::<ForeignItemFn>(stream.clone()) {
-                    for input in &function.sig.inputs {
-                        match input {
-                            FnArg::Receiver(_) => panic!(
-                                "Methods are not supported. Found `self` in function declaration: {:?}",
-                                function.sig
-                            ),
-                            FnArg::Typed(arg) => {
-                                collectable_types.insert(
-                                    extract_path_from_type(arg.ty.as_ref()).unwrap_or_else(|| {
-                                        panic!(
-                                            "Only value types are supported. \
-                                                Incompatible argument type in function declaration: {:?}",
-                                            function.sig
-                                        )
-                                    }),
+                let stream = std::mem::take(&mut current_item_tokens)
+                    .into_iter()
+                    .collect::<TokenStream>();
+
+                match get_statement_keyword(stream.clone()).as_deref() {
+                    Some("fn") => {
+                        let function = match syn::parse::<ForeignItemFn>(stream) {
+                            Ok(function) => function,
+                            Err(error) => {
+                                emit_error!(
+                                    error.span(),
+                                    "invalid function declaration: {}",
+                                    error
                                 );
+                                continue;
                             }
+                        };
+
+                        check_function_attrs(&function, allow_events);
+                        collect_function_types(&function, &mut collectable_types);
+                        if !function_names.insert(function.sig.ident.to_string()) {
+                            emit_error!(
+                                function.sig.ident,
+                                "function `{}` is declared more than once",
+                                function.sig.ident;
+                                note = "function names must be unique, even across import modules"
+                            );
                         }
+                        functions.push((None, function.into_token_stream().to_string()));
                     }
-
-                    if let Some(ty) = normalize_return_type(&function.sig.output) {
-                        collectable_types.insert(extract_path_from_type(ty).unwrap_or_else(|| {
-                            panic!(
-                                "Only value types are supported. \
-                                            Incompatible return type in function declaration: {:?}",
-                                function.sig
-                            )
-                        }));
-                    }
-
-                    functions.push(function.into_token_stream().to_string());
-                } else if let Ok(using) = syn::parse::<ItemUse>(stream.clone()) {
-                    for path in flatten_using_statement(using) {
-                        collectable_types.insert(CollectableTypeDefinition { path, array_len: 0 });
-                    }
-                } else if let Ok(type_alias) = syn::parse::<ItemType>(stream) {
-                    aliases.insert(
-                        type_alias.ident.to_string(),
-                        extract_path_from_type(type_alias.ty.as_ref()).unwrap_or_else(|| {
-                            panic!(
-                                "Only value types are supported. \
-                                    Incompatible type in alias: {:?}",
-                                type_alias
-                            )
-                        }),
-                    );
+                    Some("use") => match syn::parse::<ItemUse>(stream) {
+                        Ok(using) => {
+                            for path in flatten_using_statement(using) {
+                                collectable_types
+                                    .insert(CollectableTypeDefinition { path, array_len: 0 });
+                            }
+                        }
+                        Err(error) => {
+                            emit_error!(error.span(), "invalid `use` statement: {}", error)
+                        }
+                    },
+                    Some("type") => match syn::parse::<ItemType>(stream) {
+                        Ok(type_alias) => match extract_path_from_type(type_alias.ty.as_ref()) {
+                            Some(path) if !matches!(type_alias.ty.as_ref(), Type::Reference(_)) => {
+                                if check_arrays(type_alias.ty.as_ref()) {
+                                    aliases.insert(type_alias.ident.to_string(), path);
+                                }
+                            }
+                            _ => emit_error!(
+                                type_alias.ty,
+                                "unsupported type in alias";
+                                note = "only value types are supported"
+                            ),
+                        },
+                        Err(error) => emit_error!(error.span(), "invalid type alias: {}", error),
+                    },
+                    _ => emit_error!(
+                        proc_macro2::TokenStream::from(stream),
+                        "unsupported statement";
+                        note = "only function declarations, `use` statements and type aliases are allowed"
+                    ),
                 }
-
-                current_item_tokens = Vec::new();
             }
             other => current_item_tokens.push(other),
         }
     }
 
+    if !current_item_tokens.is_empty() {
+        let stream = current_item_tokens.into_iter().collect::<TokenStream>();
+        emit_error!(
+            proc_macro2::TokenStream::from(stream),
+            "expected `;` at the end of the statement"
+        );
+    }
+
     ParsedStatements {
         functions,
         collectable_types,
@@ -188,10 +283,225 @@ fn parse_statements(token_stream: TokenStream) -> ParsedStatements {
     }
 }
 
+/// Parses the name of a `mod name { ... }` group from the tokens in front of
+/// its body. Returns `None` if an error was reported.
+fn parse_module_name(tokens: Vec<TokenTree>, allow_modules: bool) -> Option<String> {
+    let stream = proc_macro2::TokenStream::from(tokens.into_iter().collect::<TokenStream>());
+    let mut idents = stream.clone().into_iter();
+    let name = match (idents.next(), idents.next(), idents.next()) {
+        (
+            Some(proc_macro2::TokenTree::Ident(keyword)),
+            Some(proc_macro2::TokenTree::Ident(name)),
+            None,
+        ) if keyword == "mod" => name,
+        _ => {
+            emit_error!(stream, "invalid module declaration"; help = "use `mod name { ... }`");
+            return None;
+        }
+    };
+
+    if !allow_modules {
+        emit_error!(
+            stream,
+            "unsupported module";
+            note = "modules can only be declared at the top level of `fp_import!`"
+        );
+        None
+    } else if name == DEFAULT_IMPORT_MODULE {
+        emit_error!(
+            name,
+            "module name `{}` is reserved", DEFAULT_IMPORT_MODULE;
+            help = "functions outside of a module are imported from the `{}` module", DEFAULT_IMPORT_MODULE
+        );
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Reports the arrays in the given type that cannot be used in protocols.
+/// Returns whether all of them can be used.
+fn check_arrays(ty: &Type) -> bool {
+    match ty {
+        Type::Array(TypeArray { elem, len, .. }) => {
+            let mut supported = true;
+            match elem.as_ref() {
+                Type::Path(path)
+                    if ARRAY_ELEMENT_TYPES
+                        .iter()
+                        .any(|element| path.path.is_ident(element)) => {}
+                elem => {
+                    emit_error!(
+                        elem,
+                        "unsupported array element type";
+                        note = "only arrays of {} are supported", ARRAY_ELEMENT_TYPES.join(", ")
+                    );
+                    supported = false;
+                }
+            }
+            match len {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(len), ..
+                }) if len.suffix().is_empty() && len.base10_parse::<usize>().is_ok() => {}
+                len => {
+                    emit_error!(
+                        len,
+                        "unsupported array length";
+                        note = "array lengths must be integer literals without a suffix"
+                    );
+                    supported = false;
+                }
+            }
+            supported
+        }
+        // Every argument is checked, so that all unsupported arrays are
+        // reported at once:
+        Type::Path(path) => {
+            path.path
+                .segments
+                .iter()
+                .flat_map(|segment| match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args.args.iter().collect(),
+                    _ => Vec::new(),
+                })
+                .filter(|arg| matches!(arg, GenericArgument::Type(ty) if !check_arrays(ty)))
+                .count()
+                == 0
+        }
+        _ => true,
+    }
+}
+
+/// Reports `#[fp(...)]` attributes on the given function that are not
+/// supported.
+fn check_function_attrs(function: &ForeignItemFn, allow_events: bool) {
+    for attr in function
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("fp"))
+    {
+        match attr.parse_args::<syn::Ident>() {
+            Ok(ident) if ident == "event" => {
+                if !allow_events {
+                    emit_error!(
+                        attr,
+                        "unsupported event";
+                        note = "events can only be declared in `fp_export!`"
+                    );
+                }
+            }
+            _ => emit_error!(
+                attr,
+                "unsupported attribute";
+                help = "the only attribute supported on functions is `#[fp(event)]`"
+            ),
+        }
+    }
+}
+
+/// Collects the types used by the arguments and return type of the given
+/// function, reporting those that cannot be used in a protocol.
+fn collect_function_types(
+    function: &ForeignItemFn,
+    collectable_types: &mut HashSet<CollectableTypeDefinition>,
+) {
+    for input in &function.sig.inputs {
+        match input {
+            FnArg::Receiver(receiver) => emit_error!(
+                receiver,
+                "methods are not supported";
+                help = "remove `self` from the function declaration"
+            ),
+            FnArg::Typed(arg) => match extract_path_from_type(arg.ty.as_ref()) {
+                Some(path) => {
+                    collectable_types.insert(path);
+                }
+                None => emit_error!(
+                    arg.ty,
+                    "unsupported argument type";
+                    note = "only value types and shared references are supported"
+                ),
+            },
+        }
+    }
+
+    if let Some(ty) = normalize_return_type(&function.sig.output) {
+        match extract_path_from_type(ty) {
+            Some(path) if !matches!(ty, Type::Reference(_)) => {
+                collectable_types.insert(path);
+            }
+            _ => emit_error!(
+                ty,
+                "unsupported return type";
+                note = "only value types are supported"
+            ),
+        }
+    }
+}
+
+/// Returns the keyword that determines the kind of statement, skipping over
+/// any attributes (including doc comments) and modifiers in front of it.
+fn get_statement_keyword(stream: TokenStream) -> Option<String> {
+    let mut tokens = stream.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                // Skip the bracketed group of the attribute:
+                tokens.next();
+            }
+            TokenTree::Ident(ident) => match ident.to_string().as_str() {
+                "async" | "pub" => {}
+                keyword => return Some(keyword.to_owned()),
+            },
+            TokenTree::Group(_) => {} // For instance, the group in `pub(crate)`.
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Arguments of `fp_bindgen!()`: the binding config, optionally followed by
+/// `wire_format = <expr>`.
+struct BindgenArgs {
+    config: Expr,
+    wire_format: Option<Expr>,
+}
+
+impl Parse for BindgenArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let config = input.parse()?;
+        let mut wire_format = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if key != "wire_format" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "unknown argument, expected `wire_format`",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            wire_format = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self {
+            config,
+            wire_format,
+        })
+    }
+}
+
 /// Generates bindings for the functions declared in the `fp_import!{}` and `fp_export!{}` blocks.
+///
+/// The binding config may be followed by `wire_format = <expr>`, to select the
+/// format runtimes ask plugins to use for passing values.
 #[proc_macro]
+#[proc_macro_error]
 pub fn fp_bindgen(args: TokenStream) -> TokenStream {
-    let args: proc_macro2::TokenStream = args.into();
+    let BindgenArgs {
+        config,
+        wire_format,
+    } = syn::parse::<BindgenArgs>(args).unwrap_or_abort();
+    let wire_format = wire_format.map(|wire_format| quote! { .with_wire_format(#wire_format) });
     let replacement = quote! {
         let (import_functions, import_types) = __fp_declare_import_fns();
         let (export_functions, mut export_types) = __fp_declare_export_fns();
@@ -199,12 +509,13 @@ pub fn fp_bindgen(args: TokenStream) -> TokenStream {
         let mut types = import_types;
         types.append(&mut export_types);
 
-        fp_bindgen::generate_bindings(
-            import_functions,
-            export_functions,
-            types,
-            #args
-        );
+        let protocol = fp_bindgen::prelude::Protocol::new(import_functions, export_functions, types)
+            .with_version(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+            #wire_format;
+
+        if let Err(error) = fp_bindgen::generate_protocol_bindings(protocol, #config) {
+            panic!("{}", error);
+        }
     };
     replacement.into()
 }
@@ -220,15 +531,18 @@ pub fn primitive_impls(_: TokenStream) -> TokenStream {
         Primitive::I16,
         Primitive::I32,
         Primitive::I64,
+        Primitive::I128,
         Primitive::U8,
         Primitive::U16,
         Primitive::U32,
         Primitive::U64,
+        Primitive::U128,
+        Primitive::Char,
     ];
 
     let mut token_stream = TokenStream::new();
     for primitive in primitives {
-        token_stream.extend(primitive.gen_impl().into_iter());
+        token_stream.extend(primitive.gen_impl());
     }
     token_stream
 }
@@ -398,12 +712,25 @@ pub fn fp_export_impl(attributes: TokenStream, input: TokenStream) -> TokenStrea
 
 /// Imports a signature in a provider crate.
 /// This is not meant to be used directly.
+///
+/// Functions are imported from the `fp` module, unless another module is
+/// specified using `#[fp_import_signature(module = "name")]`.
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn fp_import_signature(_attributes: TokenStream, input: TokenStream) -> TokenStream {
+pub fn fp_import_signature(attributes: TokenStream, input: TokenStream) -> TokenStream {
     proc_macro_error::set_dummy(input.clone().into());
 
     let func = syn::parse_macro_input::parse::<ForeignItemFn>(input.clone()).unwrap_or_abort();
+    let attrs = syn::parse_macro_input::parse::<AttributeArgs>(attributes).unwrap_or_abort();
+    let import_module = match attrs.first() {
+        None => DEFAULT_IMPORT_MODULE.to_owned(),
+        Some(syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(module),
+            ..
+        }))) if path.is_ident("module") => module.value(),
+        Some(attr) => abort!(attr, "unsupported attribute, expected `module = \"name\"`"),
+    };
     let args = typing::extract_args(&func.sig).collect::<Vec<_>>();
 
     let wrapper_sig = func.sig.clone();
@@ -450,7 +777,7 @@ pub fn fp_import_signature(_attributes: TokenStream, input: TokenStream) -> Toke
 
     //build the actual imported wrapper function
     (quote! {
-        #[link(wasm_import_module = "fp")]
+        #[link(wasm_import_module = #import_module)]
         extern "C" { #extern_sig; }
 
         #[inline(always)]
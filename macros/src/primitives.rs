@@ -13,10 +13,13 @@ pub enum Primitive {
     I16,
     I32,
     I64,
+    I128,
     U8,
     U16,
     U32,
     U64,
+    U128,
+    Char,
 }
 
 impl Primitive {
@@ -24,6 +27,23 @@ impl Primitive {
         let ty = self.ty();
         let ty_str = ty.to_token_stream().to_string();
 
+        // Arrays are passed as typed arrays in JavaScript, for which there is
+        // no equivalent for the primitives that require serialization:
+        let array_implementation = match self {
+            Primitive::I128 | Primitive::U128 | Primitive::Char => quote! {},
+            _ => quote! {
+                impl<const N: usize> Serializable for [#ty; N] {
+                    fn ident() -> TypeIdent {
+                        TypeIdent::from(format!("[{}; {}]", #ty_str, N).as_str())
+                    }
+
+                    fn ty() -> Type {
+                        Type::Array(Primitive::#self, N)
+                    }
+                }
+            },
+        };
+
         let implementation = quote! {
             impl Serializable for #ty {
                 fn ident() -> TypeIdent {
@@ -39,15 +59,7 @@ impl Primitive {
                 }
             }
 
-            impl<const N: usize> Serializable for [#ty; N] {
-                fn ident() -> TypeIdent {
-                    TypeIdent::from(format!("[{}; {}]", #ty_str, N).as_str())
-                }
-
-                fn ty() -> Type {
-                    Type::Array(Primitive::#self, N)
-                }
-            }
+            #array_implementation
         };
         implementation.into()
     }
@@ -62,10 +74,13 @@ impl Primitive {
             I16 => "i16",
             I32 => "i32",
             I64 => "i64",
+            I128 => "i128",
             U8 => "u8",
             U16 => "u16",
             U32 => "u32",
             U64 => "u64",
+            U128 => "u128",
+            Char => "char",
         };
         Type::Path(parse_str(string))
     }
@@ -82,10 +97,13 @@ impl ToTokens for Primitive {
             I16 => "I16",
             I32 => "I32",
             I64 => "I64",
+            I128 => "I128",
             U8 => "U8",
             U16 => "U16",
             U32 => "U32",
             U64 => "U64",
+            U128 => "U128",
+            Char => "Char",
         };
         let ident = Ident::new(ident_str, Span::call_site());
         tokens.extend(vec![TokenTree::Ident(ident)]);
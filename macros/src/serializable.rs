@@ -1,6 +1,7 @@
 use crate::utils::{extract_path_from_type, parse_type_item};
 use crate::CollectableTypeDefinition;
 use proc_macro::TokenStream;
+use proc_macro_error::emit_error;
 use quote::quote;
 use std::collections::{BTreeMap, HashSet};
 use syn::punctuated::Punctuated;
@@ -10,34 +11,30 @@ pub(crate) fn impl_derive_serializable(item: TokenStream) -> TokenStream {
     let item_str = item.to_string();
     let (item_name, item, mut generics) = parse_type_item(item);
 
-    let field_types: HashSet<CollectableTypeDefinition> = match item {
+    let fields: Vec<_> = match item {
         syn::Item::Enum(ty) => ty
             .variants
             .into_iter()
             .flat_map(|variant| variant.fields)
-            .map(|field| {
-                extract_path_from_type(&field.ty).unwrap_or_else(|| {
-                    panic!(
-                        "Only value types are supported. Incompatible type in enum variant field: {:?}",
-                        field
-                    )
-                })
-            })
-            .collect(),
-        syn::Item::Struct(ty) => ty
-            .fields
-            .into_iter()
-            .map(|field| {
-                extract_path_from_type(&field.ty).unwrap_or_else(|| {
-                    panic!(
-                        "Only value types are supported. Incompatible type in struct field: {:?}",
-                        field
-                    )
-                })
-            })
             .collect(),
-        _ => HashSet::default(),
+        syn::Item::Struct(ty) => ty.fields.into_iter().collect(),
+        _ => Vec::new(),
     };
+    let field_types: HashSet<CollectableTypeDefinition> = fields
+        .iter()
+        .filter_map(|field| {
+            let path = extract_path_from_type(&field.ty);
+            if path.is_none() || matches!(field.ty, syn::Type::Reference(_)) {
+                emit_error!(
+                    field.ty,
+                    "unsupported field type";
+                    note = "only value types are supported"
+                );
+                return None;
+            }
+            path
+        })
+        .collect();
 
     // Remove any bounds from the generic types and store them separately.
     // Otherwise, collect_types will be called like `Foo::<T: MyTrait>::collect_types()` and where clauses
@@ -43,6 +43,7 @@ pub(crate) fn is_type_complex(ty: &Type) -> bool {
                     | "usize"
             )
         }
+        Type::Reference(_) => true,
         Type::Tuple(_) => true,
         t => abort!(t, "unsupported type"),
     }
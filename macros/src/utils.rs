@@ -3,10 +3,10 @@ use std::collections::VecDeque;
 use crate::CollectableTypeDefinition;
 use proc_macro::TokenStream;
 use proc_macro2::Ident;
-use std::str::FromStr;
+use proc_macro_error::{abort, ResultExt};
 use syn::{
     punctuated::Punctuated, Expr, ExprLit, Generics, Item, ItemUse, Lit, Path, PathArguments,
-    PathSegment, ReturnType, Type, TypeArray,
+    PathSegment, ReturnType, Type, TypeArray, TypeReference, TypeSlice,
 };
 
 pub(crate) fn extract_path_from_type(ty: &Type) -> Option<CollectableTypeDefinition> {
@@ -18,10 +18,10 @@ pub(crate) fn extract_path_from_type(ty: &Type) -> Option<CollectableTypeDefinit
             }),
             ..
         }) => {
-            let def = extract_path_from_type(elem).unwrap();
+            let def = extract_path_from_type(elem)?;
             Some(CollectableTypeDefinition {
                 path: def.path,
-                array_len: usize::from_str(len.base10_digits()).unwrap(),
+                array_len: len.base10_parse().unwrap_or_abort(),
             })
         }
         Type::Path(path) if path.qself.is_none() => {
@@ -36,12 +36,26 @@ pub(crate) fn extract_path_from_type(ty: &Type) -> Option<CollectableTypeDefinit
             }
             Some(CollectableTypeDefinition { path, array_len: 0 })
         }
+        // Borrowed arguments are collected as the owned type they borrow from:
+        Type::Reference(TypeReference {
+            mutability: None,
+            elem,
+            ..
+        }) => match elem.as_ref() {
+            Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => {
+                extract_path_from_type(&syn::parse_quote!(String))
+            }
+            Type::Slice(TypeSlice { elem, .. }) => {
+                extract_path_from_type(&syn::parse_quote!(Vec<#elem>))
+            }
+            elem => extract_path_from_type(elem),
+        },
         _ => None,
     }
 }
 
 pub(crate) fn parse_type_item(item: TokenStream) -> (Ident, Item, Generics) {
-    let item = syn::parse::<Item>(item).unwrap();
+    let item = syn::parse::<Item>(item).unwrap_or_abort();
     match item {
         Item::Enum(item) => {
             let generics = item.generics.clone();
@@ -51,10 +65,7 @@ pub(crate) fn parse_type_item(item: TokenStream) -> (Ident, Item, Generics) {
             let generics = item.generics.clone();
             (item.ident.clone(), Item::Struct(item), generics)
         }
-        item => panic!(
-            "Only struct and enum types can be constructed from an item. Found: {:?}",
-            item
-        ),
+        item => abort!(item, "only structs and enums can be `Serializable`"),
     }
 }
 
@@ -90,7 +101,11 @@ pub(crate) fn flatten_using_statement(using: ItemUse) -> impl Iterator<Item = Pa
                     queue.push_back(item);
                 }
             }
-            _ => panic!("Glob and renames use statements are not supported at this time, sorry..."),
+            tree => abort!(
+                tree,
+                "glob and rename `use` statements are not supported";
+                help = "import every type by its own name instead"
+            ),
         }
     }
 